@@ -0,0 +1,161 @@
+//! MFT parsing benchmarks
+//!
+//! Measures records/sec through `MftParser::parse_record` and
+//! `MftParser::parse_batch_with_extensions` without a live volume, the
+//! latter both serially and with one parse thread per core
+//! (`MftParser::set_parse_threads`).
+//!
+//! Records come from a raw dump of consecutive MFT records: the one
+//! `EMFIT_MFT_FIXTURE` points at, else `benches/fixtures/mft.bin` if it is
+//! there. `emfit cli records -d X --start 0 --end 20000 --raw mft.bin`
+//! captures one; use a test volume, since the dump holds its file names.
+//! `EMFIT_MFT_RECORD_SIZE` overrides the 1024-byte record size. Without a
+//! dump a synthetic set is generated (files with resident and non-resident
+//! `$DATA`, directories), which is more uniform than a real MFT.
+//!
+//! ```text
+//! cargo bench --bench mft_parsing
+//! EMFIT_MFT_FIXTURE=D:\mft.bin cargo bench --bench mft_parsing
+//! ```
+//!
+//! The parallel run is named after its thread count, and the record count
+//! and processor are printed first, so a result can be quoted with the
+//! setup it was measured on.
+
+#[cfg(windows)]
+mod benches {
+    use criterion::{black_box, BatchSize, Criterion, Throughput};
+    use emfit::ntfs::fixture::{self, RECORD_SIZE};
+    use emfit::ntfs::structs::{file_attributes, MFT_RECORD_IN_USE, MFT_RECORD_IS_DIRECTORY};
+    use emfit::ntfs::{MftParser, VolumeIO};
+
+    const SYNTHETIC_RECORDS: usize = 16_384;
+    const DEFAULT_FIXTURE: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/benches/fixtures/mft.bin");
+
+    /// Raw records and the record size they were cut with
+    fn load_fixture() -> (Vec<u8>, usize) {
+        let path = std::env::var("EMFIT_MFT_FIXTURE").ok().or_else(|| {
+            let checked_in = std::path::Path::new(DEFAULT_FIXTURE).exists();
+            checked_in.then(|| DEFAULT_FIXTURE.to_string())
+        });
+        if let Some(path) = path {
+            let record_size = std::env::var("EMFIT_MFT_RECORD_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(RECORD_SIZE);
+            let mut data = std::fs::read(&path)
+                .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path, e));
+            data.truncate(data.len() / record_size * record_size);
+            return (data, record_size);
+        }
+
+        let mut data = Vec::with_capacity(SYNTHETIC_RECORDS * RECORD_SIZE);
+        for record_number in 0..SYNTHETIC_RECORDS as u64 {
+            data.extend_from_slice(&synthetic_record(record_number));
+        }
+        (data, RECORD_SIZE)
+    }
+
+    /// Split an image into (record_number, bytes) pairs as `read_records_batch` does
+    fn split_records(data: &[u8], record_size: usize) -> Vec<(u64, Vec<u8>)> {
+        data.chunks_exact(record_size)
+            .enumerate()
+            .map(|(i, chunk)| (i as u64, chunk.to_vec()))
+            .collect()
+    }
+
+    // ------------------------------------------------------------------------
+    // Synthetic records (built with `emfit::ntfs::fixture`, as the unit tests are)
+    // ------------------------------------------------------------------------
+
+    /// Build one in-use FILE record with $STANDARD_INFORMATION, $FILE_NAME and
+    /// (for files) $DATA. Every 8th record is a directory, every 4th file has
+    /// non-resident data.
+    fn synthetic_record(record_number: u64) -> Vec<u8> {
+        let is_directory = record_number.is_multiple_of(8);
+        let parent = 5 + (record_number / 64) * 8;
+        let time = fixture::SI_TIME + record_number;
+
+        if is_directory {
+            return fixture::file_record(1, MFT_RECORD_IN_USE | MFT_RECORD_IS_DIRECTORY, &[
+                fixture::standard_information(file_attributes::DIRECTORY, time),
+                fixture::file_name(parent, 1, &format!("dir_{}", record_number)),
+            ]);
+        }
+        let data = if record_number % 4 == 1 {
+            fixture::non_resident_data(1 << 20, 1000 + record_number)
+        } else {
+            fixture::resident_attribute(0x80, &vec![0xAB; (record_number % 200) as usize])
+        };
+        fixture::file_record(1, MFT_RECORD_IN_USE, &[
+            fixture::standard_information(file_attributes::ARCHIVE, time),
+            fixture::file_name(parent, 1, &format!("file_{}.dat", record_number)),
+            data,
+        ])
+    }
+
+    // ------------------------------------------------------------------------
+    // Benchmarks
+    // ------------------------------------------------------------------------
+
+    pub fn mft_parsing(c: &mut Criterion) {
+        let (image, record_size) = load_fixture();
+        let records = split_records(&image, record_size);
+        let mut parser = MftParser::new(VolumeIO::from_mft_image(image, record_size as u32))
+            .expect("parser over in-memory image");
+
+        let threads = emfit::threads::max_workers();
+        eprintln!(
+            "{} records of {} bytes, {} threads, {}",
+            records.len(),
+            record_size,
+            threads,
+            std::env::var("PROCESSOR_IDENTIFIER").unwrap_or_else(|_| "unknown processor".to_string())
+        );
+
+        let mut group = c.benchmark_group("mft");
+        group.throughput(Throughput::Elements(records.len() as u64));
+
+        group.bench_function("parse_record", |b| {
+            b.iter_batched(
+                || records.clone(),
+                |mut batch| {
+                    for (record_number, data) in batch.iter_mut() {
+                        let _ = black_box(parser.parse_record(*record_number, data));
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_function("parse_batch_with_extensions", |b| {
+            b.iter_batched(
+                || records.clone(),
+                |batch| black_box(parser.parse_batch_with_extensions(batch)),
+                BatchSize::LargeInput,
+            )
+        });
+
+        // Same batch decoded on every core
+        parser.set_parse_threads(0);
+        let parallel = format!("parse_batch_with_extensions_parallel_{}_threads", parser.parse_threads());
+        group.bench_function(parallel, |b| {
+            b.iter_batched(
+                || records.clone(),
+                |batch| black_box(parser.parse_batch_with_extensions(batch)),
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.finish();
+    }
+}
+
+#[cfg(windows)]
+criterion::criterion_group!(mft, benches::mft_parsing);
+#[cfg(windows)]
+criterion::criterion_main!(mft);
+
+#[cfg(not(windows))]
+fn main() {}
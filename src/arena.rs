@@ -0,0 +1,218 @@
+//! Compact String Storage
+//!
+//! Append-only string arena addressed by `u32` ids. Used by the TUI/GUI entry
+//! tables so that tens of millions of names and paths live in a single buffer
+//! instead of one heap allocation (plus a 24-byte `String` header) each.
+//! File extensions are interned separately into a small `ExtensionTable`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Id of a string stored in a `StringArena`
+pub type StrId = u32;
+
+/// Approximate per-allocation overhead of the system allocator
+const ALLOC_OVERHEAD: usize = 16;
+
+/// Append-only storage for many small strings.
+///
+/// Id 0 is always the empty string. `intern` deduplicates identical strings
+/// (useful for directory paths shared by many entries); `push` always appends.
+#[derive(Clone)]
+pub struct StringArena {
+    data: String,
+    /// offsets[id]..offsets[id + 1] is the byte range of string `id`
+    offsets: Vec<u64>,
+    /// hash -> id, for interning. Collisions simply store a second copy.
+    lookup: HashMap<u64, StrId>,
+    /// What the same strings would have cost as individual `String`s
+    string_equivalent_bytes: u64,
+}
+
+impl Default for StringArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringArena {
+    /// The empty string id
+    pub const EMPTY: StrId = 0;
+
+    /// Create an arena containing only the empty string
+    pub fn new() -> Self {
+        Self {
+            data: String::new(),
+            offsets: vec![0, 0],
+            lookup: HashMap::new(),
+            string_equivalent_bytes: 0,
+        }
+    }
+
+    /// Append a string and return its id
+    pub fn push(&mut self, s: &str) -> StrId {
+        self.string_equivalent_bytes += string_cost(s) as u64;
+        if s.is_empty() {
+            return Self::EMPTY;
+        }
+        self.append(s)
+    }
+
+    /// Return the id of an identical string already in the arena, or append it
+    pub fn intern(&mut self, s: &str) -> StrId {
+        self.string_equivalent_bytes += string_cost(s) as u64;
+        if s.is_empty() {
+            return Self::EMPTY;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        s.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        if let Some(&id) = self.lookup.get(&hash) {
+            if self.get(id) == s {
+                return id;
+            }
+        }
+
+        let id = self.append(s);
+        self.lookup.entry(hash).or_insert(id);
+        id
+    }
+
+    fn append(&mut self, s: &str) -> StrId {
+        let id = (self.offsets.len() - 1) as StrId;
+        self.data.push_str(s);
+        self.offsets.push(self.data.len() as u64);
+        id
+    }
+
+    /// Get a string by id. Unknown ids return the empty string.
+    pub fn get(&self, id: StrId) -> &str {
+        let id = id as usize;
+        if id + 1 >= self.offsets.len() {
+            return "";
+        }
+        let start = self.offsets[id] as usize;
+        let end = self.offsets[id + 1] as usize;
+        &self.data[start..end]
+    }
+
+    /// Number of stored strings (including the empty string)
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Check if only the empty string is stored
+    pub fn is_empty(&self) -> bool {
+        self.len() <= 1
+    }
+
+    /// Drop all strings except the empty string
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Approximate heap memory used by the arena
+    pub fn heap_bytes(&self) -> usize {
+        self.data.capacity()
+            + self.offsets.capacity() * std::mem::size_of::<u64>()
+            + self.lookup.capacity() * (std::mem::size_of::<u64>() + std::mem::size_of::<StrId>())
+    }
+
+    /// Approximate memory the same strings would use as individual `String`s
+    pub fn string_equivalent_bytes(&self) -> u64 {
+        self.string_equivalent_bytes
+    }
+}
+
+/// Memory cost of one `String` holding `s`: header + heap allocation
+fn string_cost(s: &str) -> usize {
+    let heap = if s.is_empty() { 0 } else { s.len() + ALLOC_OVERHEAD };
+    std::mem::size_of::<String>() + heap
+}
+
+// ============================================================================
+// Extension Table
+// ============================================================================
+
+/// Id of an interned file extension
+pub type ExtId = u32;
+
+/// Interned lowercase file extensions.
+///
+/// A volume has millions of files but only a few thousand distinct
+/// extensions, so entries store a `u32` handle instead of an owned String.
+/// Id 0 is "no extension".
+#[derive(Clone)]
+pub struct ExtensionTable {
+    exts: Vec<Arc<str>>,
+    lookup: HashMap<Arc<str>, ExtId>,
+}
+
+impl Default for ExtensionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExtensionTable {
+    /// The "no extension" id
+    pub const NONE: ExtId = 0;
+
+    /// Create a table containing only the empty extension
+    pub fn new() -> Self {
+        let empty: Arc<str> = Arc::from("");
+        let mut lookup = HashMap::new();
+        lookup.insert(empty.clone(), Self::NONE);
+        Self {
+            exts: vec![empty],
+            lookup,
+        }
+    }
+
+    /// Get the id for an extension, adding it if new
+    pub fn intern(&mut self, ext: &str) -> ExtId {
+        if let Some(&id) = self.lookup.get(ext) {
+            return id;
+        }
+        let id = self.exts.len() as ExtId;
+        let ext: Arc<str> = Arc::from(ext);
+        self.exts.push(ext.clone());
+        self.lookup.insert(ext, id);
+        id
+    }
+
+    /// Look up the id of an extension without adding it
+    pub fn id_of(&self, ext: &str) -> Option<ExtId> {
+        self.lookup.get(ext).copied()
+    }
+
+    /// Get an extension by id. Unknown ids return the empty string.
+    pub fn get(&self, id: ExtId) -> &str {
+        self.exts.get(id as usize).map(|s| &**s).unwrap_or("")
+    }
+
+    /// Number of distinct extensions (including the empty one)
+    pub fn len(&self) -> usize {
+        self.exts.len()
+    }
+
+    /// Check if only the empty extension is present
+    pub fn is_empty(&self) -> bool {
+        self.exts.len() <= 1
+    }
+
+    /// Alphabetical rank of every id, so sorting can compare integers
+    pub fn sort_ranks(&self) -> Vec<u32> {
+        let mut ids: Vec<ExtId> = (0..self.exts.len() as ExtId).collect();
+        ids.sort_by(|&a, &b| self.get(a).cmp(self.get(b)));
+        let mut ranks = vec![0u32; ids.len()];
+        for (rank, id) in ids.into_iter().enumerate() {
+            ranks[id as usize] = rank as u32;
+        }
+        ranks
+    }
+}
@@ -0,0 +1,338 @@
+//! Batch Rename
+//!
+//! Renames several items from one template: a pattern with `{name}`, `{ext}`
+//! and `{n}` placeholders, then an optional regex find/replace over the
+//! result. Renames are planned first so the UIs can preview them, and only
+//! the clean, changing ones are carried out.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Characters Windows does not allow in a file name
+const INVALID_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Widest padding `{n:W}` gives; wider asks are cut to this
+const MAX_NUMBER_WIDTH: usize = 10;
+
+/// How new names are built from old ones
+pub struct RenameTemplate {
+    pattern: String,
+    find: Option<Regex>,
+    replace: String,
+}
+
+impl RenameTemplate {
+    /// Build a template. An empty pattern keeps the original name and an
+    /// empty `find` skips the regex step. Fails if `find` is not a valid regex.
+    pub fn new(pattern: &str, find: &str, replace: &str) -> Result<Self, regex::Error> {
+        let find = if find.is_empty() {
+            None
+        } else {
+            Some(Regex::new(find)?)
+        };
+        Ok(Self {
+            pattern: pattern.to_string(),
+            find,
+            replace: replace.to_string(),
+        })
+    }
+
+    /// New name for `name` as the `n`th item (1-based).
+    ///
+    /// `{name}` is the name without its extension, `{ext}` the extension
+    /// without the dot and `{n}` the sequence number; `{n:3}` pads it to
+    /// three digits (at most `MAX_NUMBER_WIDTH`). A `.{ext}` on a name
+    /// without an extension is dropped.
+    pub fn apply(&self, name: &str, n: usize) -> String {
+        let (stem, ext) = split_name(name);
+
+        let expanded = if self.pattern.is_empty() {
+            name.to_string()
+        } else {
+            let pattern = if ext.is_empty() {
+                self.pattern.replace(".{ext}", "")
+            } else {
+                self.pattern.clone()
+            };
+            expand(&pattern, stem, ext, n)
+        };
+
+        match self.find {
+            Some(ref re) => re.replace_all(&expanded, self.replace.as_str()).into_owned(),
+            None => expanded,
+        }
+    }
+}
+
+/// Split "photo.jpg" into ("photo", "jpg"). Dotfiles and dotless names
+/// have no extension.
+fn split_name(name: &str) -> (&str, &str) {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, ext),
+        _ => (name, ""),
+    }
+}
+
+/// Replace placeholders in `pattern`; unknown ones are kept as written
+fn expand(pattern: &str, stem: &str, ext: &str, n: usize) -> String {
+    let mut out = String::with_capacity(pattern.len() + stem.len());
+    let mut rest = pattern;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            break;
+        };
+        let placeholder = &rest[open + 1..close];
+        match placeholder {
+            "name" => out.push_str(stem),
+            "ext" => out.push_str(ext),
+            "n" => out.push_str(&n.to_string()),
+            _ => match placeholder.strip_prefix("n:").and_then(|w| w.parse::<usize>().ok()) {
+                Some(width) => {
+                    out.push_str(&format!("{:0width$}", n, width = width.min(MAX_NUMBER_WIDTH)))
+                }
+                None => out.push_str(&rest[open..=close]),
+            },
+        }
+        rest = &rest[close + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// One planned rename
+#[derive(Debug, Clone)]
+pub struct RenameItem {
+    pub from: PathBuf,
+    /// New file name, or why the item will be skipped
+    pub to: Result<String, String>,
+}
+
+impl RenameItem {
+    /// Current file name
+    pub fn name(&self) -> String {
+        self.from
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Whether committing would rename this item
+    pub fn will_rename(&self) -> bool {
+        matches!(self.to, Ok(ref to) if *to != self.name())
+    }
+}
+
+/// Plan renames for `paths`, numbered in order from 1.
+///
+/// Items whose new name is empty, contains characters Windows rejects,
+/// repeats another new name in the same folder or is already taken on disk
+/// get an `Err` and are left alone. A name is not taken if the item holding
+/// it is renamed away in the same batch.
+pub fn plan(paths: &[String], template: &RenameTemplate) -> Vec<RenameItem> {
+    let mut taken: HashSet<String> = HashSet::new();
+
+    let mut items: Vec<RenameItem> = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let from = PathBuf::from(path);
+            let name = from
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let new_name = template.apply(&name, i + 1);
+            let to = check_name(new_name).and_then(|new_name| {
+                if taken.insert(lower_path(&from.with_file_name(&new_name))) {
+                    Ok(new_name)
+                } else {
+                    Err("duplicate name".to_string())
+                }
+            });
+            RenameItem { from, to }
+        })
+        .collect();
+
+    // Rejecting an item keeps its old name taken, which can reject another,
+    // so check until nothing changes
+    loop {
+        let leaving: HashSet<String> = items
+            .iter()
+            .filter(|item| item.will_rename())
+            .map(|item| lower_path(&item.from))
+            .collect();
+        let mut changed = false;
+        for item in items.iter_mut().filter(|item| item.will_rename()) {
+            let Ok(ref new_name) = item.to else {
+                continue;
+            };
+            let target = item.from.with_file_name(new_name);
+            let key = lower_path(&target);
+            // A case-only change renames the file onto itself
+            if key != lower_path(&item.from) && !leaving.contains(&key) && target.exists() {
+                item.to = Err("already exists".to_string());
+                changed = true;
+            }
+        }
+        if !changed {
+            return items;
+        }
+    }
+}
+
+/// NTFS names are case-insensitive, so paths are compared lowercased
+fn lower_path(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+fn check_name(new_name: String) -> Result<String, String> {
+    if new_name.trim().is_empty() {
+        return Err("empty name".to_string());
+    }
+    if let Some(c) = new_name.chars().find(|c| INVALID_NAME_CHARS.contains(c) || c.is_control()) {
+        return Err(format!("invalid character '{}'", c.escape_debug()));
+    }
+    Ok(new_name)
+}
+
+/// Carry out every rename `plan` cleared. Returns the number renamed and
+/// one message per failure.
+///
+/// An item whose new name another item still holds waits for that one to
+/// move; items swapping names go through a temporary name. Nothing is
+/// renamed onto a file that is still there.
+pub fn execute(items: &[RenameItem]) -> (usize, Vec<String>) {
+    let mut renamed = 0;
+    let mut errors = Vec::new();
+
+    // (current path, final path, path as planned for messages)
+    let mut pending: Vec<(PathBuf, PathBuf, &Path)> = items
+        .iter()
+        .filter(|item| item.will_rename())
+        .filter_map(|item| {
+            let new_name = item.to.as_ref().ok()?;
+            Some((item.from.clone(), item.from.with_file_name(new_name), item.from.as_path()))
+        })
+        .collect();
+
+    while !pending.is_empty() {
+        let held: HashSet<String> = pending.iter().map(|(from, ..)| lower_path(from)).collect();
+        let ready = pending.iter().position(|(from, to, _)| {
+            let target = lower_path(to);
+            target == lower_path(from) || !held.contains(&target)
+        });
+
+        let Some(i) = ready else {
+            // Every target is held by another pending item: a cycle. Move
+            // one aside and let the others through.
+            let (from, to, planned) = pending.remove(0);
+            let temp = temp_name(&from);
+            match rename_if_free(&from, &temp) {
+                Ok(()) => pending.push((temp, to, planned)),
+                Err(e) => errors.push(format!("{}: {}", planned.display(), e)),
+            }
+            continue;
+        };
+
+        let (from, to, planned) = pending.remove(i);
+        match rename_if_free(&from, &to) {
+            Ok(()) => renamed += 1,
+            Err(e) => errors.push(format!("{}: {}", planned.display(), e)),
+        }
+    }
+
+    (renamed, errors)
+}
+
+/// Rename `from` to `to` unless `to` exists as another file;
+/// `std::fs::rename` would replace it
+fn rename_if_free(from: &Path, to: &Path) -> std::io::Result<()> {
+    if lower_path(from) != lower_path(to) && to.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    std::fs::rename(from, to)
+}
+
+/// An unused name next to `path` to park it under while names are swapped
+fn temp_name(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (0..)
+        .map(|i| path.with_file_name(format!("{}.emfit-rename-{}", name, i)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory holding empty files named `names`
+    fn dir_with(test: &str, names: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("emfit-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in names {
+            std::fs::write(dir.join(name), name).unwrap();
+        }
+        dir
+    }
+
+    fn paths(dir: &Path, names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| dir.join(n).to_string_lossy().to_string()).collect()
+    }
+
+    #[test]
+    fn names_freed_in_the_same_batch_can_be_taken() {
+        let dir = dir_with("rename-chain", &["x", "1"]);
+        // x -> 1 while 1 -> 2
+        let template = RenameTemplate::new("{n}", "", "").unwrap();
+        let items = plan(&paths(&dir, &["x", "1"]), &template);
+        assert!(items.iter().all(|item| item.to.is_ok()), "{:?}", items);
+
+        let (renamed, errors) = execute(&items);
+        assert_eq!((renamed, errors.len()), (2, 0));
+        assert_eq!(std::fs::read_to_string(dir.join("1")).unwrap(), "x");
+        assert_eq!(std::fs::read_to_string(dir.join("2")).unwrap(), "1");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn swapped_names_go_through_a_temporary_name() {
+        let dir = dir_with("rename-swap", &["1", "2"]);
+        // 2 -> 1 while 1 -> 2
+        let template = RenameTemplate::new("{n}", "", "").unwrap();
+        let items = plan(&paths(&dir, &["2", "1"]), &template);
+        assert!(items.iter().all(|item| item.to.is_ok()), "{:?}", items);
+
+        let (renamed, errors) = execute(&items);
+        assert_eq!((renamed, errors.len()), (2, 0));
+        assert_eq!(std::fs::read_to_string(dir.join("1")).unwrap(), "2");
+        assert_eq!(std::fs::read_to_string(dir.join("2")).unwrap(), "1");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn existing_names_outside_the_batch_are_refused() {
+        let dir = dir_with("rename-taken", &["a", "b"]);
+        let template = RenameTemplate::new("b", "", "").unwrap();
+        let items = plan(&paths(&dir, &["a"]), &template);
+        assert_eq!(items[0].to, Err("already exists".to_string()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn number_padding_is_capped() {
+        let template = RenameTemplate::new("{n:99}", "", "").unwrap();
+        assert_eq!(template.apply("x", 7), "0000000007");
+    }
+}
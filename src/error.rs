@@ -13,6 +13,9 @@ pub enum EmFitError {
     #[error("Volume '{0}' is not an NTFS filesystem")]
     NotNtfsVolume(String),
 
+    #[error("Unsupported filesystem: {0} (only NTFS volumes can be scanned)")]
+    UnsupportedFilesystem(String),
+
     #[error("Failed to get NTFS volume data: {0}")]
     VolumeDataError(String),
 
@@ -25,6 +28,9 @@ pub enum EmFitError {
     #[error("MFT fixup verification failed at record {0}")]
     FixupVerificationFailed(u64),
 
+    #[error("MFT record {0} is past the valid data length (allocated during the scan)")]
+    RecordPastValidData(u64),
+
     #[error("Invalid attribute at offset {0}: {1}")]
     InvalidAttribute(u32, String),
 
@@ -63,15 +69,85 @@ pub enum EmFitError {
 
     #[error("Record {0} references non-existent parent {1}")]
     OrphanedRecord(u64, u64),
+
+    #[error("Index file error: {0}")]
+    IndexError(String),
+
+    #[error("Scan checkpoint error: {0}")]
+    CheckpointError(String),
+
+    #[error("Shadow copy error: {0}")]
+    ShadowCopyError(String),
 }
 
 /// Result type alias for EmFit operations
 pub type Result<T> = std::result::Result<T, EmFitError>;
 
+/// Broad category of an `EmFitError`, for callers that branch on the kind
+/// of failure (e.g. to pick an HTTP status) rather than on the message.
+/// New kinds may be added, so matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Not allowed; usually the process is not elevated
+    AccessDenied,
+    /// The volume, file, path or USN journal does not exist
+    NotFound,
+    /// The path or argument given is not usable
+    InvalidInput,
+    /// Not NTFS (ReFS, FAT, exFAT, or not a filesystem at all)
+    UnsupportedFilesystem,
+    /// On-disk structures, an index or a checkpoint did not parse or verify
+    Corrupt,
+    /// The operation was cancelled
+    Cancelled,
+    /// Reading or writing failed at the I/O level
+    Io,
+    /// A Windows API call failed
+    Windows,
+}
+
 impl EmFitError {
-    /// Create a Windows API error from a raw error code
+    /// Create a Windows API error from a raw error code. ERROR_ACCESS_DENIED
+    /// becomes `AccessDenied` so `kind` reports it as such.
     pub fn from_win32(code: u32, context: &str) -> Self {
-        EmFitError::WindowsError(format!("{}: Win32 error code {}", context, code))
+        const ERROR_ACCESS_DENIED: u32 = 5;
+        match code {
+            ERROR_ACCESS_DENIED => EmFitError::AccessDenied(context.to_string()),
+            _ => EmFitError::WindowsError(format!("{}: Win32 error code {}", context, code)),
+        }
+    }
+
+    /// Category of this error; stable across releases, unlike the messages
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            EmFitError::VolumeOpenError(_, e) | EmFitError::IoError(e) => match e.kind() {
+                std::io::ErrorKind::PermissionDenied => ErrorKind::AccessDenied,
+                std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+                _ => ErrorKind::Io,
+            },
+            EmFitError::NotNtfsVolume(_)
+            | EmFitError::UnsupportedFilesystem(_)
+            | EmFitError::NotNtfsBootSector => ErrorKind::UnsupportedFilesystem,
+            EmFitError::VolumeDataError(_)
+            | EmFitError::InvalidMftRecord(_, _)
+            | EmFitError::FixupVerificationFailed(_)
+            | EmFitError::RecordPastValidData(_)
+            | EmFitError::InvalidAttribute(_, _)
+            | EmFitError::DataRunError(_)
+            | EmFitError::OrphanedRecord(_, _)
+            | EmFitError::IndexError(_)
+            | EmFitError::CheckpointError(_) => ErrorKind::Corrupt,
+            EmFitError::MftReadError(_) | EmFitError::PhysicalDriveError(_) => ErrorKind::Io,
+            EmFitError::UsnJournalNotActive(_) => ErrorKind::NotFound,
+            EmFitError::InvalidPath(_) => ErrorKind::InvalidInput,
+            EmFitError::AccessDenied(_) => ErrorKind::AccessDenied,
+            EmFitError::Cancelled => ErrorKind::Cancelled,
+            EmFitError::UsnJournalError(_)
+            | EmFitError::WindowsError(_)
+            | EmFitError::BufferTooSmall { .. }
+            | EmFitError::ShadowCopyError(_) => ErrorKind::Windows,
+        }
     }
 
     /// Check if this error is recoverable (scan can continue)
@@ -80,6 +156,7 @@ impl EmFitError {
             self,
             EmFitError::InvalidMftRecord(_, _)
                 | EmFitError::FixupVerificationFailed(_)
+                | EmFitError::RecordPastValidData(_)
                 | EmFitError::InvalidAttribute(_, _)
                 | EmFitError::OrphanedRecord(_, _)
         )
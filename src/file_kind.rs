@@ -0,0 +1,142 @@
+//! File Kind Classification
+//!
+//! Buckets lowercase file extensions into broad kinds (images, video,
+//! documents, ...). Coarser than per-extension stats, for views that group
+//! results by what they are rather than by where they live.
+
+use crate::arena::{ExtId, ExtensionTable};
+use serde::{Deserialize, Serialize};
+
+/// Broad category of a file, derived from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FileKind {
+    Folder,
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+    Code,
+    Executable,
+    DiskImage,
+    Other,
+}
+
+impl FileKind {
+    /// Every kind, in display order
+    pub const ALL: [FileKind; 10] = [
+        FileKind::Folder,
+        FileKind::Image,
+        FileKind::Video,
+        FileKind::Audio,
+        FileKind::Document,
+        FileKind::Archive,
+        FileKind::Code,
+        FileKind::Executable,
+        FileKind::DiskImage,
+        FileKind::Other,
+    ];
+
+    /// Classify a lowercase extension (no dot)
+    pub fn from_extension(ext: &str) -> Self {
+        match ext {
+            "jpg" | "jpeg" | "png" | "gif" | "bmp" | "tif" | "tiff" | "webp" | "ico" | "svg"
+            | "psd" | "heic" | "raw" | "cr2" | "nef" | "dng" => FileKind::Image,
+            "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "webm" | "m4v" | "mpg" | "mpeg" => {
+                FileKind::Video
+            }
+            "mp3" | "wav" | "flac" | "ogg" | "aac" | "wma" | "m4a" | "opus" => FileKind::Audio,
+            "pdf" | "doc" | "docx" | "odt" | "rtf" | "txt" | "md" | "xls" | "xlsx" | "ods"
+            | "csv" | "ppt" | "pptx" | "odp" | "epub" => FileKind::Document,
+            "zip" | "rar" | "7z" | "gz" | "tar" | "xz" | "bz2" | "zst" | "cab" => {
+                FileKind::Archive
+            }
+            "rs" | "go" | "c" | "cpp" | "cc" | "h" | "hpp" | "cs" | "java" | "kt" | "py"
+            | "js" | "jsx" | "ts" | "tsx" | "html" | "htm" | "css" | "scss" | "json" | "xml"
+            | "yaml" | "yml" | "toml" | "sql" | "sh" | "ps1" => FileKind::Code,
+            "exe" | "dll" | "sys" | "drv" | "msi" | "com" | "scr" | "bat" | "cmd" | "ocx" => {
+                FileKind::Executable
+            }
+            "iso" | "img" | "vhd" | "vhdx" | "vmdk" | "vdi" | "qcow2" => FileKind::DiskImage,
+            _ => FileKind::Other,
+        }
+    }
+
+    /// Plural display label ("Images", "Documents", ...)
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileKind::Folder => "Folders",
+            FileKind::Image => "Images",
+            FileKind::Video => "Video",
+            FileKind::Audio => "Audio",
+            FileKind::Document => "Documents",
+            FileKind::Archive => "Archives",
+            FileKind::Code => "Code",
+            FileKind::Executable => "Executables",
+            FileKind::DiskImage => "Disk Images",
+            FileKind::Other => "Other",
+        }
+    }
+}
+
+/// Item count and summed file size per kind, for a "what's on this disk"
+/// overview. Folders are counted but add no size; their files already do.
+#[derive(Debug, Clone, Default)]
+pub struct KindSummary {
+    /// (count, size) indexed by `FileKind as usize`
+    totals: [(u64, u64); FileKind::ALL.len()],
+}
+
+impl KindSummary {
+    /// Tally entries given as (is_directory, extension id, file size).
+    /// Each distinct extension is classified once.
+    pub fn from_entries(
+        entries: impl Iterator<Item = (bool, ExtId, u64)>,
+        extensions: &ExtensionTable,
+    ) -> Self {
+        let ext_kinds: Vec<FileKind> = (0..extensions.len())
+            .map(|id| FileKind::from_extension(extensions.get(id as ExtId)))
+            .collect();
+        let mut summary = Self::default();
+        for (is_directory, ext, size) in entries {
+            let kind = if is_directory {
+                FileKind::Folder
+            } else {
+                ext_kinds.get(ext as usize).copied().unwrap_or(FileKind::Other)
+            };
+            summary.add(kind, if is_directory { 0 } else { size });
+        }
+        summary
+    }
+
+    /// Count one item of `kind`
+    pub fn add(&mut self, kind: FileKind, size: u64) {
+        let (count, total) = &mut self.totals[kind as usize];
+        *count += 1;
+        *total += size;
+    }
+
+    /// Kinds with at least one item as (kind, count, size), largest first
+    pub fn rows(&self) -> Vec<(FileKind, u64, u64)> {
+        let mut rows: Vec<(FileKind, u64, u64)> = FileKind::ALL
+            .iter()
+            .map(|&kind| {
+                let (count, size) = self.totals[kind as usize];
+                (kind, count, size)
+            })
+            .filter(|&(_, count, _)| count > 0)
+            .collect();
+        rows.sort_by_key(|&(_, _, size)| std::cmp::Reverse(size));
+        rows
+    }
+
+    /// Summed size of all files
+    pub fn total_size(&self) -> u64 {
+        self.totals.iter().map(|&(_, size)| size).sum()
+    }
+
+    /// Number of items of all kinds
+    pub fn total_count(&self) -> u64 {
+        self.totals.iter().map(|&(count, _)| count).sum()
+    }
+}
@@ -11,12 +11,20 @@
 //! which allows multiple entries for the same file with different parents.
 
 use crate::logging;
-use crate::ntfs::{FileEntry, UsnEntry};
+use crate::SizeBasis;
+use crate::ntfs::{ChangeEvent, ChangeReason, FileEntry, ReparseTarget, Storage, UsnEntry};
 use crate::ntfs::mft::{extract_parent_info, extract_parent_info_debug};
+use crate::ntfs::structs::{file_attributes, name_anomalies};
 use crate::ntfs::physical::MftRecordFetcher;
-use crate::ntfs::winapi::{get_ntfs_file_record, open_volume, open_volume_for_file_id, SafeHandle};
-use dashmap::DashMap;
+use crate::ntfs::winapi::{
+    get_ntfs_file_record, open_volume, open_volume_for_file_id, query_usn_journal, SafeHandle,
+};
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // ============================================================================
 // Node Key - Composite key for hard link support
@@ -27,7 +35,7 @@ use std::sync::Arc;
 /// This allows multiple entries for the same MFT record with different parents,
 /// which is necessary for proper hard link support. Each hard link appears as
 /// a separate entry in the file tree with its own parent.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub struct NodeKey {
     /// MFT record number
     pub record_number: u64,
@@ -46,12 +54,88 @@ impl NodeKey {
     }
 }
 
+/// MFT records below this number are reserved for NTFS metafiles
+/// ($MFT, $LogFile, $Bitmap, $Secure, $Extend, ...)
+pub const FIRST_USER_RECORD: u64 = 27;
+
+/// Check if a record number is a reserved NTFS metafile (the root directory is not)
+pub fn is_metafile_record(record_number: u64) -> bool {
+    record_number < FIRST_USER_RECORD && record_number != NodeKey::root().record_number
+}
+
+/// Alternate data stream count of an MFT entry, saturated to fit a node
+fn stream_count(entry: &FileEntry) -> u16 {
+    entry.alternate_streams.len().min(u16::MAX as usize) as u16
+}
+
+/// Anomaly flags visible in an already decoded name (control characters;
+/// a lone surrogate has become U+FFFD and can no longer be told apart)
+fn decoded_name_anomalies(name: &str) -> u8 {
+    if name.chars().any(char::is_control) {
+        name_anomalies::CONTROL_CHAR
+    } else {
+        0
+    }
+}
+
 // ============================================================================
 // Tree Node
 // ============================================================================
 
+/// Largest MFT record size; `$DATA` bigger than this is never resident
+const MAX_RESIDENT_SIZE: u64 = 4096;
+
+/// Cluster size assumed for trees without volume info
+pub const DEFAULT_BYTES_PER_CLUSTER: u32 = 4096;
+
+/// Which timestamp a date column shows and sorts by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateField {
+    #[default]
+    Modified,
+    Created,
+    Accessed,
+}
+
+impl DateField {
+    /// Every field, in menu order
+    pub const ALL: [DateField; 3] = [DateField::Modified, DateField::Created, DateField::Accessed];
+
+    /// Column header ("Date Modified", ...)
+    pub fn label(self) -> &'static str {
+        match self {
+            DateField::Modified => "Date Modified",
+            DateField::Created => "Date Created",
+            DateField::Accessed => "Date Accessed",
+        }
+    }
+
+    /// The field after this one, wrapping around
+    pub fn next(self) -> Self {
+        match self {
+            DateField::Modified => DateField::Created,
+            DateField::Created => DateField::Accessed,
+            DateField::Accessed => DateField::Modified,
+        }
+    }
+}
+
+/// Order of the nodes `FileTree::children` returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChildSort {
+    /// Whatever order the directory holds them in
+    #[default]
+    Unsorted,
+    /// By name, case-insensitive
+    Name,
+    /// Largest first on the given basis, folders by their subtree total
+    Size(SizeBasis),
+    /// Newest first by the given timestamp
+    Date(DateField),
+}
+
 /// A node in the file tree (file or directory)
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TreeNode {
     /// MFT record number
     pub record_number: u64,
@@ -73,7 +157,22 @@ pub struct TreeNode {
     pub creation_time: u64,
     /// Modification time (FILETIME)
     pub modification_time: u64,
+    /// Last access time (FILETIME, 0 if unknown)
+    #[serde(default)]
+    pub access_time: u64,
+    /// $FILE_NAME creation time (FILETIME, 0 if unknown)
+    #[serde(default)]
+    pub fn_creation_time: u64,
+    /// $FILE_NAME modification time (FILETIME, 0 if unknown)
+    #[serde(default)]
+    pub fn_modification_time: u64,
+    /// $STANDARD_INFORMATION times were zeroed; `creation_time` and
+    /// `modification_time` hold the $FILE_NAME times instead
+    #[serde(default)]
+    pub si_zeroed: bool,
     /// Children (for directories) - NodeKeys of children
+    /// Not persisted; rebuilt from parent references when an index is loaded
+    #[serde(skip)]
     pub children: Vec<NodeKey>,
     /// Aggregated size (self + all descendants)
     pub total_size: u64,
@@ -83,9 +182,90 @@ pub struct TreeNode {
     pub file_count: u64,
     /// Number of directories in subtree (including self if directory)
     pub dir_count: u64,
+    /// NTFS metafile ($MFT, $LogFile, ...) or a file inside $Extend
+    #[serde(default)]
+    pub is_system_metafile: bool,
+    /// Number of named $DATA streams (alternate data streams), saturating
+    #[serde(default)]
+    pub alternate_stream_count: u16,
+    /// Junction or symbolic link target, when the MFT scan could read it
+    #[serde(default)]
+    pub reparse_target: Option<Box<ReparseTarget>>,
+    /// `name_anomalies` flags (lone surrogates, control characters)
+    #[serde(default)]
+    pub name_anomalies: u8,
+    /// Stored UTF-16 of an anomalous name, kept only when the builder was
+    /// asked to (`TreeBuilder::set_keep_raw_names`)
+    #[serde(default)]
+    pub raw_name: Option<Box<[u16]>>,
+    /// Deleted file still described by its MFT record (scanned with
+    /// `ScanConfig::include_deleted`). Left out of its ancestors' totals
+    /// and of the tree statistics.
+    #[serde(default)]
+    pub is_deleted: bool,
+    /// Resident in the MFT record or in clusters (with fragment count)
+    #[serde(default)]
+    pub storage: Storage,
+    /// Sequence number the parent record had when this name was made
+    /// (0 if unknown). Only checked for deleted files, whose parent record
+    /// may since hold an unrelated file.
+    #[serde(default)]
+    pub parent_sequence_number: u16,
 }
 
 impl TreeNode {
+    /// Check if this node refers to its own directory: a `.`/`..` index
+    /// entry, or a record that is its own parent (only the root, legitimately).
+    /// Such nodes are never linked as children and never listed in results.
+    pub fn is_self_reference(&self) -> bool {
+        self.name == "." || self.name == ".." || self.record_number == self.parent_record_number
+    }
+
+    /// Check if this node belongs in listings and search results: it has a
+    /// name (incomplete MFT records don't) and is not a self-reference
+    pub fn is_listed(&self) -> bool {
+        !self.name.is_empty() && !self.is_self_reference()
+    }
+
+    /// Bytes lost to cluster rounding at the end of the file's data.
+    ///
+    /// Non-resident data counts allocated minus logical size; compressed and
+    /// sparse files (allocated below size) count 0. Resident data lives in
+    /// the MFT record and wastes no cluster. Data too large to be resident
+    /// but with no allocation recorded is rounded up to a whole cluster.
+    pub fn slack_bytes(&self, bytes_per_cluster: u32) -> u64 {
+        if self.is_directory {
+            return 0;
+        }
+        if self.allocated_size > 0 {
+            return self.allocated_size.saturating_sub(self.file_size);
+        }
+        if self.file_size <= MAX_RESIDENT_SIZE || bytes_per_cluster == 0 {
+            return 0;
+        }
+        self.file_size.next_multiple_of(bytes_per_cluster as u64) - self.file_size
+    }
+
+    /// The timestamp `field` selects (FILETIME, 0 if unknown)
+    pub fn time(&self, field: DateField) -> u64 {
+        match field {
+            DateField::Modified => self.modification_time,
+            DateField::Created => self.creation_time,
+            DateField::Accessed => self.access_time,
+        }
+    }
+
+    /// Lowercase extension without the dot ("" for directories and dotless names)
+    pub fn extension(&self) -> String {
+        if self.is_directory {
+            return String::new();
+        }
+        match self.name.rfind('.') {
+            Some(pos) if pos > 0 => self.name[pos + 1..].to_lowercase(),
+            _ => String::new(),
+        }
+    }
+
     /// Create from FileEntry
     pub fn from_file_entry(entry: &FileEntry) -> Self {
         Self {
@@ -100,11 +280,23 @@ impl TreeNode {
             is_directory: entry.is_directory,
             creation_time: entry.creation_time,
             modification_time: entry.modification_time,
+            access_time: entry.access_time,
+            fn_creation_time: entry.fn_creation_time,
+            fn_modification_time: entry.fn_modification_time,
+            si_zeroed: entry.si_zeroed,
             children: Vec::new(),
             total_size: entry.file_size,
             total_allocated: entry.allocated_size,
             file_count: if entry.is_directory { 0 } else { 1 },
             dir_count: if entry.is_directory { 1 } else { 0 },
+            is_system_metafile: is_metafile_record(entry.record_number),
+            alternate_stream_count: stream_count(entry),
+            reparse_target: entry.reparse_target.clone().map(Box::new),
+            name_anomalies: entry.name_anomalies,
+            raw_name: entry.raw_name.clone().map(Vec::into_boxed_slice),
+            is_deleted: entry.is_deleted,
+            storage: entry.storage,
+            parent_sequence_number: entry.parent_sequence_number,
         }
     }
 
@@ -121,11 +313,24 @@ impl TreeNode {
             is_directory: entry.is_directory,
             creation_time: 0,
             modification_time: 0,
+            access_time: 0,
+            fn_creation_time: 0,
+            fn_modification_time: 0,
+            si_zeroed: false,
             children: Vec::new(),
             total_size: 0,
             total_allocated: 0,
             file_count: if entry.is_directory { 0 } else { 1 },
             dir_count: if entry.is_directory { 1 } else { 0 },
+            is_system_metafile: is_metafile_record(entry.record_number),
+            alternate_stream_count: 0,
+            reparse_target: None,
+            // The journal hands out decoded names, so only control characters show
+            name_anomalies: decoded_name_anomalies(&entry.name),
+            raw_name: None,
+            is_deleted: false,
+            storage: Storage::Unknown,
+            parent_sequence_number: 0,
         }
     }
 
@@ -143,11 +348,94 @@ impl TreeNode {
         if entry.modification_time != 0 {
             self.modification_time = entry.modification_time;
         }
+        if entry.access_time != 0 {
+            self.access_time = entry.access_time;
+        }
+        self.fn_creation_time = entry.fn_creation_time;
+        self.fn_modification_time = entry.fn_modification_time;
+        self.si_zeroed = entry.si_zeroed;
         // Update file_reference_number if MFT provides a valid one
         // (MFT's FRN includes sequence number which is more accurate)
         if entry.file_reference_number != 0 {
             self.file_reference_number = entry.file_reference_number;
         }
+        self.alternate_stream_count = stream_count(entry);
+        self.storage = entry.storage;
+        self.reparse_target = entry.reparse_target.clone().map(Box::new);
+        if self.name == entry.name {
+            self.name_anomalies = entry.name_anomalies;
+            self.raw_name = entry.raw_name.clone().map(Vec::into_boxed_slice);
+        }
+    }
+
+    /// Check if file is hidden
+    pub fn is_hidden(&self) -> bool {
+        (self.attributes & file_attributes::HIDDEN) != 0
+    }
+
+    /// Check if file is system
+    pub fn is_system(&self) -> bool {
+        (self.attributes & file_attributes::SYSTEM) != 0
+    }
+
+    /// Check if file is compressed
+    pub fn is_compressed(&self) -> bool {
+        (self.attributes & file_attributes::COMPRESSED) != 0
+    }
+
+    /// Check if file is sparse
+    pub fn is_sparse(&self) -> bool {
+        (self.attributes & file_attributes::SPARSE_FILE) != 0
+    }
+
+    /// Check if this is a reparse point (symlink, junction, etc)
+    pub fn is_reparse_point(&self) -> bool {
+        (self.attributes & file_attributes::REPARSE_POINT) != 0
+    }
+
+    /// Check if file is EFS-encrypted. Its clusters hold ciphertext, so
+    /// anything reading contents by raw cluster access must skip it.
+    pub fn is_encrypted(&self) -> bool {
+        (self.attributes & file_attributes::ENCRYPTED) != 0
+    }
+
+    /// Name with anomalies escaped as `\u{XXXX}` (see `name_anomalies::escape`).
+    /// Lone surrogates can only be shown exactly when `raw_name` was kept.
+    pub fn escaped_name(&self) -> String {
+        match self.raw_name {
+            Some(ref raw) => name_anomalies::escape(raw),
+            None => name_anomalies::escape(&self.name.encode_utf16().collect::<Vec<_>>()),
+        }
+    }
+
+    /// Junction or symbolic link whose target is on another volume or a
+    /// network share. `drive` is the volume this node was scanned from.
+    pub fn is_off_volume_link(&self, drive: char) -> bool {
+        self.reparse_target
+            .as_ref()
+            .and_then(|target| target.is_off_volume(drive))
+            .unwrap_or(false)
+    }
+
+    /// Human-readable description of a metafile, for labelling in listings
+    pub fn metafile_label(&self) -> Option<&'static str> {
+        if !self.is_system_metafile {
+            return None;
+        }
+        Some(match self.record_number {
+            0 => "NTFS Master File Table",
+            1 => "NTFS MFT Mirror",
+            2 => "NTFS Journal",
+            3 => "NTFS Volume Info",
+            4 => "NTFS Attribute Definitions",
+            6 => "NTFS Cluster Bitmap",
+            7 => "NTFS Boot Sector",
+            8 => "NTFS Bad Clusters",
+            9 => "NTFS Security Descriptors",
+            10 => "NTFS Upcase Table",
+            11 => "NTFS Extensions",
+            _ => "NTFS Metafile",
+        })
     }
 
     /// Get the NodeKey for this node
@@ -156,6 +444,29 @@ impl TreeNode {
         NodeKey::new(self.record_number, self.parent_record_number)
     }
 
+    /// Heap memory owned by this node (name, children, link target, raw name)
+    fn heap_bytes(&self) -> usize {
+        self.name.capacity()
+            + self.children.capacity() * std::mem::size_of::<NodeKey>()
+            + self.reparse_target.as_ref().map_or(0, |target| {
+                std::mem::size_of::<ReparseTarget>() + target.path.capacity()
+            })
+            + self.raw_name.as_ref().map_or(0, |raw| raw.len() * 2)
+    }
+
+    /// All nodes an MFT entry produces: the primary name plus one per extra hard link
+    pub fn nodes_from_file_entry(entry: &FileEntry) -> Vec<TreeNode> {
+        let mut nodes = vec![TreeNode::from_file_entry(entry)];
+        for link in &entry.hard_links {
+            if link.parent_record_number == entry.parent_record_number
+                && link.name == entry.name {
+                continue;
+            }
+            nodes.push(TreeNode::from_hard_link(entry, link));
+        }
+        nodes
+    }
+
     /// Create a TreeNode for a hard link (same file, different parent)
     pub fn from_hard_link(entry: &FileEntry, link: &crate::ntfs::HardLink) -> Self {
         Self {
@@ -170,11 +481,57 @@ impl TreeNode {
             is_directory: entry.is_directory,
             creation_time: entry.creation_time,
             modification_time: entry.modification_time,
+            access_time: entry.access_time,
+            fn_creation_time: entry.fn_creation_time,
+            fn_modification_time: entry.fn_modification_time,
+            si_zeroed: entry.si_zeroed,
             children: Vec::new(),
             total_size: entry.file_size,
             total_allocated: entry.allocated_size,
             file_count: if entry.is_directory { 0 } else { 1 },
             dir_count: if entry.is_directory { 1 } else { 0 },
+            is_system_metafile: is_metafile_record(entry.record_number),
+            alternate_stream_count: stream_count(entry),
+            reparse_target: entry.reparse_target.clone().map(Box::new),
+            // Only the primary name's code units are kept
+            name_anomalies: if link.name == entry.name {
+                entry.name_anomalies
+            } else {
+                decoded_name_anomalies(&link.name)
+            },
+            raw_name: if link.name == entry.name {
+                entry.raw_name.clone().map(Vec::into_boxed_slice)
+            } else {
+                None
+            },
+            is_deleted: entry.is_deleted,
+            storage: entry.storage,
+            parent_sequence_number: link.parent_sequence_number,
+        }
+    }
+}
+
+/// The aggregates a node adds to every directory above it
+#[derive(Debug, Clone, Copy)]
+struct SubtreeTotals {
+    size: u64,
+    allocated: u64,
+    files: u64,
+    dirs: u64,
+}
+
+impl SubtreeTotals {
+    /// A node's own totals; deleted nodes add nothing to the live
+    /// directories above them
+    fn of(node: &TreeNode) -> Self {
+        if node.is_deleted {
+            return Self { size: 0, allocated: 0, files: 0, dirs: 0 };
+        }
+        Self {
+            size: node.total_size,
+            allocated: node.total_allocated,
+            files: node.file_count,
+            dirs: node.dir_count,
         }
     }
 }
@@ -202,10 +559,135 @@ pub struct FileTree {
     pub stats: TreeStats,
     /// Bytes per MFT record (for on-demand parent resolution)
     bytes_per_record: u32,
+    /// Cluster size of the volume (for slack computation)
+    bytes_per_cluster: u32,
     /// MFT record fetcher for on-demand parent resolution (replaces FSCTL_GET_NTFS_FILE_RECORD)
     record_fetcher: Option<Arc<MftRecordFetcher>>,
+    /// record_number -> key before a rename, between the RENAME_OLD_NAME
+    /// and RENAME_NEW_NAME events of a live rename
+    pending_renames: DashMap<u64, NodeKey>,
+    /// Maximum number of parent levels path building will climb
+    max_parent_depth: u32,
+    /// Records whose on-demand fetch failed; never fetched again
+    unresolvable_records: DashSet<u64>,
+    /// Wall-clock time the scan that produced this tree started
+    pub scanned_at: Option<SystemTime>,
+    /// USN journal position when that scan started
+    pub usn_cursor: Option<UsnCursor>,
+}
+
+/// Position in a volume's USN change journal. Changes recorded after it
+/// are not reflected in a tree carrying it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsnCursor {
+    pub journal_id: u64,
+    pub next_usn: i64,
+}
+
+/// How a volume's USN journal compares with a tree's `UsnCursor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalStatus {
+    /// Nothing was recorded since the cursor
+    Unchanged,
+    /// The journal still holds every change since the cursor, so an
+    /// incremental refresh can catch up
+    Changed,
+    /// The journal was recreated or has dropped records past the cursor;
+    /// only a full rescan brings the tree up to date
+    Reset,
+    /// No cursor, or the journal could not be queried
+    Unknown,
+}
+
+impl JournalStatus {
+    /// The status that matters most across several drives: a reset over
+    /// changes over unknown over unchanged
+    pub fn worst(statuses: impl IntoIterator<Item = JournalStatus>) -> JournalStatus {
+        let severity = |status: &JournalStatus| match status {
+            JournalStatus::Unchanged => 0,
+            JournalStatus::Unknown => 1,
+            JournalStatus::Changed => 2,
+            JournalStatus::Reset => 3,
+        };
+        statuses.into_iter().max_by_key(severity).unwrap_or(JournalStatus::Unknown)
+    }
+
+    /// Status bar note when the volume has moved on since the scan
+    pub fn stale_note(&self) -> Option<&'static str> {
+        match self {
+            JournalStatus::Changed => Some("changed since"),
+            JournalStatus::Reset => Some("journal reset since"),
+            JournalStatus::Unchanged | JournalStatus::Unknown => None,
+        }
+    }
+}
+
+/// How `FileTree::build_path_styled` formats a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathStyle {
+    /// Component separator, `\` by default
+    pub separator: char,
+    /// Start with `\\?\` so Win32 APIs accept paths past MAX_PATH. Such
+    /// paths take no `/`, so this only applies with the `\` separator.
+    pub long_path_prefix: bool,
+    /// End with the entry's own name; `false` gives its parent directory
+    pub include_name: bool,
+}
+
+impl Default for PathStyle {
+    fn default() -> Self {
+        Self {
+            separator: '\\',
+            long_path_prefix: false,
+            include_name: true,
+        }
+    }
+}
+
+impl PathStyle {
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn with_long_path_prefix(mut self, long_path_prefix: bool) -> Self {
+        self.long_path_prefix = long_path_prefix;
+        self
+    }
+
+    pub fn with_name(mut self, include_name: bool) -> Self {
+        self.include_name = include_name;
+        self
+    }
+
+    /// Reformat a path as built by `build_path` (`C:\dir\name`)
+    pub fn apply(&self, path: &str) -> String {
+        let path = if self.include_name {
+            path
+        } else {
+            match path.rsplit_once('\\') {
+                // Keep the root's backslash: "C:\file" -> "C:\"
+                Some((parent, _)) if parent.ends_with(':') => &path[..parent.len() + 1],
+                Some((parent, _)) => parent,
+                None => path,
+            }
+        };
+
+        if self.separator != '\\' {
+            return path.replace('\\', self.separator.encode_utf8(&mut [0; 4]));
+        }
+        let mut out = String::with_capacity(path.len() + 4);
+        if self.long_path_prefix {
+            out.push_str("\\\\?\\");
+        }
+        out.push_str(path);
+        out
+    }
 }
 
+/// Default limit on parent levels climbed when building a path
+pub const DEFAULT_MAX_PARENT_DEPTH: u32 = 1024;
+
 /// Statistics about the tree
 #[derive(Debug, Clone, Default)]
 pub struct TreeStats {
@@ -214,7 +696,60 @@ pub struct TreeStats {
     pub total_size: u64,
     pub total_allocated: u64,
     pub orphaned_files: u64,
+    /// Deleted files and folders kept by `ScanConfig::include_deleted`;
+    /// not part of any other count or size
+    pub deleted_files: u64,
     pub max_depth: u32,
+    /// Number of data runs the $MFT itself is split into (0 if unknown,
+    /// e.g. for trees loaded from an index). High values slow every scan.
+    pub mft_fragment_count: u64,
+    /// `FileTree::memory_footprint` when the stats were taken
+    /// (`MultiVolumeScanner::drive_stats`; 0 if not measured)
+    pub memory_bytes: u64,
+}
+
+impl TreeStats {
+    /// Allocated minus logical size: cluster slack, less whatever
+    /// compression and sparse files save (so it can be negative)
+    pub fn slack(&self) -> i64 {
+        self.total_allocated as i64 - self.total_size as i64
+    }
+
+    /// Field-wise sum, for a combined column next to per-drive stats.
+    /// `max_depth` is the deepest of the two; fragment counts add up.
+    pub fn combined(&self, other: &TreeStats) -> TreeStats {
+        TreeStats {
+            total_files: self.total_files + other.total_files,
+            total_directories: self.total_directories + other.total_directories,
+            total_size: self.total_size + other.total_size,
+            total_allocated: self.total_allocated + other.total_allocated,
+            orphaned_files: self.orphaned_files + other.orphaned_files,
+            deleted_files: self.deleted_files + other.deleted_files,
+            max_depth: self.max_depth.max(other.max_depth),
+            mft_fragment_count: self.mft_fragment_count + other.mft_fragment_count,
+            memory_bytes: self.memory_bytes + other.memory_bytes,
+        }
+    }
+
+    /// Labelled, formatted values for the per-drive statistics views
+    pub fn summary_rows(&self) -> [(&'static str, String); 6] {
+        [
+            ("Files", self.total_files.to_string()),
+            ("Folders", self.total_directories.to_string()),
+            ("Size", crate::format_size(self.total_size)),
+            ("Allocated", crate::format_size(self.total_allocated)),
+            ("Orphaned", self.orphaned_files.to_string()),
+            ("Memory", crate::format_size(self.memory_bytes)),
+        ]
+    }
+
+    /// Slack as a percentage of allocated space
+    pub fn slack_percent(&self) -> f64 {
+        if self.total_allocated == 0 {
+            return 0.0;
+        }
+        self.slack() as f64 * 100.0 / self.total_allocated as f64
+    }
 }
 
 impl FileTree {
@@ -228,7 +763,13 @@ impl FileTree {
             root_record: 5, // NTFS root is always record 5
             stats: TreeStats::default(),
             bytes_per_record: 1024, // Default MFT record size
+            bytes_per_cluster: DEFAULT_BYTES_PER_CLUSTER,
             record_fetcher: None,
+            pending_renames: DashMap::new(),
+            max_parent_depth: DEFAULT_MAX_PARENT_DEPTH,
+            unresolvable_records: DashSet::new(),
+            scanned_at: None,
+            usn_cursor: None,
         }
     }
 
@@ -242,7 +783,39 @@ impl FileTree {
             root_record: 5,
             stats: TreeStats::default(),
             bytes_per_record,
+            bytes_per_cluster: DEFAULT_BYTES_PER_CLUSTER,
             record_fetcher: None,
+            pending_renames: DashMap::new(),
+            max_parent_depth: DEFAULT_MAX_PARENT_DEPTH,
+            unresolvable_records: DashSet::new(),
+            scanned_at: None,
+            usn_cursor: None,
+        }
+    }
+
+    /// Time since the scan that produced this tree started, if known
+    pub fn scan_age(&self) -> Option<Duration> {
+        self.scanned_at.and_then(|t| t.elapsed().ok())
+    }
+
+    /// Compare the tree's USN cursor with the live journal on its drive
+    pub fn journal_status(&self) -> JournalStatus {
+        let Some(cursor) = self.usn_cursor else {
+            return JournalStatus::Unknown;
+        };
+        let journal = match open_volume(self.drive_letter).and_then(|h| query_usn_journal(&h)) {
+            Ok(journal) => journal,
+            Err(_) => return JournalStatus::Unknown,
+        };
+
+        if journal.usn_journal_id != cursor.journal_id
+            || (journal.lowest_valid_usn as i64) > cursor.next_usn
+        {
+            JournalStatus::Reset
+        } else if journal.next_usn as i64 > cursor.next_usn {
+            JournalStatus::Changed
+        } else {
+            JournalStatus::Unchanged
         }
     }
 
@@ -251,26 +824,85 @@ impl FileTree {
         self.record_fetcher = Some(fetcher);
     }
 
+    /// Stop on-demand parent resolution, e.g. once the volume it reads from
+    /// (a shadow copy) is gone
+    pub fn clear_record_fetcher(&mut self) {
+        self.record_fetcher = None;
+    }
+
+    /// Limit how many parent levels path building climbs before giving up.
+    /// Bounds parent cycles and on-demand record fetches on corrupt volumes.
+    pub fn set_max_parent_depth(&mut self, depth: u32) {
+        self.max_parent_depth = depth.max(1);
+    }
+
     /// Set bytes per record (for on-demand parent resolution)
     pub fn set_bytes_per_record(&mut self, bytes_per_record: u32) {
         self.bytes_per_record = bytes_per_record;
     }
 
+    /// Set the volume's cluster size (for slack computation)
+    pub fn set_bytes_per_cluster(&mut self, bytes_per_cluster: u32) {
+        self.bytes_per_cluster = bytes_per_cluster;
+    }
+
+    /// Cluster size of the volume
+    pub fn bytes_per_cluster(&self) -> u32 {
+        self.bytes_per_cluster
+    }
+
+    /// Sum of `TreeNode::slack_bytes` over all live files: space lost to
+    /// partially filled last clusters. A hard-linked file's data is counted
+    /// once, not once per name.
+    pub fn total_slack(&self) -> u64 {
+        self.record_index
+            .iter()
+            .filter_map(|entry| {
+                entry.value().iter().find_map(|key| {
+                    self.nodes
+                        .get(key)
+                        .filter(|n| !n.is_deleted)
+                        .map(|n| n.slack_bytes(self.bytes_per_cluster))
+                })
+            })
+            .sum()
+    }
+
     /// Insert a node into the tree
     /// Returns true if the node was inserted, false if a duplicate (same parent+name) already exists
+    ///
+    /// A file hard-linked twice into the same directory keeps only the first
+    /// name inserted: both names share one `NodeKey` (record, parent), so the
+    /// tree can hold just one of them. Links in different directories are
+    /// all kept.
     pub fn insert(&self, node: TreeNode) -> bool {
         let key = node.key();
-        let name_key = (key.parent_record_number, node.name.to_lowercase());
 
-        // Check if a file with the same parent and name already exists (like Everything does)
-        // This deduplicates entries that have the same path but different record numbers
-        // (e.g., from multiple $FILE_NAME attributes with different namespaces)
-        if self.name_index.contains_key(&name_key) {
-            return false; // Duplicate path - skip
+        // Only the root may point at itself
+        if node.is_self_reference() && key != NodeKey::root() {
+            return false;
         }
 
-        // Insert into name index first
-        self.name_index.insert(name_key, key);
+        let name_key = (key.parent_record_number, node.name.to_lowercase());
+
+        // Deleted files never claim their path, so a live file of the same
+        // name is listed next to them, also one created later
+        if !node.is_deleted {
+            // Check if a file with the same parent and name already exists (like Everything does)
+            // This deduplicates entries that have the same path but different record numbers
+            // (e.g., from multiple $FILE_NAME attributes with different namespaces)
+            if self.name_index.contains_key(&name_key) {
+                return false; // Duplicate path - skip
+            }
+
+            // A second name of the same file in the same directory
+            if self.nodes.get(&key).is_some_and(|n| !n.is_deleted) {
+                return false;
+            }
+
+            // Insert into name index first
+            self.name_index.insert(name_key, key);
+        }
 
         // Insert into main map
         self.nodes.insert(key, node);
@@ -282,7 +914,11 @@ impl FileTree {
             .push(key);
 
         // Update parent's children list (find parent by record_number)
-        // We look up any node with the parent's record number
+        // We look up any node with the parent's record number. Record 0 is
+        // $MFT, never a folder: parent 0 marks an orphaned deleted file.
+        if key.record_number == key.parent_record_number || key.parent_record_number == 0 {
+            return true;
+        }
         if let Some(parent_keys) = self.record_index.get(&key.parent_record_number) {
             for parent_key in parent_keys.iter() {
                 if let Some(mut parent_node) = self.nodes.get_mut(parent_key) {
@@ -297,11 +933,250 @@ impl FileTree {
         true
     }
 
+    /// Apply a live USN change event. Returns true if the tree was modified.
+    ///
+    /// The tree stores no paths: they are rebuilt by walking parent records,
+    /// and children refer to their parent by record number. Renaming or
+    /// moving a directory therefore only re-keys the directory's own node,
+    /// and `build_path` reflects the new name for every descendant at once.
+    /// Paths copied out of the tree (the UIs' `cached_path`) are not
+    /// updated and must be rebuilt by whoever holds them.
+    ///
+    /// The aggregated totals of the directories above a created, deleted or
+    /// moved node are adjusted by that node's own totals, so they match what
+    /// `calculate_sizes` would compute without walking the whole tree.
+    pub fn apply_change(&self, event: &ChangeEvent) -> bool {
+        match event.reason {
+            ChangeReason::Created => {
+                // NTFS reused the record of a deleted file kept in the tree
+                let reused = self
+                    .record_index
+                    .get(&event.record_number)
+                    .is_some_and(|keys| keys.iter().all(|k| self.nodes.get(k).is_some_and(|n| n.is_deleted)));
+                if reused {
+                    self.remove_record(event.record_number);
+                }
+                let is_directory = (event.attributes & file_attributes::DIRECTORY) != 0;
+                let node = TreeNode {
+                    record_number: event.record_number,
+                    parent_record_number: event.parent_record_number,
+                    name: event.name.clone(),
+                    attributes: event.attributes,
+                    is_directory,
+                    modification_time: event.timestamp,
+                    name_anomalies: decoded_name_anomalies(&event.name),
+                    file_count: u64::from(!is_directory),
+                    dir_count: u64::from(is_directory),
+                    ..Default::default()
+                };
+                let totals = SubtreeTotals::of(&node);
+                let inserted = self.insert(node);
+                if inserted {
+                    self.adjust_ancestor_totals(event.parent_record_number, totals, true);
+                }
+                inserted
+            }
+            ChangeReason::Deleted => {
+                let keys = self
+                    .record_index
+                    .get(&event.record_number)
+                    .map(|keys| keys.clone())
+                    .unwrap_or_default();
+                for key in &keys {
+                    let totals = self.nodes.get(key).map(|node| SubtreeTotals::of(&node));
+                    if let Some(totals) = totals {
+                        self.adjust_ancestor_totals(key.parent_record_number, totals, false);
+                    }
+                }
+                self.remove_record(event.record_number) > 0
+            }
+            ChangeReason::RenamedFrom => {
+                let key = NodeKey::new(event.record_number, event.parent_record_number);
+                if !self.nodes.contains_key(&key) {
+                    return false;
+                }
+                self.pending_renames.insert(event.record_number, key);
+                false
+            }
+            ChangeReason::RenamedTo => {
+                let old_key = self
+                    .pending_renames
+                    .remove(&event.record_number)
+                    .map(|(_, key)| key)
+                    .or_else(|| {
+                        // Old name missed (e.g. monitoring started mid-rename):
+                        // only safe to guess when there are no hard links
+                        let keys = self.record_index.get(&event.record_number)?;
+                        if keys.len() == 1 { keys.first().copied() } else { None }
+                    });
+                match old_key {
+                    Some(old_key) => {
+                        self.rename_node(&old_key, event.parent_record_number, &event.name)
+                    }
+                    None => self.apply_change(&ChangeEvent {
+                        reason: ChangeReason::Created,
+                        ..event.clone()
+                    }),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Re-key a node under a new parent and/or name, keeping its metadata
+    /// and children. Returns false, leaving the node where it was, if it is
+    /// missing or the new name is already taken in the target directory.
+    fn rename_node(&self, old_key: &NodeKey, new_parent: u64, new_name: &str) -> bool {
+        let Some(is_deleted) = self.nodes.get(old_key).map(|node| node.is_deleted) else {
+            return false;
+        };
+        // The same checks `insert` makes, before anything is taken apart;
+        // the node's own name and key don't count as taken
+        let new_key = NodeKey::new(old_key.record_number, new_parent);
+        let taken = (new_key.record_number == new_parent && new_key != NodeKey::root())
+            || (!is_deleted
+                && (self
+                    .name_index
+                    .get(&(new_parent, new_name.to_lowercase()))
+                    .is_some_and(|key| *key != *old_key)
+                    || (new_key != *old_key
+                        && self.nodes.get(&new_key).is_some_and(|node| !node.is_deleted))));
+        if taken {
+            logging::info("TREE", &format!(
+                "Rename of record {} to parent {}, '{}' skipped: name already exists",
+                old_key.record_number, new_parent, new_name
+            ));
+            return false;
+        }
+
+        let Some((_, mut node)) = self.nodes.remove(old_key) else {
+            return false;
+        };
+        let old_name = node.name.to_lowercase();
+        self.name_index.remove(&(old_key.parent_record_number, old_name));
+        self.unlink_key(old_key);
+        let totals = SubtreeTotals::of(&node);
+        self.adjust_ancestor_totals(old_key.parent_record_number, totals, false);
+
+        node.name = new_name.to_string();
+        node.name_anomalies = decoded_name_anomalies(new_name);
+        node.raw_name = None;
+        node.parent_record_number = new_parent;
+        let inserted = self.insert(node);
+        if inserted {
+            self.adjust_ancestor_totals(new_parent, totals, true);
+        }
+
+        logging::info("TREE", &format!(
+            "Renamed record {}: parent {} -> {}, '{}'",
+            old_key.record_number, old_key.parent_record_number, new_parent, new_name
+        ));
+        inserted
+    }
+
+    /// Add (or take away) a subtree's totals on every directory from
+    /// `parent_record_number` up to the root. Stops below a reparse-point
+    /// directory, whose totals never include its children, and on a parent
+    /// loop.
+    fn adjust_ancestor_totals(&self, parent_record_number: u64, totals: SubtreeTotals, add: bool) {
+        let apply = |value: &mut u64, delta: u64| {
+            *value = if add { value.saturating_add(delta) } else { value.saturating_sub(delta) };
+        };
+        let mut seen = std::collections::HashSet::new();
+        let mut current = parent_record_number;
+        // Record 0 is $MFT, never a folder: parent 0 marks an orphan
+        while current != 0 && seen.insert(current) {
+            let parent_key = self
+                .record_index
+                .get(&current)
+                .and_then(|keys| keys.iter().copied().find(|k| self.nodes.contains_key(k)));
+            let Some(parent_key) = parent_key else { break };
+            let Some(mut parent) = self.nodes.get_mut(&parent_key) else { break };
+            if parent.is_reparse_point() {
+                break;
+            }
+            apply(&mut parent.total_size, totals.size);
+            apply(&mut parent.total_allocated, totals.allocated);
+            apply(&mut parent.file_count, totals.files);
+            apply(&mut parent.dir_count, totals.dirs);
+            current = parent.parent_record_number;
+        }
+    }
+
+    /// Remove every node (hard link) of a record. Returns the number removed.
+    pub fn remove_record(&self, record_number: u64) -> usize {
+        let keys = self
+            .record_index
+            .get(&record_number)
+            .map(|keys| keys.clone())
+            .unwrap_or_default();
+
+        for key in &keys {
+            if let Some((_, node)) = self.nodes.remove(key) {
+                // A deleted file's path may belong to a live file
+                self.name_index
+                    .remove_if(&(key.parent_record_number, node.name.to_lowercase()), |_, k| k == key);
+            }
+            self.unlink_key(key);
+        }
+        self.pending_renames.remove(&record_number);
+        keys.len()
+    }
+
+    /// Drop a key from the record index and from its parent's children
+    fn unlink_key(&self, key: &NodeKey) {
+        let now_empty = match self.record_index.get_mut(&key.record_number) {
+            Some(mut record_keys) => {
+                record_keys.retain(|k| k != key);
+                record_keys.is_empty()
+            }
+            None => false,
+        };
+        if now_empty {
+            self.record_index.remove(&key.record_number);
+        }
+
+        let parent_keys = self
+            .record_index
+            .get(&key.parent_record_number)
+            .map(|keys| keys.clone())
+            .unwrap_or_default();
+        for parent_key in parent_keys {
+            if let Some(mut parent_node) = self.nodes.get_mut(&parent_key) {
+                parent_node.children.retain(|k| k != key);
+            }
+        }
+    }
+
     /// Get a node by NodeKey
     pub fn get_by_key(&self, key: &NodeKey) -> Option<TreeNode> {
         self.nodes.get(key).map(|r| r.clone())
     }
 
+    /// One timestamp of a node without cloning it (0 if the key is gone)
+    pub fn time_for_key(&self, key: &NodeKey, field: DateField) -> u64 {
+        self.nodes.get(key).map(|n| n.time(field)).unwrap_or(0)
+    }
+
+    /// Overwrite the attributes of every hard link of a record after they
+    /// were changed on disk. Returns false if the record isn't in the tree.
+    pub fn set_attributes(&self, record_number: u64, attributes: u32) -> bool {
+        let Some(keys) = self.record_index.get(&record_number).map(|k| k.clone()) else {
+            return false;
+        };
+        for key in &keys {
+            if let Some(mut node) = self.nodes.get_mut(key) {
+                node.attributes = attributes;
+            }
+        }
+        true
+    }
+
+    /// Aggregated size of a node without cloning it (0 if the key is gone)
+    pub fn total_size_for_key(&self, key: &NodeKey) -> u64 {
+        self.nodes.get(key).map(|n| n.total_size).unwrap_or(0)
+    }
+
     /// Get the first node for a record number (any parent)
     /// Used when you don't care which hard link you get
     pub fn get(&self, record_number: u64) -> Option<TreeNode> {
@@ -324,16 +1199,24 @@ impl FileTree {
         }
     }
 
-    /// Get children of a directory
-    pub fn get_children(&self, key: &NodeKey) -> Vec<TreeNode> {
-        if let Some(node) = self.nodes.get(key) {
-            node.children
+    /// Children of a directory in `sort` order (empty for files and
+    /// unknown keys). Sorting is stable, so ties keep the directory's order.
+    pub fn children(&self, key: &NodeKey, sort: ChildSort) -> Vec<TreeNode> {
+        let mut children: Vec<TreeNode> = match self.nodes.get(key) {
+            Some(node) => node
+                .children
                 .iter()
                 .filter_map(|child_key| self.get_by_key(child_key))
-                .collect()
-        } else {
-            Vec::new()
+                .collect(),
+            None => return Vec::new(),
+        };
+        match sort {
+            ChildSort::Unsorted => {}
+            ChildSort::Name => children.sort_by_cached_key(|c| c.name.to_lowercase()),
+            ChildSort::Size(basis) => children.sort_by_key(|c| std::cmp::Reverse(basis.size_of(c))),
+            ChildSort::Date(field) => children.sort_by_key(|c| std::cmp::Reverse(c.time(field))),
         }
+        children
     }
 
     /// Get the root node
@@ -342,6 +1225,56 @@ impl FileTree {
         self.get_by_key(&NodeKey::root())
     }
 
+    /// Plain-text summary of the largest folders under `key`, for pasting
+    /// into tickets: `top` folders per level, `depth` levels down
+    pub fn size_report(&self, key: &NodeKey, depth: usize, top: usize) -> String {
+        let Some(node) = self.get_by_key(key) else {
+            return String::new();
+        };
+        let mut out = format!(
+            "{}  {} ({} files, {} folders)\n",
+            self.build_path_for_key(key),
+            crate::format_size(node.total_size),
+            node.file_count,
+            node.dir_count.saturating_sub(1)
+        );
+        self.size_report_level(&node, 1, depth, top, &mut out);
+        out
+    }
+
+    fn size_report_level(&self, node: &TreeNode, level: usize, depth: usize, top: usize, out: &mut String) {
+        if level > depth {
+            return;
+        }
+        let indent = "  ".repeat(level);
+
+        // Same ordering as `tree-size`: subdirectories by total size
+        let children: Vec<TreeNode> = self
+            .children(&node.key(), ChildSort::Size(SizeBasis::Logical))
+            .into_iter()
+            .filter(|child| child.is_directory)
+            .collect();
+
+        for child in children.iter().take(top) {
+            out.push_str(&format!(
+                "{}{:>10}  {}\n",
+                indent,
+                crate::format_size(child.total_size),
+                child.name
+            ));
+            self.size_report_level(child, level + 1, depth, top, out);
+        }
+        if children.len() > top {
+            let rest: u64 = children[top..].iter().map(|c| c.total_size).sum();
+            out.push_str(&format!(
+                "{}{:>10}  ({} more folders)\n",
+                indent,
+                crate::format_size(rest),
+                children.len() - top
+            ));
+        }
+    }
+
     /// Build full path for a NodeKey
     ///
     /// This method walks up the parent chain to construct the full path.
@@ -362,6 +1295,41 @@ impl FileTree {
         self.build_path_internal(record_number, false)
     }
 
+    /// Like `build_path`, formatted by `style` (separator, `\\?\` prefix,
+    /// parent directory only)
+    pub fn build_path_styled(&self, record_number: u64, style: &PathStyle) -> String {
+        style.apply(&self.build_path(record_number))
+    }
+
+    /// Like `build_path_for_key`, formatted by `style`
+    pub fn build_path_for_key_styled(&self, key: &NodeKey, style: &PathStyle) -> String {
+        style.apply(&self.build_path_for_key(key))
+    }
+
+    /// Resolve a full path to its node, the inverse of `build_path_for_key`.
+    /// Names match case-insensitively, `/` works as a separator and a
+    /// `\\?\` prefix is ignored. A drive letter, if given, must be this
+    /// tree's; the volume root resolves to `NodeKey::root()`.
+    pub fn key_for_path(&self, path: &str) -> Option<NodeKey> {
+        let path = path.replace('/', "\\");
+        let path = path.strip_prefix(r"\\?\").unwrap_or(&path);
+        let relative = match path.as_bytes() {
+            [drive, b':', ..] => {
+                if !(*drive as char).eq_ignore_ascii_case(&self.drive_letter) {
+                    return None;
+                }
+                &path[2..]
+            }
+            _ => path,
+        };
+
+        let mut key = NodeKey::root();
+        for name in relative.split('\\').filter(|name| !name.is_empty() && *name != ".") {
+            key = *self.name_index.get(&(key.record_number, name.to_lowercase()))?;
+        }
+        Some(key)
+    }
+
     /// Build path with optional debug output
     pub fn build_path_debug(&self, record_number: u64) -> String {
         self.build_path_internal(record_number, true)
@@ -384,16 +1352,45 @@ impl FileTree {
             self.walk_parent_chain(&mut parts, current_parent, debug);
         }
 
+        self.join_path_parts(parts)
+    }
+
+    /// `C:\` followed by `parts`, which were collected leaf first. The
+    /// root's `.` name and nameless records add no component, so the root
+    /// itself renders as `C:\` and paths never contain `\.\` or `\\`.
+    fn join_path_parts(&self, mut parts: Vec<String>) -> String {
+        parts.retain(|part| !part.is_empty() && part != ".");
         parts.reverse();
         format!("{}:\\{}", self.drive_letter, parts.join("\\"))
     }
 
+    /// Count one more parent level. Returns false (and logs) once the
+    /// chain starting at `start` exceeds `max_parent_depth`.
+    fn enter_parent_level(&self, depth: &mut u32, start: u64, debug: bool) -> bool {
+        *depth += 1;
+        if *depth <= self.max_parent_depth {
+            return true;
+        }
+        if debug {
+            eprintln!("  [path] Depth limit {} reached", self.max_parent_depth);
+        }
+        logging::warn("TREE", &format!(
+            "Parent chain from record {} exceeds {} levels (cycle or corrupt volume), path truncated",
+            start, self.max_parent_depth
+        ));
+        false
+    }
+
     /// Walk up the parent chain collecting path components
     fn walk_parent_chain(&self, parts: &mut Vec<String>, start_parent: u64, debug: bool) {
         let mut current = start_parent;
         let mut volume_handle: Option<SafeHandle> = None;
+        let mut depth = 0u32;
 
         while current != self.root_record && current != 0 {
+            if !self.enter_parent_level(&mut depth, start_parent, debug) {
+                break;
+            }
             // Find any node with this record number (directories have only one entry)
             if let Some(node) = self.get(current) {
                 if debug {
@@ -407,6 +1404,11 @@ impl FileTree {
                     eprintln!("  [path] Record {} NOT in tree, attempting fetch...", current);
                 }
 
+                // A previous fetch of this record already failed
+                if self.unresolvable_records.contains(&current) {
+                    break;
+                }
+
                 // Try MftRecordFetcher first (direct disk read, works in physical mode)
                 if let Some(ref fetcher) = self.record_fetcher {
                     if debug {
@@ -431,6 +1433,7 @@ impl FileTree {
                     if debug {
                         eprintln!("  [path] MftRecordFetcher returned None");
                     }
+                    self.unresolvable_records.insert(current);
                     break;
                 }
 
@@ -504,6 +1507,7 @@ impl FileTree {
                     }
                 }
                 // Failed to fetch parent - stop here
+                self.unresolvable_records.insert(current);
                 break;
             }
         }
@@ -515,8 +1519,12 @@ impl FileTree {
 
         // Lazily open volume handle only if we encounter a missing parent
         let mut volume_handle: Option<SafeHandle> = None;
+        let mut depth = 0u32;
 
         while current != self.root_record && current != 0 {
+            if !self.enter_parent_level(&mut depth, record_number, debug) {
+                break;
+            }
             if let Some(node) = self.get(current) {
                 if debug {
                     eprintln!("  [path] Record {} '{}' -> parent {}", current, node.name, node.parent_record_number);
@@ -528,6 +1536,11 @@ impl FileTree {
                     eprintln!("  [path] Record {} NOT in tree, attempting fetch...", current);
                 }
 
+                // A previous fetch of this record already failed
+                if self.unresolvable_records.contains(&current) {
+                    break;
+                }
+
                 // Try MftRecordFetcher first (direct disk read, works in physical mode)
                 if let Some(ref fetcher) = self.record_fetcher {
                     if debug {
@@ -552,6 +1565,7 @@ impl FileTree {
                     if debug {
                         eprintln!("  [path] MftRecordFetcher returned None");
                     }
+                    self.unresolvable_records.insert(current);
                     break;
                 }
 
@@ -624,16 +1638,23 @@ impl FileTree {
                     }
                 }
                 // Failed to fetch parent - stop here
+                self.unresolvable_records.insert(current);
                 break;
             }
         }
 
-        parts.reverse();
-        format!("{}:\\{}", self.drive_letter, parts.join("\\"))
+        self.join_path_parts(parts)
     }
 
     /// Calculate aggregated sizes (call after all nodes inserted)
     /// Uses iterative post-order traversal to avoid stack overflow
+    ///
+    /// Reparse-point directories (junctions, directory symlinks) count as
+    /// leaves with only their own size, so links to other volumes never add
+    /// those volumes' contents (see `TreeNode::is_off_volume_link`). A
+    /// directory record reached a second time, through a corrupt parent link
+    /// or a second name, is skipped so its subtree is counted once and
+    /// cannot loop.
     pub fn calculate_sizes(&self) {
         use std::collections::HashMap;
 
@@ -645,15 +1666,26 @@ impl FileTree {
         let mut to_visit = vec![root_key];
         let mut visit_order = Vec::new();
         let mut visited = std::collections::HashSet::new();
+        // Directories can't be hard-linked, so one visit per record
+        let mut visited_dirs: std::collections::HashSet<u64> = std::collections::HashSet::new();
+        let mut repeated_dirs = 0usize;
 
         while let Some(key) = to_visit.pop() {
             if visited.contains(&key) {
                 continue;
             }
             visited.insert(key);
-            visit_order.push(key);
 
             if let Some(node) = self.nodes.get(&key) {
+                if node.is_directory && !visited_dirs.insert(node.record_number) {
+                    repeated_dirs += 1;
+                    continue;
+                }
+                visit_order.push(key);
+
+                if node.is_directory && node.is_reparse_point() {
+                    continue;
+                }
                 for &child_key in &node.children {
                     if !visited.contains(&child_key) {
                         to_visit.push(child_key);
@@ -662,14 +1694,27 @@ impl FileTree {
             }
         }
 
+        if repeated_dirs > 0 {
+            logging::warn("TREE", &format!(
+                "Size calculation skipped {} directories reached more than once (cycle or corrupt volume)",
+                repeated_dirs
+            ));
+        }
+
         // Second pass: process in reverse order (leaves first)
         // Store computed values in a separate map to avoid holding refs
-        let mut computed: HashMap<NodeKey, (u64, u64, u64, u64)> = HashMap::new();
+        let mut computed: HashMap<NodeKey, (u64, u64, u64, u64, bool)> = HashMap::new();
 
         for &key in visit_order.iter().rev() {
-            let (children, file_size, allocated_size, is_directory) = {
+            let (children, file_size, allocated_size, is_directory, is_deleted) = {
                 if let Some(node) = self.nodes.get(&key) {
-                    (node.children.clone(), node.file_size, node.allocated_size, node.is_directory)
+                    // A link's target is counted where it really lives
+                    let children = if node.is_directory && node.is_reparse_point() {
+                        Vec::new()
+                    } else {
+                        node.children.clone()
+                    };
+                    (children, node.file_size, node.allocated_size, node.is_directory, node.is_deleted)
                 } else {
                     continue;
                 }
@@ -680,9 +1725,13 @@ impl FileTree {
             let mut file_count = if is_directory { 0 } else { 1 };
             let mut dir_count = if is_directory { 1 } else { 0 };
 
-            // Sum up children's computed values
+            // Sum up children's computed values. Deleted files only count
+            // towards a deleted directory, not the live ones above it.
             for child_key in children {
-                if let Some(&(cs, ca, fc, dc)) = computed.get(&child_key) {
+                if let Some(&(cs, ca, fc, dc, child_deleted)) = computed.get(&child_key) {
+                    if child_deleted && !is_deleted {
+                        continue;
+                    }
                     total_size += cs;
                     total_allocated += ca;
                     file_count += fc;
@@ -690,7 +1739,7 @@ impl FileTree {
                 }
             }
 
-            computed.insert(key, (total_size, total_allocated, file_count, dir_count));
+            computed.insert(key, (total_size, total_allocated, file_count, dir_count, is_deleted));
 
             // Update the node
             if let Some(mut node) = self.nodes.get_mut(&key) {
@@ -712,19 +1761,68 @@ impl FileTree {
         self.nodes.is_empty()
     }
 
+    /// Estimated bytes held by the nodes, their names and the lookup
+    /// indexes. Hash tables are counted at capacity with one control byte
+    /// per slot; allocator overhead is not included.
+    pub fn memory_footprint(&self) -> usize {
+        use std::mem::size_of;
+
+        let nodes = self.nodes.capacity() * (size_of::<NodeKey>() + size_of::<TreeNode>() + 1)
+            + self
+                .nodes
+                .iter()
+                .map(|entry| entry.value().heap_bytes())
+                .sum::<usize>();
+        let records = self.record_index.capacity()
+            * (size_of::<u64>() + size_of::<Vec<NodeKey>>() + 1)
+            + self
+                .record_index
+                .iter()
+                .map(|entry| entry.value().capacity() * size_of::<NodeKey>())
+                .sum::<usize>();
+        let names = self.name_index.capacity()
+            * (size_of::<(u64, String)>() + size_of::<NodeKey>() + 1)
+            + self
+                .name_index
+                .iter()
+                .map(|entry| entry.key().1.capacity())
+                .sum::<usize>();
+
+        nodes + records + names
+    }
+
     /// Iterate over all nodes
     pub fn iter(&self) -> impl Iterator<Item = dashmap::mapref::multiple::RefMulti<'_, NodeKey, TreeNode>> + '_ {
         self.nodes.iter()
     }
 
+    /// Iterate over the files and directories a listing shows
+    /// (`TreeNode::is_listed`)
+    pub fn iter_entries(&self) -> impl Iterator<Item = dashmap::mapref::multiple::RefMulti<'_, NodeKey, TreeNode>> + '_ {
+        self.nodes.iter().filter(|e| e.value().is_listed())
+    }
+
+    /// Iterate over listed files, skipping directories
+    pub fn iter_files(&self) -> impl Iterator<Item = dashmap::mapref::multiple::RefMulti<'_, NodeKey, TreeNode>> + '_ {
+        self.iter_entries().filter(|e| !e.value().is_directory)
+    }
+
+    /// Iterate over listed directories (the root is a self-reference and
+    /// not among them)
+    pub fn iter_dirs(&self) -> impl Iterator<Item = dashmap::mapref::multiple::RefMulti<'_, NodeKey, TreeNode>> + '_ {
+        self.iter_entries().filter(|e| e.value().is_directory)
+    }
+
     /// Find orphaned nodes (no valid parent)
     pub fn find_orphans(&self) -> Vec<NodeKey> {
         let mut orphans = Vec::new();
 
         for entry in self.nodes.iter() {
             let node = entry.value();
-            // Check if parent exists in the record index
-            if node.parent_record_number != 0
+            // Check if parent exists in the record index. A deleted file
+            // losing its folder is expected, not a sign of corruption.
+            if !node.is_deleted
+                && node.parent_record_number != 0
                 && node.parent_record_number != self.root_record
                 && !self.record_index.contains_key(&node.parent_record_number)
             {
@@ -735,13 +1833,66 @@ impl FileTree {
         orphans
     }
 
+    /// Find records whose parent chain loops back on itself.
+    ///
+    /// Walks each record's first parent link and returns every record that is
+    /// part of a cycle (not the records that merely lead into one), sorted.
+    pub fn find_parent_cycles(&self) -> Vec<u64> {
+        // 1 = on the current walk, 2 = finished
+        let mut state: HashMap<u64, u8> = HashMap::with_capacity(self.record_index.len());
+        let mut cycles = Vec::new();
+        let mut path = Vec::new();
+
+        for entry in self.record_index.iter() {
+            let start = *entry.key();
+            if state.contains_key(&start) {
+                continue;
+            }
+
+            path.clear();
+            let mut current = start;
+            loop {
+                match state.get(&current) {
+                    Some(1) => {
+                        // Back on the current walk: everything from `current` on loops
+                        if let Some(pos) = path.iter().position(|&r| r == current) {
+                            cycles.extend_from_slice(&path[pos..]);
+                        }
+                        break;
+                    }
+                    Some(_) => break,
+                    None => {}
+                }
+                if current == self.root_record || current == 0 {
+                    break;
+                }
+                let parent = match self.record_index.get(&current).and_then(|keys| keys.first().copied()) {
+                    Some(key) => key.parent_record_number,
+                    None => break,
+                };
+                state.insert(current, 1);
+                path.push(current);
+                current = parent;
+            }
+
+            for &record in &path {
+                state.insert(record, 2);
+            }
+        }
+
+        cycles.sort_unstable();
+        cycles
+    }
+
     /// Compute final statistics
     pub fn compute_stats(&mut self) {
         let mut stats = TreeStats::default();
 
         for entry in self.nodes.iter() {
             let node = entry.value();
-            if node.is_directory {
+            if node.is_deleted {
+                stats.deleted_files += 1;
+            } else if node.is_directory {
                 stats.total_directories += 1;
             } else {
                 stats.total_files += 1;
@@ -751,6 +1902,8 @@ impl FileTree {
         }
 
         stats.orphaned_files = self.find_orphans().len() as u64;
+        // Comes from the MFT parser, not the nodes
+        stats.mft_fragment_count = self.stats.mft_fragment_count;
         self.stats = stats;
     }
 
@@ -802,6 +1955,7 @@ impl FileTree {
                                 node.file_size = metadata.file_size;
                                 node.creation_time = metadata.creation_time;
                                 node.modification_time = metadata.modification_time;
+                                node.access_time = metadata.access_time;
                                 node.total_size = metadata.file_size;
                             }
                             // Return result for all hardlinks
@@ -813,6 +1967,7 @@ impl FileTree {
                             node.file_size = metadata.file_size;
                             node.creation_time = metadata.creation_time;
                             node.modification_time = metadata.modification_time;
+                            node.access_time = metadata.access_time;
                             node.total_size = metadata.file_size;
                         }
                         results.insert(key, (metadata.file_size, metadata.modification_time));
@@ -849,6 +2004,7 @@ impl FileTree {
                     node.file_size = metadata.file_size;
                     node.creation_time = metadata.creation_time;
                     node.modification_time = metadata.modification_time;
+                    node.access_time = metadata.access_time;
                     node.total_size = metadata.file_size;
                 }
             }
@@ -858,6 +2014,7 @@ impl FileTree {
                 node.file_size = metadata.file_size;
                 node.creation_time = metadata.creation_time;
                 node.modification_time = metadata.modification_time;
+                node.access_time = metadata.access_time;
                 node.total_size = metadata.file_size;
             }
         }
@@ -873,6 +2030,12 @@ impl FileTree {
 /// Builds a FileTree from various sources
 pub struct TreeBuilder {
     tree: FileTree,
+    /// Keep NTFS metafiles in the built tree
+    include_metafiles: bool,
+    /// Keep the stored UTF-16 of anomalous names on their nodes
+    keep_raw_names: bool,
+    /// Records added from USN that MFT parsing has not (yet) confirmed
+    usn_only: std::collections::HashSet<u64>,
 }
 
 impl TreeBuilder {
@@ -880,6 +2043,9 @@ impl TreeBuilder {
     pub fn new(drive_letter: char) -> Self {
         Self {
             tree: FileTree::new(drive_letter),
+            include_metafiles: true,
+            keep_raw_names: false,
+            usn_only: std::collections::HashSet::new(),
         }
     }
 
@@ -887,14 +2053,43 @@ impl TreeBuilder {
     pub fn with_volume_info(drive_letter: char, bytes_per_record: u32) -> Self {
         Self {
             tree: FileTree::with_volume_info(drive_letter, bytes_per_record),
+            include_metafiles: true,
+            keep_raw_names: false,
+            usn_only: std::collections::HashSet::new(),
         }
     }
 
+    /// Keep or drop NTFS metafiles when the tree is built
+    pub fn set_include_metafiles(&mut self, include: bool) {
+        self.include_metafiles = include;
+    }
+
+    /// Keep anomalous names' UTF-16 on their nodes (`TreeNode::raw_name`).
+    /// Anomaly flags are recorded either way.
+    pub fn set_keep_raw_names(&mut self, keep: bool) {
+        self.keep_raw_names = keep;
+    }
+
     /// Set the MFT record fetcher for on-demand parent resolution
     pub fn set_record_fetcher(&mut self, fetcher: Arc<MftRecordFetcher>) {
         self.tree.set_record_fetcher(fetcher);
     }
 
+    /// Limit how many parent levels path building climbs
+    pub fn set_max_parent_depth(&mut self, depth: u32) {
+        self.tree.set_max_parent_depth(depth);
+    }
+
+    /// Set the volume's cluster size (for slack computation)
+    pub fn set_bytes_per_cluster(&mut self, bytes_per_cluster: u32) {
+        self.tree.set_bytes_per_cluster(bytes_per_cluster);
+    }
+
+    /// `FileTree::memory_footprint` of everything added so far
+    pub fn memory_footprint(&self) -> usize {
+        self.tree.memory_footprint()
+    }
+
     /// Add entries from USN enumeration
     pub fn add_usn_entries(&mut self, entries: impl Iterator<Item = UsnEntry>) {
         for entry in entries {
@@ -910,6 +2105,43 @@ impl TreeBuilder {
                 "USN",
             );
 
+            if self.tree.insert(node) {
+                self.usn_only.insert(entry.record_number);
+            }
+        }
+    }
+
+    /// Drop records that USN enumeration listed but MFT parsing never
+    /// confirmed as in use, returning their record numbers (sorted).
+    ///
+    /// MFT is the ground truth for existence: a record read from the MFT
+    /// that was not a valid in-use file was deleted (or reused) between the
+    /// two phases, so its USN node is transient. Records in `unread` ranges
+    /// (unreadable, or single records that failed fixup verification) could
+    /// not be checked and are kept.
+    pub fn drop_unconfirmed_usn_entries(&mut self, unread: &[std::ops::Range<u64>]) -> Vec<u64> {
+        let mut transient: Vec<u64> = std::mem::take(&mut self.usn_only)
+            .into_iter()
+            .filter(|record| !unread.iter().any(|range| range.contains(record)))
+            .collect();
+        transient.sort_unstable();
+
+        for &record in &transient {
+            self.tree.remove_record(record);
+        }
+
+        if !transient.is_empty() {
+            logging::info("TREE", &format!(
+                "Dropped {} transient USN records not confirmed by MFT",
+                transient.len()
+            ));
+        }
+        transient
+    }
+
+    /// Add already-built nodes (from a saved index or scan checkpoint)
+    pub fn add_nodes(&mut self, nodes: impl Iterator<Item = TreeNode>) {
+        for node in nodes {
             self.tree.insert(node);
         }
     }
@@ -925,10 +2157,23 @@ impl TreeBuilder {
     /// hard links discovered via USN (which has no metadata) get updated with
     /// the actual metadata from MFT.
     pub fn add_file_entries(&mut self, entries: impl Iterator<Item = FileEntry>) {
-        for entry in entries {
-            if !entry.is_valid {
+        for mut entry in entries {
+            if !entry.is_valid && !entry.is_deleted {
+                continue;
+            }
+            if !self.keep_raw_names {
+                entry.raw_name = None;
+            }
+            if entry.is_deleted {
+                // Nothing live shares the record, so there is nothing to update
+                for node in TreeNode::nodes_from_file_entry(&entry) {
+                    if !self.tree.nodes.contains_key(&node.key()) {
+                        self.tree.insert(node);
+                    }
+                }
                 continue;
             }
+            self.usn_only.remove(&entry.record_number);
 
             // Primary key for this entry
             let primary_key = NodeKey::new(entry.record_number, entry.parent_record_number);
@@ -1025,12 +2270,16 @@ impl TreeBuilder {
                         node.allocated_size = entry.allocated_size;
                         node.total_size = entry.file_size;
                         node.total_allocated = entry.allocated_size;
+                        node.storage = entry.storage;
                         if entry.creation_time != 0 {
                             node.creation_time = entry.creation_time;
                         }
                         if entry.modification_time != 0 {
                             node.modification_time = entry.modification_time;
                         }
+                        if entry.access_time != 0 {
+                            node.access_time = entry.access_time;
+                        }
                         if entry.file_reference_number != 0 {
                             node.file_reference_number = entry.file_reference_number;
                         }
@@ -1040,11 +2289,56 @@ impl TreeBuilder {
         }
     }
 
+    /// Copy of everything added so far, to build a provisional tree from
+    /// while this builder keeps going (e.g. names from USN enumeration shown
+    /// before the MFT pass fills in sizes).
+    ///
+    /// The name index is left out to keep the copy small: it only guards
+    /// inserts and serves `FileTree::find_by_path`, so paths can't be looked
+    /// up in the provisional tree. See `memory_footprint` for the cost.
+    /// Nor does it get the record fetcher, so it holds no volume handle
+    /// open after the scan that made it.
+    pub fn snapshot(&self) -> TreeBuilder {
+        let tree = FileTree {
+            drive_letter: self.tree.drive_letter,
+            nodes: self.tree.nodes.clone(),
+            record_index: self.tree.record_index.clone(),
+            name_index: DashMap::new(),
+            root_record: self.tree.root_record,
+            stats: TreeStats::default(),
+            bytes_per_record: self.tree.bytes_per_record,
+            bytes_per_cluster: self.tree.bytes_per_cluster,
+            record_fetcher: None,
+            pending_renames: DashMap::new(),
+            max_parent_depth: self.tree.max_parent_depth,
+            unresolvable_records: DashSet::new(),
+            scanned_at: None,
+            usn_cursor: None,
+        };
+        TreeBuilder {
+            tree,
+            include_metafiles: self.include_metafiles,
+            keep_raw_names: self.keep_raw_names,
+            usn_only: std::collections::HashSet::new(),
+        }
+    }
+
     /// Finalize the tree
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn build(mut self) -> FileTree {
+        // Deleted files whose folder record was reused lose their folder
+        self.orphan_misparented_deleted();
+
         // Link children to parents
         self.link_children();
 
+        // Flag metafiles (and everything under $Extend), dropping them if unwanted
+        let metafiles = self.mark_metafiles();
+        if !self.include_metafiles {
+            self.remove_nodes(&metafiles);
+            logging::info("TREE", &format!("Removed {} NTFS metafile nodes", metafiles.len()));
+        }
+
         // Calculate aggregated sizes
         self.tree.calculate_sizes();
 
@@ -1054,6 +2348,119 @@ impl TreeBuilder {
         self.tree
     }
 
+    /// Move deleted files to parent 0 (orphans, listed at the volume root)
+    /// when their parent record now has another sequence number: NTFS
+    /// reused it, so the folder there is not the one the file was in.
+    fn orphan_misparented_deleted(&mut self) {
+        let misparented: Vec<NodeKey> = self
+            .tree
+            .nodes
+            .iter()
+            .filter(|e| e.value().is_deleted && e.key().parent_record_number != 0)
+            .filter(|e| {
+                let node = e.value();
+                let Some(parent_keys) = self.tree.record_index.get(&node.parent_record_number) else {
+                    return false;
+                };
+                let Some(parent) = parent_keys.first().and_then(|k| self.tree.nodes.get(k)) else {
+                    return false;
+                };
+                let sequence = (parent.file_reference_number >> 48) as u16;
+                // Freeing a record bumps its sequence number, so a folder
+                // deleted along with the file is one ahead
+                parent.file_reference_number != 0
+                    && node.parent_sequence_number != 0
+                    && sequence != node.parent_sequence_number
+                    && !(parent.is_deleted && sequence == node.parent_sequence_number.wrapping_add(1))
+            })
+            .map(|e| *e.key())
+            .collect();
+
+        for key in &misparented {
+            let Some((_, mut node)) = self.tree.nodes.remove(key) else {
+                continue;
+            };
+            if let Some(mut record_keys) = self.tree.record_index.get_mut(&key.record_number) {
+                record_keys.retain(|k| k != key);
+            }
+            if let Some(parent_keys) = self.tree.record_index.get(&key.parent_record_number) {
+                for parent_key in parent_keys.iter() {
+                    if let Some(mut parent) = self.tree.nodes.get_mut(parent_key) {
+                        parent.children.retain(|k| k != key);
+                    }
+                }
+            }
+            node.parent_record_number = 0;
+            if !self.tree.nodes.contains_key(&node.key()) {
+                self.tree.insert(node);
+            }
+        }
+
+        if !misparented.is_empty() {
+            logging::info("TREE", &format!(
+                "Orphaned {} deleted files whose folder record was reused",
+                misparented.len()
+            ));
+        }
+    }
+
+    /// Set `is_system_metafile` on reserved records and their descendants.
+    /// Returns the keys of all flagged nodes.
+    fn mark_metafiles(&mut self) -> Vec<NodeKey> {
+        let mut stack: Vec<NodeKey> = self
+            .tree
+            .nodes
+            .iter()
+            .filter(|e| is_metafile_record(e.key().record_number))
+            .map(|e| *e.key())
+            .collect();
+        let mut marked = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        while let Some(key) = stack.pop() {
+            if !seen.insert(key) {
+                continue;
+            }
+            if let Some(mut node) = self.tree.nodes.get_mut(&key) {
+                node.is_system_metafile = true;
+                stack.extend(node.children.iter().copied());
+                marked.push(key);
+            }
+        }
+
+        if !marked.is_empty() {
+            logging::info("TREE", &format!("Flagged {} NTFS metafile nodes", marked.len()));
+        }
+        marked
+    }
+
+    /// Remove nodes from the tree and all of its indexes
+    fn remove_nodes(&mut self, keys: &[NodeKey]) {
+        let removed: std::collections::HashSet<NodeKey> = keys.iter().copied().collect();
+
+        for key in keys {
+            if let Some((_, node)) = self.tree.nodes.remove(key) {
+                self.tree
+                    .name_index
+                    .remove(&(key.parent_record_number, node.name.to_lowercase()));
+            }
+            let now_empty = match self.tree.record_index.get_mut(&key.record_number) {
+                Some(mut record_keys) => {
+                    record_keys.retain(|k| k != key);
+                    record_keys.is_empty()
+                }
+                None => false,
+            };
+            if now_empty {
+                self.tree.record_index.remove(&key.record_number);
+            }
+        }
+
+        for mut node in self.tree.nodes.iter_mut() {
+            node.children.retain(|k| !removed.contains(k));
+        }
+    }
+
     /// Ensure all children are linked to parents
     fn link_children(&mut self) {
         // Collect all (child_key, parent_record_number) pairs
@@ -1067,6 +2474,12 @@ impl TreeBuilder {
         // Link children to parents
         // For each child, find the parent node(s) by record number
         for (child_key, parent_record) in pairs {
+            // A directory is never its own child (the root's parent is itself),
+            // and parent 0 ($MFT) marks an orphan
+            if child_key.record_number == parent_record || parent_record == 0 {
+                continue;
+            }
+
             // Look up all nodes with this parent record number
             if let Some(parent_keys) = self.tree.record_index.get(&parent_record) {
                 // Add child to the first matching parent (directories typically have one entry)
@@ -1098,7 +2511,12 @@ pub struct SearchResult {
     pub path: String,
     pub file_size: u64,
     pub is_directory: bool,
+    /// Creation time (FILETIME, 0 if unknown)
+    pub creation_time: u64,
+    /// Modification time (FILETIME, 0 if unknown)
     pub modification_time: u64,
+    /// Lowercase extension without the dot ("" for directories)
+    pub extension: String,
 }
 
 impl SearchResult {
@@ -1111,19 +2529,231 @@ impl SearchResult {
             path,
             file_size: node.file_size,
             is_directory: node.is_directory,
+            creation_time: node.creation_time,
             modification_time: node.modification_time,
+            extension: node.extension(),
         }
     }
 }
 
+/// Attribute criteria for `FileTree::find_by_attributes`; all must hold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AttributeFilter {
+    /// File attribute bits that must all be set
+    pub required: u32,
+    /// Require at least one alternate data stream
+    pub alternate_streams: bool,
+}
+
+impl AttributeFilter {
+    /// Attribute names accepted by `add`
+    pub const NAMES: &'static [&'static str] = &[
+        "readonly", "hidden", "system", "archive", "temporary", "sparse", "reparse",
+        "compressed", "offline", "encrypted", "ads",
+    ];
+
+    /// Add a criterion by name (see `NAMES`). Returns false for unknown names.
+    pub fn add(&mut self, name: &str) -> bool {
+        let flag = match name.to_ascii_lowercase().as_str() {
+            "readonly" | "ro" => file_attributes::READONLY,
+            "hidden" => file_attributes::HIDDEN,
+            "system" => file_attributes::SYSTEM,
+            "archive" => file_attributes::ARCHIVE,
+            "temporary" | "temp" => file_attributes::TEMPORARY,
+            "sparse" => file_attributes::SPARSE_FILE,
+            "reparse" => file_attributes::REPARSE_POINT,
+            "compressed" => file_attributes::COMPRESSED,
+            "offline" => file_attributes::OFFLINE,
+            "encrypted" => file_attributes::ENCRYPTED,
+            "ads" | "streams" => {
+                self.alternate_streams = true;
+                return true;
+            }
+            _ => return false,
+        };
+        self.required |= flag;
+        true
+    }
+
+    /// Check if no criteria are set (everything matches)
+    pub fn is_empty(&self) -> bool {
+        self.required == 0 && !self.alternate_streams
+    }
+
+    /// Check a node against the criteria
+    pub fn matches(&self, node: &TreeNode) -> bool {
+        self.matches_flags(node.attributes, node.alternate_stream_count > 0)
+    }
+
+    /// Check raw attribute bits and whether alternate data streams exist
+    pub fn matches_flags(&self, attributes: u32, has_streams: bool) -> bool {
+        (attributes & self.required) == self.required && (!self.alternate_streams || has_streams)
+    }
+}
+
+/// How far $STANDARD_INFORMATION may predate $FILE_NAME before a file is
+/// flagged (one day, in FILETIME ticks)
+const TIMESTOMP_TOLERANCE: u64 = 24 * 60 * 60 * 10_000_000;
+
+/// A file whose $STANDARD_INFORMATION times were zeroed, or whose
+/// $STANDARD_INFORMATION creation time predates its $FILE_NAME one.
+///
+/// Tools that backdate files usually only rewrite $STANDARD_INFORMATION;
+/// $FILE_NAME keeps the real creation time. Modification times are not
+/// compared: copying or unpacking a file keeps its old modification time
+/// in $STANDARD_INFORMATION while $FILE_NAME records the copy.
+#[derive(Debug, Clone)]
+pub struct TimestompCandidate {
+    pub key: NodeKey,
+    pub path: String,
+    /// $STANDARD_INFORMATION times were both 0
+    pub si_zeroed: bool,
+    /// $STANDARD_INFORMATION times (FILETIME, 0 when zeroed)
+    pub si_creation_time: u64,
+    pub si_modification_time: u64,
+    /// $FILE_NAME times (FILETIME)
+    pub fn_creation_time: u64,
+    pub fn_modification_time: u64,
+}
+
+impl TimestompCandidate {
+    /// FILETIME ticks by which the SI creation time predates the FN one
+    /// (the whole FN time when SI was zeroed)
+    pub fn divergence(&self) -> u64 {
+        self.fn_creation_time.saturating_sub(self.si_creation_time)
+    }
+}
+
 impl FileTree {
-    /// Search for files matching a pattern
+    /// Files whose $STANDARD_INFORMATION times were zeroed, or whose
+    /// $STANDARD_INFORMATION creation time is more than a day earlier than
+    /// the $FILE_NAME one; largest divergence first. Entries without
+    /// $FILE_NAME times are skipped.
+    pub fn timestomp_candidates(&self) -> Vec<TimestompCandidate> {
+        let mut candidates: Vec<TimestompCandidate> = self
+            .iter_files()
+            .filter(|e| {
+                let node = e.value();
+                node.fn_creation_time != 0
+                    && (node.si_zeroed
+                        || node.fn_creation_time.saturating_sub(node.creation_time)
+                            > TIMESTOMP_TOLERANCE)
+            })
+            .map(|e| {
+                let node = e.value();
+                // Zeroed SI times were replaced by the FN ones when parsing
+                let (si_creation_time, si_modification_time) = if node.si_zeroed {
+                    (0, 0)
+                } else {
+                    (node.creation_time, node.modification_time)
+                };
+                TimestompCandidate {
+                    key: *e.key(),
+                    path: self.build_path_for_key(e.key()),
+                    si_zeroed: node.si_zeroed,
+                    si_creation_time,
+                    si_modification_time,
+                    fn_creation_time: node.fn_creation_time,
+                    fn_modification_time: node.fn_modification_time,
+                }
+            })
+            .collect();
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.divergence()));
+
+        logging::info("SEARCH", &format!(
+            "{} timestomp candidates (SI zeroed or created more than a day before FN)",
+            candidates.len()
+        ));
+        candidates
+    }
+
+    /// Files with more than one name in the tree (hard links), grouped by
+    /// base MFT record. Largest files first; keys within a group are ordered
+    /// by parent record. Two links in the same directory count once (see
+    /// [`FileTree::insert`]).
+    pub fn hardlink_groups(&self) -> Vec<Vec<NodeKey>> {
+        let mut groups: Vec<(u64, Vec<NodeKey>)> = self
+            .record_index
+            .iter()
+            .filter_map(|entry| {
+                let mut keys: Vec<NodeKey> = entry
+                    .value()
+                    .iter()
+                    .filter(|key| {
+                        self.nodes
+                            .get(key)
+                            .is_some_and(|n| !n.is_directory && !n.is_self_reference() && !n.is_deleted)
+                    })
+                    .copied()
+                    .collect();
+                if keys.len() < 2 {
+                    return None;
+                }
+                keys.sort_unstable_by_key(|key| key.parent_record_number);
+                let size = self.nodes.get(&keys[0]).map(|n| n.file_size).unwrap_or(0);
+                Some((size, keys))
+            })
+            .collect();
+        groups.sort_by_key(|(size, keys)| (std::cmp::Reverse(*size), keys[0].record_number));
+
+        logging::info("SEARCH", &format!("{} hard-linked files", groups.len()));
+        groups.into_iter().map(|(_, keys)| keys).collect()
+    }
+
+    /// Entries whose names have lone surrogates or control characters
+    /// (`TreeNode::name_anomalies`), ordered by record number
+    pub fn anomalous_names(&self) -> Vec<NodeKey> {
+        let mut keys: Vec<NodeKey> = self
+            .nodes
+            .iter()
+            .filter(|e| e.value().name_anomalies != 0 && !e.value().is_self_reference())
+            .map(|e| *e.key())
+            .collect();
+        keys.sort_unstable_by_key(|key| (key.record_number, key.parent_record_number));
+        keys
+    }
+
+    /// EFS-encrypted live files, largest first. Folders are left out: their
+    /// flag only makes new files inside them encrypted.
+    pub fn encrypted_files(&self) -> Vec<NodeKey> {
+        let mut files: Vec<(u64, NodeKey)> = self
+            .nodes
+            .iter()
+            .filter(|e| e.value().is_encrypted() && !e.value().is_directory && !e.value().is_deleted)
+            .map(|e| (e.value().file_size, *e.key()))
+            .collect();
+        files.sort_unstable_by_key(|(size, key)| (std::cmp::Reverse(*size), key.record_number));
+
+        logging::info("SEARCH", &format!("{} encrypted files", files.len()));
+        files.into_iter().map(|(_, key)| key).collect()
+    }
+
+    /// Full paths of all entries matching an attribute filter, sorted
+    pub fn find_by_attributes(&self, filter: &AttributeFilter) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .iter_entries()
+            .filter(|e| filter.matches(e.value()))
+            .map(|e| self.build_path_for_key(e.key()))
+            .collect();
+        paths.sort_unstable();
+
+        logging::info("SEARCH", &format!(
+            "{} entries match attributes 0x{:08X}{}",
+            paths.len(),
+            filter.required,
+            if filter.alternate_streams { " + ADS" } else { "" }
+        ));
+        paths
+    }
+
+    /// Search for files whose name matches a pattern (plain text, `^`/`$`
+    /// anchors or wildcards, see `search_index::matches_pattern`)
     pub fn search(&self, pattern: &str, max_results: usize) -> Vec<SearchResult> {
         logging::separator(&format!("SEARCH: '{}'", pattern));
         let pattern_lower = pattern.to_lowercase();
         let mut results = Vec::new();
 
-        for entry in self.nodes.iter() {
+        for entry in self.iter_entries() {
             if results.len() >= max_results {
                 break;
             }
@@ -1131,11 +2761,7 @@ impl FileTree {
             let node = entry.value();
             let key = *entry.key();
 
-            // Skip entries with no name (incomplete MFT records)
-            if node.name.is_empty() {
-                continue;
-            }
-            if node.name.to_lowercase().contains(&pattern_lower) {
+            if crate::search_index::matches_pattern(&node.name.to_lowercase(), &pattern_lower) {
                 let path = self.build_path_for_key(&key);
 
                 // Log each search result with full details
@@ -1158,23 +2784,17 @@ impl FileTree {
         results
     }
 
-    /// Search with regex (requires regex crate)
+    /// Search for files whose name matches a wildcard pattern (`*`, `?`);
+    /// the same as `search`, which takes wildcards too
     pub fn search_glob(&self, pattern: &str, max_results: usize) -> Vec<SearchResult> {
-        // Simple glob-to-contains conversion
-        let search_term = pattern
-            .replace("*", "")
-            .replace("?", "")
-            .to_lowercase();
-
-        self.search(&search_term, max_results)
+        self.search(pattern, max_results)
     }
 
-    /// Get largest files
+    /// Get largest files (deleted ones take no space and are left out)
     pub fn largest_files(&self, count: usize) -> Vec<SearchResult> {
         let mut files: Vec<_> = self
-            .nodes
-            .iter()
-            .filter(|e| !e.value().is_directory && !e.value().name.is_empty())
+            .iter_files()
+            .filter(|e| !e.value().is_deleted)
             .map(|e| {
                 let key = *e.key();
                 let node = e.value();
@@ -1201,7 +2821,7 @@ impl FileTree {
         let mut dirs: Vec<_> = self
             .nodes
             .iter()
-            .filter(|e| e.value().is_directory)
+            .filter(|e| e.value().is_directory && !e.value().is_deleted)
             .map(|e| {
                 let key = *e.key();
                 let node = e.value();
@@ -1218,16 +2838,869 @@ impl FileTree {
                     let path = self.build_path_for_key(&key);
                     // For directories, use total_size instead of file_size
                     SearchResult {
-                        key,
-                        record_number: node.record_number,
-                        name: node.name.clone(),
-                        path,
                         file_size: node.total_size,
-                        is_directory: node.is_directory,
-                        modification_time: node.modification_time,
+                        ..SearchResult::from_node(&node, path)
                     }
                 })
             })
             .collect()
     }
 }
+
+// ============================================================================
+// Custom Sorting
+// ============================================================================
+
+impl FileTree {
+    /// Sort node keys by a key extracted from each node.
+    ///
+    /// Lets library users order results by anything derivable from a
+    /// `TreeNode`, not just the built-in UI columns. `key` runs once per
+    /// node, on the node in place, so nothing is cloned but the keys. The
+    /// sort is stable; keys that are not in the tree are moved to the end.
+    pub fn sort_by_key<K, F>(&self, keys: &mut [NodeKey], key: F)
+    where
+        K: Ord,
+        F: Fn(&TreeNode) -> K,
+    {
+        // None sorts first, so the flag puts missing nodes last
+        keys.sort_by_cached_key(|k| match self.nodes.get(k) {
+            Some(node) => (false, Some(key(&node))),
+            None => (true, None),
+        });
+    }
+
+    /// Sort node keys with a caller-supplied comparator, for orders that
+    /// don't reduce to a single key. Each node is copied once to compare, so
+    /// prefer `sort_by_key` where a key will do. Stable, missing keys last.
+    pub fn sort_by<F>(&self, keys: &mut [NodeKey], cmp: F)
+    where
+        F: Fn(&TreeNode, &TreeNode) -> Ordering,
+    {
+        let mut resolved: Vec<(NodeKey, Option<TreeNode>)> = keys
+            .iter()
+            .map(|key| (*key, self.get_by_key(key)))
+            .collect();
+
+        resolved.sort_by(|a, b| match (&a.1, &b.1) {
+            (Some(x), Some(y)) => cmp(x, y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+
+        for (slot, (key, _)) in keys.iter_mut().zip(resolved) {
+            *slot = key;
+        }
+    }
+}
+
+// ============================================================================
+// Index Persistence
+// ============================================================================
+
+/// Current on-disk index format version
+const INDEX_VERSION: u32 = 1;
+
+/// Serialized form of a FileTree
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    drive_letter: char,
+    bytes_per_record: u32,
+    #[serde(default = "default_bytes_per_cluster")]
+    bytes_per_cluster: u32,
+    /// Scan start, in seconds since the Unix epoch
+    #[serde(default)]
+    scanned_at: Option<u64>,
+    #[serde(default)]
+    usn_cursor: Option<UsnCursor>,
+    nodes: Vec<TreeNode>,
+}
+
+fn default_bytes_per_cluster() -> u32 {
+    DEFAULT_BYTES_PER_CLUSTER
+}
+
+impl FileTree {
+    /// Save the tree to an index file (JSON) so it can be reloaded or diffed later
+    pub fn save_index(&self, path: impl AsRef<std::path::Path>) -> crate::Result<()> {
+        let index = IndexFile {
+            version: INDEX_VERSION,
+            drive_letter: self.drive_letter,
+            bytes_per_record: self.bytes_per_record,
+            bytes_per_cluster: self.bytes_per_cluster,
+            scanned_at: self
+                .scanned_at
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            usn_cursor: self.usn_cursor,
+            nodes: self.nodes.iter().map(|e| e.value().clone()).collect(),
+        };
+
+        let file = std::fs::File::create(path.as_ref())?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer(writer, &index)
+            .map_err(|e| crate::EmFitError::IndexError(e.to_string()))?;
+
+        logging::info("TREE", &format!(
+            "Saved index for {}: ({} nodes) to {}",
+            self.drive_letter,
+            index.nodes.len(),
+            path.as_ref().display()
+        ));
+        Ok(())
+    }
+
+    /// Load a tree from an index file written by `save_index`.
+    /// Children, aggregated sizes and stats are rebuilt after loading.
+    pub fn load_index(path: impl AsRef<std::path::Path>) -> crate::Result<FileTree> {
+        let file = std::fs::File::open(path.as_ref())?;
+        let reader = std::io::BufReader::new(file);
+        let index: IndexFile = serde_json::from_reader(reader)
+            .map_err(|e| crate::EmFitError::IndexError(e.to_string()))?;
+
+        if index.version != INDEX_VERSION {
+            return Err(crate::EmFitError::IndexError(format!(
+                "Unsupported index version {} (expected {})",
+                index.version, INDEX_VERSION
+            )));
+        }
+
+        let mut builder = TreeBuilder::with_volume_info(index.drive_letter, index.bytes_per_record);
+        builder.set_bytes_per_cluster(index.bytes_per_cluster);
+        builder.add_nodes(index.nodes.into_iter());
+        let mut tree = builder.build();
+        tree.scanned_at = index.scanned_at.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        tree.usn_cursor = index.usn_cursor;
+
+        logging::info("TREE", &format!(
+            "Loaded index for {}: ({} nodes) from {}",
+            tree.drive_letter,
+            tree.len(),
+            path.as_ref().display()
+        ));
+        Ok(tree)
+    }
+}
+
+// ============================================================================
+// File List Import
+// ============================================================================
+
+/// Header-selected columns of an imported file list. Everything lists use
+/// `Filename,Size,Date Modified,Date Created,Attributes`; `cli export -f csv`
+/// writes `Path,Name,Size,Allocated,IsDirectory,Modified`.
+struct ListColumns {
+    path: usize,
+    size: Option<usize>,
+    allocated: Option<usize>,
+    modified: Option<usize>,
+    created: Option<usize>,
+    attributes: Option<usize>,
+    is_directory: Option<usize>,
+}
+
+impl ListColumns {
+    /// Locate columns by (case-insensitive) name; None without a path column
+    fn from_header(fields: &[String]) -> Option<Self> {
+        let find = |names: &[&str]| {
+            fields
+                .iter()
+                .position(|f| names.iter().any(|n| f.trim().eq_ignore_ascii_case(n)))
+        };
+        Some(Self {
+            path: find(&["filename", "path"])?,
+            size: find(&["size"]),
+            allocated: find(&["allocated"]),
+            modified: find(&["date modified", "modified"]),
+            created: find(&["date created", "created"]),
+            attributes: find(&["attributes"]),
+            is_directory: find(&["isdirectory"]),
+        })
+    }
+}
+
+/// Split one CSV line, honouring double quotes and `""` escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Assembles imported rows into tree nodes, creating folders that only
+/// appear as a parent in some row's path
+struct FileListBuilder {
+    nodes: Vec<TreeNode>,
+    /// Lowercased folder path below the drive root -> index in `nodes`
+    dirs: HashMap<String, usize>,
+}
+
+impl FileListBuilder {
+    fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            dirs: HashMap::new(),
+        }
+    }
+
+    /// Record number of the node at `index`; user records start after the
+    /// reserved range so nothing is mistaken for a metafile
+    fn record_at(index: usize) -> u64 {
+        FIRST_USER_RECORD + index as u64
+    }
+
+    /// Record number of a folder, creating it (and its parents) if needed
+    fn dir_record(&mut self, components: &[&str]) -> u64 {
+        if components.is_empty() {
+            return NodeKey::root().record_number;
+        }
+        let lower = components.join("\\").to_lowercase();
+        if let Some(&index) = self.dirs.get(&lower) {
+            return Self::record_at(index);
+        }
+        let parent = self.dir_record(&components[..components.len() - 1]);
+        let index = self.push(TreeNode {
+            parent_record_number: parent,
+            name: components[components.len() - 1].to_string(),
+            attributes: file_attributes::DIRECTORY,
+            is_directory: true,
+            ..Default::default()
+        });
+        self.dirs.insert(lower, index);
+        Self::record_at(index)
+    }
+
+    fn push(&mut self, mut node: TreeNode) -> usize {
+        let index = self.nodes.len();
+        node.record_number = Self::record_at(index);
+        node.file_reference_number = node.record_number;
+        node.name_anomalies = decoded_name_anomalies(&node.name);
+        self.nodes.push(node);
+        index
+    }
+
+    /// Add one row given its path components below the drive root
+    fn add(&mut self, components: &[&str], mut node: TreeNode) {
+        let Some((name, parents)) = components.split_last() else {
+            return;
+        };
+        node.parent_record_number = self.dir_record(parents);
+        node.name = name.to_string();
+
+        if node.is_directory {
+            let lower = components.join("\\").to_lowercase();
+            // A folder seen earlier as a parent gets its own row's metadata
+            if let Some(&index) = self.dirs.get(&lower) {
+                let existing = &mut self.nodes[index];
+                node.record_number = existing.record_number;
+                node.file_reference_number = existing.file_reference_number;
+                node.name_anomalies = existing.name_anomalies;
+                *existing = node;
+            } else {
+                let index = self.push(node);
+                self.dirs.insert(lower, index);
+            }
+        } else {
+            self.push(node);
+        }
+    }
+}
+
+impl FileTree {
+    /// Rebuild a tree from an Everything file list (`.efu`, e.g. written by
+    /// `cli export -f efu`), without touching any volume.
+    ///
+    /// Folders are rebuilt from the full paths; ones that never get a row of
+    /// their own are created empty. Record numbers are made up, so imported
+    /// trees can't be diffed against scans. The drive is taken from the first
+    /// path and rows on other drives are skipped. Folder sizes are
+    /// recomputed from their files, and files without an allocated size are
+    /// treated as having no slack.
+    pub fn from_efu(path: impl AsRef<std::path::Path>) -> crate::Result<FileTree> {
+        Self::from_file_list(path.as_ref())
+    }
+
+    /// Rebuild a tree from a CSV written by `cli export -f csv`, without
+    /// touching any volume. Rows become nodes as in `from_efu`.
+    pub fn from_csv(path: impl AsRef<std::path::Path>) -> crate::Result<FileTree> {
+        Self::from_file_list(path.as_ref())
+    }
+
+    /// Shared reader for `from_efu` / `from_csv`. Columns are picked by the
+    /// header, so either format is accepted by both.
+    fn from_file_list(path: &std::path::Path) -> crate::Result<FileTree> {
+        use std::io::BufRead;
+
+        let file = std::fs::File::open(path)?;
+        let mut lines = std::io::BufReader::new(file).lines();
+        let header = lines.next().transpose()?.unwrap_or_default();
+        let header = split_csv_line(header.trim_start_matches('\u{FEFF}'));
+        let columns = ListColumns::from_header(&header).ok_or_else(|| {
+            crate::EmFitError::IndexError(format!(
+                "{}: no Filename or Path column in header",
+                path.display()
+            ))
+        })?;
+
+        let mut builder = FileListBuilder::new();
+        let mut drive_letter: Option<char> = None;
+        let mut skipped = 0usize;
+
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = split_csv_line(&line);
+            let field = |col: Option<usize>| col.and_then(|c| fields.get(c)).map(|f| f.trim());
+            let number = |col: Option<usize>| {
+                field(col).and_then(|f| f.parse::<u64>().ok()).unwrap_or(0)
+            };
+
+            // Accept \\?\ prefixes and forward slashes as exported with --long-paths / --separator
+            let full = field(Some(columns.path)).unwrap_or_default().replace('/', "\\");
+            let full = full.strip_prefix("\\\\?\\").unwrap_or(&full);
+            let mut chars = full.chars();
+            let (Some(letter), Some(':')) = (chars.next(), chars.next()) else {
+                skipped += 1;
+                continue;
+            };
+            let letter = letter.to_ascii_uppercase();
+            if *drive_letter.get_or_insert(letter) != letter {
+                skipped += 1;
+                continue;
+            }
+            let components: Vec<&str> = chars
+                .as_str()
+                .split('\\')
+                .filter(|c| !c.is_empty())
+                .collect();
+            // The drive root itself
+            if components.is_empty() {
+                continue;
+            }
+
+            let attributes = number(columns.attributes) as u32;
+            let is_directory = match field(columns.is_directory) {
+                Some(flag) => flag.eq_ignore_ascii_case("true") || flag == "1",
+                None => attributes & file_attributes::DIRECTORY != 0,
+            };
+            let file_size = if is_directory { 0 } else { number(columns.size) };
+            let allocated_size = match number(columns.allocated) {
+                0 => file_size,
+                allocated => allocated,
+            };
+
+            builder.add(
+                &components,
+                TreeNode {
+                    file_size,
+                    allocated_size,
+                    attributes,
+                    is_directory,
+                    creation_time: number(columns.created),
+                    modification_time: number(columns.modified),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let drive_letter = drive_letter.ok_or_else(|| {
+            crate::EmFitError::IndexError(format!("{}: no drive paths found", path.display()))
+        })?;
+
+        let root = TreeNode {
+            record_number: NodeKey::root().record_number,
+            parent_record_number: NodeKey::root().parent_record_number,
+            file_reference_number: NodeKey::root().record_number,
+            name: ".".to_string(),
+            attributes: file_attributes::DIRECTORY,
+            is_directory: true,
+            ..Default::default()
+        };
+        let mut tree_builder = TreeBuilder::new(drive_letter);
+        tree_builder.add_nodes(std::iter::once(root).chain(builder.nodes));
+        let tree = tree_builder.build();
+
+        logging::info("TREE", &format!(
+            "Imported file list for {}: ({} nodes, {} rows skipped) from {}",
+            drive_letter,
+            tree.len(),
+            skipped,
+            path.display()
+        ));
+        Ok(tree)
+    }
+}
+
+// ============================================================================
+// Scan Diffing
+// ============================================================================
+
+/// An entry that exists in only one of two compared trees
+#[derive(Debug, Clone)]
+pub struct DiffEntry {
+    pub key: NodeKey,
+    pub name: String,
+    pub path: String,
+    pub file_size: u64,
+    pub is_directory: bool,
+}
+
+/// A file whose size differs between two compared trees
+#[derive(Debug, Clone)]
+pub struct SizeChange {
+    pub key: NodeKey,
+    pub name: String,
+    pub path: String,
+    pub old_size: u64,
+    pub new_size: u64,
+}
+
+impl SizeChange {
+    /// Size difference (new - old) in bytes
+    pub fn delta(&self) -> i64 {
+        self.new_size as i64 - self.old_size as i64
+    }
+}
+
+/// Differences between two scans of the same volume
+#[derive(Debug, Clone, Default)]
+pub struct ScanDiff {
+    /// Entries present in the new tree but not the old one
+    pub added: Vec<DiffEntry>,
+    /// Entries present in the old tree but not the new one
+    pub removed: Vec<DiffEntry>,
+    /// Files present in both trees with a different size
+    pub size_changed: Vec<SizeChange>,
+}
+
+impl ScanDiff {
+    /// Check if the two trees were identical
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.size_changed.is_empty()
+    }
+
+    /// Total size of added files
+    pub fn bytes_added(&self) -> u64 {
+        self.added.iter().filter(|e| !e.is_directory).map(|e| e.file_size).sum()
+    }
+
+    /// Total size of removed files
+    pub fn bytes_removed(&self) -> u64 {
+        self.removed.iter().filter(|e| !e.is_directory).map(|e| e.file_size).sum()
+    }
+
+    /// Net change in used bytes (added - removed + growth of changed files)
+    pub fn net_size_delta(&self) -> i64 {
+        let changed: i64 = self.size_changed.iter().map(|c| c.delta()).sum();
+        self.bytes_added() as i64 - self.bytes_removed() as i64 + changed
+    }
+}
+
+impl FileTree {
+    /// Compare this tree (old) against `other` (new).
+    ///
+    /// Entries are matched on their `NodeKey` (record and parent) and
+    /// lowercase name, so each hard link is compared on its own, and a
+    /// rename, a move or a record reused for a different file counts as
+    /// removed + added. Deleted files (`is_deleted`) are left out.
+    /// Results are sorted by size, largest first.
+    pub fn diff(&self, other: &FileTree) -> ScanDiff {
+        use std::collections::HashMap;
+
+        fn index_of(tree: &FileTree) -> HashMap<(NodeKey, String), (u64, bool)> {
+            tree.nodes
+                .iter()
+                .filter(|e| !e.value().name.is_empty() && !e.value().is_deleted)
+                .map(|e| {
+                    let node = e.value();
+                    (
+                        (*e.key(), node.name.to_lowercase()),
+                        (node.file_size, node.is_directory),
+                    )
+                })
+                .collect()
+        }
+
+        fn diff_entry(tree: &FileTree, key: NodeKey) -> Option<DiffEntry> {
+            tree.get_by_key(&key).map(|node| DiffEntry {
+                key,
+                name: node.name.clone(),
+                path: tree.build_path_for_key(&key),
+                file_size: node.file_size,
+                is_directory: node.is_directory,
+            })
+        }
+
+        let old_index = index_of(self);
+        let new_index = index_of(other);
+        let mut diff = ScanDiff::default();
+
+        for (id, &(old_size, is_dir)) in &old_index {
+            let key = id.0;
+            match new_index.get(id) {
+                None => diff.removed.extend(diff_entry(self, key)),
+                Some(&(new_size, _)) => {
+                    if !is_dir && old_size != new_size {
+                        if let Some(node) = other.get_by_key(&key) {
+                            diff.size_changed.push(SizeChange {
+                                key,
+                                name: node.name.clone(),
+                                path: other.build_path_for_key(&key),
+                                old_size,
+                                new_size,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        for id in new_index.keys() {
+            if !old_index.contains_key(id) {
+                diff.added.extend(diff_entry(other, id.0));
+            }
+        }
+
+        diff.added.sort_by_key(|e| std::cmp::Reverse(e.file_size));
+        diff.removed.sort_by_key(|e| std::cmp::Reverse(e.file_size));
+        diff.size_changed
+            .sort_by_key(|c| std::cmp::Reverse(c.delta().unsigned_abs()));
+
+        logging::info("TREE", &format!(
+            "Diff {}: -> {}: {} added, {} removed, {} size changed",
+            self.drive_letter,
+            other.drive_letter,
+            diff.added.len(),
+            diff.removed.len(),
+            diff.size_changed.len()
+        ));
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(record_number: u64, parent_record_number: u64, name: &str) -> TreeNode {
+        TreeNode {
+            record_number,
+            parent_record_number,
+            name: name.to_string(),
+            is_directory: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn root_is_a_self_reference_and_never_its_own_child() {
+        let root = dir(5, 5, ".");
+        assert!(root.is_self_reference());
+        assert!(!root.is_listed());
+
+        let tree = FileTree::new('C');
+        assert!(tree.insert(root));
+        let root = tree.get_by_key(&NodeKey::root()).unwrap();
+        assert!(root.children.is_empty());
+    }
+
+    #[test]
+    fn ordinary_child_is_listed_under_the_root() {
+        let child = dir(40, 5, "Windows");
+        assert!(!child.is_self_reference());
+        assert!(child.is_listed());
+
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        assert!(tree.insert(child));
+        let root = tree.get_by_key(&NodeKey::root()).unwrap();
+        assert_eq!(root.children, vec![NodeKey::new(40, 5)]);
+        assert_eq!(tree.build_path_for_key(&NodeKey::new(40, 5)), "C:\\Windows");
+    }
+
+    #[test]
+    fn dot_entries_of_other_directories_are_rejected() {
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        tree.insert(dir(40, 5, "Windows"));
+
+        let dot = dir(40, 40, ".");
+        assert!(dot.is_self_reference());
+        assert!(!tree.insert(dot));
+        assert!(!dir(41, 40, "..").is_listed());
+        let windows = tree.get_by_key(&NodeKey::new(40, 5)).unwrap();
+        assert!(windows.children.is_empty());
+    }
+
+    #[test]
+    fn slack_of_a_hard_linked_file_is_counted_once() {
+        let file = |parent_record_number: u64, name: &str, is_deleted: bool| TreeNode {
+            record_number: if is_deleted { 60 } else { 50 },
+            parent_record_number,
+            name: name.to_string(),
+            file_size: 1000,
+            allocated_size: 4096,
+            is_deleted,
+            ..Default::default()
+        };
+
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        tree.insert(dir(40, 5, "Windows"));
+        tree.insert(file(5, "a.txt", false));
+        tree.insert(file(40, "b.txt", false));
+        tree.insert(file(5, "old.txt", true));
+        assert_eq!(tree.total_slack(), 3096);
+    }
+
+    #[test]
+    fn second_link_in_the_same_directory_keeps_the_first_name() {
+        let link = |parent_record_number: u64, name: &str| TreeNode {
+            record_number: 50,
+            parent_record_number,
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        tree.insert(dir(40, 5, "Windows"));
+        assert!(tree.insert(link(5, "a.txt")));
+        assert!(!tree.insert(link(5, "b.txt")));
+        assert!(tree.insert(link(40, "c.txt")));
+
+        assert_eq!(tree.get_by_key(&NodeKey::new(50, 5)).unwrap().name, "a.txt");
+        assert_eq!(tree.hardlink_groups(), vec![vec![NodeKey::new(50, 5), NodeKey::new(50, 40)]]);
+    }
+
+    /// A file created (SI) at `si_created`, modified at `si_modified`, whose
+    /// $FILE_NAME says it was created and modified at `fn_time`
+    fn timed_file(record_number: u64, name: &str, si_created: u64, si_modified: u64, fn_time: u64) -> TreeNode {
+        TreeNode {
+            record_number,
+            parent_record_number: 5,
+            name: name.to_string(),
+            creation_time: si_created,
+            modification_time: si_modified,
+            fn_creation_time: fn_time,
+            fn_modification_time: fn_time,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn zeroed_or_backdated_creation_is_a_timestomp_candidate() {
+        const DAY: u64 = 24 * 60 * 60 * 10_000_000;
+        let fn_time = 133_000_000_000_000_000;
+
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        // Zeroed SI: the parser filled in the FN times and set the flag
+        let mut zeroed = timed_file(40, "zeroed.exe", fn_time, fn_time, fn_time);
+        zeroed.si_zeroed = true;
+        tree.insert(zeroed);
+        tree.insert(timed_file(41, "backdated.exe", fn_time - 400 * DAY, fn_time, fn_time));
+        tree.insert(timed_file(42, "fresh.exe", fn_time, fn_time, fn_time));
+
+        let candidates = tree.timestomp_candidates();
+        let names: Vec<&str> = candidates.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(names, vec!["C:\\zeroed.exe", "C:\\backdated.exe"]);
+        assert!(candidates[0].si_zeroed);
+        assert_eq!(candidates[0].si_creation_time, 0);
+        assert_eq!(candidates[1].divergence(), 400 * DAY);
+    }
+
+    #[test]
+    fn copied_file_with_an_older_modification_time_is_not_flagged() {
+        const DAY: u64 = 24 * 60 * 60 * 10_000_000;
+        let fn_time = 133_000_000_000_000_000;
+
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        // Copying keeps the source's modification time in SI; both
+        // creation times are the copy's
+        tree.insert(timed_file(40, "copied.zip", fn_time, fn_time - 900 * DAY, fn_time));
+        assert!(tree.timestomp_candidates().is_empty());
+    }
+
+    #[test]
+    fn long_path_prefix_is_only_added_with_backslashes() {
+        let style = PathStyle::default().with_long_path_prefix(true);
+        assert_eq!(style.apply(r"C:\dir\name"), r"\\?\C:\dir\name");
+        assert_eq!(style.with_separator('/').apply(r"C:\dir\name"), "C:/dir/name");
+        assert_eq!(style.with_name(false).apply(r"C:\name"), r"\\?\C:\");
+    }
+
+    fn change(reason: ChangeReason, record_number: u64, parent: u64, name: &str) -> ChangeEvent {
+        ChangeEvent {
+            record_number,
+            parent_record_number: parent,
+            name: name.to_string(),
+            reason,
+            attributes: 0,
+            usn: 0,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn moving_a_file_moves_its_size_between_ancestors() {
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        tree.insert(dir(40, 5, "Old"));
+        tree.insert(dir(41, 5, "New"));
+        tree.insert(dir(42, 41, "Inner"));
+        tree.insert(TreeNode {
+            record_number: 50,
+            parent_record_number: 40,
+            name: "big.bin".to_string(),
+            file_size: 100,
+            ..Default::default()
+        });
+        tree.calculate_sizes();
+
+        tree.apply_change(&change(ChangeReason::RenamedFrom, 50, 40, "big.bin"));
+        assert!(tree.apply_change(&change(ChangeReason::RenamedTo, 50, 42, "big.bin")));
+
+        let total = |record, parent| tree.total_size_for_key(&NodeKey::new(record, parent));
+        assert_eq!(total(40, 5), 0);
+        assert_eq!(total(42, 41), 100);
+        assert_eq!(total(41, 5), 100);
+        assert_eq!(total(5, 5), 100);
+        assert_eq!(tree.get_by_key(&NodeKey::new(40, 5)).unwrap().file_count, 0);
+        assert_eq!(tree.get_by_key(&NodeKey::new(41, 5)).unwrap().file_count, 1);
+
+        assert!(tree.apply_change(&change(ChangeReason::Deleted, 50, 42, "big.bin")));
+        assert_eq!(total(5, 5), 0);
+        assert_eq!(tree.get_by_key(&NodeKey::root()).unwrap().file_count, 0);
+    }
+
+    #[test]
+    fn key_for_path_resolves_any_spelling_of_a_full_path() {
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        tree.insert(dir(40, 5, "Windows"));
+        tree.insert(dir(41, 40, "System32"));
+
+        assert_eq!(tree.key_for_path(r"c:/windows\SYSTEM32"), Some(NodeKey::new(41, 40)));
+        assert_eq!(tree.key_for_path(r"\\?\C:\Windows"), Some(NodeKey::new(40, 5)));
+        assert_eq!(tree.key_for_path(r"C:\"), Some(NodeKey::root()));
+        assert_eq!(tree.key_for_path(r"C:\Windows\Fonts"), None);
+        assert_eq!(tree.key_for_path(r"D:\Windows"), None);
+    }
+
+    #[test]
+    fn renaming_onto_a_taken_name_leaves_the_node_in_place() {
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        tree.insert(dir(40, 5, "Projects"));
+        tree.insert(dir(41, 5, "Archive"));
+        tree.insert(TreeNode {
+            record_number: 50,
+            parent_record_number: 40,
+            name: "notes.txt".to_string(),
+            file_size: 100,
+            ..Default::default()
+        });
+        tree.calculate_sizes();
+
+        tree.apply_change(&change(ChangeReason::RenamedFrom, 40, 5, "Projects"));
+        assert!(!tree.apply_change(&change(ChangeReason::RenamedTo, 40, 5, "ARCHIVE")));
+
+        let projects = tree.get_by_key(&NodeKey::new(40, 5)).unwrap();
+        assert_eq!(projects.name, "Projects");
+        assert_eq!(projects.children, vec![NodeKey::new(50, 40)]);
+        assert_eq!(tree.key_for_path(r"C:\Projects\notes.txt"), Some(NodeKey::new(50, 40)));
+        assert_eq!(tree.key_for_path(r"C:\Archive"), Some(NodeKey::new(41, 5)));
+        assert_eq!(tree.total_size_for_key(&NodeKey::root()), 100);
+
+        // A change of case only is not a collision with itself
+        tree.apply_change(&change(ChangeReason::RenamedFrom, 40, 5, "Projects"));
+        assert!(tree.apply_change(&change(ChangeReason::RenamedTo, 40, 5, "projects")));
+        assert_eq!(tree.build_path(50), r"C:\projects\notes.txt");
+    }
+
+    #[test]
+    fn diff_compares_same_name_links_in_different_folders_separately() {
+        let link = |parent_record_number: u64| TreeNode {
+            record_number: 50,
+            parent_record_number,
+            name: "shared.dll".to_string(),
+            file_size: 100,
+            ..Default::default()
+        };
+        let base = || {
+            let tree = FileTree::new('C');
+            tree.insert(dir(5, 5, "."));
+            tree.insert(dir(40, 5, "A"));
+            tree.insert(dir(41, 5, "B"));
+            tree.insert(dir(42, 5, "C"));
+            tree
+        };
+
+        let old = base();
+        old.insert(link(40));
+        old.insert(link(41));
+        old.insert(TreeNode {
+            record_number: 60,
+            parent_record_number: 5,
+            name: "gone.txt".to_string(),
+            is_deleted: true,
+            ..Default::default()
+        });
+
+        // The link in B moved to C; the one in A stays
+        let new = base();
+        new.insert(link(40));
+        new.insert(link(42));
+
+        let diff = old.diff(&new);
+        let paths = |entries: &[DiffEntry]| entries.iter().map(|e| e.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&diff.removed), vec![r"C:\B\shared.dll".to_string()]);
+        assert_eq!(paths(&diff.added), vec![r"C:\C\shared.dll".to_string()]);
+        assert!(diff.size_changed.is_empty());
+    }
+
+    #[test]
+    fn sort_by_key_orders_by_the_extracted_key_and_puts_missing_keys_last() {
+        let tree = FileTree::new('C');
+        tree.insert(dir(5, 5, "."));
+        for (record, size) in [(60, 30), (61, 10), (62, 20)] {
+            tree.insert(TreeNode {
+                record_number: record,
+                parent_record_number: 5,
+                name: format!("f{}", record),
+                file_size: size,
+                ..Default::default()
+            });
+        }
+
+        let mut keys = vec![
+            NodeKey::new(99, 5),
+            NodeKey::new(60, 5),
+            NodeKey::new(61, 5),
+            NodeKey::new(62, 5),
+        ];
+        tree.sort_by_key(&mut keys, |node| node.file_size);
+        let records: Vec<u64> = keys.iter().map(|k| k.record_number).collect();
+        assert_eq!(records, vec![61, 62, 60, 99]);
+    }
+}
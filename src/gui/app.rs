@@ -1,14 +1,28 @@
-use crate::file_tree::NodeKey;
+use crate::arena::{ExtId, ExtensionTable, StrId, StringArena};
+use crate::file_tree::{DateField, NodeKey};
+use crate::ntfs::structs::{file_attributes, Storage};
+use crate::shell;
 use crate::gui::colors;
 use crate::gui::dialogs::{self, SearchFilters};
-use crate::gui::search::{matches_pattern, SearchState};
-use crate::gui::table::{SortColumn, SortOrder, TableState};
-use crate::gui::treemap::TreemapState;
-use crate::{FileTree, MultiVolumeScanner, ScanConfig, VolumeScanner};
+use crate::gui::groups::{self, KindGroup, ViewMode};
+use crate::gui::search::{
+    matches_pattern, pattern_counts_label, relevance_rank, ResultOrder, SearchState,
+};
+use crate::gui::table::{RowDensity, SortColumn, SortOrder, TableState};
+use crate::treemap::TreemapState;
+use crate::palette::{self, TreemapPalette};
+use crate::search_index::{full_path_lower, matches_path, next_row_by_initial};
+use crate::threads;
+use crate::{
+    ErrorKind, FileKind, FileTree, JournalStatus, KindSummary, MultiVolumeScanner, ScanConfig,
+    SizeBasis, SizeSum, VolumeScanner,
+};
 use eframe::egui;
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // Background messages
@@ -16,13 +30,47 @@ use std::thread;
 
 pub enum BgMessage {
     ScanProgress(String),
+    /// Names-only tree from USN enumeration, replaced by `ScanComplete`
+    ScanPreview(Arc<FileTree>),
     ScanComplete(Arc<FileTree>),
     ScanError(String),
-    SortComplete(SortColumn, Vec<usize>),
+    /// Sorted result indices, tagged with the sort's generation
+    SortComplete(u64, SortColumn, Vec<usize>),
     MetadataRefreshComplete(Vec<(usize, u64, u64)>),
-    PathCacheComplete(Vec<(usize, String)>),
+    /// One batch of resolved parent paths, tagged with the path cache's
+    /// generation
+    PathCacheProgress(u64, Vec<(usize, String)>),
+    PathCacheComplete(u64),
+    /// How the loaded drives' USN journals compare with their scans
+    JournalChecked(JournalStatus),
 }
 
+impl BgMessage {
+    /// Whether handling this message adds to the string arena
+    fn writes_strings(&self) -> bool {
+        matches!(
+            self,
+            BgMessage::ScanPreview(_) | BgMessage::ScanComplete(_) | BgMessage::PathCacheProgress(..)
+        )
+    }
+}
+
+/// Entries resolved per path-cache message
+const PATH_CACHE_BATCH: usize = 50_000;
+
+/// How often the loaded drives' USN journals are compared with their scans
+const JOURNAL_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Levels and folders per level in a copied size report
+const SIZE_REPORT_DEPTH: usize = 2;
+const SIZE_REPORT_TOP: usize = 10;
+
+/// eframe storage key of the chosen row density
+const ROW_DENSITY_KEY: &str = "row_density";
+
+/// Name colour of deleted files (`ScanConfig::include_deleted`)
+const DELETED_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 90, 90);
+
 // ============================================================================
 // Cached entry data (same as TUI)
 // ============================================================================
@@ -32,14 +80,45 @@ pub struct EntryData {
     pub tree_index: usize,
     pub key: NodeKey,
     pub file_reference_number: u64,
-    pub name: String,
-    pub name_lower: String,
-    pub extension: String,
+    pub name: StrId,
+    pub name_lower: StrId,
+    /// Interned lowercase extension (see `extensions`)
+    pub extension: ExtId,
     pub file_size: u64,
     pub modification_time: u64,
     pub is_directory: bool,
-    pub cached_path: String,
-    pub path_lower: String,
+    /// File attributes (hidden, system, ...)
+    pub attributes: u32,
+    /// Type label for NTFS metafiles, shown instead of the extension type
+    pub metafile_label: Option<&'static str>,
+    /// Junction or symlink pointing to another volume; shown with a link icon
+    pub off_volume_link: bool,
+    /// Has alternate data streams (for `attr:ads`)
+    pub has_streams: bool,
+    /// Deleted file still in the MFT; drawn struck through
+    pub is_deleted: bool,
+    /// Resident or non-resident data, for the Storage column
+    pub storage: Storage,
+    pub cached_path: StrId,
+    pub path_lower: StrId,
+}
+
+impl EntryData {
+    /// Check if the entry has the hidden attribute
+    pub fn is_hidden(&self) -> bool {
+        (self.attributes & file_attributes::HIDDEN) != 0
+    }
+
+    /// Check if the entry has the system attribute
+    pub fn is_system(&self) -> bool {
+        (self.attributes & file_attributes::SYSTEM) != 0
+    }
+
+    /// File whose size and modification time have not been read yet
+    /// (USN scans leave both at zero until the metadata refresh)
+    pub fn has_unknown_metadata(&self) -> bool {
+        !self.is_directory && self.file_size == 0 && self.modification_time == 0
+    }
 }
 
 // ============================================================================
@@ -62,12 +141,17 @@ enum ActiveDialog {
     SearchFilters(SearchFilters),
     Confirm { message: String, action: PendingAction },
     Rename { original_path: String, original_name: String, new_name: String },
+    BatchRename(dialogs::BatchRename),
+    DriveStats(Vec<(char, crate::TreeStats)>),
+    KindSummary(KindSummary),
     Info { title: String, lines: Vec<String> },
 }
 
 #[derive(Clone)]
 enum PendingAction {
     Delete,
+    /// Set or clear one attribute flag (read-only or hidden) on the selection
+    ToggleAttribute(u32),
 }
 
 #[derive(Clone)]
@@ -85,6 +169,8 @@ pub struct GuiApp {
     // Data
     trees: Vec<Arc<FileTree>>,
     all_entries: Vec<EntryData>,
+    strings: Arc<StringArena>,
+    extensions: ExtensionTable,
     filtered_indices: Vec<usize>,
 
     // Sub-states
@@ -94,13 +180,32 @@ pub struct GuiApp {
     // Scanning
     is_scanning: bool,
     is_sorting: bool,
+    // Tags each background sort; bumped whenever a running sort's results
+    // go stale, so its SortComplete is dropped
+    sort_generation: u64,
     is_refreshing_metadata: bool,
+    // Parent paths resolved so far and total, while caching runs
+    path_cache_progress: Option<(usize, usize)>,
+    path_cache_cancel: Arc<AtomicBool>,
+    // Tags each path cache run; bumped when a new run starts or a drive
+    // rescan shifts entry indices, so stale batches are dropped
+    path_cache_generation: u64,
+    // Stops the scan thread; set when the window closes mid-scan
+    scan_cancel: Arc<AtomicBool>,
     scan_progress: String,
     status_message: String,
     total_count: u64,
 
     // Drives
     selected_drives: Vec<char>,
+    // Drive being rescanned on its own (Shift+F9)
+    rescan_drive: Option<char>,
+    // Drives whose loaded tree is a scan preview, still without sizes
+    preview_drives: Vec<char>,
+    // Worst USN journal status of the loaded drives, and when it was last
+    // asked for; checked every `JOURNAL_CHECK_INTERVAL`
+    journal_status: JournalStatus,
+    journal_checked_at: Option<Instant>,
 
     // Sort cache
     last_sort_column: Option<SortColumn>,
@@ -109,6 +214,10 @@ pub struct GuiApp {
     // Channel
     bg_receiver: Option<Receiver<BgMessage>>,
     bg_sender: Option<Sender<BgMessage>>,
+    // Messages waiting for a sort to drop its clone of `strings`, so
+    // handling them doesn't copy the arena; looked at again when the next
+    // message arrives
+    held_messages: Vec<BgMessage>,
 
     // Metadata
     pending_metadata_refresh: std::collections::HashSet<usize>,
@@ -121,6 +230,8 @@ pub struct GuiApp {
 
     // Treemap
     treemap: Option<TreemapState>,
+    // Treemap sized by logical or allocated size (kept while it is closed)
+    treemap_size: SizeBasis,
 
     // Context menu
     context_menu: Option<ContextMenu>,
@@ -130,45 +241,173 @@ pub struct GuiApp {
 
     // Search focus flag (for auto-focus TextEdit)
     request_search_focus: bool,
+
+    // How often to poll background work while it is running
+    refresh_interval: std::time::Duration,
+
+    // List hidden and system files in results (filtered at search time,
+    // so toggling needs no rescan)
+    show_hidden: bool,
+    show_system: bool,
+
+    // Match text patterns against the full path rather than the name
+    match_path: bool,
+
+    // Folder Enter browsed into (tree index, key) and the query that set;
+    // while the query is unchanged the results are its direct children
+    browsed_folder: Option<(String, usize, NodeKey)>,
+
+    // Hide files whose size and date are not known yet
+    hide_unknown_metadata: bool,
+    // Results held back by `hide_unknown_metadata`, refreshed in the background
+    unknown_metadata_held: std::collections::HashSet<usize>,
+
+    // Whether the selected size in the status bar includes directories
+    size_sum: SizeSum,
+    // Timestamp shown and sorted in the date column
+    date_field: DateField,
+
+    // Optional MFT record number column
+    show_record_column: bool,
+    // Optional resident / fragment count column
+    show_storage_column: bool,
+    // Result row height and text size, remembered between sessions
+    row_density: RowDensity,
+
+    // Treemap colours
+    palette: &'static TreemapPalette,
+
+    // Flat table or grouped by file kind
+    view_mode: ViewMode,
+    // Groups for the current results (rebuilt lazily when results change)
+    kind_groups: Option<Vec<KindGroup>>,
+
+    // Path to select once its drive is loaded (`--select`)
+    pending_select: Option<String>,
+    // Row the file table scrolls to on its next frame
+    scroll_to_row: Option<usize>,
+
+    // Read sizes from the MFT while scanning (`--no-sizes` turns it off)
+    calculate_sizes: bool,
+    // List deleted files still in the MFT (`--deleted`)
+    include_deleted: bool,
+    // First scan, started on the first frame once the builder options are set
+    initial_scan_pending: bool,
 }
 
 impl GuiApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let available_drives = MultiVolumeScanner::detect_ntfs_volumes();
         let selected_drives = available_drives.clone();
         let preset_filters = load_preset_filters();
 
-        let mut app = Self {
+        Self {
             trees: Vec::new(),
             all_entries: Vec::new(),
+            strings: Arc::new(StringArena::new()),
+            extensions: ExtensionTable::new(),
             filtered_indices: Vec::new(),
             search: SearchState::default(),
             table: TableState::default(),
             is_scanning: false,
             is_sorting: false,
+            sort_generation: 0,
             is_refreshing_metadata: false,
+            path_cache_progress: None,
+            path_cache_cancel: Arc::new(AtomicBool::new(false)),
+            path_cache_generation: 0,
+            scan_cancel: Arc::new(AtomicBool::new(false)),
             scan_progress: String::new(),
             status_message: "Ready".to_string(),
             total_count: 0,
             selected_drives,
+            rescan_drive: None,
+            preview_drives: Vec::new(),
+            journal_status: JournalStatus::Unknown,
+            journal_checked_at: None,
             last_sort_column: None,
             last_sort_order: SortOrder::Ascending,
             bg_receiver: None,
             bg_sender: None,
+            held_messages: Vec::new(),
             pending_metadata_refresh: std::collections::HashSet::new(),
             active_dialog: ActiveDialog::None,
             search_filters: SearchFilters::new(),
             treemap: None,
+            treemap_size: SizeBasis::default(),
             preset_filters,
             request_search_focus: true,
             context_menu: None,
-        };
-
-        if !app.selected_drives.is_empty() {
-            app.start_scan();
+            refresh_interval: std::time::Duration::from_millis(
+                crate::AppConfig::default().refresh_interval_ms,
+            ),
+            show_hidden: crate::AppConfig::default().show_hidden_results,
+            show_system: crate::AppConfig::default().show_system_results,
+            match_path: false,
+            browsed_folder: None,
+            hide_unknown_metadata: false,
+            unknown_metadata_held: std::collections::HashSet::new(),
+            size_sum: SizeSum::default(),
+            date_field: DateField::default(),
+            show_record_column: false,
+            show_storage_column: false,
+            row_density: cc
+                .storage
+                .and_then(|storage| storage.get_string(ROW_DENSITY_KEY))
+                .and_then(|label| RowDensity::from_label(&label))
+                .unwrap_or_default(),
+            palette: &palette::DEFAULT,
+            view_mode: ViewMode::default(),
+            kind_groups: None,
+            pending_select: None,
+            scroll_to_row: None,
+            calculate_sizes: true,
+            include_deleted: false,
+            initial_scan_pending: true,
         }
+    }
+
+    /// Set how often the UI repaints while background work is running
+    pub fn with_refresh_interval(mut self, interval: std::time::Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Show or hide hidden files in results
+    pub fn with_show_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self
+    }
 
-        app
+    /// Show or hide system files in results
+    pub fn with_show_system(mut self, show_system: bool) -> Self {
+        self.show_system = show_system;
+        self
+    }
+
+    /// Select `path` in the results once the scan has loaded it
+    pub fn with_select_path(mut self, path: Option<String>) -> Self {
+        self.pending_select = path;
+        self
+    }
+
+    /// Read sizes during the scan, or list names first and let the metadata
+    /// refresh fill in the sizes of shown results
+    pub fn with_calculate_sizes(mut self, calculate_sizes: bool) -> Self {
+        self.calculate_sizes = calculate_sizes;
+        self
+    }
+
+    /// Also list deleted files whose MFT records were not reused yet
+    pub fn with_include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = include_deleted;
+        self
+    }
+
+    /// Colour the treemap with the named palette (unknown names keep the default)
+    pub fn with_palette(mut self, name: &str) -> Self {
+        self.palette = TreemapPalette::by_name(name).unwrap_or(&palette::DEFAULT);
+        self
     }
 
     // ====================================================================
@@ -180,41 +419,48 @@ impl GuiApp {
             return;
         }
 
+        self.cancel_path_cache();
         self.is_scanning = true;
         self.scan_progress = "Starting scan...".to_string();
         self.trees.clear();
+        self.preview_drives.clear();
         self.all_entries.clear();
+        self.strings = Arc::new(StringArena::new());
+        self.extensions = ExtensionTable::new();
         self.filtered_indices.clear();
+        self.kind_groups = None;
         self.table.selected = None;
         self.total_count = 0;
         self.last_sort_column = None;
 
         let (tx, rx) = channel();
         self.bg_receiver = Some(rx);
+        self.held_messages.clear();
         self.bg_sender = Some(tx.clone());
 
         let drives = self.selected_drives.clone();
+        let config = scan_config(self.calculate_sizes, self.include_deleted);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.scan_cancel = cancel.clone();
 
         thread::spawn(move || {
             for drive in drives {
                 let _ = tx.send(BgMessage::ScanProgress(format!("Scanning {}:...", drive)));
 
-                let config = ScanConfig {
-                    use_usn: true,
-                    use_mft: true,
-                    include_hidden: true,
-                    include_system: true,
-                    calculate_sizes: true,
-                    show_progress: false,
-                    ..Default::default()
-                };
-
-                let mut scanner = VolumeScanner::new(drive).with_config(config);
+                let preview_tx = tx.clone();
+                let mut scanner = VolumeScanner::new(drive)
+                    .with_config(config.clone())
+                    .with_cancel_token(cancel.clone())
+                    .with_preview(move |tree| {
+                        let _ = preview_tx.send(BgMessage::ScanPreview(Arc::new(tree)));
+                    });
 
                 match scanner.scan() {
                     Ok(tree) => {
                         let _ = tx.send(BgMessage::ScanComplete(Arc::new(tree)));
                     }
+                    // The window is gone; leave the remaining drives alone
+                    Err(e) if e.kind() == ErrorKind::Cancelled => break,
                     Err(e) => {
                         let _ = tx.send(BgMessage::ScanError(format!(
                             "Error scanning {}: {}",
@@ -226,58 +472,135 @@ impl GuiApp {
         });
     }
 
+    /// Rescan only the drive of the selected result. The other drives keep
+    /// their trees and entries; the new tree replaces the old one when done.
+    fn start_drive_rescan(&mut self) {
+        if self.is_scanning {
+            return;
+        }
+        let Some(drive) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+            .and_then(|entry| self.trees.get(entry.tree_index))
+            .map(|tree| tree.drive_letter)
+        else {
+            self.status_message = "Select a result to rescan its drive".to_string();
+            return;
+        };
+        let Some(tx) = self.bg_sender.clone() else {
+            return;
+        };
+
+        // Path cache batches are addressed by entry index, which the
+        // replacement shifts
+        self.cancel_path_cache();
+        self.is_scanning = true;
+        self.rescan_drive = Some(drive);
+        self.scan_progress = format!("Rescanning {}:...", drive);
+        let config = scan_config(self.calculate_sizes, self.include_deleted);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.scan_cancel = cancel.clone();
+
+        thread::spawn(move || {
+            let mut scanner = VolumeScanner::new(drive)
+                .with_config(config)
+                .with_cancel_token(cancel);
+            match scanner.scan() {
+                Ok(tree) => {
+                    let _ = tx.send(BgMessage::ScanComplete(Arc::new(tree)));
+                }
+                Err(e) if e.kind() == ErrorKind::Cancelled => {}
+                Err(e) => {
+                    let _ = tx.send(BgMessage::ScanError(format!(
+                        "Error scanning {}: {}",
+                        drive, e
+                    )));
+                }
+            }
+        });
+    }
+
     // ====================================================================
     // Message processing (called every frame)
     // ====================================================================
 
-    fn process_messages(&mut self) {
-        let rx = match &self.bg_receiver {
-            Some(rx) => rx,
-            None => return,
+    /// Drain background messages. Returns true if any were received.
+    fn process_messages(&mut self) -> bool {
+        let Some(rx) = &self.bg_receiver else {
+            return false;
         };
-
-        while let Ok(msg) = rx.try_recv() {
+        // Workers drop their clones before sending their result, so held
+        // messages can only go once something new arrives
+        let received: Vec<BgMessage> = rx.try_iter().collect();
+        if received.is_empty() {
+            return false;
+        }
+        let mut messages = std::mem::take(&mut self.held_messages);
+        messages.extend(received);
+
+        let mut scan_finished = false;
+        let mut tree_loaded = false;
+        let mut messages = messages.into_iter();
+        while let Some(msg) = messages.next() {
+            // Workers only read their clone of the arena, but writing to it
+            // while they hold one copies it whole. Hold this message and the
+            // ones after it (to keep their order) until they are done.
+            if msg.writes_strings() && Arc::get_mut(&mut self.strings).is_none() {
+                self.held_messages.push(msg);
+                self.held_messages.extend(messages);
+                break;
+            }
             match msg {
                 BgMessage::ScanProgress(msg) => {
                     self.scan_progress = msg;
                 }
+                BgMessage::ScanPreview(tree) => {
+                    // Stale once the drive's full tree is in
+                    let drive = tree.drive_letter;
+                    if self.trees.iter().any(|t| t.drive_letter == drive) {
+                        continue;
+                    }
+                    self.scan_progress = format!(
+                        "{}: {} names found, reading sizes...",
+                        drive,
+                        tree.stats.total_files + tree.stats.total_directories
+                    );
+                    self.load_tree(tree);
+                    self.preview_drives.push(drive);
+                    tree_loaded = true;
+                }
                 BgMessage::ScanComplete(tree) => {
                     let drive = tree.drive_letter;
                     let files = tree.stats.total_files;
                     let dirs = tree.stats.total_directories;
 
-                    if self.trees.iter().any(|t| t.drive_letter == drive) {
+                    // Prevent duplicates, unless this drive is being rescanned
+                    // or only its preview is loaded
+                    let existing = self.trees.iter().any(|t| t.drive_letter == drive);
+                    if existing
+                        && !self.preview_drives.contains(&drive)
+                        && self.rescan_drive != Some(drive)
+                    {
                         continue;
                     }
-
-                    let tree_index = self.trees.len();
-                    for entry in tree.iter() {
-                        let key = *entry.key();
-                        let node = entry.value();
-                        if !node.name.is_empty() {
-                            let extension = extract_extension(&node.name);
-                            self.all_entries.push(EntryData {
-                                tree_index,
-                                key,
-                                file_reference_number: node.file_reference_number,
-                                name: node.name.clone(),
-                                name_lower: node.name.to_lowercase(),
-                                extension,
-                                file_size: node.file_size,
-                                modification_time: node.modification_time,
-                                is_directory: node.is_directory,
-                                cached_path: String::new(),
-                                path_lower: String::new(),
-                            });
-                        }
-                    }
-
-                    self.trees.push(tree);
-                    self.total_count += files + dirs;
+                    self.load_tree(tree);
+                    self.preview_drives.retain(|&d| d != drive);
+                    tree_loaded = true;
                     self.status_message =
                         format!("Loaded {}: - {} files, {} directories", drive, files, dirs);
 
-                    if self.trees.len() >= self.selected_drives.len() {
+                    if self.rescan_drive == Some(drive) {
+                        self.rescan_drive = None;
+                        self.is_scanning = false;
+                        self.scan_progress.clear();
+                        self.status_message = format!(
+                            "Rescanned {}: - {} files, {} directories",
+                            drive, files, dirs
+                        );
+                        scan_finished = true;
+                    } else if self.scanned_tree_count() >= self.selected_drives.len() {
                         self.is_scanning = false;
                         self.scan_progress.clear();
                         let total_files: u64 =
@@ -286,51 +609,206 @@ impl GuiApp {
                             self.trees.iter().map(|t| t.stats.total_directories).sum();
                         self.status_message =
                             format!("{} files, {} folders", total_files, total_dirs);
-                        self.search.needs_search = true;
-                        self.start_path_cache();
+                        scan_finished = true;
                     }
                 }
                 BgMessage::ScanError(msg) => {
                     self.status_message = msg;
-                    if self.trees.len() >= self.selected_drives.len().saturating_sub(1) {
+                    if self.rescan_drive.take().is_some()
+                        || self.scanned_tree_count() >= self.selected_drives.len().saturating_sub(1)
+                    {
                         self.is_scanning = false;
                         self.scan_progress.clear();
+                        // A failed drive's preview is all there will be
+                        self.preview_drives.clear();
                         if !self.all_entries.is_empty() {
                             self.search.needs_search = true;
                         }
                     }
                 }
-                BgMessage::SortComplete(column, sorted_indices) => {
+                BgMessage::SortComplete(generation, column, sorted_indices) => {
+                    // Superseded by a new search or sort, or a drive rescan
+                    if generation != self.sort_generation {
+                        continue;
+                    }
                     self.filtered_indices = sorted_indices;
+                    self.kind_groups = None;
                     self.last_sort_column = Some(column);
                     self.last_sort_order = self.table.sort_order;
                     self.is_sorting = false;
                     self.status_message = format!("{} objects", self.filtered_indices.len());
                 }
                 BgMessage::MetadataRefreshComplete(updates) => {
+                    let mut held_resolved = false;
                     for (entry_idx, file_size, modification_time) in updates {
+                        // Not pending if a drive rescan dropped it meanwhile
+                        if !self.pending_metadata_refresh.remove(&entry_idx) {
+                            continue;
+                        }
                         if let Some(entry) = self.all_entries.get_mut(entry_idx) {
                             entry.file_size = file_size;
                             entry.modification_time = modification_time;
                         }
-                        self.pending_metadata_refresh.remove(&entry_idx);
+                        if self.unknown_metadata_held.contains(&entry_idx) {
+                            held_resolved = true;
+                        }
                     }
                     self.is_refreshing_metadata = false;
+                    // Held-back results now have metadata; search again to show them
+                    if held_resolved {
+                        self.search.needs_search = true;
+                    }
+                    self.kind_groups = None;
                 }
-                BgMessage::PathCacheComplete(paths) => {
+                BgMessage::PathCacheProgress(generation, paths) => {
+                    // Indices from before a drive rescan, or a replaced run
+                    if generation != self.path_cache_generation {
+                        continue;
+                    }
+                    if let Some((done, _)) = self.path_cache_progress.as_mut() {
+                        *done += paths.len();
+                    }
+                    let strings = Arc::make_mut(&mut self.strings);
                     for (entry_idx, path) in paths {
                         if let Some(entry) = self.all_entries.get_mut(entry_idx) {
-                            entry.path_lower = path.to_lowercase();
-                            entry.cached_path = path;
+                            entry.cached_path = strings.intern(&path);
+                            entry.path_lower = strings.intern(&path.to_lowercase());
                         }
                     }
+                }
+                BgMessage::JournalChecked(status) => {
+                    self.journal_status = status;
+                }
+                BgMessage::PathCacheComplete(generation) => {
+                    if generation != self.path_cache_generation {
+                        continue;
+                    }
+                    self.path_cache_progress = None;
                     self.status_message = format!(
-                        "{} objects (paths cached)",
-                        self.filtered_indices.len()
+                        "{} objects (paths cached, {})",
+                        self.filtered_indices.len(),
+                        self.memory_report()
                     );
                 }
             }
         }
+
+        if scan_finished {
+            // Search now so the path cache can start with the results
+            self.perform_search();
+            self.search.needs_search = false;
+            if let Some(path) = self.pending_select.take() {
+                if !self.reveal_path(&path) {
+                    self.status_message = format!("Not in the results: {}", path);
+                }
+            }
+            self.start_path_cache();
+        } else if tree_loaded {
+            // Show what the scan has found so far
+            self.perform_search();
+            self.search.needs_search = false;
+        }
+        true
+    }
+
+    /// Add a scanned tree and its entries, replacing the drive's current
+    /// tree (its scan preview, or the old tree on a rescan) if there is one
+    fn load_tree(&mut self, tree: Arc<FileTree>) {
+        let drive = tree.drive_letter;
+        // A fresh tree is current; look again after the usual interval
+        self.journal_status = JournalStatus::Unknown;
+        self.journal_checked_at = Some(Instant::now());
+        let existing = self.trees.iter().position(|t| t.drive_letter == drive);
+
+        // Names of the replaced tree, reused where unchanged so the arena
+        // doesn't hold them twice
+        let mut old_names: std::collections::HashMap<NodeKey, (StrId, StrId)> =
+            std::collections::HashMap::new();
+        if let Some(old_index) = existing {
+            // Forget the old tree's entries. Indices shift, so results and
+            // background work that refer to them go too; names that changed
+            // stay in `strings` until a full scan.
+            let stats = &self.trees[old_index].stats;
+            self.total_count = self
+                .total_count
+                .saturating_sub(stats.total_files + stats.total_directories);
+            old_names = self
+                .all_entries
+                .iter()
+                .filter(|e| e.tree_index == old_index)
+                .map(|e| (e.key, (e.name, e.name_lower)))
+                .collect();
+            self.all_entries.retain(|e| e.tree_index != old_index);
+            self.filtered_indices.clear();
+            self.unknown_metadata_held.clear();
+            self.pending_metadata_refresh.clear();
+            // A sort still running would hand back stale indices
+            self.is_sorting = false;
+            self.sort_generation += 1;
+            // Likewise a path cache still running
+            self.path_cache_cancel.store(true, Ordering::Relaxed);
+            self.path_cache_generation += 1;
+            self.path_cache_progress = None;
+            self.last_sort_column = None;
+            self.kind_groups = None;
+            self.table.selected = None;
+            self.table.selections.clear();
+            self.treemap = None;
+        }
+
+        // Build EntryData with cached sort keys
+        let tree_index = existing.unwrap_or(self.trees.len());
+        let strings = Arc::make_mut(&mut self.strings);
+        for entry in tree.iter_entries() {
+            let key = *entry.key();
+            let node = entry.value();
+            let extension = self
+                .extensions
+                .intern(&extract_extension(&node.name));
+            let (name, name_lower) = match old_names.get(&key) {
+                Some(&(name, name_lower)) if strings.get(name) == node.name => (name, name_lower),
+                _ => {
+                    let name = strings.push(&node.name);
+                    let lower = node.name.to_lowercase();
+                    let name_lower = if lower == node.name {
+                        name
+                    } else {
+                        strings.push(&lower)
+                    };
+                    (name, name_lower)
+                }
+            };
+            self.all_entries.push(EntryData {
+                tree_index,
+                key,
+                file_reference_number: node.file_reference_number,
+                name,
+                name_lower,
+                extension,
+                file_size: node.file_size,
+                modification_time: node.modification_time,
+                is_directory: node.is_directory,
+                attributes: node.attributes,
+                metafile_label: node.metafile_label(),
+                off_volume_link: node.is_off_volume_link(drive),
+                has_streams: node.alternate_stream_count > 0,
+                is_deleted: node.is_deleted,
+                storage: node.storage,
+                cached_path: StringArena::EMPTY,
+                path_lower: StringArena::EMPTY,
+            });
+        }
+
+        self.total_count += tree.stats.total_files + tree.stats.total_directories;
+        match existing {
+            Some(old_index) => self.trees[old_index] = tree,
+            None => self.trees.push(tree),
+        }
+    }
+
+    /// Trees loaded from finished scans, not counting previews
+    fn scanned_tree_count(&self) -> usize {
+        self.trees.len() - self.preview_drives.len()
     }
 
     // ====================================================================
@@ -339,6 +817,12 @@ impl GuiApp {
 
     fn perform_search(&mut self) {
         self.filtered_indices.clear();
+        self.unknown_metadata_held.clear();
+        // A sort still running works on the old results
+        self.is_sorting = false;
+        self.sort_generation += 1;
+        self.search.pattern_counts.clear();
+        self.kind_groups = None;
         self.last_sort_column = None;
 
         if self.trees.is_empty() || self.all_entries.is_empty() {
@@ -347,6 +831,7 @@ impl GuiApp {
 
         let raw_query = self.search.query.trim().to_lowercase();
         let (scope_path, search_query) = parse_scope_path(&raw_query);
+        let folder = self.browsed_folder();
 
         let regex_filter = if !self.search_filters.regex_pattern.is_empty() {
             regex::Regex::new(&self.search_filters.regex_pattern).ok()
@@ -368,27 +853,45 @@ impl GuiApp {
             Vec::new()
         };
 
+        // Extensions that don't exist in the table can never match
+        let ext_filter_ids: Vec<ExtId> = ext_filter
+            .iter()
+            .filter_map(|e| self.extensions.id_of(e))
+            .collect();
+
+        // Inline `size:`, `modified:` and `ext:` operators from the query bar
+        let query = crate::search_index::parse_query(&search_query);
+
         let has_filters = regex_filter.is_some()
             || date_filter.is_some()
             || size_filter.is_some()
-            || !ext_filter.is_empty();
+            || !ext_filter.is_empty()
+            || query.has_operators();
 
-        let no_text_query = search_query.is_empty();
+        let no_text_query = query.patterns.is_empty();
+        let match_path = self.match_path || query.match_path;
 
-        let patterns: Vec<&str> = if no_text_query {
-            Vec::new()
-        } else {
-            search_query
-                .split(';')
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .collect()
-        };
+        let patterns: Vec<&str> = query.patterns.iter().map(|p| p.as_str()).collect();
+        // Results per pattern, only worth telling apart with several
+        let mut pattern_counts = vec![0usize; if patterns.len() > 1 { patterns.len() } else { 0 }];
 
         for (idx, entry) in self.all_entries.iter().enumerate() {
-            if let Some(ref scope) = scope_path {
-                if entry.path_lower.is_empty()
-                    || !entry.path_lower.starts_with(scope.as_str())
+            if (!self.show_hidden && entry.is_hidden()) || (!self.show_system && entry.is_system())
+            {
+                continue;
+            }
+
+            if let Some((tree_index, folder)) = folder {
+                if entry.tree_index != tree_index
+                    || entry.key.parent_record_number != folder
+                    || entry.key.record_number == folder
+                {
+                    continue;
+                }
+            } else if let Some(ref scope) = scope_path {
+                let path_lower = self.strings.get(entry.path_lower);
+                if path_lower.is_empty()
+                    || !path_in_scope(path_lower, self.strings.get(entry.name_lower), scope)
                 {
                     continue;
                 }
@@ -396,10 +899,16 @@ impl GuiApp {
 
             let text_match = if no_text_query {
                 true
+            } else if match_path {
+                let full_path = full_path_lower(
+                    self.strings.get(entry.path_lower),
+                    self.strings.get(entry.name_lower),
+                );
+                patterns.iter().any(|p| matches_path(&full_path, p))
             } else {
                 patterns
                     .iter()
-                    .any(|p| matches_pattern(&entry.name_lower, p))
+                    .any(|p| matches_pattern(self.strings.get(entry.name_lower), p))
             };
 
             if !text_match {
@@ -407,7 +916,7 @@ impl GuiApp {
             }
 
             if let Some(ref re) = regex_filter {
-                if !re.is_match(&entry.name) {
+                if !re.is_match(self.strings.get(entry.name)) {
                     continue;
                 }
             }
@@ -424,17 +933,60 @@ impl GuiApp {
                 }
             }
 
-            if !ext_filter.is_empty() && !ext_filter.contains(&entry.extension) {
+            if !ext_filter.is_empty() && !ext_filter_ids.contains(&entry.extension) {
+                continue;
+            }
+
+            if query.has_operators()
+                && !query.matches_operators(
+                    self.extensions.get(entry.extension),
+                    entry.file_size,
+                    entry.modification_time,
+                    entry.attributes,
+                    entry.has_streams,
+                )
+            {
+                continue;
+            }
+
+            if self.hide_unknown_metadata && entry.has_unknown_metadata() {
+                self.unknown_metadata_held.insert(idx);
                 continue;
             }
 
             self.filtered_indices.push(idx);
-        }
 
-        if no_text_query && !has_filters && scope_path.is_none() {
+            if !pattern_counts.is_empty() {
+                let name_lower = self.strings.get(entry.name_lower);
+                let full_path = match_path
+                    .then(|| full_path_lower(self.strings.get(entry.path_lower), name_lower));
+                for (count, pattern) in pattern_counts.iter_mut().zip(&patterns) {
+                    let matched = match full_path {
+                        Some(ref full_path) => matches_path(full_path, pattern),
+                        None => matches_pattern(name_lower, pattern),
+                    };
+                    if matched {
+                        *count += 1;
+                    }
+                }
+            }
+        }
+        self.search.pattern_counts = query.patterns.iter().cloned().zip(pattern_counts).collect();
+
+        if no_text_query
+            && !has_filters
+            && scope_path.is_none()
+            && self.show_hidden
+            && self.show_system
+            && !self.hide_unknown_metadata
+        {
             self.filtered_indices = (0..self.all_entries.len()).collect();
         }
 
+        if !patterns.is_empty() {
+            self.apply_result_order(&patterns);
+        }
+
         self.table.selected = if self.filtered_indices.is_empty() {
             None
         } else {
@@ -450,6 +1002,37 @@ impl GuiApp {
         self.trigger_metadata_refresh();
     }
 
+    /// Order fresh text-query results by `search.default_order`
+    fn apply_result_order(&mut self, patterns: &[&str]) {
+        let entries = &self.all_entries;
+        let strings = &self.strings;
+
+        match self.search.default_order {
+            ResultOrder::Relevance => {
+                self.filtered_indices.sort_by_cached_key(|&idx| {
+                    let name_lower = strings.get(entries[idx].name_lower);
+                    (relevance_rank(name_lower, patterns), name_lower)
+                });
+            }
+            ResultOrder::Name => {
+                self.filtered_indices
+                    .sort_by_key(|&idx| strings.get(entries[idx].name_lower));
+                self.table.sort_column = SortColumn::Name;
+                self.table.sort_order = SortOrder::Ascending;
+                self.last_sort_column = Some(SortColumn::Name);
+                self.last_sort_order = SortOrder::Ascending;
+            }
+            ResultOrder::SizeDescending => {
+                self.filtered_indices
+                    .sort_by_key(|&idx| std::cmp::Reverse(entries[idx].file_size));
+                self.table.sort_column = SortColumn::Size;
+                self.table.sort_order = SortOrder::Descending;
+                self.last_sort_column = Some(SortColumn::Size);
+                self.last_sort_order = SortOrder::Descending;
+            }
+        }
+    }
+
     fn build_date_filter(&self) -> Option<DateFilter> {
         match self.search_filters.date_mode {
             dialogs::DateFilterMode::None => None,
@@ -493,14 +1076,25 @@ impl GuiApp {
     // ====================================================================
 
     fn trigger_metadata_refresh(&mut self) {
-        if self.is_refreshing_metadata || self.filtered_indices.is_empty() {
+        // A preview's sizes come with its full tree
+        if self.is_refreshing_metadata
+            || !self.preview_drives.is_empty()
+            || (self.filtered_indices.is_empty() && self.unknown_metadata_held.is_empty())
+        {
             return;
         }
 
         let max_refresh = 10000;
         let mut needs_refresh: Vec<(usize, usize, NodeKey, u64)> = Vec::new();
 
-        for &entry_idx in self.filtered_indices.iter().take(max_refresh) {
+        // Held-back results too, with a budget of their own: behind a long
+        // result list they would never be refreshed and stay hidden
+        let candidates = self
+            .filtered_indices
+            .iter()
+            .take(max_refresh)
+            .chain(self.unknown_metadata_held.iter().take(max_refresh));
+        for &entry_idx in candidates {
             if self.pending_metadata_refresh.contains(&entry_idx) {
                 continue;
             }
@@ -529,7 +1123,7 @@ impl GuiApp {
             None => return,
         };
 
-        thread::spawn(move || {
+        threads::spawn_worker(move || {
             use std::collections::HashMap;
 
             let mut by_tree: HashMap<usize, Vec<(usize, NodeKey, u64)>> = HashMap::new();
@@ -563,7 +1157,31 @@ impl GuiApp {
         });
     }
 
-    fn start_path_cache(&self) {
+    /// Compare the loaded drives' USN journals with their scans in the
+    /// background, at most every `JOURNAL_CHECK_INTERVAL`, so the status bar
+    /// can tell when the results have gone stale
+    fn check_journals(&mut self) {
+        if self.is_scanning
+            || self.trees.is_empty()
+            || self.journal_checked_at.is_some_and(|at| at.elapsed() < JOURNAL_CHECK_INTERVAL)
+        {
+            return;
+        }
+        let Some(tx) = self.bg_sender.clone() else {
+            return;
+        };
+        self.journal_checked_at = Some(Instant::now());
+
+        let trees = self.trees.clone();
+        threads::spawn_worker(move || {
+            let status = JournalStatus::worst(trees.iter().map(|tree| tree.journal_status()));
+            let _ = tx.send(BgMessage::JournalChecked(status));
+        });
+    }
+
+    /// Resolve every entry's parent path in the background, current results
+    /// first, in batches so path search and sorting improve as caching goes.
+    fn start_path_cache(&mut self) {
         if self.all_entries.is_empty() {
             return;
         }
@@ -573,33 +1191,71 @@ impl GuiApp {
             None => return,
         };
 
-        let work: Vec<(usize, usize, NodeKey)> = self
-            .all_entries
-            .iter()
-            .enumerate()
-            .map(|(i, e)| (i, e.tree_index, e.key))
-            .collect();
+        let mut queued = vec![false; self.all_entries.len()];
+        let mut work: Vec<(usize, usize, NodeKey)> = Vec::with_capacity(self.all_entries.len());
+        for idx in self.filtered_indices.iter().copied().chain(0..self.all_entries.len()) {
+            if std::mem::replace(&mut queued[idx], true) {
+                continue;
+            }
+            let entry = &self.all_entries[idx];
+            // Entries kept across a drive rescan already have their path
+            if entry.cached_path != StringArena::EMPTY {
+                continue;
+            }
+            work.push((idx, entry.tree_index, entry.key));
+        }
 
         let trees = self.trees.clone();
+        self.path_cache_cancel.store(true, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.path_cache_cancel = cancel.clone();
+        self.path_cache_generation += 1;
+        let generation = self.path_cache_generation;
+        self.path_cache_progress = Some((0, work.len()));
+
+        threads::spawn_long_lived(move || {
+            for chunk in work.chunks(PATH_CACHE_BATCH) {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
 
-        thread::spawn(move || {
-            let mut results: Vec<(usize, String)> = Vec::with_capacity(work.len());
-
-            for (idx, tree_index, key) in work {
-                if let Some(tree) = trees.get(tree_index) {
-                    let full_path = tree.build_path_for_key(&key);
-                    let parent_dir = std::path::Path::new(&full_path)
-                        .parent()
-                        .map(|p| p.to_string_lossy().to_string())
-                        .unwrap_or_else(|| full_path);
-                    results.push((idx, parent_dir));
+                let batch: Vec<(usize, String)> = chunk
+                    .iter()
+                    .filter_map(|&(idx, tree_index, key)| {
+                        let tree = trees.get(tree_index)?;
+                        let full_path = tree.build_path_for_key(&key);
+                        let parent_dir = std::path::Path::new(&full_path)
+                            .parent()
+                            .map(|p| p.to_string_lossy().to_string())
+                            .unwrap_or_else(|| full_path);
+                        Some((idx, parent_dir))
+                    })
+                    .collect();
+
+                if tx.send(BgMessage::PathCacheProgress(generation, batch)).is_err() {
+                    return;
                 }
             }
 
-            let _ = tx.send(BgMessage::PathCacheComplete(results));
+            let _ = tx.send(BgMessage::PathCacheComplete(generation));
         });
     }
 
+    /// Stop the scan thread and path caching, so nothing keeps reading
+    /// the disk once the window is closed
+    fn cancel_background_work(&mut self) {
+        self.scan_cancel.store(true, Ordering::Relaxed);
+        self.cancel_path_cache();
+    }
+
+    /// Stop a running path cache; paths resolved so far are kept
+    fn cancel_path_cache(&mut self) {
+        self.path_cache_cancel.store(true, Ordering::Relaxed);
+        if let Some((done, total)) = self.path_cache_progress.take() {
+            self.status_message = format!("Path caching stopped ({} of {} entries)", done, total);
+        }
+    }
+
     // ====================================================================
     // Sort
     // ====================================================================
@@ -627,30 +1283,45 @@ impl GuiApp {
             return;
         }
 
+        self.sort_results(column, new_order);
+    }
+
+    /// Sort the results in the background by `column` in `order`
+    fn sort_results(&mut self, column: SortColumn, order: SortOrder) {
+        if self.is_sorting {
+            return;
+        }
         self.table.sort_column = column;
-        self.table.sort_order = new_order;
+        self.table.sort_order = order;
         self.is_sorting = true;
+        self.sort_generation += 1;
+        let generation = self.sort_generation;
 
         let mut indices = self.filtered_indices.clone();
         let entries = self.all_entries.clone();
+        let strings = self.strings.clone();
+        let extensions = self.extensions.clone();
+        let ext_ranks = self.extensions.sort_ranks();
         let sort_column = column;
-        let sort_order = new_order;
+        let sort_order = order;
+        let date_field = self.date_field;
         let trees = self.trees.clone();
 
         if let Some(tx) = &self.bg_sender {
             let tx = tx.clone();
-            thread::spawn(move || {
+            threads::spawn_worker(move || {
                 let path_cache: Option<std::collections::HashMap<usize, String>> =
                     if sort_column == SortColumn::Path {
                         let mut cache = std::collections::HashMap::new();
                         for &idx in &indices {
                             let entry = &entries[idx];
-                            let full_path = if !entry.path_lower.is_empty() {
-                                format!("{}\\{}", entry.path_lower, entry.name_lower)
+                            let path_lower = strings.get(entry.path_lower);
+                            let full_path = if !path_lower.is_empty() {
+                                format!("{}\\{}", path_lower, strings.get(entry.name_lower))
                             } else if let Some(tree) = trees.get(entry.tree_index) {
                                 tree.build_path_for_key(&entry.key).to_lowercase()
                             } else {
-                                entry.name_lower.clone()
+                                strings.get(entry.name_lower).to_string()
                             };
                             cache.insert(idx, full_path);
                         }
@@ -659,12 +1330,33 @@ impl GuiApp {
                         None
                     };
 
+                // Created/accessed times live only in the trees; look each up once
+                let time_cache: Option<std::collections::HashMap<usize, u64>> =
+                    if sort_column == SortColumn::DateModified && date_field != DateField::Modified {
+                        let cache = indices
+                            .iter()
+                            .map(|&idx| {
+                                let entry = &entries[idx];
+                                let time = trees
+                                    .get(entry.tree_index)
+                                    .map(|t| t.time_for_key(&entry.key, date_field))
+                                    .unwrap_or(0);
+                                (idx, time)
+                            })
+                            .collect();
+                        Some(cache)
+                    } else {
+                        None
+                    };
+
                 indices.sort_by(|&a, &b| {
                     let ea = &entries[a];
                     let eb = &entries[b];
 
                     let cmp = match sort_column {
-                        SortColumn::Name => ea.name_lower.cmp(&eb.name_lower),
+                        SortColumn::Name => {
+                            strings.get(ea.name_lower).cmp(strings.get(eb.name_lower))
+                        }
                         SortColumn::Path => {
                             let pa = path_cache
                                 .as_ref()
@@ -681,17 +1373,38 @@ impl GuiApp {
                             pa.cmp(pb)
                         }
                         SortColumn::Size => ea.file_size.cmp(&eb.file_size),
-                        SortColumn::Extension => ea.extension.cmp(&eb.extension),
-                        SortColumn::DateModified => {
-                            ea.modification_time.cmp(&eb.modification_time)
+                        SortColumn::Extension => {
+                            ext_ranks[ea.extension as usize].cmp(&ext_ranks[eb.extension as usize])
                         }
+                        SortColumn::DateModified => match time_cache {
+                            Some(ref times) => times.get(&a).cmp(&times.get(&b)),
+                            None => ea.modification_time.cmp(&eb.modification_time),
+                        },
                         SortColumn::Type => {
-                            let ta = colors::type_label(ea.is_directory, &ea.extension);
-                            let tb = colors::type_label(eb.is_directory, &eb.extension);
+                            let ta = ea.metafile_label.unwrap_or_else(|| {
+                                colors::type_label(ea.is_directory, extensions.get(ea.extension))
+                            });
+                            let tb = eb.metafile_label.unwrap_or_else(|| {
+                                colors::type_label(eb.is_directory, extensions.get(eb.extension))
+                            });
                             ta.cmp(tb)
                         }
+                        SortColumn::Drive => {
+                            let da = trees.get(ea.tree_index).map(|t| t.drive_letter);
+                            let db = trees.get(eb.tree_index).map(|t| t.drive_letter);
+                            da.cmp(&db)
+                        }
+                        SortColumn::Record => ea.key.record_number.cmp(&eb.key.record_number),
+                        SortColumn::Storage => ea.storage.cmp(&eb.storage),
                     };
 
+                    // Equal keys fall back to drive and record number so the
+                    // order doesn't depend on scan or previous-sort order
+                    let cmp = cmp.then_with(|| {
+                        (ea.tree_index, ea.key.record_number)
+                            .cmp(&(eb.tree_index, eb.key.record_number))
+                    });
+
                     if sort_order == SortOrder::Descending {
                         cmp.reverse()
                     } else {
@@ -699,42 +1412,397 @@ impl GuiApp {
                     }
                 });
 
-                let _ = tx.send(BgMessage::SortComplete(sort_column, indices));
-            });
+                // Let held messages through as soon as this arrives
+                drop((entries, strings));
+                let _ = tx.send(BgMessage::SortComplete(generation, sort_column, indices));
+            });
+        }
+    }
+
+    // ====================================================================
+    // Actions
+    // ====================================================================
+
+    /// Cached parent path + name (only valid once paths are cached)
+    fn entry_full_path(&self, entry: &EntryData) -> String {
+        format!(
+            "{}\\{}",
+            self.strings.get(entry.cached_path),
+            self.strings.get(entry.name)
+        )
+    }
+
+    /// Clear the filters dialog state but keep the text query
+    fn clear_search_filters(&mut self) {
+        if !self.search_filters.has_any_filter() {
+            return;
+        }
+        self.search_filters.clear_all();
+        self.search.needs_search = true;
+        self.status_message = "Filters cleared".to_string();
+    }
+
+    /// Narrow results to the selected row's extension: fills the extension
+    /// filter and clears the text query, keeping any other filters
+    fn filter_to_selected_extension(&mut self) {
+        let Some(entry) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+        else {
+            return;
+        };
+        let ext = self.extensions.get(entry.extension);
+        if entry.is_directory || ext.is_empty() {
+            self.status_message = "Selected item has no extension".to_string();
+            return;
+        }
+        self.search_filters.extension_filter = ext.to_string();
+        self.search.query.clear();
+        self.search.needs_search = true;
+        self.status_message = format!("Filter: .{} (Ctrl+L clears)", ext);
+    }
+
+    /// Scope the search to the selected folder, replacing the query, so the
+    /// results list that folder's contents. Returns false if the selected
+    /// row is not a folder.
+    fn browse_selected_folder(&mut self) -> bool {
+        let Some(entry) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+        else {
+            return false;
+        };
+        if !entry.is_directory {
+            return false;
+        }
+        let (tree_index, key) = (entry.tree_index, entry.key);
+        self.browse_folder(tree_index, key)
+    }
+
+    /// Replace the query with a folder's path and list its direct children
+    fn browse_folder(&mut self, tree_index: usize, key: NodeKey) -> bool {
+        let Some(tree) = self.trees.get(tree_index) else {
+            return false;
+        };
+        let path = tree.build_path_for_key(&key);
+        self.set_scope(Some(&path));
+        self.browsed_folder = Some((self.search.query.clone(), tree_index, key));
+        self.status_message = format!("Browsing {} (Backspace goes up)", path);
+        true
+    }
+
+    /// Tree index and record number of the browsed folder, if the query is
+    /// still the one `browse_folder` set
+    fn browsed_folder(&self) -> Option<(usize, u64)> {
+        self.browsed_folder
+            .as_ref()
+            .filter(|(query, ..)| *query == self.search.query)
+            .map(|&(_, tree_index, key)| (tree_index, key.record_number))
+    }
+
+    /// Whether the results are in name order, so type-ahead makes sense
+    fn sorted_by_name(&self) -> bool {
+        // Until a column is clicked, text results keep `ResultOrder::Name`
+        let by_name = match self.last_sort_column {
+            Some(column) => column == SortColumn::Name,
+            None => self.search.default_order == ResultOrder::Name,
+        };
+        !self.is_sorting && by_name
+    }
+
+    /// Move the selection to the next row (wrapping) whose name starts
+    /// with `c`, like type-ahead in a file manager
+    fn jump_to_initial(&mut self, c: char) {
+        let entries = &self.all_entries;
+        let strings = &self.strings;
+        let filtered = &self.filtered_indices;
+        let found = next_row_by_initial(filtered.len(), self.table.selected, c, |row| {
+            strings.get(entries[filtered[row]].name_lower)
+        });
+        match found {
+            Some(row) => {
+                self.table.select_row(row);
+                self.scroll_to_row = Some(row);
+            }
+            None => self.status_message = format!("No name starting with '{}'", c),
+        }
+    }
+
+    /// Move the search scope up one folder, or drop it at the drive root
+    fn browse_up(&mut self) {
+        // A browsed folder goes up through the tree, so the parent lists
+        // its children too; the root's parent is itself
+        let browsed = self
+            .browsed_folder
+            .as_ref()
+            .filter(|(query, _, key)| *query == self.search.query && key.parent_record_number != key.record_number)
+            .map(|&(_, tree_index, key)| (tree_index, key));
+        if let Some((tree_index, key)) = browsed {
+            let parent = self.trees.get(tree_index).and_then(|tree| tree.get(key.parent_record_number));
+            if let Some(parent) = parent {
+                self.browse_folder(tree_index, parent.key());
+                return;
+            }
+        }
+        let (Some(scope), _) = parse_scope_path(&self.search.query) else {
+            return;
+        };
+        match scope.rsplit_once('\\') {
+            Some((parent, _)) => {
+                self.set_scope(Some(parent));
+                self.status_message = format!("Browsing {}", parent);
+            }
+            None => {
+                self.set_scope(None);
+                self.status_message = "Scope cleared".to_string();
+            }
+        }
+    }
+
+    /// Replace the query with a bare `scope` (or clear it)
+    fn set_scope(&mut self, scope: Option<&str>) {
+        self.search.query = match scope {
+            // A drive root normalises to "c:"; keep the backslash so it reads as a path
+            Some(s) if s.ends_with(':') => format!("`{}\\` ", s),
+            Some(s) => format!("`{}` ", s),
+            None => String::new(),
+        };
+        self.search.needs_search = true;
+    }
+
+    /// Copy a text report of the selected folder's largest subfolders
+    fn copy_size_report(&mut self) {
+        let Some(entry) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+        else {
+            return;
+        };
+        if !entry.is_directory {
+            self.status_message = "Size report needs a folder".to_string();
+            return;
+        }
+        let Some(tree) = self.trees.get(entry.tree_index) else {
+            return;
+        };
+        let report = tree.size_report(&entry.key, SIZE_REPORT_DEPTH, SIZE_REPORT_TOP);
+        dialogs::copy_to_clipboard(&report);
+        self.status_message = format!("Copied size report ({} lines)", report.lines().count());
+    }
+
+    /// Summary of name/path storage, e.g. "names 1.2 GB (saved 2.3 GB)"
+    fn memory_report(&self) -> String {
+        // Each entry holds 4 ids instead of 4 Strings
+        let id_bytes = self.all_entries.len() as u64 * 4 * std::mem::size_of::<StrId>() as u64;
+        let used = self.strings.heap_bytes() as u64 + id_bytes;
+        let saved = self.strings.string_equivalent_bytes().saturating_sub(used);
+        format!(
+            "names {} (saved {})",
+            crate::format_size(used),
+            crate::format_size(saved)
+        )
+    }
+
+    /// The timestamp `date_field` picks for an entry. Only modification
+    /// times are kept per entry; the others are read from the tree.
+    fn entry_time(&self, entry: &EntryData) -> u64 {
+        match self.date_field {
+            DateField::Modified => entry.modification_time,
+            field => self
+                .trees
+                .get(entry.tree_index)
+                .map(|t| t.time_for_key(&entry.key, field))
+                .unwrap_or(0),
+        }
+    }
+
+    /// Summed size of the selected items (or the cursor row), following `size_sum`
+    fn selected_size(&self) -> u64 {
+        let size_of = |logical_idx: &usize| -> u64 {
+            let Some(entry) = self
+                .filtered_indices
+                .get(*logical_idx)
+                .and_then(|&i| self.all_entries.get(i))
+            else {
+                return 0;
+            };
+            if !entry.is_directory {
+                entry.file_size
+            } else if self.size_sum == SizeSum::AsIs {
+                self.trees
+                    .get(entry.tree_index)
+                    .map(|t| t.total_size_for_key(&entry.key))
+                    .unwrap_or(0)
+            } else {
+                0
+            }
+        };
+        if self.table.selections.is_empty() {
+            self.table.selected.as_ref().map(size_of).unwrap_or(0)
+        } else {
+            self.table.selections.iter().map(size_of).sum()
+        }
+    }
+
+    /// Indices into `all_entries` of the selected items, or of the cursor
+    /// row when nothing is multi-selected
+    fn selected_entry_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .table
+            .selections
+            .iter()
+            .filter_map(|&logical_idx| self.filtered_indices.get(logical_idx).copied())
+            .filter(|&entry_idx| entry_idx < self.all_entries.len())
+            .collect();
+        if indices.is_empty() {
+            if let Some(&entry_idx) = self
+                .table
+                .selected
+                .and_then(|logical_idx| self.filtered_indices.get(logical_idx))
+            {
+                if entry_idx < self.all_entries.len() {
+                    indices.push(entry_idx);
+                }
+            }
+        }
+        indices
+    }
+
+    /// Full path of an entry, built from the tree if not cached yet
+    fn entry_path(&self, entry: &EntryData) -> Option<String> {
+        if entry.cached_path != StringArena::EMPTY {
+            Some(self.entry_full_path(entry))
+        } else {
+            self.trees
+                .get(entry.tree_index)
+                .map(|tree| tree.build_path_for_key(&entry.key))
+        }
+    }
+
+    fn get_selected_paths(&self) -> Vec<String> {
+        self.selected_entry_indices()
+            .into_iter()
+            .filter_map(|entry_idx| self.entry_path(&self.all_entries[entry_idx]))
+            .collect()
+    }
+
+    /// Index into `all_entries` of the entry at a full path, if its drive
+    /// is loaded
+    fn index_of_path(&self, path: &str) -> Option<usize> {
+        let (tree_index, key) = self
+            .trees
+            .iter()
+            .enumerate()
+            .find_map(|(i, tree)| tree.key_for_path(path).map(|key| (i, key)))?;
+        self.all_entries
+            .iter()
+            .position(|entry| entry.tree_index == tree_index && entry.key == key)
+    }
+
+    /// Select the entry at `path` and scroll to it, clearing the search
+    /// and filters if they hide it. Returns false if the path is not loaded
+    /// or is hidden by the hidden/system file toggles.
+    fn reveal_path(&mut self, path: &str) -> bool {
+        let Some(entry_idx) = self.index_of_path(path) else {
+            return false;
+        };
+        if !self.filtered_indices.contains(&entry_idx) {
+            self.search_filters.clear_all();
+            self.search.query.clear();
+            self.perform_search();
+            self.search.needs_search = false;
+        }
+        let Some(row) = self.filtered_indices.iter().position(|&idx| idx == entry_idx) else {
+            return false;
+        };
+        self.table.select_row(row);
+        self.scroll_to_row = Some(row);
+        true
+    }
+
+    /// Ask before setting or clearing `flag` on the selection. The flag is
+    /// set unless every selected item already has it, so mixed selections
+    /// end up uniform.
+    fn confirm_attribute_toggle(&mut self, flag: u32) {
+        let paths = self.get_selected_paths();
+        if paths.is_empty() {
+            return;
         }
+        let verb = if self.attribute_toggle_sets(flag) { "Set" } else { "Clear" };
+        let message = if paths.len() == 1 {
+            format!("{} {} on {}?", verb, attribute_name(flag), paths[0])
+        } else {
+            format!("{} {} on {} items?", verb, attribute_name(flag), paths.len())
+        };
+        self.active_dialog = ActiveDialog::Confirm {
+            message,
+            action: PendingAction::ToggleAttribute(flag),
+        };
     }
 
-    // ====================================================================
-    // Actions
-    // ====================================================================
+    fn attribute_toggle_sets(&self, flag: u32) -> bool {
+        self.selected_entry_indices()
+            .iter()
+            .any(|&entry_idx| self.all_entries[entry_idx].attributes & flag == 0)
+    }
 
-    fn get_selected_paths(&self) -> Vec<String> {
-        let mut paths = Vec::new();
-        for &logical_idx in &self.table.selections {
-            if let Some(&entry_idx) = self.filtered_indices.get(logical_idx) {
-                if let Some(entry) = self.all_entries.get(entry_idx) {
-                    if !entry.cached_path.is_empty() {
-                        paths.push(format!("{}\\{}", entry.cached_path, entry.name));
-                    } else if let Some(tree) = self.trees.get(entry.tree_index) {
-                        paths.push(tree.build_path_for_key(&entry.key));
-                    }
+    /// Set or clear `flag` on the selected items on disk, then patch the
+    /// cached attributes of the entries and trees so no rescan is needed
+    fn execute_attribute_toggle(&mut self, flag: u32) {
+        let on = self.attribute_toggle_sets(flag);
+        let mut changed: std::collections::HashMap<(usize, u64), u32> =
+            std::collections::HashMap::new();
+        let mut failed = 0;
+        for entry_idx in self.selected_entry_indices() {
+            let entry = &self.all_entries[entry_idx];
+            let Some(path) = self.entry_path(entry) else {
+                continue;
+            };
+            match shell::set_attribute_flag(&path, flag, on) {
+                Ok(attributes) => {
+                    changed.insert((entry.tree_index, entry.key.record_number), attributes);
+                }
+                Err(e) => {
+                    failed += 1;
+                    self.status_message = format!("Error changing {}: {}", path, e);
                 }
             }
         }
-        if paths.is_empty() {
-            if let Some(logical_idx) = self.table.selected {
-                if let Some(&entry_idx) = self.filtered_indices.get(logical_idx) {
-                    if let Some(entry) = self.all_entries.get(entry_idx) {
-                        if !entry.cached_path.is_empty() {
-                            paths.push(format!("{}\\{}", entry.cached_path, entry.name));
-                        } else if let Some(tree) = self.trees.get(entry.tree_index) {
-                            paths.push(tree.build_path_for_key(&entry.key));
-                        }
-                    }
-                }
+
+        for (&(tree_index, record_number), &attributes) in &changed {
+            if let Some(tree) = self.trees.get(tree_index) {
+                tree.set_attributes(record_number, attributes);
+            }
+        }
+        // Hard links are separate entries sharing one record
+        for entry in &mut self.all_entries {
+            if let Some(&attributes) = changed.get(&(entry.tree_index, entry.key.record_number)) {
+                entry.attributes = attributes;
             }
         }
-        paths
+
+        if !changed.is_empty() {
+            let verb = if on { "Set" } else { "Cleared" };
+            self.status_message = if failed > 0 {
+                format!(
+                    "{} {} on {} item(s), {} failed",
+                    verb,
+                    attribute_name(flag),
+                    changed.len(),
+                    failed
+                )
+            } else {
+                format!("{} {} on {} item(s)", verb, attribute_name(flag), changed.len())
+            };
+            // Filters such as hiding hidden files may now apply differently
+            self.search.needs_search = true;
+        }
     }
 
     fn execute_delete(&mut self) {
@@ -760,6 +1828,25 @@ impl GuiApp {
         }
     }
 
+    fn execute_batch_rename(&mut self, items: &[crate::batch_rename::RenameItem]) {
+        let skipped = items.iter().filter(|item| item.to.is_err()).count();
+        let (renamed, errors) = crate::batch_rename::execute(items);
+
+        self.status_message = match errors.first() {
+            Some(first) => format!(
+                "Renamed {} item(s), {} failed (first: {})",
+                renamed,
+                errors.len(),
+                first
+            ),
+            None if skipped > 0 => format!("Renamed {} item(s), skipped {}", renamed, skipped),
+            None => format!("Renamed {} item(s)", renamed),
+        };
+        if renamed > 0 {
+            self.search.needs_search = true;
+        }
+    }
+
     fn execute_rename(&mut self, original_path: &str, new_name: &str) {
         let old = std::path::Path::new(original_path);
         let new = old.parent().map(|p| p.join(new_name));
@@ -793,6 +1880,7 @@ impl GuiApp {
             self.search.needs_search = false;
 
             self.filtered_indices.clear();
+            self.kind_groups = None;
             self.last_sort_column = None;
             for (idx, entry) in self.all_entries.iter().enumerate() {
                 if entry.is_directory {
@@ -829,8 +1917,8 @@ impl GuiApp {
 
     fn get_parent_path(&self, entry_idx: usize) -> String {
         let entry = &self.all_entries[entry_idx];
-        if !entry.cached_path.is_empty() {
-            entry.cached_path.clone()
+        if entry.cached_path != StringArena::EMPTY {
+            self.strings.get(entry.cached_path).to_string()
         } else if let Some(tree) = self.trees.get(entry.tree_index) {
             let path = tree.build_path_for_key(&entry.key);
             std::path::Path::new(&path)
@@ -848,19 +1936,42 @@ impl GuiApp {
 // ============================================================================
 
 impl eframe::App for GuiApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(ROW_DENSITY_KEY, self.row_density.label().to_string());
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Always repaint while scanning/sorting so the UI stays live
-        if self.is_scanning || self.is_sorting || self.is_refreshing_metadata {
-            ctx.request_repaint();
+        if std::mem::take(&mut self.initial_scan_pending) {
+            self.start_scan();
+        }
+
+        // Title bar close, Alt+F4 and Quit (`ViewportCommand::Close`)
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.cancel_background_work();
+        }
+
+        // Poll at the refresh interval while background work is running;
+        // when idle, egui only repaints on input
+        if self.is_scanning
+            || self.is_sorting
+            || self.is_refreshing_metadata
+            || self.path_cache_progress.is_some()
+            || !self.held_messages.is_empty()
+        {
+            ctx.request_repaint_after(self.refresh_interval);
         }
 
-        // Process background messages
-        self.process_messages();
+        // Process background messages, repainting only if state changed
+        if self.process_messages() {
+            ctx.request_repaint();
+        }
+        self.check_journals();
 
         // Deferred search
-        if self.search.needs_search && !self.is_scanning {
+        if self.search.needs_search && !(self.is_scanning && self.all_entries.is_empty()) {
             self.perform_search();
             self.search.needs_search = false;
+            ctx.request_repaint();
         }
 
         // ── Dark theme ──────────────────────────────────────────────────
@@ -871,7 +1982,11 @@ impl eframe::App for GuiApp {
         // ── Keyboard shortcuts ──────────────────────────────────────────
         ctx.input(|i| {
             if i.key_pressed(egui::Key::F9) {
-                self.start_scan();
+                if i.modifiers.shift {
+                    self.start_drive_rescan();
+                } else {
+                    self.start_scan();
+                }
             }
         });
 
@@ -903,6 +2018,14 @@ impl eframe::App for GuiApp {
                         }
                         ui.close();
                     }
+                    if ui.button("Open Terminal Here").clicked() {
+                        if let Some(p) = self.get_selected_paths().first() {
+                            if let Err(e) = shell::open_terminal_here(p) {
+                                self.status_message = format!("Error opening terminal: {}", e);
+                            }
+                        }
+                        ui.close();
+                    }
                     if ui.button("Properties").clicked() {
                         let paths = self.get_selected_paths();
                         for p in &paths {
@@ -915,6 +2038,10 @@ impl eframe::App for GuiApp {
                         self.start_scan();
                         ui.close();
                     }
+                    if ui.button("Rescan Selected Drive  (Shift+F9)").clicked() {
+                        self.start_drive_rescan();
+                        ui.close();
+                    }
                     ui.separator();
                     if ui.button("Quit  (Ctrl+Q)").clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
@@ -950,6 +2077,14 @@ impl eframe::App for GuiApp {
                         }
                         ui.close();
                     }
+                    if ui.button("Batch Rename").clicked() {
+                        let paths = self.get_selected_paths();
+                        if !paths.is_empty() {
+                            self.active_dialog =
+                                ActiveDialog::BatchRename(dialogs::BatchRename::new(paths));
+                        }
+                        ui.close();
+                    }
                     if ui.button("Delete").clicked() {
                         let paths = self.get_selected_paths();
                         if !paths.is_empty() {
@@ -965,6 +2100,14 @@ impl eframe::App for GuiApp {
                         }
                         ui.close();
                     }
+                    if ui.button("Toggle Read-only").clicked() {
+                        self.confirm_attribute_toggle(file_attributes::READONLY);
+                        ui.close();
+                    }
+                    if ui.button("Toggle Hidden").clicked() {
+                        self.confirm_attribute_toggle(file_attributes::HIDDEN);
+                        ui.close();
+                    }
                 });
 
                 // View menu
@@ -973,11 +2116,97 @@ impl eframe::App for GuiApp {
                         self.toggle_treemap();
                         ui.close();
                     }
+                    ui.radio_value(&mut self.view_mode, ViewMode::Table, "Flat List");
+                    ui.radio_value(&mut self.view_mode, ViewMode::ByKind, "Group by Type");
+                    ui.separator();
                     if ui.button("Search Filters  (Ctrl+F)").clicked() {
                         self.active_dialog =
                             ActiveDialog::SearchFilters(self.search_filters.clone());
                         ui.close();
                     }
+                    if ui.checkbox(&mut self.show_hidden, "Show Hidden Files").changed() {
+                        self.search.needs_search = true;
+                    }
+                    if ui.checkbox(&mut self.show_system, "Show System Files").changed() {
+                        self.search.needs_search = true;
+                    }
+                    if ui.checkbox(&mut self.match_path, "Match Full Path").changed() {
+                        self.search.needs_search = true;
+                    }
+                    if ui
+                        .checkbox(&mut self.hide_unknown_metadata, "Hide Unknown Size/Date")
+                        .on_hover_text("Hide files whose size and date have not been read yet")
+                        .changed()
+                    {
+                        self.search.needs_search = true;
+                    }
+                    let mut sum_dirs = self.size_sum == SizeSum::AsIs;
+                    if ui
+                        .checkbox(&mut sum_dirs, "Sum Directories in Selection")
+                        .on_hover_text(
+                            "Add selected folders' full size to the selected total. \
+                             Off counts files only, so nothing is counted twice",
+                        )
+                        .changed()
+                    {
+                        self.size_sum = self.size_sum.toggled();
+                    }
+                    ui.separator();
+                    if ui.button("Drive Statistics").clicked() {
+                        self.active_dialog = ActiveDialog::DriveStats(
+                            MultiVolumeScanner::drive_stats(self.trees.iter().map(|t| t.as_ref())),
+                        );
+                        ui.close();
+                    }
+                    if ui.button("File Categories").clicked() {
+                        self.active_dialog = ActiveDialog::KindSummary(KindSummary::from_entries(
+                            self.all_entries
+                                .iter()
+                                .map(|e| (e.is_directory, e.extension, e.file_size)),
+                            &self.extensions,
+                        ));
+                        ui.close();
+                    }
+                    ui.checkbox(&mut self.show_record_column, "Record Column");
+                    ui.checkbox(&mut self.show_storage_column, "Storage Column");
+                    ui.menu_button("Row Density", |ui| {
+                        for density in RowDensity::ALL {
+                            if ui.radio(self.row_density == density, density.label()).clicked() {
+                                self.row_density = density;
+                                ui.close();
+                            }
+                        }
+                    });
+                    ui.menu_button("Date Column", |ui| {
+                        for field in DateField::ALL {
+                            if ui.radio(self.date_field == field, field.label()).clicked() {
+                                self.date_field = field;
+                                // A date sort was by the old field
+                                if self.table.sort_column == SortColumn::DateModified {
+                                    self.last_sort_column = None;
+                                    self.sort_results(SortColumn::DateModified, self.table.sort_order);
+                                }
+                                ui.close();
+                            }
+                        }
+                    });
+                    ui.menu_button("Result Order", |ui| {
+                        for order in ResultOrder::ALL {
+                            if ui.radio(self.search.default_order == order, order.label()).clicked() {
+                                self.search.default_order = order;
+                                self.search.needs_search = true;
+                                ui.close();
+                            }
+                        }
+                    });
+                    ui.menu_button("Treemap Palette", |ui| {
+                        for p in palette::ALL {
+                            if ui.radio(std::ptr::eq(self.palette, p), p.label).clicked() {
+                                self.palette = p;
+                                ui.close();
+                            }
+                        }
+                    });
                 });
 
                 // Tools menu
@@ -1006,10 +2235,14 @@ impl eframe::App for GuiApp {
                             title: "Keyboard Shortcuts".to_string(),
                             lines: vec![
                                 "Ctrl+F          Search filters".into(),
+                                "Ctrl+L          Clear search filters".into(),
+                                "Ctrl+E          Filter to selected extension".into(),
                                 "Ctrl+A          Select all".into(),
                                 "F9              Rescan drives".into(),
+                                "Shift+F9        Rescan selected result's drive".into(),
                                 "T               Toggle treemap".into(),
-                                "Enter           Open file".into(),
+                                "Enter           Open file / browse folder".into(),
+                                "Backspace       Browse parent folder".into(),
                                 "Delete          Delete file(s)".into(),
                                 "F2              Rename file".into(),
                                 "Ctrl+C          Copy path".into(),
@@ -1063,11 +2296,43 @@ impl eframe::App for GuiApp {
                         let total_size: u64 =
                             self.trees.iter().map(|t| t.stats.total_size).sum();
                         ui.label(format!(
-                            "{} objects | {} selected | {} total",
+                            "{} objects | {} selected ({}, {}) | {} total",
                             obj_count,
                             selected_count,
+                            crate::format_size(self.selected_size()),
+                            self.size_sum.label(),
                             crate::format_size(total_size)
                         ));
+                        if let Some(counts) = pattern_counts_label(&self.search.pattern_counts) {
+                            ui.separator();
+                            ui.label(counts).on_hover_text(
+                                "Results per pattern; a result matching several patterns counts for each",
+                            );
+                        }
+                        // Oldest drive decides how stale the results are
+                        if let Some(age) = self.trees.iter().filter_map(|t| t.scan_age()).max() {
+                            ui.separator();
+                            ui.label(format!("scanned {}", crate::format_age(age)))
+                                .on_hover_text("Time since the last full scan of the oldest drive");
+                        }
+                        if let Some(note) = self.journal_status.stale_note() {
+                            ui.label(
+                                egui::RichText::new(format!("({}, F9 to rescan)", note))
+                                    .color(egui::Color32::from_rgb(230, 190, 80)),
+                            )
+                            .on_hover_text(
+                                "The USN journal of a loaded drive has recorded changes since it was scanned",
+                            );
+                        }
+
+                        if let Some((done, total)) = self.path_cache_progress {
+                            ui.separator();
+                            ui.spinner();
+                            ui.label(format!("Caching paths {}%", done * 100 / total.max(1)));
+                            if ui.small_button("Stop").clicked() {
+                                self.cancel_path_cache();
+                            }
+                        }
                     }
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -1082,12 +2347,18 @@ impl eframe::App for GuiApp {
         // ── Central panel: search + table ───────────────────────────────
         egui::CentralPanel::default().show(ctx, |ui| {
             // Search bar
+            let filter_tags = self.search_filters.indicator();
+            let tags_width = if filter_tags.is_empty() {
+                0.0
+            } else {
+                filter_tags.chars().count() as f32 * 7.5 + 40.0
+            };
             ui.horizontal(|ui| {
                 ui.label("\u{1F50D}");
                 let response = ui.add(
                     egui::TextEdit::singleline(&mut self.search.query)
-                        .desired_width(ui.available_width() - 120.0)
-                        .hint_text("Search files... (use ; to separate patterns, `path` to scope)")
+                        .desired_width(ui.available_width() - 120.0 - tags_width)
+                        .hint_text("Search files... (; separates patterns, `path` scopes, path:users\\me matches folders)")
                         .font(egui::TextStyle::Body),
                 );
                 if self.request_search_focus {
@@ -1108,6 +2379,32 @@ impl eframe::App for GuiApp {
                     self.active_dialog = ActiveDialog::SearchFilters(self.search_filters.clone());
                 }
 
+                // Active filters are easy to forget, so show them next to the button
+                if !filter_tags.is_empty() {
+                    ui.label(
+                        egui::RichText::new(&filter_tags)
+                            .color(egui::Color32::from_rgb(255, 200, 60))
+                            .strong(),
+                    );
+                    if ui
+                        .small_button("\u{2716}")
+                        .on_hover_text("Clear search filters (Ctrl+L)")
+                        .clicked()
+                    {
+                        self.clear_search_filters();
+                    }
+                }
+
+                // Grouping toggle
+                let grouped = self.view_mode == ViewMode::ByKind;
+                if ui
+                    .selectable_label(grouped, "\u{1F5C2} Group")
+                    .on_hover_text("Group results by file type")
+                    .clicked()
+                {
+                    self.view_mode = if grouped { ViewMode::Table } else { ViewMode::ByKind };
+                }
+
                 // Treemap button
                 if ui.button("\u{1F4CA} Treemap").clicked() {
                     self.toggle_treemap();
@@ -1116,8 +2413,11 @@ impl eframe::App for GuiApp {
 
             ui.separator();
 
-            // Table
-            self.draw_file_table(ui);
+            // Results
+            match self.view_mode {
+                ViewMode::Table => self.draw_file_table(ui),
+                ViewMode::ByKind => self.draw_grouped_view(ui),
+            }
         });
 
         // ── Global keyboard shortcuts ───────────────────────────────────
@@ -1135,6 +2435,42 @@ impl eframe::App for GuiApp {
         // Deferred ctrl+F and ctrl+A (needs &mut self)
         let ctrl_f = ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::F));
         let ctrl_a = ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::A));
+        let ctrl_l = ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::L));
+        let ctrl_e = ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::E));
+        if ctrl_l {
+            self.clear_search_filters();
+        }
+        if ctrl_e {
+            self.filter_to_selected_extension();
+        }
+        // Enter/Backspace browse the results like folders, unless typing or in the treemap
+        let browse_keys = !ctx.wants_keyboard_input()
+            && self.treemap.is_none()
+            && matches!(self.active_dialog, ActiveDialog::None);
+        // Enter on a file opens it
+        if browse_keys
+            && ctx.input(|i| i.key_pressed(egui::Key::Enter))
+            && !self.browse_selected_folder()
+        {
+            for path in self.get_selected_paths() {
+                dialogs::open_file(&path);
+            }
+        }
+        if browse_keys && ctx.input(|i| i.key_pressed(egui::Key::Backspace)) {
+            self.browse_up();
+        }
+        // Sorted by name, typing a letter jumps to the next name starting with it
+        if browse_keys && self.sorted_by_name() {
+            let typed = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Text(text) => text.chars().next(),
+                    _ => None,
+                })
+            });
+            if let Some(c) = typed.filter(|c| c.is_alphanumeric()) {
+                self.jump_to_initial(c);
+            }
+        }
         if ctrl_f {
             self.active_dialog = ActiveDialog::SearchFilters(self.search_filters.clone());
         }
@@ -1155,7 +2491,11 @@ impl GuiApp {
         use egui_extras::{Column, TableBuilder};
 
         let total = self.filtered_indices.len();
-        let row_height = 20.0;
+        let row_height = self.row_density.row_height();
+        ui.style_mut().text_styles.insert(
+            egui::TextStyle::Body,
+            egui::FontId::proportional(self.row_density.font_size()),
+        );
 
         // Collect sort click outside the borrow
         let mut sort_click: Option<SortColumn> = None;
@@ -1165,9 +2505,11 @@ impl GuiApp {
         // Cache sort state to avoid borrowing self in closures
         let current_sort_col = self.table.sort_column;
         let current_sort_order = self.table.sort_order;
+        let show_record = self.show_record_column;
+        let show_storage = self.show_storage_column;
         let ctx = ui.ctx().clone();
 
-        let table = TableBuilder::new(ui)
+        let mut table = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
             .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
@@ -1175,19 +2517,39 @@ impl GuiApp {
             .column(Column::remainder().at_least(100.0))   // Path
             .column(Column::initial(100.0).at_least(50.0)) // Size
             .column(Column::initial(70.0).at_least(40.0))  // Ext
-            .column(Column::initial(150.0).at_least(80.0)) // Date Modified
+            .column(Column::initial(150.0).at_least(80.0)) // Date
             .column(Column::initial(120.0).at_least(50.0)) // Type
-            .sense(egui::Sense::click())
-            .min_scrolled_height(0.0);
+            .column(Column::initial(50.0).at_least(30.0)); // Drive
+        if show_record {
+            table = table.column(Column::initial(80.0).at_least(40.0)); // Record
+        }
+        if show_storage {
+            table = table.column(Column::initial(100.0).at_least(50.0)); // Storage
+        }
+        let mut table = table.sense(egui::Sense::click()).min_scrolled_height(0.0);
+        if let Some(row) = self.scroll_to_row.take() {
+            table = table.scroll_to_row(row, Some(egui::Align::Center));
+        }
 
-        let columns: [(& str, SortColumn); 6] = [
+        let all_columns: [(& str, SortColumn); 9] = [
             ("Name", SortColumn::Name),
             ("Path", SortColumn::Path),
             ("Size", SortColumn::Size),
             ("Ext", SortColumn::Extension),
-            ("Date Modified", SortColumn::DateModified),
+            (self.date_field.label(), SortColumn::DateModified),
             ("Type", SortColumn::Type),
+            ("Drive", SortColumn::Drive),
+            ("Record", SortColumn::Record),
+            ("Storage", SortColumn::Storage),
         ];
+        let columns: Vec<(&str, SortColumn)> = all_columns
+            .into_iter()
+            .filter(|(_, col)| match col {
+                SortColumn::Record => show_record,
+                SortColumn::Storage => show_storage,
+                _ => true,
+            })
+            .collect();
 
         table
             .header(22.0, |mut header| {
@@ -1223,11 +2585,20 @@ impl GuiApp {
 
                     if let Some(&entry_idx) = self.filtered_indices.get(logical_idx) {
                         let entry = &self.all_entries[entry_idx];
-                        let icon = colors::icon_for_entry(entry.is_directory, &entry.extension);
-                        let name_color = if entry.is_directory {
+                        let extension = self.extensions.get(entry.extension);
+                        let icon = if entry.off_volume_link {
+                            colors::LINK_ICON
+                        } else {
+                            colors::icon_for_entry(entry.is_directory, extension)
+                        };
+                        let name_color = if entry.is_deleted {
+                            DELETED_COLOR
+                        } else if entry.is_hidden() {
+                            egui::Color32::from_rgb(110, 110, 110)
+                        } else if entry.is_directory {
                             egui::Color32::from_rgb(100, 180, 255)
                         } else {
-                            colors::color_for_extension(&entry.extension)
+                            colors::color_for_extension(extension)
                         };
 
                         let parent_path = self.get_parent_path(entry_idx);
@@ -1237,19 +2608,31 @@ impl GuiApp {
                         } else {
                             crate::format_size(entry.file_size)
                         };
-                        let date_str = if entry.modification_time > 0 {
-                            crate::format_filetime(entry.modification_time)
+                        let date = self.entry_time(entry);
+                        let date_str = if date > 0 {
+                            crate::format_filetime(date)
                         } else {
                             String::new()
                         };
-                        let type_str = colors::type_label(entry.is_directory, &entry.extension);
+                        let type_str = entry
+                            .metafile_label
+                            .unwrap_or_else(|| colors::type_label(entry.is_directory, extension));
 
-                        // Name
+                        // Name (system files in italics)
                         row.col(|ui| {
-                            ui.label(
-                                egui::RichText::new(format!("{} {}", icon, entry.name))
-                                    .color(name_color),
-                            );
+                            let mut name_text = egui::RichText::new(format!(
+                                "{} {}",
+                                icon,
+                                self.strings.get(entry.name)
+                            ))
+                            .color(name_color);
+                            if entry.is_system() {
+                                name_text = name_text.italics();
+                            }
+                            if entry.is_deleted {
+                                name_text = name_text.strikethrough();
+                            }
+                            ui.label(name_text);
                         });
                         // Path
                         row.col(|ui| {
@@ -1268,7 +2651,7 @@ impl GuiApp {
                         // Ext
                         row.col(|ui| {
                             ui.label(
-                                egui::RichText::new(&entry.extension)
+                                egui::RichText::new(extension)
                                     .color(egui::Color32::from_rgb(100, 140, 255)),
                             );
                         });
@@ -1284,6 +2667,37 @@ impl GuiApp {
                                     .italics(),
                             );
                         });
+                        // Drive
+                        row.col(|ui| {
+                            if let Some(tree) = self.trees.get(entry.tree_index) {
+                                ui.label(
+                                    egui::RichText::new(format!("{}:", tree.drive_letter))
+                                        .color(colors::color_for_drive(entry.tree_index)),
+                                );
+                            }
+                        });
+                        // Record
+                        if show_record {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(entry.key.record_number.to_string())
+                                        .color(egui::Color32::from_rgb(140, 140, 140)),
+                                );
+                            });
+                        }
+                        // Storage
+                        if show_storage {
+                            row.col(|ui| {
+                                let color = match entry.storage {
+                                    Storage::Resident => egui::Color32::from_rgb(100, 200, 220),
+                                    Storage::NonResident { fragments } if fragments > 1 => {
+                                        egui::Color32::from_rgb(230, 190, 80)
+                                    }
+                                    _ => egui::Color32::from_rgb(140, 140, 140),
+                                };
+                                ui.label(egui::RichText::new(entry.storage.label()).color(color));
+                            });
+                        }
 
                         // Click handling
                         let response = row.response();
@@ -1294,8 +2708,8 @@ impl GuiApp {
                             self.table.anchor = Some(logical_idx);
                         }
                         if response.double_clicked() {
-                            let full_path = if !entry.cached_path.is_empty() {
-                                format!("{}\\{}", entry.cached_path, entry.name)
+                            let full_path = if entry.cached_path != StringArena::EMPTY {
+                                self.entry_full_path(entry)
                             } else if let Some(tree) = self.trees.get(entry.tree_index) {
                                 tree.build_path_for_key(&entry.key)
                             } else {
@@ -1309,8 +2723,8 @@ impl GuiApp {
                             self.table.selected = Some(logical_idx);
                             self.table.selections.clear();
                             self.table.selections.insert(logical_idx);
-                            let full_path = if !entry.cached_path.is_empty() {
-                                format!("{}\\{}", entry.cached_path, entry.name)
+                            let full_path = if entry.cached_path != StringArena::EMPTY {
+                                self.entry_full_path(entry)
                             } else if let Some(tree) = self.trees.get(entry.tree_index) {
                                 tree.build_path_for_key(&entry.key)
                             } else {
@@ -1319,7 +2733,7 @@ impl GuiApp {
                             let pos = ctx.pointer_latest_pos().unwrap_or_default();
                             self.context_menu = Some(ContextMenu {
                                 path: full_path,
-                                name: entry.name.clone(),
+                                name: self.strings.get(entry.name).to_string(),
                                 pos,
                             });
                         }
@@ -1341,6 +2755,159 @@ impl GuiApp {
         self.draw_context_menu(ui);
     }
 
+    /// Draw the results as collapsible per-kind sections with count and size.
+    fn draw_grouped_view(&mut self, ui: &mut egui::Ui) {
+        if self.kind_groups.is_none() {
+            self.kind_groups = Some(groups::group_by_kind(
+                &self.filtered_indices,
+                &self.all_entries,
+                &self.extensions,
+                &self.trees,
+            ));
+        }
+        // Taken out so rows can borrow `self` mutably; put back below
+        let kind_groups = self.kind_groups.take().unwrap_or_default();
+        // Shares are of the files; folder sizes overlap them
+        let total_size: u64 = kind_groups
+            .iter()
+            .filter(|g| g.kind != FileKind::Folder)
+            .map(|g| g.total_size)
+            .sum::<u64>()
+            .max(1);
+        let mut open_path: Option<String> = None;
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for group in &kind_groups {
+                    let header = if group.kind == FileKind::Folder {
+                        format!(
+                            "{}  \u{2014}  {} items, {} inside",
+                            group.kind.label(),
+                            group.rows.len(),
+                            crate::format_size(group.total_size)
+                        )
+                    } else {
+                        format!(
+                            "{}  \u{2014}  {} items, {} ({:.1}%)",
+                            group.kind.label(),
+                            group.rows.len(),
+                            crate::format_size(group.total_size),
+                            group.total_size as f64 * 100.0 / total_size as f64
+                        )
+                    };
+                    egui::CollapsingHeader::new(egui::RichText::new(header).strong())
+                        .id_salt(group.kind)
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            for &logical_idx in group.rows.iter().take(groups::GROUP_ROW_LIMIT) {
+                                if let Some(path) = self.draw_group_row(ui, logical_idx) {
+                                    open_path = Some(path);
+                                }
+                            }
+                            if group.rows.len() > groups::GROUP_ROW_LIMIT {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "\u{2026} and {} more (switch to the flat list to see all)",
+                                        group.rows.len() - groups::GROUP_ROW_LIMIT
+                                    ))
+                                    .color(egui::Color32::from_rgb(140, 140, 140))
+                                    .italics(),
+                                );
+                            }
+                        });
+                }
+            });
+
+        self.kind_groups = Some(kind_groups);
+
+        if let Some(path) = open_path {
+            dialogs::open_file(&path);
+        }
+
+        self.draw_context_menu(ui);
+    }
+
+    /// One result row inside a group. Returns the path to open on double-click.
+    fn draw_group_row(&mut self, ui: &mut egui::Ui, logical_idx: usize) -> Option<String> {
+        let entry_idx = *self.filtered_indices.get(logical_idx)?;
+        let entry = &self.all_entries[entry_idx];
+        let extension = self.extensions.get(entry.extension);
+        let is_selected = self.table.selections.contains(&logical_idx)
+            || self.table.selected == Some(logical_idx);
+
+        let name_color = if entry.is_deleted {
+            DELETED_COLOR
+        } else if entry.is_hidden() {
+            egui::Color32::from_rgb(110, 110, 110)
+        } else if entry.is_directory {
+            egui::Color32::from_rgb(100, 180, 255)
+        } else {
+            colors::color_for_extension(extension)
+        };
+        let icon = if entry.off_volume_link {
+            colors::LINK_ICON
+        } else {
+            colors::icon_for_entry(entry.is_directory, extension)
+        };
+        let mut name_text = egui::RichText::new(format!("{} {}", icon, self.strings.get(entry.name)))
+        .color(name_color);
+        if entry.is_system() {
+            name_text = name_text.italics();
+        }
+        if entry.is_deleted {
+            name_text = name_text.strikethrough();
+        }
+        let size_str = if entry.is_directory {
+            String::new()
+        } else {
+            crate::format_size(entry.file_size)
+        };
+        let parent_path = self.get_parent_path(entry_idx);
+
+        let response = ui
+            .horizontal(|ui| {
+                let response = ui.selectable_label(is_selected, name_text);
+                ui.label(egui::RichText::new(size_str).color(egui::Color32::from_rgb(80, 200, 80)));
+                ui.label(
+                    egui::RichText::new(parent_path).color(egui::Color32::from_rgb(160, 160, 160)),
+                );
+                response
+            })
+            .inner;
+
+        if !(response.clicked() || response.double_clicked() || response.secondary_clicked()) {
+            return None;
+        }
+
+        self.table.selected = Some(logical_idx);
+        self.table.selections.clear();
+        self.table.selections.insert(logical_idx);
+        self.table.anchor = Some(logical_idx);
+
+        let entry = &self.all_entries[entry_idx];
+        let full_path = if entry.cached_path != StringArena::EMPTY {
+            self.entry_full_path(entry)
+        } else if let Some(tree) = self.trees.get(entry.tree_index) {
+            tree.build_path_for_key(&entry.key)
+        } else {
+            String::new()
+        };
+
+        if response.secondary_clicked() {
+            self.context_menu = Some(ContextMenu {
+                path: full_path,
+                name: self.strings.get(entry.name).to_string(),
+                pos: ui.ctx().pointer_latest_pos().unwrap_or_default(),
+            });
+            return None;
+        }
+        if response.double_clicked() && !full_path.is_empty() {
+            return Some(full_path);
+        }
+        None
+    }
+
     fn draw_context_menu(&mut self, ui: &mut egui::Ui) {
         if let Some(cm) = self.context_menu.clone() {
             let area_resp = egui::Area::new(ui.id().with("ctx_menu"))
@@ -1357,6 +2924,12 @@ impl GuiApp {
                             dialogs::open_in_explorer(&cm.path);
                             self.context_menu = None;
                         }
+                        if ui.button("Open Terminal Here").clicked() {
+                            if let Err(e) = shell::open_terminal_here(&cm.path) {
+                                self.status_message = format!("Error opening terminal: {}", e);
+                            }
+                            self.context_menu = None;
+                        }
                         if ui.button("Properties").clicked() {
                             dialogs::show_properties(&cm.path);
                             self.context_menu = None;
@@ -1367,6 +2940,18 @@ impl GuiApp {
                             self.status_message = "Path copied".to_string();
                             self.context_menu = None;
                         }
+                        if ui.button("Filter to This Extension  (Ctrl+E)").clicked() {
+                            self.filter_to_selected_extension();
+                            self.context_menu = None;
+                        }
+                        if ui.button("Copy Size Report").clicked() {
+                            self.copy_size_report();
+                            self.context_menu = None;
+                        }
+                        if ui.button("Browse Folder  (Enter)").clicked() {
+                            self.browse_selected_folder();
+                            self.context_menu = None;
+                        }
                         ui.separator();
                         if ui.button("Rename").clicked() {
                             self.active_dialog = ActiveDialog::Rename {
@@ -1383,6 +2968,14 @@ impl GuiApp {
                             };
                             self.context_menu = None;
                         }
+                        if ui.button("Toggle Read-only").clicked() {
+                            self.confirm_attribute_toggle(file_attributes::READONLY);
+                            self.context_menu = None;
+                        }
+                        if ui.button("Toggle Hidden").clicked() {
+                            self.confirm_attribute_toggle(file_attributes::HIDDEN);
+                            self.context_menu = None;
+                        }
                     });
                 });
 
@@ -1417,6 +3010,9 @@ impl GuiApp {
                     if result {
                         match action {
                             PendingAction::Delete => self.execute_delete(),
+                            PendingAction::ToggleAttribute(flag) => {
+                                self.execute_attribute_toggle(flag)
+                            }
                         }
                     }
                 } else {
@@ -1445,6 +3041,25 @@ impl GuiApp {
                     }
                 }
             }
+            ActiveDialog::BatchRename(mut state) => {
+                match dialogs::show_batch_rename_dialog(ctx, &mut state) {
+                    Some(Some(items)) => self.execute_batch_rename(&items),
+                    Some(None) => {
+                        // cancelled
+                    }
+                    None => self.active_dialog = ActiveDialog::BatchRename(state),
+                }
+            }
+            ActiveDialog::DriveStats(stats) => {
+                if dialogs::show_drive_stats_dialog(ctx, &stats) {
+                    self.active_dialog = ActiveDialog::DriveStats(stats);
+                }
+            }
+            ActiveDialog::KindSummary(summary) => {
+                if dialogs::show_kind_summary_dialog(ctx, &summary) {
+                    self.active_dialog = ActiveDialog::KindSummary(summary);
+                }
+            }
             ActiveDialog::Info { title, lines } => {
                 if dialogs::show_info_dialog(ctx, &title, &lines) {
                     self.active_dialog = ActiveDialog::Info { title, lines };
@@ -1462,11 +3077,32 @@ impl GuiApp {
             self.treemap = None;
         } else {
             let mut state = TreemapState::new();
+            state.size_basis = self.treemap_size;
             state.build_from_trees(&self.trees);
             self.treemap = Some(state);
         }
     }
 
+    /// Lay the treemap out again at its current level (after the size
+    /// basis changed)
+    fn rebuild_treemap(&mut self) {
+        let Some(tm) = self.treemap.as_mut() else {
+            return;
+        };
+        tm.size_basis = self.treemap_size;
+        let key = tm.current_key;
+        if key == NodeKey::root() {
+            tm.build_from_trees(&self.trees);
+        } else if let Some(tree) = self.trees.iter().find(|t| t.get_by_key(&key).is_some()) {
+            tm.build_from_node(tree, &key);
+        }
+    }
+
+    fn toggle_treemap_size(&mut self) {
+        self.treemap_size = self.treemap_size.toggled();
+        self.rebuild_treemap();
+    }
+
     fn draw_treemap_view(&mut self, ctx: &egui::Context) {
         // Top panel: breadcrumb + back / close buttons
         egui::TopBottomPanel::top("treemap_toolbar").show(ctx, |ui| {
@@ -1478,6 +3114,16 @@ impl GuiApp {
                     self.treemap = None;
                     return;
                 }
+                if ui
+                    .selectable_label(
+                        self.treemap_size == SizeBasis::Allocated,
+                        "Allocated Size",
+                    )
+                    .on_hover_text("Size blocks by space used on disk instead of file length (A)")
+                    .clicked()
+                {
+                    self.toggle_treemap_size();
+                }
 
                 ui.separator();
 
@@ -1510,15 +3156,16 @@ impl GuiApp {
                                 "\u{1F4C4}"
                             };
                             ui.label(format!(
-                                "{} {} — {}",
+                                "{} {} — {} {}",
                                 icon,
                                 r.name,
-                                crate::format_size(r.size)
+                                crate::format_size(r.size),
+                                tm.size_basis.label()
                             ));
                         }
                     }
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.label("Click to select · Double-click to drill down · Backspace/Back to go up");
+                        ui.label("Click to select · Double-click to drill down · Backspace/Back to go up · A: logical/allocated size");
                     });
                 });
             });
@@ -1558,6 +3205,7 @@ impl GuiApp {
                 }
 
                 let tm = self.treemap.as_ref().unwrap();
+                let palette = self.palette;
                 let cw = canvas_rect.width() as f64;
                 let ch = canvas_rect.height() as f64;
 
@@ -1584,11 +3232,11 @@ impl GuiApp {
 
                     if rect.children_rendered {
                         // Container: border + dark bg
-                        let bg = colors::depth_bg_color(rect.depth);
-                        let border = colors::depth_border_color(rect.depth);
+                        let bg = colors::rgb(palette.depth_bg(rect.depth));
+                        let border = colors::rgb(palette.depth_border(rect.depth));
                         painter.rect_filled(r, 0.0, bg);
                         let stroke_color = if is_sel {
-                            egui::Color32::from_rgb(255, 255, 100)
+                            colors::rgb(palette.selection_outline)
                         } else {
                             border
                         };
@@ -1612,19 +3260,35 @@ impl GuiApp {
                             }
                         }
                     } else {
-                        // Leaf
-                        let bg = if is_sel {
-                            egui::Color32::from_rgb(0, 0, 170) // CGA blue
+                        // Leaf; selection gets an outline as well as a fill so
+                        // it does not depend on telling hues apart
+                        let (bg, text_color) = if is_sel {
+                            (colors::rgb(palette.selection_bg), colors::rgb(palette.selection_fg))
                         } else {
-                            colors::leaf_color(&rect.name, rect.is_directory, i)
+                            (
+                                colors::rgb(palette.leaf(&rect.name, rect.is_directory, i)),
+                                colors::rgb(palette.text),
+                            )
                         };
                         painter.rect_filled(r, 0.0, bg);
-                        painter.rect_stroke(
-                            r,
-                            0.0,
-                            egui::Stroke::new(0.5, egui::Color32::from_rgba_premultiplied(0, 0, 0, 80)),
-                            egui::StrokeKind::Outside,
-                        );
+                        let stroke = if is_sel {
+                            egui::Stroke::new(2.0, colors::rgb(palette.selection_outline))
+                        } else {
+                            egui::Stroke::new(0.5, egui::Color32::from_rgba_premultiplied(0, 0, 0, 80))
+                        };
+                        painter.rect_stroke(r, 0.0, stroke, egui::StrokeKind::Outside);
+
+                        // Amber corner marker for system files
+                        if rect.is_system && rw > 8.0 && rh > 8.0 {
+                            painter.rect_filled(
+                                egui::Rect::from_min_size(
+                                    egui::pos2(rx + rw - 5.0, ry),
+                                    egui::vec2(5.0, 5.0),
+                                ),
+                                0.0,
+                                colors::rgb(palette.system_text),
+                            );
+                        }
 
                         // Name + size text
                         if rw > 20.0 && rh > 12.0 {
@@ -1634,7 +3298,7 @@ impl GuiApp {
                                 egui::Align2::LEFT_TOP,
                                 &name_trunc,
                                 egui::FontId::proportional(11.0),
-                                egui::Color32::from_rgb(235, 235, 235),
+                                text_color,
                             );
                             if rh > 26.0 {
                                 painter.text(
@@ -1642,7 +3306,7 @@ impl GuiApp {
                                     egui::Align2::LEFT_TOP,
                                     crate::format_size(rect.size),
                                     egui::FontId::proportional(10.0),
-                                    egui::Color32::from_rgb(200, 200, 200),
+                                    text_color.gamma_multiply(0.85),
                                 );
                             }
                         }
@@ -1684,6 +3348,7 @@ impl GuiApp {
         let press_right = ctx.input(|i| i.key_pressed(egui::Key::ArrowRight));
         let press_left = ctx.input(|i| i.key_pressed(egui::Key::ArrowLeft));
         let press_enter = ctx.input(|i| i.key_pressed(egui::Key::Enter));
+        let press_a = ctx.input(|i| i.key_pressed(egui::Key::A));
 
         if go_up {
             self.treemap_go_up();
@@ -1705,6 +3370,9 @@ impl GuiApp {
             let sel = self.treemap.as_ref().map(|tm| tm.selected).unwrap_or(0);
             self.treemap_drill_down(sel);
         }
+        if press_a {
+            self.toggle_treemap_size();
+        }
     }
 
     fn treemap_drill_down(&mut self, idx: usize) {
@@ -1806,6 +3474,28 @@ impl SizeFilter {
     }
 }
 
+/// Lowercase name of a togglable attribute flag, for prompts
+fn attribute_name(flag: u32) -> &'static str {
+    if flag == file_attributes::READONLY {
+        "read-only"
+    } else {
+        "hidden"
+    }
+}
+
+/// Scan settings for full scans and single-drive rescans
+fn scan_config(calculate_sizes: bool, include_deleted: bool) -> ScanConfig {
+    ScanConfig::builder()
+        .use_usn(true)
+        .use_mft(true)
+        .include_hidden(true)
+        .include_system(true)
+        .calculate_sizes(calculate_sizes)
+        .include_deleted(include_deleted)
+        .show_progress(false) // Don't write progress bars to stdout
+        .build()
+}
+
 fn extract_extension(name: &str) -> String {
     if let Some(dot_pos) = name.rfind('.') {
         if dot_pos > 0 && dot_pos < name.len() - 1 {
@@ -1818,7 +3508,7 @@ fn extract_extension(name: &str) -> String {
 fn parse_scope_path(query: &str) -> (Option<String>, String) {
     if let Some(start) = query.find('`') {
         if let Some(end) = query[start + 1..].find('`') {
-            let scope = query[start + 1..start + 1 + end].trim().to_string();
+            let scope = normalize_scope(&query[start + 1..start + 1 + end]);
             let rest_before = query[..start].trim();
             let rest_after = query[start + 1 + end + 1..].trim();
             let remaining = format!("{} {}", rest_before, rest_after)
@@ -1832,6 +3522,29 @@ fn parse_scope_path(query: &str) -> (Option<String>, String) {
     (None, query.to_string())
 }
 
+/// Normalize a scope path for matching: forward slashes become backslashes
+/// and trailing separators are dropped, so `C:\` and `c:/` both become `c:`.
+fn normalize_scope(scope: &str) -> String {
+    scope
+        .trim()
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_string()
+}
+
+/// Check whether the full path (`parent_lower` + name) is the scope folder
+/// or lies beneath it. Matches on whole path components.
+fn path_in_scope(parent_lower: &str, name_lower: &str, scope: &str) -> bool {
+    let parent = parent_lower.trim_end_matches('\\');
+    if scope.len() <= parent.len() {
+        parent.starts_with(scope)
+            && (scope.len() == parent.len() || parent.as_bytes()[scope.len()] == b'\\')
+    } else {
+        // The scope names this entry itself
+        scope.starts_with(parent) && scope[parent.len()..].strip_prefix('\\') == Some(name_lower)
+    }
+}
+
 fn fit_title(name: &str, size: u64, max_chars: usize) -> String {
     if max_chars < 6 {
         return String::new();
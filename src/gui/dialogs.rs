@@ -1,62 +1,8 @@
+use crate::batch_rename::{self, RenameItem, RenameTemplate};
+use crate::search_index::filter_tags;
 use eframe::egui;
 
-// ============================================================================
-// Date / Size filter types (mirrored from TUI menu)
-// ============================================================================
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DateFilterMode {
-    None,
-    After,
-    Before,
-    Between,
-}
-
-impl DateFilterMode {
-    pub fn label(&self) -> &'static str {
-        match self {
-            DateFilterMode::None => "None",
-            DateFilterMode::After => "After",
-            DateFilterMode::Before => "Before",
-            DateFilterMode::Between => "Between",
-        }
-    }
-    pub fn all() -> &'static [DateFilterMode] {
-        &[
-            DateFilterMode::None,
-            DateFilterMode::After,
-            DateFilterMode::Before,
-            DateFilterMode::Between,
-        ]
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SizeFilterMode {
-    None,
-    GreaterThan,
-    LessThan,
-    Between,
-}
-
-impl SizeFilterMode {
-    pub fn label(&self) -> &'static str {
-        match self {
-            SizeFilterMode::None => "None",
-            SizeFilterMode::GreaterThan => ">",
-            SizeFilterMode::LessThan => "<",
-            SizeFilterMode::Between => "Between",
-        }
-    }
-    pub fn all() -> &'static [SizeFilterMode] {
-        &[
-            SizeFilterMode::None,
-            SizeFilterMode::GreaterThan,
-            SizeFilterMode::LessThan,
-            SizeFilterMode::Between,
-        ]
-    }
-}
+pub use crate::search_index::{DateFilterMode, SizeFilterMode};
 
 // ============================================================================
 // Search filters state
@@ -98,6 +44,16 @@ impl SearchFilters {
             || self.size_mode != SizeFilterMode::None
             || !self.extension_filter.is_empty()
     }
+
+    /// Summary of the active filters for the search bar (see `filter_tags`)
+    pub fn indicator(&self) -> String {
+        filter_tags(
+            &self.regex_pattern,
+            (self.size_mode, &self.size_value, &self.size_end),
+            (self.date_mode, &self.date_start, &self.date_end),
+            &self.extension_filter,
+        )
+    }
 }
 
 // ============================================================================
@@ -290,6 +246,246 @@ pub fn show_rename_dialog(
     result
 }
 
+// ============================================================================
+// Batch rename dialog
+// ============================================================================
+
+/// Batch rename fields and the items they apply to
+#[derive(Clone)]
+pub struct BatchRename {
+    pub paths: Vec<String>,
+    pub pattern: String,
+    pub find: String,
+    pub replace: String,
+}
+
+impl BatchRename {
+    pub fn new(paths: Vec<String>) -> Self {
+        Self {
+            paths,
+            pattern: "{name}.{ext}".to_string(),
+            find: String::new(),
+            replace: String::new(),
+        }
+    }
+}
+
+/// Returns `Some(Some(plan))` when confirmed, `Some(None)` when cancelled
+/// and `None` while still editing.
+pub fn show_batch_rename_dialog(
+    ctx: &egui::Context,
+    state: &mut BatchRename,
+) -> Option<Option<Vec<RenameItem>>> {
+    let mut result: Option<Option<Vec<RenameItem>>> = None;
+    let mut open = true;
+
+    egui::Window::new("Batch Rename")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .open(&mut open)
+        .show(ctx, |ui| {
+            egui::Grid::new("batch_rename_grid")
+                .num_columns(2)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("Pattern:");
+                    ui.text_edit_singleline(&mut state.pattern);
+                    ui.end_row();
+                    ui.label("Find (regex):");
+                    ui.text_edit_singleline(&mut state.find);
+                    ui.end_row();
+                    ui.label("Replace:");
+                    ui.text_edit_singleline(&mut state.replace);
+                    ui.end_row();
+                });
+            ui.label(
+                egui::RichText::new("{name}, {ext}, {n} (sequence), {n:3} (zero-padded)")
+                    .small()
+                    .color(egui::Color32::GRAY),
+            );
+            ui.separator();
+
+            let preview = RenameTemplate::new(&state.pattern, &state.find, &state.replace)
+                .map(|template| batch_rename::plan(&state.paths, &template));
+            let rename_count = match preview {
+                Ok(ref items) => items.iter().filter(|item| item.will_rename()).count(),
+                Err(_) => 0,
+            };
+
+            match preview {
+                Ok(ref items) => {
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for item in items {
+                            ui.horizontal(|ui| {
+                                ui.label(item.name());
+                                ui.label("\u{2192}");
+                                match item.to {
+                                    Ok(ref to) if item.will_rename() => {
+                                        ui.colored_label(egui::Color32::from_rgb(80, 200, 80), to);
+                                    }
+                                    Ok(_) => {
+                                        ui.colored_label(egui::Color32::GRAY, "(unchanged)");
+                                    }
+                                    Err(ref why) => {
+                                        ui.colored_label(
+                                            egui::Color32::from_rgb(230, 80, 80),
+                                            format!("skipped: {}", why),
+                                        );
+                                    }
+                                }
+                            });
+                        }
+                    });
+                }
+                Err(ref e) => {
+                    ui.colored_label(egui::Color32::from_rgb(230, 80, 80), format!("Regex: {}", e));
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let label = format!("Rename {} of {}", rename_count, state.paths.len());
+                if ui.add_enabled(rename_count > 0, egui::Button::new(label)).clicked() {
+                    result = Some(preview.ok());
+                }
+                if ui.button("Cancel").clicked() {
+                    result = Some(None);
+                }
+            });
+        });
+
+    if !open {
+        return Some(None);
+    }
+    result
+}
+
+// ============================================================================
+// Drive statistics dialog
+// ============================================================================
+
+/// Per-drive stats side by side, with a total column for several drives.
+/// Returns false once closed.
+pub fn show_drive_stats_dialog(ctx: &egui::Context, stats: &[(char, crate::TreeStats)]) -> bool {
+    let mut open = true;
+    let mut close = false;
+
+    let mut columns: Vec<(String, [(&str, String); 6])> = stats
+        .iter()
+        .map(|(drive, s)| (format!("{}:", drive), s.summary_rows()))
+        .collect();
+    if stats.len() > 1 {
+        let total = stats
+            .iter()
+            .fold(crate::TreeStats::default(), |acc, (_, s)| acc.combined(s));
+        columns.push(("Total".to_string(), total.summary_rows()));
+    }
+
+    egui::Window::new("Drive Statistics")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .open(&mut open)
+        .show(ctx, |ui| {
+            if let Some((_, first)) = columns.first() {
+                egui::Grid::new("drive_stats_grid")
+                    .striped(true)
+                    .spacing([24.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("");
+                        for (label, _) in &columns {
+                            ui.strong(label);
+                        }
+                        ui.end_row();
+                        for (row, (label, _)) in first.iter().enumerate() {
+                            ui.label(*label);
+                            for (_, rows) in &columns {
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| ui.label(&rows[row].1),
+                                );
+                            }
+                            ui.end_row();
+                        }
+                    });
+            } else {
+                ui.label("No drives scanned yet.");
+            }
+            ui.add_space(8.0);
+            if ui.button("OK").clicked() {
+                close = true;
+            }
+        });
+
+    open && !close
+}
+
+// ============================================================================
+// File categories dialog
+// ============================================================================
+
+/// Show item count and size per file kind, with each kind's share of the
+/// total size as a bar. Returns false once closed.
+pub fn show_kind_summary_dialog(ctx: &egui::Context, summary: &crate::KindSummary) -> bool {
+    let mut open = true;
+    let mut close = false;
+    let total = summary.total_size().max(1);
+
+    egui::Window::new("File Categories")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .open(&mut open)
+        .show(ctx, |ui| {
+            let rows = summary.rows();
+            if rows.is_empty() {
+                ui.label("No drives scanned yet.");
+            } else {
+                egui::Grid::new("kind_summary_grid")
+                    .striped(true)
+                    .spacing([24.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.strong("Items");
+                        ui.strong("Size");
+                        ui.strong("Share");
+                        ui.end_row();
+                        for (kind, count, size) in rows {
+                            let share = size as f64 / total as f64;
+                            ui.label(kind.label());
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.label(count.to_string())
+                            });
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.label(crate::format_size(size))
+                            });
+                            ui.add(
+                                egui::ProgressBar::new(share as f32)
+                                    .desired_width(160.0)
+                                    .text(format!("{:.0}%", share * 100.0)),
+                            );
+                            ui.end_row();
+                        }
+                        ui.strong("Total");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.strong(summary.total_count().to_string())
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.strong(crate::format_size(summary.total_size()))
+                        });
+                        ui.end_row();
+                    });
+            }
+            ui.add_space(8.0);
+            if ui.button("OK").clicked() {
+                close = true;
+            }
+        });
+
+    open && !close
+}
+
 // ============================================================================
 // Info / About dialog
 // ============================================================================
@@ -381,8 +577,14 @@ pub fn open_file(path: &str) {
 
 pub fn open_in_explorer(path: &str) {
     use std::os::windows::process::CommandExt;
+    // Files are selected inside their parent folder; directories open directly
+    let arg = if std::path::Path::new(path).is_dir() {
+        format!("\"{}\"", path)
+    } else {
+        format!("/select,\"{}\"", path.trim_end_matches('\\'))
+    };
     let _ = std::process::Command::new("explorer.exe")
-        .raw_arg(format!("/select,\"{}\"", path))
+        .raw_arg(arg)
         .creation_flags(0x08000000)
         .spawn();
 }
@@ -0,0 +1,76 @@
+use crate::arena::ExtensionTable;
+use crate::file_kind::FileKind;
+use crate::file_tree::FileTree;
+use crate::gui::app::EntryData;
+use std::sync::Arc;
+
+/// Rows listed under an expanded group header; the rest are summarised
+pub const GROUP_ROW_LIMIT: usize = 500;
+
+/// How results are laid out in the central panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewMode {
+    /// One flat, sortable table
+    #[default]
+    Table,
+    /// Collapsible sections per `FileKind`
+    ByKind,
+}
+
+/// Results of one file kind.
+pub struct KindGroup {
+    pub kind: FileKind,
+    /// Logical indices into `filtered_indices`, in current sort order
+    pub rows: Vec<usize>,
+    /// Summed file sizes; for folders, everything beneath each one, so it
+    /// overlaps the other groups and nested folders count more than once
+    pub total_size: u64,
+}
+
+/// Bucket the filtered results by kind, largest group first.
+pub fn group_by_kind(
+    filtered_indices: &[usize],
+    entries: &[EntryData],
+    extensions: &ExtensionTable,
+    trees: &[Arc<FileTree>],
+) -> Vec<KindGroup> {
+    let mut groups: Vec<KindGroup> = FileKind::ALL
+        .iter()
+        .map(|&kind| KindGroup {
+            kind,
+            rows: Vec::new(),
+            total_size: 0,
+        })
+        .collect();
+
+    // Classify each distinct extension once
+    let mut ext_kinds: Vec<Option<FileKind>> = vec![None; extensions.len()];
+
+    for (logical_idx, &entry_idx) in filtered_indices.iter().enumerate() {
+        let entry = &entries[entry_idx];
+        let kind = if entry.is_directory {
+            FileKind::Folder
+        } else {
+            match ext_kinds.get_mut(entry.extension as usize) {
+                Some(slot) => *slot.get_or_insert_with(|| {
+                    FileKind::from_extension(extensions.get(entry.extension))
+                }),
+                None => FileKind::from_extension(extensions.get(entry.extension)),
+            }
+        };
+
+        let group = &mut groups[kind as usize];
+        group.rows.push(logical_idx);
+        group.total_size += if entry.is_directory {
+            trees
+                .get(entry.tree_index)
+                .map_or(0, |t| t.total_size_for_key(&entry.key))
+        } else {
+            entry.file_size
+        };
+    }
+
+    groups.retain(|g| !g.rows.is_empty());
+    groups.sort_by_key(|g| std::cmp::Reverse(g.total_size));
+    groups
+}
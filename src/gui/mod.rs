@@ -2,11 +2,24 @@ pub mod app;
 pub mod colors;
 pub mod table;
 pub mod search;
-pub mod treemap;
 pub mod dialogs;
+pub mod groups;
 
 /// Entry point: launch the native GUI window
 pub fn run() -> crate::Result<()> {
+    run_with_config(&crate::AppConfig::default())
+}
+
+/// Same as `run`, using the refresh interval from `config`
+pub fn run_with_config(config: &crate::AppConfig) -> crate::Result<()> {
+    let refresh_interval = std::time::Duration::from_millis(config.refresh_interval_ms.max(1));
+    let show_hidden = config.show_hidden_results;
+    let show_system = config.show_system_results;
+    let palette = config.treemap_palette.clone();
+    let select_path = config.select_path.clone();
+    let calculate_sizes = config.calculate_sizes;
+    let include_deleted = config.include_deleted;
+
     let native_options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
             .with_title("EmFit — Ultra-fast NTFS File Scanner")
@@ -18,7 +31,18 @@ pub fn run() -> crate::Result<()> {
     eframe::run_native(
         "EmFit",
         native_options,
-        Box::new(|cc| Ok(Box::new(app::GuiApp::new(cc)))),
+        Box::new(move |cc| {
+            Ok(Box::new(
+                app::GuiApp::new(cc)
+                    .with_refresh_interval(refresh_interval)
+                    .with_show_hidden(show_hidden)
+                    .with_show_system(show_system)
+                    .with_palette(&palette)
+                    .with_select_path(select_path)
+                    .with_calculate_sizes(calculate_sizes)
+                    .with_include_deleted(include_deleted),
+            ))
+        }),
     )
     .map_err(|e| crate::EmFitError::WindowsError(format!("GUI error: {}", e)))
 }
@@ -3,6 +3,14 @@ pub struct SearchState {
     pub query: String,
     pub focused: bool,
     pub needs_search: bool,
+    /// Ordering applied to fresh results of a text query
+    pub default_order: ResultOrder,
+    /// Results each `;`-separated pattern matched in the last search, when
+    /// there was more than one. A result matching several patterns counts
+    /// for each, so the counts can add up to more than the results. Results
+    /// held back for unknown metadata are not counted; their metadata
+    /// arriving runs the search again, which recounts.
+    pub pattern_counts: Vec<(String, usize)>,
 }
 
 impl Default for SearchState {
@@ -11,25 +19,14 @@ impl Default for SearchState {
             query: String::new(),
             focused: true,
             needs_search: false,
+            default_order: ResultOrder::default(),
+            pattern_counts: Vec::new(),
         }
     }
 }
 
-/// Check if a filename matches a pattern.
-/// Supports `*` wildcards: `*.ext`, `prefix*`, `*text*`, or plain substring.
-pub fn matches_pattern(name: &str, pattern: &str) -> bool {
-    if pattern.is_empty() {
-        return true;
-    }
-
-    let has_leading_star = pattern.starts_with('*');
-    let has_trailing_star = pattern.ends_with('*');
+/// Check if a filename matches a pattern (the index's rules: whole-name
+/// text and wildcards, `^`/`$` to leave one end open)
+pub use crate::search_index::matches_pattern;
 
-    match (has_leading_star, has_trailing_star) {
-        (true, true) if pattern.len() > 2 => name.contains(&pattern[1..pattern.len() - 1]),
-        (true, true) => true,
-        (true, false) => name.ends_with(&pattern[1..]),
-        (false, true) => name.starts_with(&pattern[..pattern.len() - 1]),
-        (false, false) => name.contains(pattern),
-    }
-}
+pub use crate::search_index::{pattern_counts_label, relevance_rank, ResultOrder};
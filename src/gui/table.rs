@@ -10,6 +10,12 @@ pub enum SortColumn {
     Extension,
     DateModified,
     Type,
+    /// Drive letter of the volume the entry was scanned from.
+    Drive,
+    /// MFT record number; roughly the order records were allocated in.
+    Record,
+    /// Resident in the MFT record, or the number of fragments on disk.
+    Storage,
 }
 
 /// Sort direction.
@@ -29,6 +35,53 @@ impl SortOrder {
     }
 }
 
+/// How tightly result rows are packed: row height and text size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowDensity {
+    /// More rows on a small screen
+    Compact,
+    #[default]
+    Normal,
+    /// Larger rows and text for high-DPI displays
+    Comfortable,
+}
+
+impl RowDensity {
+    /// Every density, in menu order
+    pub const ALL: [RowDensity; 3] = [RowDensity::Compact, RowDensity::Normal, RowDensity::Comfortable];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RowDensity::Compact => "Compact",
+            RowDensity::Normal => "Normal",
+            RowDensity::Comfortable => "Comfortable",
+        }
+    }
+
+    /// Parse a label as stored by `label`
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.label().eq_ignore_ascii_case(label))
+    }
+
+    /// Height of one table row in points
+    pub fn row_height(self) -> f32 {
+        match self {
+            RowDensity::Compact => 16.0,
+            RowDensity::Normal => 20.0,
+            RowDensity::Comfortable => 26.0,
+        }
+    }
+
+    /// Body text size in points
+    pub fn font_size(self) -> f32 {
+        match self {
+            RowDensity::Compact => 11.0,
+            RowDensity::Normal => 12.5,
+            RowDensity::Comfortable => 15.0,
+        }
+    }
+}
+
 /// Table display state (mirrors the TUI version).
 pub struct TableState {
     pub selected: Option<usize>,
@@ -191,6 +244,15 @@ impl TableState {
         self.ensure_visible(total - 1);
     }
 
+    /// Select a single row and scroll it into view
+    pub fn select_row(&mut self, index: usize) {
+        self.selected = Some(index);
+        self.selections.clear();
+        self.selections.insert(index);
+        self.anchor = Some(index);
+        self.ensure_visible(index);
+    }
+
     fn ensure_visible(&mut self, index: usize) {
         if index < self.scroll_offset {
             self.scroll_offset = index;
@@ -14,8 +14,18 @@
 //!
 //! # Example
 //!
+//! The one-liner for the common case:
+//!
+//! ```no_run
+//! let tree = emfit::scan('C')?;
+//! println!("{} files, {}", tree.stats.total_files, emfit::format_size(tree.stats.total_size));
+//! # Ok::<(), emfit::EmFitError>(())
+//! ```
+//!
+//! With full control over the scan:
+//!
 //! ```no_run
-//! use emfit::{VolumeScanner, ScanConfig, SearchIndex, parse_query};
+//! use emfit::{VolumeScanner, ScanConfig, SearchIndex, IndexEntry, parse_query};
 //!
 //! fn main() -> emfit::Result<()> {
 //!     // Scan C: drive
@@ -29,7 +39,7 @@
 //!     println!("Total size: {} bytes", tree.stats.total_size);
 //!     
 //!     // Build search index for instant searching
-//!     let index = SearchIndex::new('C');
+//!     let mut index = SearchIndex::new('C');
 //!     for entry in tree.iter() {
 //!         let node = entry.value();
 //!         index.add(IndexEntry::from_tree_node(node));
@@ -48,29 +58,109 @@
 
 #![cfg(windows)]
 
+pub mod arena;
+pub mod batch_rename;
 pub mod error;
+pub mod file_kind;
 pub mod file_tree;
 pub mod gui;
 pub mod tui;
 pub mod logging;
 pub mod ntfs;
+pub mod palette;
+pub mod report;
 pub mod scanner;
+pub mod search_index;
+pub mod shell;
+pub mod threads;
+pub mod treemap;
 
 // Re-export main types
-pub use error::{Result, EmFitError};
-pub use file_tree::{FileTree, NodeKey, SearchResult, TreeBuilder, TreeNode, TreeStats};
+pub use error::{Result, EmFitError, ErrorKind};
+pub use file_kind::{FileKind, KindSummary};
+pub use file_tree::{
+    AttributeFilter, ChildSort, DateField, DiffEntry, FileTree, JournalStatus, NodeKey, PathStyle, ScanDiff,
+    SearchResult, SizeChange, TimestompCandidate, TreeBuilder, TreeNode, TreeStats, UsnCursor,
+};
 pub use scanner::{
-    ChangeMonitor, MultiVolumeScanner, ScanConfig, ScanPhase, ScanProgress, VolumeScanner,
+    ChangeMonitor, DriveChangeEvent, MultiChangeMonitor, MultiVolumeScanner, ScanCheckpoint,
+    ScanConfig, ScanConfigBuilder, ScanPhase, ScanProgress, ScanReport, VolumeScanner,
+    IMAGE_DRIVE_LETTER,
 };
+pub use search_index::{
+    matches_path, matches_pattern, parse_query, CompositeIndex, IndexEntry, QueryRange, SearchHit, SearchIndex, SearchQuery,
+};
+pub use threads::ThreadConfig;
 
 // Re-export NTFS types that users might need
 pub use ntfs::{
-    ChangeEvent, ChangeReason, FileEntry, NtfsVolumeData, UsnEntry, UsnJournalData,
+    ChangeEvent, ChangeReason, FileEntry, NtfsVolumeData, ReparseTarget, Storage, UsnEntry,
+    UsnJournalData, VolumeInfo,
 };
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Scan one drive with the default configuration and no progress output.
+/// Use `VolumeScanner` for control over the scan.
+pub fn scan(drive: char) -> Result<FileTree> {
+    let config = ScanConfig::builder().show_progress(false).build();
+    VolumeScanner::new(drive).with_config(config).scan()
+}
+
+/// Scan every NTFS volume on the machine, one after another, with the same
+/// defaults as `scan`.
+pub fn scan_all() -> Vec<(char, Result<FileTree>)> {
+    let config = ScanConfig::builder().show_progress(false).build();
+    MultiVolumeScanner::new()
+        .with_config(config)
+        .scan_drives(&MultiVolumeScanner::detect_ntfs_volumes())
+}
+
+/// Parse the MFT record of a single file or directory without scanning the
+/// volume: sizes, times, attributes, hard links, streams and data runs.
+/// Extension records are followed as in a scan. Needs the same
+/// administrator rights as a scan. A link at `path` is described itself,
+/// not its target; a path that leads onto another volume through a mount
+/// point in a parent folder is an error.
+pub fn stat(path: &str) -> Result<FileEntry> {
+    use ntfs::winapi::{get_ntfs_volume_data, get_record_number};
+    use std::path::{Component, Prefix};
+
+    let absolute = std::path::absolute(path)?;
+    let drive = match absolute.components().next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+                (letter as char).to_ascii_uppercase()
+            }
+            _ => return Err(EmFitError::InvalidPath(format!("{} is not on a drive letter", path))),
+        },
+        _ => return Err(EmFitError::InvalidPath(path.to_string())),
+    };
+
+    let (record, volume_serial) = get_record_number(path)?;
+
+    let handle = ntfs::open_volume(drive)?;
+    let volume_data = get_ntfs_volume_data(&handle)?;
+    // The file index only means something in the MFT of its own volume
+    if volume_data.volume_serial_number as u32 != volume_serial {
+        return Err(EmFitError::InvalidPath(format!(
+            "{} is not on {}: (reached through a mount point)",
+            path, drive
+        )));
+    }
+    let mut parser = ntfs::MftParser::new(ntfs::VolumeIO::Volume { handle, volume_data })?;
+    // Extension records may sit in any MFT fragment
+    parser.load_mft_extents(drive)?;
+
+    let data = parser.read_record(record)?;
+    parser
+        .parse_batch_with_extensions(vec![(record, data)])
+        .into_iter()
+        .next()
+        .ok_or_else(|| EmFitError::InvalidMftRecord(record, "not in use or unreadable".to_string()))
+}
+
 /// Format bytes as human-readable string
 pub fn format_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB"];
@@ -90,12 +180,101 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Format how long ago something happened: "just now", "5m ago", "3h ago", "2d ago"
+pub fn format_age(age: std::time::Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        "just now".to_string()
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86400)
+    }
+}
+
 /// Format a Windows FILETIME as a human-readable date string
 pub fn format_filetime(filetime: u64) -> String {
     use ntfs::structs::filetime_to_datetime;
     filetime_to_datetime(filetime).format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+/// How the status bar sums the size of the selected items
+///
+/// A directory's `total_size` already includes everything below it, so
+/// adding it to the sizes of files inside it counts those files twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeSum {
+    /// Add only file sizes; selected directories contribute nothing
+    #[default]
+    FilesOnly,
+    /// Add every selected item's size, directories with their whole subtree
+    AsIs,
+}
+
+impl SizeSum {
+    /// Short label for menus and the status bar
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeSum::FilesOnly => "files only",
+            SizeSum::AsIs => "items as-is",
+        }
+    }
+
+    /// The other policy
+    pub fn toggled(self) -> Self {
+        match self {
+            SizeSum::FilesOnly => SizeSum::AsIs,
+            SizeSum::AsIs => SizeSum::FilesOnly,
+        }
+    }
+}
+
+/// Which size the treemap draws each file and folder with
+///
+/// Compressed and sparse files occupy less than their logical size, and
+/// small files round up to whole clusters, so the two can differ a lot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeBasis {
+    /// File length (`file_size`, `total_size` for folders)
+    #[default]
+    Logical,
+    /// Clusters on disk (`allocated_size`, `total_allocated` for folders)
+    Allocated,
+}
+
+impl SizeBasis {
+    /// Short label for the treemap info bar
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeBasis::Logical => "logical",
+            SizeBasis::Allocated => "allocated",
+        }
+    }
+
+    /// The other basis
+    pub fn toggled(self) -> Self {
+        match self {
+            SizeBasis::Logical => SizeBasis::Allocated,
+            SizeBasis::Allocated => SizeBasis::Logical,
+        }
+    }
+
+    /// Size of `node` on this basis; a folder whose subtree total is not
+    /// known yet falls back to its own size
+    pub fn size_of(self, node: &TreeNode) -> u64 {
+        match self {
+            SizeBasis::Logical if node.is_directory && node.total_size > 0 => node.total_size,
+            SizeBasis::Logical => node.file_size,
+            SizeBasis::Allocated if node.is_directory && node.total_allocated > 0 => {
+                node.total_allocated
+            }
+            SizeBasis::Allocated => node.allocated_size,
+        }
+    }
+}
+
 /// Application configuration
 #[derive(Debug, Clone)]
 pub struct AppConfig {
@@ -113,6 +292,29 @@ pub struct AppConfig {
     pub include_hidden: bool,
     /// Include system files
     pub include_system: bool,
+    /// UI refresh interval in milliseconds (TUI tick rate / GUI repaint throttle)
+    pub refresh_interval_ms: u64,
+    /// Show hidden files in UI results. Unlike `include_hidden` this only
+    /// filters what is listed, not what is scanned, so it can be toggled
+    /// in the UI without a rescan.
+    pub show_hidden_results: bool,
+    /// Show system files in UI results; the `include_system` counterpart
+    /// of `show_hidden_results`
+    pub show_system_results: bool,
+    /// Draw the TUI with ASCII glyphs only. When false, ASCII is still used
+    /// if the terminal does not look Unicode-capable.
+    pub ascii_ui: bool,
+    /// Treemap palette name (see `palette::ALL`)
+    pub treemap_palette: String,
+    /// Full path to select in the TUI/GUI results once it is loaded
+    pub select_path: Option<String>,
+    /// Read sizes from the MFT during TUI/GUI scans. When false the names
+    /// are listed as soon as the USN journal is read, and the sizes of shown
+    /// results are filled in afterwards by the metadata refresh.
+    pub calculate_sizes: bool,
+    /// List deleted files still in the MFT in TUI/GUI scans
+    /// (`ScanConfig::include_deleted`)
+    pub include_deleted: bool,
 }
 
 impl Default for AppConfig {
@@ -125,6 +327,14 @@ impl Default for AppConfig {
             max_search_results: 1000,
             include_hidden: true,
             include_system: true,
+            refresh_interval_ms: 50,
+            show_hidden_results: true,
+            show_system_results: true,
+            ascii_ui: false,
+            treemap_palette: palette::DEFAULT.name.to_string(),
+            select_path: None,
+            calculate_sizes: true,
+            include_deleted: false,
         }
     }
 }
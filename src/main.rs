@@ -6,11 +6,14 @@
 use clap::{Parser, Subcommand};
 use console::style;
 use indicatif::HumanDuration;
+use emfit::ntfs::structs::{file_attributes, name_anomalies};
+use emfit::report::ReportOptions;
 use emfit::{
-    format_size, FileTree,
-    MultiVolumeScanner, ScanConfig, VolumeScanner,
+    format_filetime, format_size, AttributeFilter, ChildSort, FileTree,
+    MultiVolumeScanner, PathStyle, ScanConfig, ScanReport, SizeBasis, Storage, VolumeScanner,
 };
 use std::io::Write;
+use std::path::Path;
 use std::time::Instant;
 
 /// EmFit - Ultra-fast NTFS file scanner
@@ -25,6 +28,48 @@ use std::time::Instant;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// UI refresh interval in milliseconds (TUI tick rate / GUI repaint throttle)
+    #[arg(long, global = true, default_value = "50")]
+    refresh_ms: u64,
+
+    /// Hide hidden files from TUI/GUI results (toggle in the View menu)
+    #[arg(long, global = true)]
+    hide_hidden: bool,
+
+    /// Hide system files from TUI/GUI results (toggle in the View menu)
+    #[arg(long, global = true)]
+    hide_system: bool,
+
+    /// Use ASCII instead of emoji and box drawing in the TUI
+    /// (automatic on consoles that do not look Unicode-capable)
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Treemap colour palette in the TUI and GUI
+    #[arg(long, global = true, default_value = "default",
+          value_parser = clap::builder::PossibleValuesParser::new(emfit::palette::TreemapPalette::names()))]
+    palette: String,
+
+    /// Most background threads busy at once, shared by MFT parsing,
+    /// sorting and other background jobs, path caching and multi-drive
+    /// search (0 = one per core)
+    #[arg(long, global = true, default_value = "0")]
+    threads: usize,
+
+    /// Select this file or folder in the TUI/GUI once its drive is scanned
+    #[arg(long, global = true, value_name = "PATH")]
+    select: Option<String>,
+
+    /// List names in the TUI/GUI as soon as the USN journal is read and
+    /// fetch sizes only for shown results, skipping the MFT size pass
+    #[arg(long, global = true)]
+    no_sizes: bool,
+
+    /// Also list deleted files whose MFT records were not reused yet, struck
+    /// through and left out of all totals (TUI/GUI, `cli scan`, `cli records`)
+    #[arg(long, global = true)]
+    deleted: bool,
 }
 
 #[derive(Subcommand)]
@@ -44,8 +89,17 @@ enum CliCommands {
     /// Scan a volume and display statistics
     Scan {
         /// Drive letter to scan (e.g., C)
-        #[arg(short, long)]
-        drive: char,
+        #[arg(short, long, required_unless_present = "image")]
+        drive: Option<char>,
+
+        /// Scan a raw NTFS disk image (.img/.dd) instead of a drive.
+        /// Needs no admin rights.
+        #[arg(long, conflicts_with = "drive")]
+        image: Option<String>,
+
+        /// Byte offset of the NTFS partition inside the image
+        #[arg(long, default_value = "0", requires = "image")]
+        partition_offset: u64,
 
         /// Use USN Journal for fast enumeration (default: false, uses MFT for accuracy)
         #[arg(long, default_value = "false")]
@@ -61,6 +115,11 @@ enum CliCommands {
         #[arg(long)]
         no_physical: bool,
 
+        /// Scan a Volume Shadow Copy snapshot of the drive for a consistent
+        /// result on a busy volume. The snapshot is deleted afterwards.
+        #[arg(long, conflicts_with = "image")]
+        vss: bool,
+
         /// Include hidden files
         #[arg(long, default_value = "true")]
         hidden: bool,
@@ -72,6 +131,21 @@ enum CliCommands {
         /// Output format (text, json)
         #[arg(short, long, default_value = "text")]
         output: String,
+
+        /// Same as `--output json`: a summary on stdout with `success`,
+        /// `partial` and an `errors` array, also when the scan fails.
+        /// Exits non-zero only if no tree could be built.
+        #[arg(long, conflicts_with = "output")]
+        json: bool,
+
+        /// Checkpoint file for a resumable MFT scan.
+        /// If the file exists, the scan continues from it.
+        #[arg(long)]
+        resume: Option<String>,
+
+        /// Maximum parent levels to climb when resolving paths
+        #[arg(long, default_value = "1024")]
+        max_parent_depth: u32,
     },
 
     /// Search for files matching a pattern
@@ -80,7 +154,9 @@ enum CliCommands {
         #[arg(short, long)]
         drive: char,
 
-        /// Search pattern (use -- before pattern if it starts with -)
+        /// Search pattern, matched against the whole name: `setup.exe` finds
+        /// only that name, `*setup*` finds it anywhere, `^setup` / `.exe$`
+        /// pin one end (use -- before pattern if it starts with -)
         #[arg(allow_hyphen_values = true)]
         pattern: String,
 
@@ -89,6 +165,57 @@ enum CliCommands {
         max: usize,
     },
 
+    /// List full paths of entries with given attributes, one per line
+    Find {
+        /// Drive letter to scan
+        #[arg(short, long)]
+        drive: char,
+
+        /// Required attribute (repeatable, all must match): readonly, hidden,
+        /// system, archive, temporary, sparse, reparse, compressed, offline,
+        /// encrypted, ads (has alternate data streams)
+        #[arg(long = "attr", required = true, value_parser = parse_attr_name)]
+        attrs: Vec<String>,
+
+        /// Only files with this extension (repeatable, any may match)
+        #[arg(long = "ext")]
+        extensions: Vec<String>,
+
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// List files with several names (hard links), grouped by MFT record
+    Hardlinks {
+        /// Drive letter to scan
+        #[arg(short, long)]
+        drive: char,
+
+        /// Maximum groups to show, largest files first
+        #[arg(short, long, default_value = "100")]
+        max: usize,
+    },
+
+    /// List EFS-encrypted files, largest first
+    Encrypted {
+        /// Drive letter to scan
+        #[arg(short, long)]
+        drive: char,
+
+        /// Maximum files to show
+        #[arg(short, long, default_value = "100")]
+        max: usize,
+    },
+
+    /// List names with lone UTF-16 surrogates or control characters,
+    /// escaped as \u{XXXX}
+    OddNames {
+        /// Drive letter to scan
+        #[arg(short, long)]
+        drive: char,
+    },
+
     /// Show largest files
     Largest {
         /// Drive letter
@@ -139,9 +266,59 @@ enum CliCommands {
         #[arg(short, long)]
         output: String,
 
-        /// Format (json, csv)
+        /// Format (json, csv, efu, index). efu is Everything's file list
+        /// format (File > Open File List in Everything)
         #[arg(short, long, default_value = "json")]
         format: String,
+
+        /// Path separator in exported paths (\ or /)
+        #[arg(long, default_value = "\\")]
+        separator: char,
+
+        /// Prefix exported paths with \\?\ so they work past MAX_PATH
+        /// (only with the \ separator)
+        #[arg(long)]
+        long_paths: bool,
+    },
+
+    /// Write a self-contained HTML report (largest items, extensions, treemap)
+    Report {
+        /// Drive letter to scan
+        #[arg(short, long)]
+        drive: char,
+
+        /// Output HTML file
+        #[arg(short, long, default_value = "report.html")]
+        output: String,
+
+        /// Number of directories/files in the "largest" tables
+        #[arg(short, long, default_value = "25")]
+        top: usize,
+    },
+
+    /// Compare two saved indexes (created with `export -f index`)
+    Diff {
+        /// Older index file
+        old: String,
+
+        /// Newer index file
+        new: String,
+
+        /// Maximum entries to list per category
+        #[arg(short, long, default_value = "50")]
+        max: usize,
+    },
+
+    /// Show when a saved index was scanned and whether its drive changed since
+    IndexInfo {
+        /// Index file (created with `export -f index`)
+        path: String,
+    },
+
+    /// Show the parsed MFT record of one file or folder, without a scan
+    Stat {
+        /// File or folder path
+        path: String,
     },
 
     /// Debug: trace a file's parent chain
@@ -165,6 +342,27 @@ enum CliCommands {
         record: u64,
     },
 
+    /// Parse a window of MFT records without scanning the whole drive,
+    /// e.g. the records around a known deletion
+    Records {
+        /// Drive letter
+        #[arg(short, long)]
+        drive: char,
+
+        /// First record number
+        #[arg(long)]
+        start: u64,
+
+        /// Record number to stop before
+        #[arg(long)]
+        end: u64,
+
+        /// Write the records' raw bytes to this file instead of listing
+        /// them (the format `EMFIT_MFT_FIXTURE` takes for benchmarks)
+        #[arg(long)]
+        raw: Option<String>,
+    },
+
     /// Debug: count raw USN enumeration results
     UsnCount {
         /// Drive letter
@@ -173,39 +371,125 @@ enum CliCommands {
     },
 }
 
+/// Send `tracing` spans and events to `emfit-trace.log` next to the
+/// executable when `EMFIT_LOG` is set (e.g. `EMFIT_LOG=info` or
+/// `EMFIT_LOG=emfit::ntfs=trace`). A file rather than stderr so the TUI
+/// is left alone.
+#[cfg(feature = "tracing")]
+fn init_tracing() {
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::EnvFilter;
+
+    let Ok(filter) = EnvFilter::try_from_env("EMFIT_LOG") else {
+        return;
+    };
+
+    let path = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("emfit-trace.log");
+    let file = match std::fs::File::create(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("[EmFit] Cannot create {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(false)
+        .with_writer(std::sync::Mutex::new(file))
+        .init();
+    eprintln!("[EmFit] Tracing to: {}", path.display());
+}
+
 fn main() {
     // Initialize logging
     emfit::logging::init();
     emfit::logging::info("MAIN", "EmFit starting up");
+    #[cfg(feature = "tracing")]
+    init_tracing();
 
     let cli = Cli::parse();
+    emfit::threads::configure(emfit::ThreadConfig::new(cli.threads));
+    let app_config = emfit::AppConfig {
+        refresh_interval_ms: cli.refresh_ms,
+        show_hidden_results: !cli.hide_hidden,
+        show_system_results: !cli.hide_system,
+        ascii_ui: cli.ascii,
+        treemap_palette: cli.palette,
+        select_path: cli.select,
+        calculate_sizes: !cli.no_sizes,
+        include_deleted: cli.deleted,
+        ..Default::default()
+    };
 
     match cli.command {
         None => {
             // No subcommand -> launch TUI
-            if let Err(e) = emfit::tui::run() {
+            if let Err(e) = emfit::tui::run_with_config(&app_config) {
                 eprintln!("{} {}", style("Error:").red().bold(), e);
                 std::process::exit(1);
             }
         }
         Some(Commands::Gui) => {
             // Launch GUI
-            let _ = emfit::gui::run();
+            let _ = emfit::gui::run_with_config(&app_config);
         }
         Some(Commands::Cli { subcmd }) => {
             let result = match subcmd {
                 CliCommands::Scan {
                     drive,
+                    image,
+                    partition_offset,
                     usn,
                     mft,
                     no_physical,
+                    vss,
                     hidden,
                     system,
                     output,
-                } => cmd_scan(drive, usn, mft, !no_physical, hidden, system, &output),
+                    json,
+                    resume,
+                    max_parent_depth,
+                } => {
+                    let config = ScanConfig::builder()
+                        .use_usn(usn)
+                        .use_mft(mft)
+                        .use_physical_drive(!no_physical)
+                        .use_vss(vss)
+                        .include_hidden(hidden)
+                        .include_system(system)
+                        .include_deleted(app_config.include_deleted)
+                        .show_progress(true)
+                        .max_parent_resolution_depth(max_parent_depth)
+                        .build();
+                    let source = match image {
+                        Some(ref path) => ScanSource::Image(Path::new(path), partition_offset),
+                        None => ScanSource::Drive(drive.expect("clap requires --drive without --image")),
+                    };
+                    let output = if json { "json" } else { output.as_str() };
+                    cmd_scan(source, config, resume.as_deref(), output)
+                }
 
                 CliCommands::Search { drive, pattern, max } => cmd_search(drive, &pattern, max),
 
+                CliCommands::Find {
+                    drive,
+                    attrs,
+                    extensions,
+                    output,
+                } => cmd_find(drive, &attrs, &extensions, output.as_deref()),
+
+                CliCommands::Hardlinks { drive, max } => cmd_hardlinks(drive, max),
+
+                CliCommands::Encrypted { drive, max } => cmd_encrypted(drive, max),
+
+                CliCommands::OddNames { drive } => cmd_odd_names(drive),
+
                 CliCommands::Largest { drive, count, dirs } => cmd_largest(drive, count, dirs),
 
                 CliCommands::TreeSize { drive, path, depth } => {
@@ -220,12 +504,35 @@ fn main() {
                     drive,
                     output,
                     format,
-                } => cmd_export(drive, &output, &format),
+                    separator,
+                    long_paths,
+                } => {
+                    let path_style = PathStyle::default()
+                        .with_separator(separator)
+                        .with_long_path_prefix(long_paths);
+                    cmd_export(drive, &output, &format, &path_style)
+                }
+
+                CliCommands::Report { drive, output, top } => cmd_report(drive, &output, top),
+
+                CliCommands::Diff { old, new, max } => cmd_diff(&old, &new, max),
+
+                CliCommands::IndexInfo { path } => cmd_index_info(&path),
+
+                CliCommands::Stat { path } => cmd_stat(&path),
 
                 CliCommands::Debug { drive, pattern } => cmd_debug(drive, &pattern),
 
                 CliCommands::ReadMft { drive, record } => cmd_read_mft(drive, record),
 
+                CliCommands::Records { drive, start, end, raw: Some(output) } => {
+                    cmd_dump_records(drive, start, end, &output)
+                }
+
+                CliCommands::Records { drive, start, end, raw: None } => {
+                    cmd_records(drive, start, end, app_config.include_deleted)
+                }
+
                 CliCommands::UsnCount { drive } => cmd_usn_count(drive),
             };
 
@@ -237,38 +544,86 @@ fn main() {
     }
 }
 
+/// What `cli scan` reads from
+enum ScanSource<'a> {
+    Drive(char),
+    /// Image path and partition offset
+    Image(&'a Path, u64),
+}
+
 /// Scan command implementation
 fn cmd_scan(
-    drive: char,
-    use_usn: bool,
-    use_mft: bool,
-    use_physical_drive: bool,
-    include_hidden: bool,
-    include_system: bool,
+    source: ScanSource,
+    config: ScanConfig,
+    resume: Option<&str>,
     output_format: &str,
 ) -> emfit::Result<()> {
     let start = Instant::now();
-
-    println!(
-        "{} Scanning drive {}:{}",
-        style("→").cyan().bold(),
-        style(format!("{}:", drive.to_ascii_uppercase())).yellow(),
-        if use_physical_drive { " (physical drive mode)" } else { "" }
-    );
-
-    let config = ScanConfig {
-        use_usn,
-        use_mft,
-        use_physical_drive,
-        include_hidden,
-        include_system,
-        calculate_sizes: true,
-        show_progress: true,
-        batch_size: 1024,
+    // Keep stdout to the JSON document alone
+    let announce = |line: String| {
+        if output_format == "json" {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
     };
 
-    let mut scanner = VolumeScanner::new(drive).with_config(config);
-    let tree = scanner.scan()?;
+    let (scanner, drive) = match source {
+        ScanSource::Drive(drive) => {
+            announce(format!(
+                "{} Scanning drive {}{}",
+                style("→").cyan().bold(),
+                style(format!("{}:", drive.to_ascii_uppercase())).yellow(),
+                if config.use_vss {
+                    " (shadow copy)"
+                } else if config.use_physical_drive {
+                    " (physical drive mode)"
+                } else {
+                    ""
+                }
+            ));
+            (Ok(VolumeScanner::new(drive)), drive.to_string())
+        }
+        ScanSource::Image(path, partition_offset) => {
+            announce(format!(
+                "{} Scanning image {}{}",
+                style("→").cyan().bold(),
+                style(path.display()).yellow(),
+                if partition_offset > 0 {
+                    format!(" (partition at offset {})", partition_offset)
+                } else {
+                    String::new()
+                }
+            ));
+            (VolumeScanner::from_image(path, partition_offset), path.display().to_string())
+        }
+    };
+    let scanned = scanner.and_then(|scanner| {
+        let mut scanner = scanner.with_config(config);
+        let tree = match resume {
+            Some(checkpoint) => scanner.scan_resumable(checkpoint)?,
+            None => scanner.scan()?,
+        };
+        Ok((tree, scanner.report().cloned().unwrap_or_default()))
+    });
+    let (tree, report) = match scanned {
+        Ok(scanned) => scanned,
+        Err(e) if output_format == "json" => {
+            // Scripts still get a parseable outcome
+            println!(
+                "{}",
+                serde_json::json!({
+                    "drive": drive,
+                    "success": false,
+                    "partial": false,
+                    "errors": [e.to_string()],
+                    "elapsed_seconds": start.elapsed().as_secs_f64(),
+                })
+            );
+            return Err(e);
+        }
+        Err(e) => return Err(e),
+    };
 
     let elapsed = start.elapsed();
 
@@ -277,13 +632,23 @@ fn cmd_scan(
         println!(
             "{}",
             serde_json::json!({
-                "drive": drive.to_string(),
+                "drive": drive,
+                "success": true,
+                "partial": report.is_partial(),
+                "errors": scan_errors(&report),
                 "files": tree.stats.total_files,
                 "directories": tree.stats.total_directories,
                 "total_size": tree.stats.total_size,
                 "total_size_formatted": format_size(tree.stats.total_size),
                 "allocated_size": tree.stats.total_allocated,
+                "slack": tree.stats.slack(),
+                "slack_percent": tree.stats.slack_percent(),
+                "cluster_slack": tree.total_slack(),
                 "orphaned": tree.stats.orphaned_files,
+                "deleted": tree.stats.deleted_files,
+                "mft_fragments": tree.stats.mft_fragment_count,
+                "memory_bytes": tree.memory_footprint(),
+                "anomalies": report,
                 "elapsed_seconds": elapsed.as_secs_f64(),
             })
         );
@@ -312,6 +677,25 @@ fn cmd_scan(
             style("Allocated:").bold(),
             format_size(tree.stats.total_allocated)
         );
+        let slack = tree.stats.slack();
+        println!(
+            "  {} {}{} ({:.1}% of allocated)",
+            style("Slack:").bold(),
+            if slack < 0 { "-" } else { "" },
+            format_size(slack.unsigned_abs()),
+            tree.stats.slack_percent()
+        );
+        println!(
+            "  {} {} in partly filled clusters ({} cluster size)",
+            style("Cluster Slack:").bold(),
+            format_size(tree.total_slack()),
+            format_size(tree.bytes_per_cluster() as u64)
+        );
+        println!(
+            "  {} {} for the tree (estimated)",
+            style("Memory:").bold(),
+            format_size(tree.memory_footprint() as u64)
+        );
         if tree.stats.orphaned_files > 0 {
             println!(
                 "  {} {}",
@@ -319,6 +703,69 @@ fn cmd_scan(
                 style(tree.stats.orphaned_files).red()
             );
         }
+        if tree.stats.deleted_files > 0 {
+            println!(
+                "  {} {} (not counted above)",
+                style("Deleted:").bold(),
+                tree.stats.deleted_files
+            );
+        }
+        if tree.stats.mft_fragment_count > 0 {
+            // A contiguous $MFT is a single extent
+            let fragments = tree.stats.mft_fragment_count;
+            println!(
+                "  {} {}",
+                style("MFT Fragments:").bold(),
+                if fragments > 1 {
+                    style(fragments).yellow()
+                } else {
+                    style(fragments).green()
+                }
+            );
+        }
+        if report.volume_dirty {
+            println!(
+                "  {} {}",
+                style("Volume:").bold(),
+                style("dirty, run chkdsk before trusting these numbers").red()
+            );
+        }
+        if let Some(ref reason) = report.usn_interruption {
+            println!(
+                "  {} stopped early, completed from MFT ({})",
+                style("USN:").bold(),
+                style(reason).yellow()
+            );
+        }
+        if !report.transient.is_empty() {
+            println!(
+                "  {} {} (changed during scan)",
+                style("Transient:").bold(),
+                report.transient.len()
+            );
+        }
+        if !report.past_valid_data.is_empty() {
+            println!(
+                "  {} {} records created during scan, not read from the MFT",
+                style("MFT Grew:").bold(),
+                style(report.past_valid_data.len()).yellow()
+            );
+        }
+        if !report.unread.is_empty() {
+            println!(
+                "  {} {} MFT records could not be read, results are partial",
+                style("Unreadable:").bold(),
+                style(report.unread_count()).red()
+            );
+        }
+        if !report.is_clean() {
+            println!();
+            println!("  {}", style("Anomalies:").bold());
+            print_anomalies("Parent cycles", &report.parent_cycles);
+            print_anomalies("Missing parents", &report.missing_parents);
+            print_anomalies("Fixup failures", &report.fixup_failures);
+            print_anomalies("Unparsable records", &report.parse_failures);
+        }
         println!();
         println!(
             "  {} {:.0} files/sec",
@@ -330,6 +777,253 @@ fn cmd_scan(
     Ok(())
 }
 
+/// What left a scan incomplete, one message per problem, for the JSON summary
+fn scan_errors(report: &ScanReport) -> Vec<String> {
+    let mut errors: Vec<String> = report
+        .unread
+        .iter()
+        .map(|range| format!("MFT records {}..{} could not be read", range.start, range.end))
+        .collect();
+    if !report.fixup_failures.is_empty() {
+        errors.push(format!(
+            "{} MFT records failed fixup verification",
+            report.fixup_failures.len()
+        ));
+    }
+    if !report.parse_failures.is_empty() {
+        errors.push(format!(
+            "{} MFT records could not be parsed",
+            report.parse_failures.len()
+        ));
+    }
+    errors
+}
+
+/// Print one anomaly category with a few example record numbers
+fn print_anomalies(label: &str, records: &[u64]) {
+    const SHOWN: usize = 8;
+
+    if records.is_empty() {
+        return;
+    }
+    let mut sample = records
+        .iter()
+        .take(SHOWN)
+        .map(|r| r.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if records.len() > SHOWN {
+        sample.push_str(", ...");
+    }
+    println!(
+        "    {} {} (records {})",
+        style(format!("{}:", label)).yellow(),
+        records.len(),
+        sample
+    );
+}
+
+/// Validate a `--attr` name for the find command
+fn parse_attr_name(name: &str) -> Result<String, String> {
+    if AttributeFilter::default().add(name) {
+        Ok(name.to_ascii_lowercase())
+    } else {
+        Err(format!(
+            "unknown attribute '{}' (expected one of: {})",
+            name,
+            AttributeFilter::NAMES.join(", ")
+        ))
+    }
+}
+
+/// Find command - write paths of entries matching attribute criteria.
+/// Status goes to stderr so stdout stays a clean path list for piping.
+fn cmd_find(
+    drive: char,
+    attrs: &[String],
+    extensions: &[String],
+    output: Option<&str>,
+) -> emfit::Result<()> {
+    let mut filter = AttributeFilter::default();
+    for attr in attrs {
+        filter.add(attr);
+    }
+    let extensions: Vec<String> = extensions
+        .iter()
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .collect();
+
+    eprintln!(
+        "{} Finding [{}] on {}:",
+        style("→").cyan().bold(),
+        style(attrs.join(", ")).yellow(),
+        drive.to_ascii_uppercase()
+    );
+
+    let config = ScanConfig::builder()
+        .calculate_sizes(false)
+        .show_progress(true)
+        .build();
+    let mut scanner = VolumeScanner::new(drive).with_config(config);
+    let tree = scanner.scan()?;
+
+    let paths: Vec<String> = tree
+        .find_by_attributes(&filter)
+        .into_iter()
+        .filter(|path| {
+            extensions.is_empty()
+                || std::path::Path::new(path)
+                    .extension()
+                    .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+        })
+        .collect();
+
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+    for path in &paths {
+        writeln!(writer, "{}", path)?;
+    }
+    writer.flush()?;
+
+    eprintln!(
+        "{} {} matching entries{}",
+        style("✓").green().bold(),
+        paths.len(),
+        output.map(|p| format!(" written to {}", p)).unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Hard link groups command implementation
+fn cmd_hardlinks(drive: char, max_groups: usize) -> emfit::Result<()> {
+    println!(
+        "{} Finding hard links on {}:",
+        style("→").cyan().bold(),
+        drive.to_ascii_uppercase()
+    );
+
+    let config = ScanConfig::builder()
+        .calculate_sizes(false)
+        .show_progress(true)
+        .build();
+    let mut scanner = VolumeScanner::new(drive).with_config(config);
+    let tree = scanner.scan()?;
+
+    let groups = tree.hardlink_groups();
+    let names: usize = groups.iter().map(|g| g.len()).sum();
+
+    println!();
+    for group in groups.iter().take(max_groups) {
+        let size = tree.get_by_key(&group[0]).map(|n| n.file_size).unwrap_or(0);
+        println!(
+            "  {} {} {}",
+            style(format!("{:>12}", format_size(size))).yellow(),
+            style(format!("record {}", group[0].record_number)).dim(),
+            style(format!("({} names)", group.len())).dim()
+        );
+        for key in group {
+            println!("      {}", style(tree.build_path_for_key(key)).cyan());
+        }
+    }
+
+    println!();
+    println!(
+        "{} {} hard-linked files, {} names{}",
+        style("✓").green().bold(),
+        groups.len(),
+        names,
+        if groups.len() > max_groups {
+            format!(" (showing {})", max_groups)
+        } else {
+            String::new()
+        }
+    );
+    Ok(())
+}
+
+/// Encrypted files command implementation
+fn cmd_encrypted(drive: char, max_files: usize) -> emfit::Result<()> {
+    println!(
+        "{} Finding encrypted files on {}:",
+        style("→").cyan().bold(),
+        drive.to_ascii_uppercase()
+    );
+
+    let config = ScanConfig::builder().show_progress(true).build();
+    let mut scanner = VolumeScanner::new(drive).with_config(config);
+    let tree = scanner.scan()?;
+
+    let keys = tree.encrypted_files();
+    let mut total_size = 0u64;
+
+    println!();
+    for (i, key) in keys.iter().enumerate() {
+        let size = tree.get_by_key(key).map(|n| n.file_size).unwrap_or(0);
+        total_size += size;
+        if i < max_files {
+            println!(
+                "  {} {}",
+                style(format!("{:>12}", format_size(size))).yellow(),
+                style(tree.build_path_for_key(key)).cyan()
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "{} {} encrypted files, {}{}",
+        style("✓").green().bold(),
+        keys.len(),
+        format_size(total_size),
+        if keys.len() > max_files {
+            format!(" (showing {})", max_files)
+        } else {
+            String::new()
+        }
+    );
+    Ok(())
+}
+
+fn cmd_odd_names(drive: char) -> emfit::Result<()> {
+    println!(
+        "{} Finding unusual names on {}:",
+        style("→").cyan().bold(),
+        drive.to_ascii_uppercase()
+    );
+
+    let config = ScanConfig::builder()
+        .calculate_sizes(false)
+        .show_progress(true)
+        .keep_raw_names(true)
+        .build();
+    let mut scanner = VolumeScanner::new(drive).with_config(config);
+    let tree = scanner.scan()?;
+
+    let keys = tree.anomalous_names();
+
+    println!();
+    for key in &keys {
+        let Some(node) = tree.get_by_key(key) else {
+            continue;
+        };
+        // Only the last component is escaped; anomalous parents are listed on their own
+        let path = tree.build_path_for_key(key);
+        let dir = path.rsplit_once('\\').map(|(dir, _)| dir).unwrap_or("");
+        println!(
+            "  {} {}\\{}",
+            style(format!("[{}]", name_anomalies::describe(node.name_anomalies).join(", "))).yellow(),
+            style(dir).dim(),
+            style(node.escaped_name()).cyan()
+        );
+    }
+
+    println!();
+    println!("{} {} unusual names", style("✓").green().bold(), keys.len());
+    Ok(())
+}
+
 /// Search command implementation
 fn cmd_search(drive: char, pattern: &str, max_results: usize) -> emfit::Result<()> {
     println!(
@@ -342,13 +1036,12 @@ fn cmd_search(drive: char, pattern: &str, max_results: usize) -> emfit::Result<(
     let start = Instant::now();
 
     // Use MFT for complete file enumeration including hard links
-    let config = ScanConfig {
-        use_usn: false,
-        use_mft: true,
-        calculate_sizes: false, // Skip size calculation for search speed
-        show_progress: true,
-        ..Default::default()
-    };
+    let config = ScanConfig::builder()
+        .use_usn(false)
+        .use_mft(true)
+        .calculate_sizes(false) // Skip size calculation for search speed
+        .show_progress(true)
+        .build();
 
     let mut scanner = VolumeScanner::new(drive).with_config(config);
     let tree = scanner.scan()?;
@@ -378,6 +1071,13 @@ fn cmd_search(drive: char, pattern: &str, max_results: usize) -> emfit::Result<(
                 format_size(result.file_size)
             );
         }
+        if result.modification_time > 0 {
+            println!(
+                "      {} {}",
+                style("Modified:").dim(),
+                format_filetime(result.modification_time)
+            );
+        }
     }
 
     Ok(())
@@ -394,11 +1094,10 @@ fn cmd_largest(drive: char, count: usize, show_dirs: bool) -> emfit::Result<()>
         drive.to_ascii_uppercase()
     );
 
-    let config = ScanConfig {
-        calculate_sizes: true,
-        show_progress: true,
-        ..Default::default()
-    };
+    let config = ScanConfig::builder()
+        .calculate_sizes(true)
+        .show_progress(true)
+        .build();
 
     let mut scanner = VolumeScanner::new(drive).with_config(config);
     let tree = scanner.scan()?;
@@ -437,11 +1136,10 @@ fn cmd_tree_size(drive: char, path: Option<&str>, depth: usize) -> emfit::Result
         drive.to_ascii_uppercase()
     );
 
-    let config = ScanConfig {
-        calculate_sizes: true,
-        show_progress: true,
-        ..Default::default()
-    };
+    let config = ScanConfig::builder()
+        .calculate_sizes(true)
+        .show_progress(true)
+        .build();
 
     let mut scanner = VolumeScanner::new(drive).with_config(config);
     let tree = scanner.scan()?;
@@ -481,9 +1179,7 @@ fn print_tree_node(tree: &FileTree, node: &emfit::TreeNode, indent: usize, max_d
             style(&node.name).cyan()
         );
 
-        // Get children sorted by size
-        let mut children = tree.get_children(&node.key());
-        children.sort_by(|a, b| b.total_size.cmp(&a.total_size));
+        let children = tree.children(&node.key(), ChildSort::Size(SizeBasis::Logical));
 
         // Show top children
         for child in children.iter().take(10) {
@@ -514,13 +1210,28 @@ fn cmd_volumes() -> emfit::Result<()> {
                 if let Ok(data) = emfit::ntfs::winapi::get_ntfs_volume_data(&handle) {
                     let total = data.total_clusters * data.bytes_per_cluster as u64;
                     let free = data.free_clusters * data.bytes_per_cluster as u64;
-                    println!(
+                    print!(
                         " {} total, {} free",
                         style(format_size(total)).yellow(),
                         style(format_size(free)).green()
                     );
-                } else {
-                    println!();
+                }
+                match VolumeScanner::new(letter).volume_info() {
+                    Ok(info) => {
+                        if !info.label.is_empty() {
+                            print!(" \"{}\"", info.label);
+                        }
+                        println!(
+                            " (NTFS {}, {})",
+                            info.version(),
+                            if info.is_dirty() {
+                                style("dirty, needs chkdsk").red()
+                            } else {
+                                style("clean").green()
+                            }
+                        );
+                    }
+                    Err(_) => println!(),
                 }
             } else {
                 println!(" (access denied)");
@@ -561,22 +1272,32 @@ fn cmd_monitor(drive: char) -> emfit::Result<()> {
 }
 
 /// Export command
-fn cmd_export(drive: char, output: &str, format: &str) -> emfit::Result<()> {
+fn cmd_export(drive: char, output: &str, format: &str, path_style: &PathStyle) -> emfit::Result<()> {
     println!(
         "{} Exporting scan results to {}",
         style("→").cyan().bold(),
         style(output).yellow()
     );
 
-    let config = ScanConfig {
-        calculate_sizes: true,
-        show_progress: true,
-        ..Default::default()
-    };
+    let config = ScanConfig::builder()
+        .calculate_sizes(true)
+        .show_progress(true)
+        .build();
 
     let mut scanner = VolumeScanner::new(drive).with_config(config);
     let tree = scanner.scan()?;
 
+    if format == "index" {
+        tree.save_index(output)?;
+        println!(
+            "{} Saved index of {} entries to {}",
+            style("✓").green().bold(),
+            tree.len(),
+            output
+        );
+        return Ok(());
+    }
+
     let mut file = std::fs::File::create(output)?;
 
     match format {
@@ -587,7 +1308,7 @@ fn cmd_export(drive: char, output: &str, format: &str) -> emfit::Result<()> {
                 writeln!(
                     file,
                     "\"{}\",\"{}\",{},{},{},{}",
-                    tree.build_path(node.record_number),
+                    tree.build_path_styled(node.record_number, path_style),
                     node.name,
                     node.file_size,
                     node.allocated_size,
@@ -596,6 +1317,33 @@ fn cmd_export(drive: char, output: &str, format: &str) -> emfit::Result<()> {
                 )?;
             }
         }
+        "efu" => {
+            // Everything file list: dates are raw FILETIMEs, attributes a
+            // decimal mask; empty fields are left blank
+            writeln!(file, "Filename,Size,Date Modified,Date Created,Attributes")?;
+            let blank_if_zero =
+                |value: u64| if value == 0 { String::new() } else { value.to_string() };
+            for entry in tree.iter_entries() {
+                let node = entry.value();
+                // $STANDARD_INFORMATION doesn't carry the directory bit, and
+                // Everything relies on it to tell folders from files
+                let (size, attributes) = if node.is_directory {
+                    (node.total_size, node.attributes | file_attributes::DIRECTORY)
+                } else {
+                    (node.file_size, node.attributes)
+                };
+                writeln!(
+                    file,
+                    "\"{}\",{},{},{},{}",
+                    tree.build_path_for_key_styled(entry.key(), path_style)
+                        .replace('"', "\"\""),
+                    size,
+                    blank_if_zero(node.modification_time),
+                    blank_if_zero(node.creation_time),
+                    attributes
+                )?;
+            }
+        }
         _ => {
             // JSON format
             writeln!(file, "{{")?;
@@ -617,7 +1365,8 @@ fn cmd_export(drive: char, output: &str, format: &str) -> emfit::Result<()> {
                 write!(
                     file,
                     "    {{\"path\": \"{}\", \"size\": {}, \"is_dir\": {}}}",
-                    tree.build_path(node.record_number).replace('\\', "\\\\"),
+                    tree.build_path_styled(node.record_number, path_style)
+                        .replace('\\', "\\\\"),
                     node.file_size,
                     node.is_directory
                 )?;
@@ -639,6 +1388,239 @@ fn cmd_export(drive: char, output: &str, format: &str) -> emfit::Result<()> {
     Ok(())
 }
 
+/// Report command - write a static HTML report
+fn cmd_report(drive: char, output: &str, top: usize) -> emfit::Result<()> {
+    println!(
+        "{} Building report for {}: -> {}",
+        style("→").cyan().bold(),
+        drive.to_ascii_uppercase(),
+        style(output).yellow()
+    );
+
+    let config = ScanConfig::builder()
+        .calculate_sizes(true)
+        .show_progress(true)
+        .build();
+
+    let mut scanner = VolumeScanner::new(drive).with_config(config);
+    let tree = scanner.scan()?;
+
+    let options = ReportOptions {
+        top_count: top,
+        ..Default::default()
+    };
+    emfit::report::write_html(&tree, &options, output)?;
+
+    println!(
+        "{} Report written to {}",
+        style("✓").green().bold(),
+        output
+    );
+    Ok(())
+}
+
+/// Diff command - compare two saved indexes
+fn cmd_diff(old_path: &str, new_path: &str, max: usize) -> emfit::Result<()> {
+    println!(
+        "{} Comparing {} -> {}",
+        style("→").cyan().bold(),
+        style(old_path).yellow(),
+        style(new_path).yellow()
+    );
+
+    let old_tree = FileTree::load_index(old_path)?;
+    let new_tree = FileTree::load_index(new_path)?;
+    println!("  Old: {}", scan_time_text(&old_tree));
+    println!("  New: {}", scan_time_text(&new_tree));
+    let diff = old_tree.diff(&new_tree);
+
+    if diff.is_empty() {
+        println!("{} No differences", style("✓").green().bold());
+        return Ok(());
+    }
+
+    let net = diff.net_size_delta();
+    let net_str = if net < 0 {
+        format!("-{}", format_size(net.unsigned_abs()))
+    } else {
+        format!("+{}", format_size(net as u64))
+    };
+
+    println!();
+    println!("  Added:        {} ({})", diff.added.len(), format_size(diff.bytes_added()));
+    println!("  Removed:      {} ({})", diff.removed.len(), format_size(diff.bytes_removed()));
+    println!("  Size changed: {}", diff.size_changed.len());
+    println!("  Net change:   {}", style(net_str).yellow().bold());
+
+    let print_entries = |title: &str, entries: &[emfit::DiffEntry]| {
+        if entries.is_empty() {
+            return;
+        }
+        println!();
+        println!("{}:", title);
+        for entry in entries.iter().take(max) {
+            let icon = if entry.is_directory { "📁" } else { "📄" };
+            let size_str = if entry.is_directory {
+                String::new()
+            } else {
+                format_size(entry.file_size)
+            };
+            println!(
+                "  {} {} {}",
+                style(format!("{:>12}", size_str)).yellow(),
+                icon,
+                style(&entry.path).cyan()
+            );
+        }
+        if entries.len() > max {
+            println!("  ... and {} more", entries.len() - max);
+        }
+    };
+
+    print_entries("Added", &diff.added);
+    print_entries("Removed", &diff.removed);
+
+    if !diff.size_changed.is_empty() {
+        println!();
+        println!("Size changed:");
+        for change in diff.size_changed.iter().take(max) {
+            let delta = change.delta();
+            let delta_str = if delta < 0 {
+                format!("-{}", format_size(delta.unsigned_abs()))
+            } else {
+                format!("+{}", format_size(delta as u64))
+            };
+            println!(
+                "  {} 📄 {} ({} -> {})",
+                style(format!("{:>12}", delta_str)).yellow(),
+                style(&change.path).cyan(),
+                format_size(change.old_size),
+                format_size(change.new_size)
+            );
+        }
+        if diff.size_changed.len() > max {
+            println!("  ... and {} more", diff.size_changed.len() - max);
+        }
+    }
+
+    Ok(())
+}
+
+/// "2024-05-01 14:03:12 (3h ago)", or a note for indexes saved without a scan time
+fn scan_time_text(tree: &FileTree) -> String {
+    match (tree.scanned_at, tree.scan_age()) {
+        (Some(at), Some(age)) => format!(
+            "{} ({})",
+            chrono::DateTime::<chrono::Local>::from(at).format("%Y-%m-%d %H:%M:%S"),
+            emfit::format_age(age)
+        ),
+        _ => "scan time unknown".to_string(),
+    }
+}
+
+/// Index info command
+fn cmd_index_info(path: &str) -> emfit::Result<()> {
+    let tree = FileTree::load_index(path)?;
+
+    println!("{} {}", style("→").cyan().bold(), style(path).yellow());
+    println!("  Drive:    {}:", tree.drive_letter);
+    println!("  Entries:  {} files, {} folders", tree.stats.total_files, tree.stats.total_directories);
+    println!("  Size:     {}", format_size(tree.stats.total_size));
+    println!("  Scanned:  {}", scan_time_text(&tree));
+
+    let (journal, color) = match tree.journal_status() {
+        emfit::JournalStatus::Unchanged => ("no changes since the scan", console::Color::Green),
+        emfit::JournalStatus::Changed => (
+            "changed since the scan; the USN journal still covers it, so an incremental refresh can catch up",
+            console::Color::Yellow,
+        ),
+        emfit::JournalStatus::Reset => (
+            "journal reset or wrapped since the scan; rescan the drive",
+            console::Color::Red,
+        ),
+        emfit::JournalStatus::Unknown => (
+            "unknown (no USN position saved, or drive not available)",
+            console::Color::White,
+        ),
+    };
+    println!("  Journal:  {}", style(journal).fg(color));
+
+    Ok(())
+}
+
+/// Stat command
+fn cmd_stat(path: &str) -> emfit::Result<()> {
+    let entry = emfit::stat(path)?;
+
+    println!("{} {}", style("→").cyan().bold(), style(path).yellow());
+    println!("  Record:      {} (FRN 0x{:016X})", entry.record_number, entry.file_reference_number);
+    println!("  Parent:      {}", entry.parent_record_number);
+    println!("  Type:        {}", if entry.is_directory { "folder" } else { "file" });
+    println!("  Size:        {} ({} bytes)", format_size(entry.file_size), entry.file_size);
+    println!("  Allocated:   {} ({} bytes)", format_size(entry.allocated_size), entry.allocated_size);
+    match entry.storage {
+        Storage::Unknown => {}
+        Storage::Resident => println!("  Storage:     Resident (inside the MFT record, no clusters)"),
+        storage => println!("  Storage:     {}", storage.label()),
+    }
+    println!("  Attributes:  0x{:08X}", entry.attributes);
+    if entry.is_encrypted() {
+        println!("  Encrypted:   EFS (data runs point at ciphertext)");
+    }
+    println!("  Created:     {}", format_filetime(entry.creation_time));
+    println!("  Modified:    {}", format_filetime(entry.modification_time));
+    println!("  Accessed:    {}", format_filetime(entry.access_time));
+    if entry.name_anomalies != 0 {
+        let raw = entry
+            .raw_name
+            .clone()
+            .unwrap_or_else(|| entry.name.encode_utf16().collect());
+        println!(
+            "  Raw name:    {} ({})",
+            name_anomalies::escape(&raw),
+            name_anomalies::describe(entry.name_anomalies).join(", ")
+        );
+    }
+    println!("  Hard links:  {}", entry.hard_link_count);
+    for link in &entry.hard_links {
+        println!("    parent {}: {}", link.parent_record_number, link.name);
+    }
+    if let Some(ref target) = entry.reparse_target {
+        let kind = if target.is_mount_point() { "junction" } else { "symlink" };
+        let drive = std::path::absolute(path)
+            .ok()
+            .and_then(|p| p.to_string_lossy().chars().next());
+        let off_volume = drive.and_then(|d| target.is_off_volume(d)) == Some(true);
+        println!(
+            "  Target:      {} ({}{})",
+            target.path,
+            kind,
+            if off_volume { ", other volume" } else { "" }
+        );
+    }
+    if !entry.alternate_streams.is_empty() {
+        println!("  Streams:");
+        for (name, size) in &entry.alternate_streams {
+            println!("    :{} ({})", name, format_size(*size));
+        }
+    }
+    if !entry.data_runs.is_empty() {
+        println!("  Data runs:   {}", entry.data_runs.len());
+        // Run offsets are relative to the previous run's LCN
+        let mut lcn = 0i64;
+        for run in &entry.data_runs {
+            if run.is_sparse {
+                println!("    {} clusters sparse", run.cluster_count);
+            } else {
+                lcn += run.lcn_offset;
+                println!("    {} clusters at LCN {}", run.cluster_count, lcn);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Debug command - trace parent chain for a file
 fn cmd_debug(drive: char, pattern: &str) -> emfit::Result<()> {
     println!(
@@ -648,13 +1630,12 @@ fn cmd_debug(drive: char, pattern: &str) -> emfit::Result<()> {
         drive.to_ascii_uppercase()
     );
 
-    let config = ScanConfig {
-        use_usn: false,
-        use_mft: true,
-        calculate_sizes: false,
-        show_progress: true,
-        ..Default::default()
-    };
+    let config = ScanConfig::builder()
+        .use_usn(false)
+        .use_mft(true)
+        .calculate_sizes(false)
+        .show_progress(true)
+        .build();
 
     let mut scanner = VolumeScanner::new(drive).with_config(config);
     let tree = scanner.scan()?;
@@ -760,7 +1741,7 @@ fn cmd_debug(drive: char, pattern: &str) -> emfit::Result<()> {
         // Check children of $Recycle.Bin (record 36)
         println!("\n  Children of $Recycle.Bin (record 36):");
         if let Some(recycle_bin) = tree.get(36) {
-            let children = tree.get_children(&recycle_bin.key());
+            let children = tree.children(&recycle_bin.key(), ChildSort::Unsorted);
              if children.is_empty() {
                  println!("    No children found!");
              } else {
@@ -920,6 +1901,79 @@ fn cmd_read_mft(drive: char, record_num: u64) -> emfit::Result<()> {
     Ok(())
 }
 
+/// Records command - list the in-use records of a record range
+fn cmd_records(drive: char, start: u64, end: u64, deleted: bool) -> emfit::Result<()> {
+    println!(
+        "{} Reading MFT records {}..{} from {}:",
+        style("→").cyan().bold(),
+        style(start).yellow(),
+        style(end).yellow(),
+        drive.to_ascii_uppercase()
+    );
+
+    let config = ScanConfig::builder()
+        .show_progress(false)
+        .include_deleted(deleted)
+        .build();
+    let mut scanner = VolumeScanner::new(drive).with_config(config);
+    let entries = scanner.scan_record_range(start, end)?;
+
+    println!();
+    for entry in &entries {
+        println!(
+            "  {} {} {} {} {}",
+            style(format!("{:>10}", entry.record_number)).dim(),
+            style(format!("parent {:>10}", entry.parent_record_number)).dim(),
+            style(format!("{:>12}", if entry.is_directory {
+                "<DIR>".to_string()
+            } else {
+                format_size(entry.file_size)
+            }))
+            .yellow(),
+            format_filetime(entry.modification_time),
+            if entry.is_deleted {
+                style(format!("{} (deleted)", entry.name)).red()
+            } else {
+                style(entry.name.clone()).cyan()
+            }
+        );
+    }
+
+    let deleted_count = entries.iter().filter(|entry| entry.is_deleted).count();
+    println!();
+    println!(
+        "{} {} records in use in {}..{}",
+        style("✓").green().bold(),
+        entries.len() - deleted_count,
+        start,
+        end
+    );
+    if deleted_count > 0 {
+        println!("  {} deleted file(s) still in the MFT", deleted_count);
+    }
+    Ok(())
+}
+
+/// Save MFT records `start..end` as raw bytes
+fn cmd_dump_records(drive: char, start: u64, end: u64, output: &str) -> emfit::Result<()> {
+    let config = ScanConfig::builder().show_progress(false).build();
+    let mut scanner = VolumeScanner::new(drive).with_config(config);
+    let raw = scanner.read_raw_records(start, end)?;
+    std::fs::write(output, &raw)?;
+
+    let record_size = scanner
+        .volume_data()
+        .map_or(1024, |data| data.bytes_per_file_record_segment as usize);
+    println!(
+        "{} Wrote {} records of {} bytes to {}",
+        style("✓").green().bold(),
+        raw.len() / record_size,
+        record_size,
+        output
+    );
+    Ok(())
+}
+
 /// Debug: count raw USN enumeration results
 fn cmd_usn_count(drive: char) -> emfit::Result<()> {
     use emfit::ntfs::{open_volume, UsnScanner};
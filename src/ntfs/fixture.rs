@@ -0,0 +1,120 @@
+//! Synthetic MFT records
+//!
+//! Builds FILE records the way NTFS lays them out on disk, update sequence
+//! applied, so the parser can be exercised without a volume. Shared by the
+//! unit tests and the `mft_parsing` benchmark; not part of the public API.
+
+use crate::ntfs::structs::*;
+
+/// Size of every record built here
+pub const RECORD_SIZE: usize = 1024;
+/// Update sequence array offset, right after the 0x30-byte header
+pub const USA_OFFSET: usize = 0x30;
+/// First attribute offset, after the header and a 3-entry update sequence array
+pub const FIRST_ATTRIBUTE: usize = 0x38;
+
+/// `$STANDARD_INFORMATION` creation time used by the tests
+pub const SI_TIME: u64 = 0x01D0_0000_0000_0000;
+/// `$FILE_NAME` creation and modification time
+pub const FN_TIME: u64 = 0x01D8_0000_0000_0000;
+
+/// A resident attribute with no name holding `value`
+pub fn resident_attribute(attribute_type: u32, value: &[u8]) -> Vec<u8> {
+    let length = (24 + value.len()).div_ceil(8) * 8;
+    let mut attr = vec![0u8; length];
+    attr[0..4].copy_from_slice(&attribute_type.to_le_bytes());
+    attr[4..8].copy_from_slice(&(length as u32).to_le_bytes());
+    attr[10..12].copy_from_slice(&24u16.to_le_bytes()); // name offset
+    attr[16..20].copy_from_slice(&(value.len() as u32).to_le_bytes());
+    attr[20..22].copy_from_slice(&24u16.to_le_bytes()); // value offset
+    attr[24..24 + value.len()].copy_from_slice(value);
+    attr
+}
+
+/// An unnamed non-resident `$DATA` of `size` bytes in a single run at `lcn`
+pub fn non_resident_data(size: u64, lcn: u64) -> Vec<u8> {
+    let clusters = size.div_ceil(4096);
+    let mut attr = vec![0u8; 80];
+    attr[0..4].copy_from_slice(&0x80u32.to_le_bytes());
+    attr[4..8].copy_from_slice(&80u32.to_le_bytes());
+    attr[8] = 1; // non-resident
+    attr[10..12].copy_from_slice(&64u16.to_le_bytes()); // name offset
+    attr[24..32].copy_from_slice(&(clusters - 1).to_le_bytes()); // last VCN
+    attr[32..34].copy_from_slice(&64u16.to_le_bytes()); // data runs offset
+    attr[40..48].copy_from_slice(&(clusters * 4096).to_le_bytes()); // allocated
+    attr[48..56].copy_from_slice(&size.to_le_bytes()); // data size
+    attr[56..64].copy_from_slice(&size.to_le_bytes()); // initialized size
+
+    // Run header 0x42: 2-byte length, 4-byte LCN offset
+    attr[64] = 0x42;
+    attr[65..67].copy_from_slice(&(clusters as u16).to_le_bytes());
+    attr[67..71].copy_from_slice(&(lcn as u32).to_le_bytes());
+    attr
+}
+
+/// `$STANDARD_INFORMATION` created at `time` and modified just after
+/// (0 zeroes both)
+pub fn standard_information(attributes: u32, time: u64) -> Vec<u8> {
+    let mut value = vec![0u8; 72];
+    value[0..8].copy_from_slice(&time.to_le_bytes());
+    value[8..16].copy_from_slice(&(if time == 0 { 0 } else { time + 1 }).to_le_bytes());
+    value[32..36].copy_from_slice(&attributes.to_le_bytes());
+    resident_attribute(0x10, &value)
+}
+
+/// A Win32 `$FILE_NAME` under `parent` (record number and sequence number)
+pub fn file_name(parent: u64, parent_sequence: u16, name: &str) -> Vec<u8> {
+    let name_u16: Vec<u16> = name.encode_utf16().collect();
+    let mut value = vec![0u8; 66];
+    value[0..8].copy_from_slice(&(parent | (parent_sequence as u64) << 48).to_le_bytes());
+    value[8..16].copy_from_slice(&FN_TIME.to_le_bytes());
+    value[16..24].copy_from_slice(&FN_TIME.to_le_bytes());
+    value[64] = name_u16.len() as u8;
+    value[65] = 1;
+    value.extend(name_u16.iter().flat_map(|c| c.to_le_bytes()));
+    resident_attribute(0x30, &value)
+}
+
+/// A FILE record with the update sequence applied, as it sits on disk
+pub fn file_record(sequence_number: u16, flags: u16, attributes: &[Vec<u8>]) -> Vec<u8> {
+    let mut record = vec![0u8; RECORD_SIZE];
+    record[0..4].copy_from_slice(&MFT_RECORD_SIGNATURE.to_le_bytes());
+    record[4..6].copy_from_slice(&(USA_OFFSET as u16).to_le_bytes());
+    record[6..8].copy_from_slice(&3u16.to_le_bytes());
+    record[16..18].copy_from_slice(&sequence_number.to_le_bytes());
+    record[18..20].copy_from_slice(&1u16.to_le_bytes());
+    record[20..22].copy_from_slice(&(FIRST_ATTRIBUTE as u16).to_le_bytes());
+    record[22..24].copy_from_slice(&flags.to_le_bytes());
+    record[28..32].copy_from_slice(&(RECORD_SIZE as u32).to_le_bytes());
+
+    let mut offset = FIRST_ATTRIBUTE;
+    for attr in attributes {
+        record[offset..offset + attr.len()].copy_from_slice(attr);
+        offset += attr.len();
+    }
+    record[offset..offset + 4].copy_from_slice(&ATTRIBUTE_END_MARKER.to_le_bytes());
+    record[24..28].copy_from_slice(&((offset + 8) as u32).to_le_bytes());
+
+    // Move the last two bytes of each sector into the update sequence
+    // array and stamp the update sequence number in their place
+    let usn = 0x0007u16.to_le_bytes();
+    record[USA_OFFSET..USA_OFFSET + 2].copy_from_slice(&usn);
+    for sector in 1..=RECORD_SIZE / SECTOR_SIZE as usize {
+        let sector_end = sector * SECTOR_SIZE as usize - 2;
+        let fixup = USA_OFFSET + sector * 2;
+        record.copy_within(sector_end..sector_end + 2, fixup);
+        record[sector_end..sector_end + 2].copy_from_slice(&usn);
+    }
+    record
+}
+
+/// Volume geometry for an MFT image of `len` bytes starting at offset 0
+pub fn volume_data(len: usize) -> NtfsVolumeData {
+    NtfsVolumeData {
+        bytes_per_sector: SECTOR_SIZE,
+        bytes_per_cluster: 4096,
+        bytes_per_file_record_segment: RECORD_SIZE as u32,
+        mft_valid_data_length: len as u64,
+        ..Default::default()
+    }
+}
@@ -7,7 +7,14 @@ use crate::error::{Result, EmFitError};
 use crate::logging;
 use crate::ntfs::structs::*;
 use crate::ntfs::winapi::*;
+use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Records each parse worker takes at a time; smaller splits cost more in
+/// scheduling than the records take to decode
+const MIN_RECORDS_PER_WORKER: usize = 64;
 
 // ============================================================================
 // Parsed File Entry
@@ -18,6 +25,8 @@ use std::collections::{HashMap, HashSet};
 pub struct HardLink {
     /// Parent directory record number for this link
     pub parent_record_number: u64,
+    /// Sequence number of the parent record this link was made in
+    pub parent_sequence_number: u16,
     /// File name for this link
     pub name: String,
     /// Filename namespace (Win32, DOS, POSIX, Win32AndDos)
@@ -31,6 +40,9 @@ pub struct FileEntry {
     pub record_number: u64,
     /// Parent directory record number (from best filename)
     pub parent_record_number: u64,
+    /// Sequence number the parent record had when the best filename was
+    /// made. Differs from the parent's current one once NTFS reused it.
+    pub parent_sequence_number: u16,
     /// Full file reference number (record_number | (sequence_number << 48))
     /// This is needed for OpenFileById API calls
     pub file_reference_number: u64,
@@ -50,23 +62,48 @@ pub struct FileEntry {
     pub modification_time: u64,
     /// Last access time (FILETIME)
     pub access_time: u64,
+    /// Creation time from the primary $FILE_NAME (FILETIME).
+    /// Rarely touched by user-mode tools, so it is a reference for the
+    /// $STANDARD_INFORMATION times above.
+    pub fn_creation_time: u64,
+    /// Modification time from the primary $FILE_NAME (FILETIME)
+    pub fn_modification_time: u64,
+    /// $STANDARD_INFORMATION creation and modification times were both 0
+    /// (the times above then come from $FILE_NAME)
+    pub si_zeroed: bool,
     /// Hard link count
     pub hard_link_count: u16,
     /// Data runs for non-resident files
     pub data_runs: Vec<DataRun>,
+    /// Resident or non-resident main data stream, with its fragment count
+    pub storage: Storage,
     /// Alternate data streams (name -> size)
     pub alternate_streams: HashMap<String, u64>,
     /// Is this record valid/in use?
     pub is_valid: bool,
+    /// Record of a deleted file whose attributes were still readable
+    /// (`MftParser::set_include_deleted`). Never set together with `is_valid`.
+    pub is_deleted: bool,
     /// Has this record been fully parsed?
     pub is_complete: bool,
     /// Extension MFT record numbers that may contain additional attributes (e.g., $FILE_NAME)
     pub extension_records: Vec<u64>,
     /// Extension record containing the $DATA attribute (if not in base record)
     pub data_extension_record: Option<u64>,
+    /// Extension records holding pieces of the main $DATA (any VCN), in
+    /// attribute list order. Heavily fragmented files spread their runs
+    /// over several.
+    pub data_segment_records: Vec<u64>,
     /// All hard links (different $FILE_NAME attributes with different parents)
     /// Each entry represents a different location where this file appears
     pub hard_links: Vec<HardLink>,
+    /// Junction or symbolic link target (resident $REPARSE_POINT only)
+    pub reparse_target: Option<ReparseTarget>,
+    /// `name_anomalies` flags of the primary name (0 for an ordinary name)
+    pub name_anomalies: u8,
+    /// Stored UTF-16 of `name`, kept only when it has `name_anomalies`
+    /// (lone surrogates do not survive decoding into `name`)
+    pub raw_name: Option<Vec<u16>>,
 }
 
 impl Default for FileEntry {
@@ -74,6 +111,7 @@ impl Default for FileEntry {
         Self {
             record_number: 0,
             parent_record_number: 0,
+            parent_sequence_number: 0,
             file_reference_number: 0,
             name: String::new(),
             file_size: 0,
@@ -83,14 +121,23 @@ impl Default for FileEntry {
             creation_time: 0,
             modification_time: 0,
             access_time: 0,
+            fn_creation_time: 0,
+            fn_modification_time: 0,
+            si_zeroed: false,
             hard_link_count: 0,
             data_runs: Vec::new(),
+            storage: Storage::Unknown,
             alternate_streams: HashMap::new(),
             is_valid: false,
+            is_deleted: false,
             is_complete: false,
             extension_records: Vec::new(),
             data_extension_record: None,
+            data_segment_records: Vec::new(),
             hard_links: Vec::new(),
+            reparse_target: None,
+            name_anomalies: 0,
+            raw_name: None,
         }
     }
 }
@@ -120,6 +167,24 @@ impl FileEntry {
     pub fn is_reparse_point(&self) -> bool {
         (self.attributes & file_attributes::REPARSE_POINT) != 0
     }
+
+    /// Check if file is EFS-encrypted. Its clusters hold ciphertext, so
+    /// anything reading contents by raw cluster access must skip it.
+    pub fn is_encrypted(&self) -> bool {
+        (self.attributes & file_attributes::ENCRYPTED) != 0
+    }
+}
+
+/// The piece of a file's main $DATA attribute found in one extension record
+#[derive(Debug)]
+struct DataPiece {
+    /// (file_size, allocated_size); only the piece starting at VCN 0 has them
+    sizes: Option<(u64, u64)>,
+    /// First VCN this piece maps, to put the pieces in order
+    lowest_vcn: u64,
+    /// Data runs of this piece (empty for resident data)
+    runs: Vec<DataRun>,
+    resident: bool,
 }
 
 // ============================================================================
@@ -136,11 +201,35 @@ pub struct MftParser {
     mft_extents: Vec<Extent>,
     /// Sector-aligned read buffer
     read_buffer: Vec<u8>,
+    /// Held around every read of `io`: a volume handle read is a seek and
+    /// a read, so a read-ahead thread and extension record lookups from the
+    /// parsing thread must not interleave
+    io_lock: Mutex<()>,
+    /// Records skipped by `parse_batch_with_extensions` because their
+    /// update sequence did not verify
+    fixup_failures: Mutex<Vec<u64>>,
+    /// Records skipped by `parse_batch_with_extensions` because their
+    /// header or attributes could not be parsed (never-used, zeroed
+    /// records are not counted)
+    parse_failures: Mutex<Vec<u64>>,
+    /// Record ranges `read_records` could not read (errors, short reads)
+    unread_ranges: Mutex<Vec<Range<u64>>>,
+    /// Records `read_record` skipped because they lie past the valid data
+    /// length read at the start: the MFT grew while scanning
+    past_valid_data: Mutex<Vec<u64>>,
+    /// Workers decoding the records of a batch in parallel; None parses
+    /// on the calling thread
+    parse_pool: Option<rayon::ThreadPool>,
+    /// Also decode records no longer in use (deleted files)
+    include_deleted: bool,
 }
 
 impl MftParser {
-    /// Create a new MFT parser from a VolumeIO source
-    pub fn new(io: crate::ntfs::physical::VolumeIO) -> Result<Self> {
+    /// Create a new MFT parser over any source: a `VolumeIO`, or a
+    /// `(reader, volume_data)` pair for any `VolumeReader` (e.g. an
+    /// in-memory MFT image)
+    pub fn new(io: impl Into<crate::ntfs::physical::VolumeIO>) -> Result<Self> {
+        let io = io.into();
         let volume_data = io.volume_data().clone();
         // Allocate aligned buffer for reading
         let buffer_size = (volume_data.bytes_per_file_record_segment * 16) as usize;
@@ -151,30 +240,138 @@ impl MftParser {
             volume_data,
             mft_extents: Vec::new(),
             read_buffer,
+            io_lock: Mutex::new(()),
+            fixup_failures: Mutex::new(Vec::new()),
+            parse_failures: Mutex::new(Vec::new()),
+            unread_ranges: Mutex::new(Vec::new()),
+            past_valid_data: Mutex::new(Vec::new()),
+            parse_pool: None,
+            include_deleted: false,
         })
     }
 
+    /// Decode the records of each batch on `threads` worker threads
+    /// (0 = `threads::max_workers()`, 1 = on the calling thread, the
+    /// default; never more than `max_workers()`, and each batch only uses
+    /// the slots of the thread budget that are free). Reading stays serial;
+    /// only the CPU-bound record parsing is spread out, and entries come
+    /// back in record order either way.
+    pub fn set_parse_threads(&mut self, threads: usize) {
+        let threads = match threads {
+            0 => crate::threads::max_workers(),
+            n => n.min(crate::threads::max_workers()),
+        };
+        if threads <= 1 {
+            self.parse_pool = None;
+            return;
+        }
+
+        match rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("emfit-mft-{}", i))
+            .build()
+        {
+            Ok(pool) => self.parse_pool = Some(pool),
+            Err(e) => {
+                logging::warn("MFT", &format!("No parse workers, parsing serially: {}", e));
+                self.parse_pool = None;
+            }
+        }
+    }
+
+    /// Number of threads decoding records (1 when parsing serially)
+    pub fn parse_threads(&self) -> usize {
+        self.parse_pool.as_ref().map_or(1, |pool| pool.current_num_threads())
+    }
+
+    /// Also return deleted files: base records whose in-use flag is clear
+    /// but whose attributes are intact until NTFS reuses the record. They
+    /// come back from `parse_batch_with_extensions` with `is_deleted` set.
+    /// Their data runs may already point at clusters holding other data.
+    pub fn set_include_deleted(&mut self, include_deleted: bool) {
+        self.include_deleted = include_deleted;
+    }
+
     /// Get number of MFT extents (0 = contiguous, >0 = fragmented)
     pub fn extent_count(&self) -> usize {
         self.mft_extents.len()
     }
 
+    /// Record numbers that failed fixup verification so far
+    pub fn fixup_failures(&self) -> Vec<u64> {
+        lock(&self.fixup_failures).clone()
+    }
+
+    /// Take the record numbers that failed fixup verification since the last call
+    pub fn take_fixup_failures(&mut self) -> Vec<u64> {
+        std::mem::take(self.fixup_failures.get_mut().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Record numbers that could not be parsed so far (corrupt header or
+    /// attributes), not counting fixup failures
+    pub fn parse_failures(&self) -> Vec<u64> {
+        lock(&self.parse_failures).clone()
+    }
+
+    /// Take the record numbers that could not be parsed since the last call
+    pub fn take_parse_failures(&mut self) -> Vec<u64> {
+        std::mem::take(self.parse_failures.get_mut().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Record a range of records the caller gave up on without reading
+    pub(crate) fn mark_unread(&self, range: Range<u64>) {
+        if !range.is_empty() {
+            lock(&self.unread_ranges).push(range);
+        }
+    }
+
+    /// Take the record ranges that could not be read since the last call
+    pub fn take_unread_ranges(&mut self) -> Vec<Range<u64>> {
+        std::mem::take(self.unread_ranges.get_mut().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Take the records skipped since the last call for lying past the
+    /// valid data length
+    pub fn take_past_valid_data(&mut self) -> Vec<u64> {
+        std::mem::take(self.past_valid_data.get_mut().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Check if a record lies past the MFT's valid data length as read when
+    /// the parser was created (never, while that length is still unknown)
+    pub fn is_past_valid_data(&self, record_number: u64) -> bool {
+        let valid_records = self.estimated_records();
+        valid_records > 0 && record_number >= valid_records
+    }
+
+    /// Read from the volume at `offset`, one caller at a time
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize> {
+        let _io = lock(&self.io_lock);
+        self.io.read_at(offset, buffer)
+    }
+
     /// Get MFT extents for debugging
     pub fn extents(&self) -> &[Extent] {
         &self.mft_extents
     }
 
     /// Get MFT extents.
-    /// In physical drive mode, always parses record 0 directly (no NTFS driver IOCTLs).
+    /// In physical drive and image mode, always parses record 0 directly (no NTFS driver IOCTLs).
     /// In volume mode, tries opening $MFT first, then falls back to record 0 parsing.
     pub fn load_mft_extents(&mut self, drive_letter: char) -> Result<()> {
-        if self.io.is_physical() {
-            // Physical drive mode: parse record 0's data runs directly
+        self.load_mft_extents_at(&format!("{}:", drive_letter))
+    }
+
+    /// `load_mft_extents` for a volume opened by device path (such as a
+    /// shadow copy) rather than drive letter. `volume_root` is the path
+    /// that `\$MFT` is appended to.
+    pub fn load_mft_extents_at(&mut self, volume_root: &str) -> Result<()> {
+        if !self.io.is_volume_handle() {
+            // Physical drive or image: parse record 0's data runs directly
             return self.load_mft_extents_from_record_zero();
         }
 
         // Volume mode: try FSCTL_GET_RETRIEVAL_POINTERS first
-        let mft_path = format!("{}:\\$MFT", drive_letter);
+        let mft_path = format!("{}\\$MFT", volume_root);
 
         match open_file_read(&mft_path) {
             Ok(mft_handle) => {
@@ -198,7 +395,7 @@ impl MftParser {
         let mft_start = self.volume_data.mft_byte_offset();
 
         let mut buffer = vec![0u8; record_size];
-        let bytes_read = self.io.read_at(mft_start, &mut buffer)?;
+        let bytes_read = self.read_at(mft_start, &mut buffer)?;
         
         if bytes_read < record_size {
             return Ok(()); // Fallback: no extents, use linear calculation
@@ -286,13 +483,22 @@ impl MftParser {
         Ok(())
     }
 
-    /// Read a single MFT record by record number
-    pub fn read_record(&mut self, record_number: u64) -> Result<Vec<u8>> {
+    /// Read a single MFT record by record number. Records past the valid
+    /// data length were allocated after the volume data was read and are
+    /// not read (their offset may be outside the known extents); they fail
+    /// with the recoverable `RecordPastValidData` and are noted for
+    /// `take_past_valid_data`.
+    pub fn read_record(&self, record_number: u64) -> Result<Vec<u8>> {
+        if self.is_past_valid_data(record_number) {
+            lock(&self.past_valid_data).push(record_number);
+            return Err(EmFitError::RecordPastValidData(record_number));
+        }
+
         let record_size = self.volume_data.bytes_per_file_record_segment as usize;
         let mut buffer = vec![0u8; record_size];
 
         let offset = self.calculate_record_offset(record_number);
-        let bytes_read = self.io.read_at(offset, &mut buffer)?;
+        let bytes_read = self.read_at(offset, &mut buffer)?;
 
         if bytes_read < record_size {
             return Err(EmFitError::MftReadError(format!(
@@ -304,6 +510,64 @@ impl MftParser {
         Ok(buffer)
     }
 
+    /// Read the volume label, NTFS version and flags from `$Volume`
+    pub fn read_volume_info(&mut self) -> Result<VolumeInfo> {
+        let mut data = self.read_record(VOLUME_RECORD_NUMBER)?;
+
+        let header = MftRecordHeader::from_bytes(&data).ok_or_else(|| {
+            EmFitError::InvalidMftRecord(VOLUME_RECORD_NUMBER, "Failed to parse header".to_string())
+        })?;
+        if !header.is_valid() {
+            return Err(EmFitError::InvalidMftRecord(
+                VOLUME_RECORD_NUMBER,
+                "Invalid signature".to_string(),
+            ));
+        }
+        self.apply_fixup(VOLUME_RECORD_NUMBER, &mut data, &header)?;
+
+        let mut info = VolumeInfo::default();
+        let mut found_information = false;
+        let mut offset = header.first_attribute_offset as usize;
+
+        while offset + 16 <= data.len() {
+            let Some(attr_header) = AttributeHeader::from_bytes(&data[offset..]) else {
+                break;
+            };
+            if attr_header.attribute_type == ATTRIBUTE_END_MARKER
+                || attr_header.length == 0
+                || offset + attr_header.length as usize > data.len()
+            {
+                break;
+            }
+
+            let attr_data = &data[offset..offset + attr_header.length as usize];
+            if let Some(h) = ResidentAttributeHeader::from_bytes(attr_data) {
+                let start = h.value_offset as usize;
+                let end = start + h.value_length as usize;
+                if !attr_header.non_resident && end <= attr_data.len() {
+                    let content = &attr_data[start..end];
+                    match AttributeType::from_u32(attr_header.attribute_type) {
+                        Some(AttributeType::VolumeName) => info.parse_name(content),
+                        Some(AttributeType::VolumeInformation) => {
+                            found_information = info.parse_information(content);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            offset += attr_header.length as usize;
+        }
+
+        if !found_information {
+            return Err(EmFitError::InvalidMftRecord(
+                VOLUME_RECORD_NUMBER,
+                "No $VOLUME_INFORMATION attribute".to_string(),
+            ));
+        }
+        Ok(info)
+    }
+
     /// Calculate the byte offset of an MFT record
     fn calculate_record_offset(&self, record_number: u64) -> u64 {
         let record_size = self.volume_data.bytes_per_file_record_segment as u64;
@@ -362,6 +626,16 @@ impl MftParser {
         };
 
         if !entry.is_valid {
+            // Deleting a file only clears the in-use flag; the attributes
+            // stay readable until the record is reused
+            if self.include_deleted
+                && header.is_base_record()
+                && self.parse_attributes(data, &header, &mut entry).is_ok()
+                && !entry.name.is_empty()
+            {
+                entry.is_deleted = true;
+                entry.is_complete = true;
+            }
             return Ok(entry);
         }
 
@@ -569,6 +843,7 @@ impl MftParser {
                             if fn_attr.namespace != FilenameNamespace::Dos {
                                 links.push(HardLink {
                                     parent_record_number: fn_attr.parent_record_number(),
+                                    parent_sequence_number: fn_attr.parent_sequence_number(),
                                     name: fn_attr.name.clone(),
                                     namespace: fn_attr.namespace,
                                 });
@@ -584,10 +859,9 @@ impl MftParser {
         links
     }
 
-    /// Extract file size from an extension MFT record containing $DATA attribute
-    ///
-    /// Returns (file_size, allocated_size) if found
-    fn extract_size_from_extension(&self, data: &mut [u8]) -> Option<(u64, u64)> {
+    /// Extract the piece of the main $DATA attribute held by an extension
+    /// MFT record: its sizes (first piece only) and data runs
+    fn extract_data_from_extension(&self, data: &mut [u8]) -> Option<DataPiece> {
         // Parse header
         let header = MftRecordHeader::from_bytes(data)?;
 
@@ -625,16 +899,31 @@ impl MftParser {
                 if attr_header.non_resident {
                     // Non-resident $DATA - size is in the non-resident header
                     if let Some(nr_header) = NonResidentAttributeHeader::from_bytes(attr_data) {
-                        // Only process if this is the first extent (lowest VCN == 0)
-                        if nr_header.lowest_vcn == 0 {
-                            return Some((nr_header.data_size, nr_header.allocated_size));
-                        }
+                        let runs_offset = nr_header.data_runs_offset as usize;
+                        let runs = if runs_offset < attr_data.len() {
+                            DataRun::decode_runs(&attr_data[runs_offset..]).0
+                        } else {
+                            Vec::new()
+                        };
+                        // Sizes are only valid in the first extent (lowest VCN == 0)
+                        return Some(DataPiece {
+                            sizes: (nr_header.lowest_vcn == 0)
+                                .then_some((nr_header.data_size, nr_header.allocated_size)),
+                            lowest_vcn: nr_header.lowest_vcn,
+                            runs,
+                            resident: false,
+                        });
                     }
                 } else {
                     // Resident $DATA - size is the content length
                     if let Some(r_header) = ResidentAttributeHeader::from_bytes(attr_data) {
                         let size = r_header.value_length as u64;
-                        return Some((size, 0)); // Resident has no allocated clusters
+                        return Some(DataPiece {
+                            sizes: Some((size, 0)), // Resident has no allocated clusters
+                            lowest_vcn: 0,
+                            runs: Vec::new(),
+                            resident: true,
+                        });
                     }
                 }
             }
@@ -700,12 +989,11 @@ impl MftParser {
         let mut offset = header.first_attribute_offset as usize;
 
         // Track best filename (prefer Win32 namespace) for the primary name/parent
-        // Tuple: (namespace, name, parent, creation_time, modification_time)
-        let mut best_filename: Option<(FilenameNamespace, String, u64, u64, u64)> = None;
+        let mut best_filename: Option<FileNameAttribute> = None;
 
         // Collect ALL $FILE_NAME attributes for hard link support
         // Each $FILE_NAME can have a different parent directory (hard link)
-        let mut all_filenames: Vec<(FilenameNamespace, String, u64)> = Vec::new();
+        let mut all_filenames: Vec<(FilenameNamespace, String, u64, u16)> = Vec::new();
 
         // Track extension record numbers we need to read for $FILE_NAME
         let mut extension_records: Vec<u64> = Vec::new();
@@ -732,42 +1020,58 @@ impl MftParser {
                     self.parse_standard_information(attr_data, entry)?;
                 }
                 Some(AttributeType::FileName) => {
-                    if let Some((ns, name, parent, ctime, mtime)) = self.parse_filename(attr_data)? {
+                    if let Some(fn_attr) = self.parse_filename(attr_data)? {
+                        let ns = fn_attr.namespace;
                         // Skip DOS-only names (like Everything does)
                         // DOS names are short 8.3 aliases, not real hard links
                         if ns != FilenameNamespace::Dos {
                             // Collect ALL non-DOS filenames for hard link tracking
-                            all_filenames.push((ns, name.clone(), parent));
+                            all_filenames.push((
+                                ns,
+                                fn_attr.name.clone(),
+                                fn_attr.parent_record_number(),
+                                fn_attr.parent_sequence_number(),
+                            ));
                         }
 
                         // Keep best filename for primary entry (Win32 > Win32+DOS > POSIX > DOS)
-                        let dominated = match (&best_filename, ns) {
+                        let dominated = match (best_filename.as_ref().map(|b| b.namespace), ns) {
                             (None, _) => true,
-                            (Some((FilenameNamespace::Dos, _, ..)), _) => ns != FilenameNamespace::Dos,
-                            (Some((FilenameNamespace::Posix, _, ..)), ns) => {
+                            (Some(FilenameNamespace::Dos), _) => ns != FilenameNamespace::Dos,
+                            (Some(FilenameNamespace::Posix), ns) => {
                                 ns == FilenameNamespace::Win32 || ns == FilenameNamespace::Win32AndDos
                             }
-                            (Some((FilenameNamespace::Win32AndDos, _, ..)), ns) => {
+                            (Some(FilenameNamespace::Win32AndDos), ns) => {
                                 ns == FilenameNamespace::Win32
                             }
-                            (Some((FilenameNamespace::Win32, _, ..)), _) => false,
+                            (Some(FilenameNamespace::Win32), _) => false,
                         };
 
                         if dominated {
-                            best_filename = Some((ns, name, parent, ctime, mtime));
+                            best_filename = Some(fn_attr);
                         }
                     }
                 }
                 Some(AttributeType::Data) => {
                     self.parse_data_attribute(attr_data, &attr_header, entry)?;
                 }
+                Some(AttributeType::ReparsePoint) if !attr_header.non_resident => {
+                    entry.reparse_target = ResidentAttributeHeader::from_bytes(attr_data)
+                        .and_then(|h| {
+                            let start = h.value_offset as usize;
+                            attr_data.get(start..start + h.value_length as usize)
+                        })
+                        .and_then(ReparseTarget::from_bytes);
+                }
                 Some(AttributeType::AttributeList) => {
                     // Parse the attribute list to find extension records with $FILE_NAME and $DATA
-                    let (filename_ext_records, data_ext_record) = self.parse_attribute_list(attr_data, entry.record_number)?;
+                    let (filename_ext_records, data_ext_record, data_segment_records) =
+                        self.parse_attribute_list(attr_data, entry.record_number)?;
                     extension_records.extend(filename_ext_records);
                     if data_ext_record.is_some() {
                         entry.data_extension_record = data_ext_record;
                     }
+                    entry.data_segment_records = data_segment_records;
                 }
                 _ => {
                     // Skip other attributes
@@ -778,13 +1082,24 @@ impl MftParser {
         }
 
         // Set primary filename and parent from best match
-        if let Some((_, name, parent, fn_ctime, fn_mtime)) = best_filename {
-            entry.name = name;
+        if let Some(fn_attr) = best_filename {
+            let parent = fn_attr.parent_record_number();
+            entry.parent_sequence_number = fn_attr.parent_sequence_number();
+            let fn_ctime = fn_attr.creation_time;
+            let fn_mtime = fn_attr.modification_time;
+            entry.name_anomalies = fn_attr.name_anomalies();
+            if entry.name_anomalies != 0 {
+                entry.raw_name = Some(fn_attr.raw_name);
+            }
+            entry.name = fn_attr.name;
             entry.parent_record_number = parent;
+            entry.fn_creation_time = fn_ctime;
+            entry.fn_modification_time = fn_mtime;
 
             // Fallback: if $STANDARD_INFORMATION timestamps are missing (0),
             // use $FILE_NAME timestamps instead. This handles cases where
             // $STANDARD_INFORMATION is corrupt or missing.
+            entry.si_zeroed = entry.creation_time == 0 && entry.modification_time == 0;
             if entry.creation_time == 0 && fn_ctime != 0 {
                 entry.creation_time = fn_ctime;
             }
@@ -797,8 +1112,9 @@ impl MftParser {
         // This allows creating separate tree entries for each hard link location
         entry.hard_links = all_filenames
             .into_iter()
-            .map(|(ns, name, parent)| HardLink {
+            .map(|(ns, name, parent, parent_sequence)| HardLink {
                 parent_record_number: parent,
+                parent_sequence_number: parent_sequence,
                 name,
                 namespace: ns,
             })
@@ -812,14 +1128,15 @@ impl MftParser {
 
     /// Parse an Attribute List to find extension records containing important attributes
     ///
-    /// Returns a tuple of (filename_extension_records, data_extension_record)
+    /// Returns a tuple of (filename_extension_records, data_extension_record, data_segment_records)
     /// - filename_extension_records: Records containing $FILE_NAME (0x30)
     /// - data_extension_record: Record containing the primary $DATA (0x80) attribute with VCN 0
+    /// - data_segment_records: Records containing any piece of the primary $DATA
     fn parse_attribute_list(
         &self,
         attr_data: &[u8],
         base_record_number: u64,
-    ) -> Result<(Vec<u64>, Option<u64>)> {
+    ) -> Result<(Vec<u64>, Option<u64>, Vec<u64>)> {
         // Get the attribute list content
         let attr_header = AttributeHeader::from_bytes(attr_data).ok_or_else(|| {
             EmFitError::InvalidAttribute(0, "Failed to parse attr list header".to_string())
@@ -828,7 +1145,7 @@ impl MftParser {
         let list_data = if attr_header.non_resident {
             // Non-resident attribute list - we'd need to read the data runs
             // This is rare for attribute lists, skip for now
-            return Ok((Vec::new(), None));
+            return Ok((Vec::new(), None, Vec::new()));
         } else {
             // Resident - get the content directly
             let res_header = ResidentAttributeHeader::from_bytes(attr_data).ok_or_else(|| {
@@ -839,7 +1156,7 @@ impl MftParser {
             let content_len = res_header.value_length as usize;
 
             if content_offset + content_len > attr_data.len() {
-                return Ok((Vec::new(), None));
+                return Ok((Vec::new(), None, Vec::new()));
             }
 
             &attr_data[content_offset..content_offset + content_len]
@@ -852,6 +1169,8 @@ impl MftParser {
         let mut filename_extension_records = Vec::new();
         // Find extension record containing $DATA (type 0x80) with starting VCN 0 (primary data stream)
         let mut data_extension_record: Option<u64> = None;
+        // Every extension record with a piece of the unnamed $DATA, for the fragment count
+        let mut data_segment_records = Vec::new();
 
         for entry in entries {
             let ext_record = entry.record_number();
@@ -870,9 +1189,17 @@ impl MftParser {
                     data_extension_record = Some(ext_record);
                 }
             }
+
+            if entry.attribute_type == 0x80
+                && entry.name_length == 0
+                && ext_record != base_record_number
+                && !data_segment_records.contains(&ext_record)
+            {
+                data_segment_records.push(ext_record);
+            }
         }
 
-        Ok((filename_extension_records, data_extension_record))
+        Ok((filename_extension_records, data_extension_record, data_segment_records))
     }
 
     /// Parse $STANDARD_INFORMATION attribute
@@ -903,11 +1230,7 @@ impl MftParser {
     }
 
     /// Parse $FILE_NAME attribute
-    /// Returns (namespace, name, parent_record_number, creation_time, modification_time)
-    fn parse_filename(
-        &self,
-        attr_data: &[u8],
-    ) -> Result<Option<(FilenameNamespace, String, u64, u64, u64)>> {
+    fn parse_filename(&self, attr_data: &[u8]) -> Result<Option<FileNameAttribute>> {
         let header = ResidentAttributeHeader::from_bytes(attr_data);
 
         if let Some(h) = header {
@@ -918,14 +1241,7 @@ impl MftParser {
                 let content = &attr_data[content_offset..content_offset + content_len];
 
                 if let Some(fn_attr) = FileNameAttribute::from_bytes(content) {
-                    let parent_ref = fn_attr.parent_record_number();
-                    return Ok(Some((
-                        fn_attr.namespace,
-                        fn_attr.name,
-                        parent_ref,
-                        fn_attr.creation_time,
-                        fn_attr.modification_time,
-                    )));
+                    return Ok(Some(fn_attr));
                 }
             }
         }
@@ -942,17 +1258,17 @@ impl MftParser {
     ) -> Result<()> {
         // Check for named stream (alternate data stream)
         let stream_name = if header.name_length > 0 {
-            let name_offset = header.name_offset as usize;
-            let name_len = header.name_length as usize * 2;
-            if name_offset + name_len <= attr_data.len() {
-                let name_data = &attr_data[name_offset..name_offset + name_len];
-                let name_u16: Vec<u16> = name_data
-                    .chunks_exact(2)
-                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
-                    .collect();
-                Some(String::from_utf16_lossy(&name_u16))
-            } else {
-                None
+            match read_attribute_name(attr_data, header) {
+                Some(name) => Some(name),
+                None => {
+                    // A corrupt name must not be mistaken for the unnamed
+                    // (main) stream, so skip the attribute entirely
+                    logging::warn("MFT", &format!(
+                        "Record {}: $DATA name out of bounds (offset {}, {} chars, attribute length {})",
+                        entry.record_number, header.name_offset, header.name_length, header.length
+                    ));
+                    return Ok(());
+                }
             }
         } else {
             None
@@ -976,6 +1292,9 @@ impl MftParser {
                             let (runs, _) = DataRun::decode_runs(&attr_data[runs_offset..]);
                             entry.data_runs = runs;
                         }
+                        entry.storage = Storage::NonResident {
+                            fragments: DataRun::fragment_count(&entry.data_runs),
+                        };
                     }
                     Some(name) => {
                         // Alternate data stream
@@ -992,6 +1311,7 @@ impl MftParser {
                     None => {
                         entry.file_size = size;
                         entry.allocated_size = 0; // Resident data doesn't use clusters
+                        entry.storage = Storage::Resident;
                     }
                     Some(name) => {
                         entry.alternate_streams.insert(name.clone(), size);
@@ -1024,34 +1344,97 @@ impl MftParser {
     }
 }
 
+/// Lock one of the parser's mutexes; a panicked holder leaves nothing
+/// half-written that later reads could trip over
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Read an attribute's name, validating that it lies inside the attribute
+/// (`header.length`), after the fixed resident/non-resident header and before
+/// the resident value or the data runs. Returns None if any bound is violated.
+fn read_attribute_name(attr_data: &[u8], header: &AttributeHeader) -> Option<String> {
+    let attr_len = (header.length as usize).min(attr_data.len());
+    let name_offset = header.name_offset as usize;
+    let name_end = name_offset + header.name_length as usize * 2;
+
+    let (header_size, content_offset) = if header.non_resident {
+        let nr = NonResidentAttributeHeader::from_bytes(attr_data)?;
+        (64, nr.data_runs_offset as usize)
+    } else {
+        let r = ResidentAttributeHeader::from_bytes(attr_data)?;
+        let value_offset = if r.value_length > 0 { r.value_offset as usize } else { attr_len };
+        (24, value_offset)
+    };
+
+    if name_offset < header_size || name_end > attr_len || name_end > content_offset {
+        return None;
+    }
+
+    let name_u16: Vec<u16> = attr_data[name_offset..name_end]
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    Some(String::from_utf16_lossy(&name_u16))
+}
+
 // ============================================================================
 // Batch Reading for Performance
 // ============================================================================
 
 impl MftParser {
     /// Read multiple consecutive MFT records at once
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), err))]
     pub fn read_records_batch(
         &mut self,
         start_record: u64,
         count: usize,
+    ) -> Result<Vec<(u64, Vec<u8>)>> {
+        let mut buffer = std::mem::take(&mut self.read_buffer);
+        let results = self.read_records_into(start_record, count, &mut buffer);
+        self.read_buffer = buffer;
+        results
+    }
+
+    /// Read multiple consecutive MFT records into a fresh buffer.
+    ///
+    /// Unlike `read_records_batch` this only borrows the parser, so one
+    /// thread can read the next batch while another parses the current one.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), err))]
+    pub fn read_records(&self, start_record: u64, count: usize) -> Result<Vec<(u64, Vec<u8>)>> {
+        self.read_records_into(start_record, count, &mut Vec::new())
+    }
+
+    fn read_records_into(
+        &self,
+        start_record: u64,
+        count: usize,
+        buffer: &mut Vec<u8>,
     ) -> Result<Vec<(u64, Vec<u8>)>> {
         let record_size = self.volume_data.bytes_per_file_record_segment as usize;
         let total_size = record_size * count;
 
         // Resize buffer if needed
-        if self.read_buffer.len() < total_size {
-            self.read_buffer.resize(total_size, 0);
+        if buffer.len() < total_size {
+            buffer.resize(total_size, 0);
         }
 
         let offset = self.calculate_record_offset(start_record);
-        let bytes_read = self.io.read_at(offset, &mut self.read_buffer[..total_size])?;
+        let bytes_read = match self.read_at(offset, &mut buffer[..total_size]) {
+            Ok(n) => n,
+            Err(e) => {
+                self.mark_unread(start_record..start_record + count as u64);
+                return Err(e);
+            }
+        };
 
         let records_read = bytes_read / record_size;
+        self.mark_unread(start_record + records_read as u64..start_record + count as u64);
         let mut results = Vec::with_capacity(records_read);
 
         for i in 0..records_read {
             let record_offset = i * record_size;
-            let record_data = self.read_buffer[record_offset..record_offset + record_size].to_vec();
+            let record_data = buffer[record_offset..record_offset + record_size].to_vec();
             results.push((start_record + i as u64, record_data));
         }
 
@@ -1065,21 +1448,50 @@ impl MftParser {
     /// 2. Second pass: Batch-read all needed extension records and resolve missing names/sizes
     ///
     /// This is more efficient than reading extension records one-by-one as it minimizes disk seeks.
+    /// With `set_parse_threads` the first pass runs on the worker pool.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(records = batch.len()))
+    )]
     pub fn parse_batch_with_extensions(
-        &mut self,
+        &self,
         batch: Vec<(u64, Vec<u8>)>,
     ) -> Vec<FileEntry> {
         let mut entries: Vec<FileEntry> = Vec::with_capacity(batch.len());
         let mut needs_name_extension: Vec<usize> = Vec::new(); // Indices into entries that need name resolution
         let mut needs_hardlink_extension: Vec<usize> = Vec::new(); // Indices that have extension records with potential additional hard links
-        let mut needs_data_extension: Vec<usize> = Vec::new(); // Indices into entries that need size resolution
+        let mut needs_data_extension: Vec<usize> = Vec::new(); // Indices into entries with $DATA pieces in extension records
         let mut extension_record_set: HashSet<u64> = HashSet::new(); // All extension records we need to read
 
-        // First pass: Parse all records
-        for (record_num, mut data) in batch {
-            match self.parse_record(record_num, &mut data) {
+        // Decode every record first; this is the CPU-bound part and each
+        // record is independent. Collecting keeps record order.
+        let parse = |(record_num, mut data): (u64, Vec<u8>)| {
+            // A zeroed signature is a record never used, not a corrupt one
+            let unused = data.iter().take(4).all(|&b| b == 0);
+            (record_num, unused, self.parse_record(record_num, &mut data))
+        };
+        let parsed: Vec<(u64, bool, Result<FileEntry>)> = match &self.parse_pool {
+            Some(pool) if batch.len() > MIN_RECORDS_PER_WORKER => {
+                let want = pool
+                    .current_num_threads()
+                    .min(batch.len() / MIN_RECORDS_PER_WORKER);
+                // Only as many pieces as the thread budget has slots for
+                crate::threads::with_workers(want, |workers| {
+                    let piece = batch.len().div_ceil(workers);
+                    pool.install(|| batch.into_par_iter().with_min_len(piece).map(parse).collect())
+                })
+            }
+            _ => batch.into_iter().map(parse).collect(),
+        };
+
+        // First pass: Collect parsed records
+        for (record_num, unused, parsed) in parsed {
+            match parsed {
                 Ok(entry) => {
-                    if entry.is_valid {
+                    if entry.is_deleted {
+                        // Extension records of a deleted file may have been reused
+                        entries.push(entry);
+                    } else if entry.is_valid {
                         let idx = entries.len();
 
                         if !entry.extension_records.is_empty() {
@@ -1100,22 +1512,31 @@ impl MftParser {
                             }
                         }
 
-                        // Check if this entry needs $DATA extension record resolution
-                        // (file_size == 0 and we have a data extension record to read)
-                        if entry.file_size == 0 && !entry.is_directory {
-                            if let Some(data_ext_rec) = entry.data_extension_record {
-                                if data_ext_rec != record_num {
-                                    extension_record_set.insert(data_ext_rec);
-                                    needs_data_extension.push(idx);
-                                }
-                            }
+                        // Check if this entry needs $DATA extension record resolution:
+                        // the size when the first piece is elsewhere, and the
+                        // storage (fragment count) from every piece
+                        if !entry.is_directory && !entry.data_segment_records.is_empty() {
+                            extension_record_set.extend(entry.data_segment_records.iter().copied());
+                            needs_data_extension.push(idx);
                         }
 
                         entries.push(entry);
                     }
                 }
-                Err(_) => {
-                    // Skip invalid records
+                Err(EmFitError::FixupVerificationFailed(record)) => {
+                    // Torn write or corrupt record; remember it for the scan report
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(record, "MFT fixup verification failed");
+                    lock(&self.fixup_failures).push(record);
+                    continue;
+                }
+                Err(_) if unused => continue,
+                Err(_e) => {
+                    // Bad signature ("BAAD" or garbage) or unparsable
+                    // attributes; remember it for the scan report
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(record = record_num, error = %_e, "MFT record could not be parsed");
+                    lock(&self.parse_failures).push(record_num);
                     continue;
                 }
             }
@@ -1134,7 +1555,7 @@ impl MftParser {
         // Read extension records and extract filenames, hard links, and sizes
         let mut extension_names: HashMap<u64, (String, u64)> = HashMap::new();
         let mut extension_hardlinks: HashMap<u64, Vec<HardLink>> = HashMap::new();
-        let mut extension_sizes: HashMap<u64, (u64, u64)> = HashMap::new(); // record -> (file_size, allocated_size)
+        let mut extension_data: HashMap<u64, DataPiece> = HashMap::new(); // record -> its piece of the main $DATA
 
         for ext_record_num in extension_records {
             match self.read_record(ext_record_num) {
@@ -1150,12 +1571,12 @@ impl MftParser {
                         }
                         extension_hardlinks.insert(ext_record_num, all_links);
                     }
-                    // Extract file size if available
+                    // Extract the $DATA piece if available
                     // Note: need to re-read since extract_all_filenames_from_extension consumed the fixup
                     match self.read_record(ext_record_num) {
                         Ok(mut ext_data2) => {
-                            if let Some((file_size, allocated_size)) = self.extract_size_from_extension(&mut ext_data2) {
-                                extension_sizes.insert(ext_record_num, (file_size, allocated_size));
+                            if let Some(piece) = self.extract_data_from_extension(&mut ext_data2) {
+                                extension_data.insert(ext_record_num, piece);
                             }
                         }
                         Err(_) => {}
@@ -1209,22 +1630,60 @@ impl MftParser {
             }
         }
 
-        // Fifth pass: Apply extension record sizes to entries that need them
+        // Fifth pass: Apply extension record sizes and data runs to entries that need them
         for idx in needs_data_extension {
             let entry = &mut entries[idx];
 
-            if let Some(data_ext_rec) = entry.data_extension_record {
-                if let Some(&(file_size, allocated_size)) = extension_sizes.get(&data_ext_rec) {
-                    entry.file_size = file_size;
-                    entry.allocated_size = allocated_size;
+            if entry.file_size == 0 {
+                if let Some(data_ext_rec) = entry.data_extension_record {
+                    if let Some((file_size, allocated_size)) =
+                        extension_data.get(&data_ext_rec).and_then(|piece| piece.sizes)
+                    {
+                        entry.file_size = file_size;
+                        entry.allocated_size = allocated_size;
+                    }
                 }
             }
+
+            let mut pieces: Vec<&DataPiece> = entry
+                .data_segment_records
+                .iter()
+                .filter_map(|record| extension_data.get(record))
+                .collect();
+            if pieces.is_empty() {
+                continue;
+            }
+            if pieces.iter().any(|piece| piece.resident) {
+                entry.storage = Storage::Resident;
+                continue;
+            }
+            pieces.sort_by_key(|piece| piece.lowest_vcn);
+
+            // Runs in VCN order, so pieces continuing on disk count once.
+            // The base record's own runs come first when it holds VCN 0;
+            // otherwise their place is unknown and they go last.
+            let base_runs = std::mem::take(&mut entry.data_runs);
+            let mut runs = Vec::new();
+            if entry.data_extension_record.is_none() {
+                runs.extend(base_runs.iter().cloned());
+            }
+            for piece in pieces {
+                runs.extend(piece.runs.iter().cloned());
+            }
+            if entry.data_extension_record.is_some() {
+                runs.extend(base_runs);
+            }
+            entry.storage = Storage::NonResident {
+                fragments: DataRun::fragment_count(&runs),
+            };
+            entry.data_runs = runs;
         }
 
         // Clear extension_records from all entries (no longer needed)
         for entry in &mut entries {
             entry.extension_records.clear();
             entry.data_extension_record = None;
+            entry.data_segment_records.clear();
         }
 
         entries
@@ -1403,3 +1862,133 @@ pub fn apply_fixup_standalone(data: &mut [u8], header: &MftRecordHeader) -> Resu
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ntfs::fixture::*;
+
+    /// An MFT of eight records: 0-4 unused, 5 the root directory, 6 a
+    /// small file in it and 7 a deleted file
+    fn synthetic_mft() -> Vec<u8> {
+        let mut image = vec![0u8; RECORD_SIZE * 5];
+        image.extend(file_record(5, MFT_RECORD_IN_USE | MFT_RECORD_IS_DIRECTORY, &[
+            standard_information(file_attributes::DIRECTORY, SI_TIME),
+            file_name(5, 5, "."),
+        ]));
+        image.extend(file_record(3, MFT_RECORD_IN_USE, &[
+            standard_information(0, SI_TIME),
+            file_name(5, 5, "hello.txt"),
+            resident_attribute(0x80, b"hello"),
+        ]));
+        image.extend(file_record(9, 0, &[
+            standard_information(0, SI_TIME),
+            file_name(5, 5, "old.log"),
+            resident_attribute(0x80, b"gone"),
+        ]));
+        image
+    }
+
+    fn parser_over(image: Vec<u8>) -> MftParser {
+        let volume_data = volume_data(image.len());
+        MftParser::new((image, volume_data)).unwrap()
+    }
+
+    fn parse_all(parser: &MftParser, count: usize) -> Vec<FileEntry> {
+        let batch = parser.read_records(0, count).unwrap();
+        parser.parse_batch_with_extensions(batch)
+    }
+
+    #[test]
+    fn parses_records_from_an_in_memory_mft() {
+        let parser = parser_over(synthetic_mft());
+        let entries = parse_all(&parser, 8);
+
+        let live: Vec<&FileEntry> = entries.iter().filter(|e| e.is_valid).collect();
+        assert_eq!(live.len(), 2);
+
+        let root = live[0];
+        assert_eq!(root.record_number, 5);
+        assert_eq!(root.file_reference_number, 5 | 5 << 48);
+        assert_eq!(root.name, ".");
+        assert_eq!(root.parent_record_number, 5);
+        assert!(root.is_directory);
+
+        let file = live[1];
+        assert_eq!(file.record_number, 6);
+        assert_eq!(file.name, "hello.txt");
+        assert_eq!(file.parent_record_number, 5);
+        assert_eq!(file.parent_sequence_number, 5);
+        assert!(!file.is_directory);
+        assert_eq!(file.file_size, 5);
+        assert_eq!(file.storage, Storage::Resident);
+        assert_eq!(file.creation_time, SI_TIME);
+        assert_eq!(file.fn_creation_time, FN_TIME);
+        assert!(!file.si_zeroed);
+        assert_eq!(file.hard_links.len(), 1);
+
+        assert!(entries.iter().all(|e| !e.is_deleted));
+        assert!(parser.fixup_failures().is_empty());
+    }
+
+    #[test]
+    fn deleted_records_are_returned_only_when_asked_for() {
+        let mut parser = parser_over(synthetic_mft());
+        parser.set_include_deleted(true);
+        let entries = parse_all(&parser, 8);
+
+        let deleted: Vec<&FileEntry> = entries.iter().filter(|e| e.is_deleted).collect();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].record_number, 7);
+        assert_eq!(deleted[0].name, "old.log");
+        assert_eq!(deleted[0].file_size, 4);
+        assert!(!deleted[0].is_valid);
+    }
+
+    #[test]
+    fn zeroed_standard_information_is_flagged_and_falls_back() {
+        let mut image = vec![0u8; RECORD_SIZE * 6];
+        image[RECORD_SIZE * 5..].copy_from_slice(&file_record(2, MFT_RECORD_IN_USE, &[
+            standard_information(0, 0),
+            file_name(5, 5, "stomped.exe"),
+        ]));
+        let parser = parser_over(image);
+        let entries = parse_all(&parser, 6);
+
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].si_zeroed);
+        assert_eq!(entries[0].creation_time, FN_TIME);
+        assert_eq!(entries[0].modification_time, FN_TIME);
+    }
+
+    #[test]
+    fn torn_record_is_reported_as_a_fixup_failure() {
+        let mut image = synthetic_mft();
+        // Overwrite the update sequence number at the end of record 6's
+        // second sector, as a write interrupted between sectors would
+        let sector_end = 6 * RECORD_SIZE + 2 * SECTOR_SIZE as usize - 2;
+        image[sector_end] ^= 0xFF;
+
+        let parser = parser_over(image);
+        let entries = parse_all(&parser, 8);
+
+        assert!(entries.iter().all(|e| e.record_number != 6));
+        assert!(entries.iter().any(|e| e.record_number == 5 && e.is_valid));
+        assert_eq!(parser.fixup_failures(), vec![6]);
+    }
+
+    #[test]
+    fn corrupt_record_is_reported_but_unused_ones_are_not() {
+        let mut image = synthetic_mft();
+        // Mark record 6 bad the way chkdsk does
+        image[6 * RECORD_SIZE..6 * RECORD_SIZE + 4].copy_from_slice(b"BAAD");
+
+        let parser = parser_over(image);
+        let entries = parse_all(&parser, 8);
+
+        assert!(entries.iter().all(|e| e.record_number != 6));
+        assert!(entries.iter().any(|e| e.record_number == 5 && e.is_valid));
+        assert_eq!(parser.parse_failures(), vec![6]);
+        assert!(parser.fixup_failures().is_empty());
+    }
+}
@@ -5,21 +5,32 @@
 //! - USN (Update Sequence Number) Journal scanning
 //! - Real-time change monitoring
 //! - Fixup verification for data integrity
+//! - Volume Shadow Copy snapshots for consistent scans of live volumes
 
+#[doc(hidden)]
+pub mod fixture;
 pub mod mft;
 pub mod physical;
 pub mod structs;
 pub mod usn;
+pub mod vss;
 pub mod winapi;
 
 // Re-export commonly used types
 pub use mft::{FileEntry, HardLink, MftParser};
-pub use physical::{MftRecordFetcher, VolumeIO, open_physical_drive_for_volume};
+pub use physical::{
+    ImageReader, MftRecordFetcher, VolumeIO, VolumeReader, open_image,
+    open_physical_drive_for_volume,
+};
 pub use structs::{
     AttributeType, DataRun, FileNameAttribute, FilenameNamespace, MftRecordHeader,
-    NtfsBootSector, NtfsVolumeData, StandardInformation, UsnJournalData, UsnRecord,
+    NtfsBootSector, NtfsVolumeData, ReparseTarget, StandardInformation, Storage, UsnJournalData,
+    UsnRecord, VolumeInfo,
+};
+pub use usn::{
+    ChangeEvent, ChangeReason, HybridScanner, UsnEntry, UsnMonitor, UsnRecords, UsnScanner,
 };
-pub use usn::{ChangeEvent, ChangeReason, HybridScanner, UsnEntry, UsnMonitor, UsnScanner};
+pub use vss::ShadowCopy;
 pub use winapi::{
     open_volume, open_volume_for_file_id, batch_get_file_metadata, get_file_metadata_by_id,
     FileMetadata, SafeHandle,
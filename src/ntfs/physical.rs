@@ -2,21 +2,90 @@
 //!
 //! Provides direct physical drive reading.
 //! Bypasses the NTFS filesystem driver by reading raw sectors from \\.\PhysicalDriveN.
+//! Raw disk image files (.img/.dd) are read the same way, through plain file offsets.
 
 use crate::error::{EmFitError, Result};
 use crate::logging;
 use crate::ntfs::mft::{extract_parent_info, apply_fixup_standalone};
 use crate::ntfs::structs::*;
 use crate::ntfs::winapi::*;
+use std::fs::File;
+use std::os::windows::fs::FileExt;
+use std::path::Path;
 use std::sync::Mutex;
 
+// ============================================================================
+// VolumeReader — Positioned reads from any byte source
+// ============================================================================
+
+/// A source of volume bytes addressed by offset.
+///
+/// Implemented for `SafeHandle` (volume or physical drive handles) and for
+/// in-memory buffers, so the MFT parser can run over synthetic or captured
+/// records without a live volume.
+pub trait VolumeReader: Send + Sync {
+    /// Read up to `buffer.len()` bytes at `offset`. Returns the number of bytes read.
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize>;
+}
+
+impl VolumeReader for SafeHandle {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize> {
+        read_volume_at(self, offset, buffer)
+    }
+}
+
+impl VolumeReader for Vec<u8> {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize> {
+        let start = usize::try_from(offset).unwrap_or(usize::MAX).min(self.len());
+        let len = buffer.len().min(self.len() - start);
+        buffer[..len].copy_from_slice(&self[start..start + len]);
+        Ok(len)
+    }
+}
+
+/// A raw disk or partition image file (.img/.dd).
+///
+/// Reads are offset by `partition_offset`, so a whole-disk image can be read
+/// as the NTFS volume of one of its partitions. No admin rights are needed.
+pub struct ImageReader {
+    file: File,
+    partition_offset: u64,
+}
+
+impl ImageReader {
+    /// Open an image file for reading
+    pub fn open(path: &Path, partition_offset: u64) -> Result<Self> {
+        Ok(Self {
+            file: File::open(path)?,
+            partition_offset,
+        })
+    }
+}
+
+impl VolumeReader for ImageReader {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize> {
+        // seek_read may return short counts; keep going until EOF
+        let mut total = 0;
+        while total < buffer.len() {
+            let n = self
+                .file
+                .seek_read(&mut buffer[total..], self.partition_offset + offset + total as u64)?;
+            if n == 0 {
+                break;
+            }
+            total += n;
+        }
+        Ok(total)
+    }
+}
+
 // ============================================================================
 // VolumeIO — Abstraction over volume reads
 // ============================================================================
 
 /// Abstraction over volume I/O source.
 /// Either a volume handle (\\.\C:) or a physical drive handle (\\.\PhysicalDrive0)
-/// with a partition offset applied to all reads.
+/// with a partition offset applied to all reads, or any other `VolumeReader`.
 pub enum VolumeIO {
     /// Traditional volume handle — reads are volume-relative
     Volume {
@@ -29,6 +98,12 @@ pub enum VolumeIO {
         partition_offset: u64,
         volume_data: NtfsVolumeData,
     },
+    /// Any other reader (in-memory MFT images, tests, benchmarks).
+    /// Reads are volume-relative.
+    Reader {
+        reader: Box<dyn VolumeReader>,
+        volume_data: NtfsVolumeData,
+    },
 }
 
 impl VolumeIO {
@@ -36,21 +111,43 @@ impl VolumeIO {
     /// For Physical mode, the partition_offset is added automatically.
     pub fn read_at(&self, volume_offset: u64, buffer: &mut [u8]) -> Result<usize> {
         match self {
-            VolumeIO::Volume { handle, .. } => {
-                read_volume_at(handle, volume_offset, buffer)
-            }
+            VolumeIO::Volume { handle, .. } => handle.read_at(volume_offset, buffer),
             VolumeIO::Physical { handle, partition_offset, .. } => {
                 let physical_offset = partition_offset + volume_offset;
-                read_volume_at(handle, physical_offset, buffer)
+                handle.read_at(physical_offset, buffer)
             }
+            VolumeIO::Reader { reader, .. } => reader.read_at(volume_offset, buffer),
+        }
+    }
+
+    /// Wrap any reader with the volume geometry it should be parsed with
+    pub fn from_reader(reader: impl VolumeReader + 'static, volume_data: NtfsVolumeData) -> Self {
+        VolumeIO::Reader {
+            reader: Box::new(reader),
+            volume_data,
         }
     }
 
+    /// Wrap a raw dump of consecutive MFT records (e.g. a copy of `$MFT`).
+    /// The MFT is assumed to start at offset 0 with 4 KB clusters.
+    pub fn from_mft_image(data: Vec<u8>, bytes_per_record: u32) -> Self {
+        let volume_data = NtfsVolumeData {
+            bytes_per_sector: SECTOR_SIZE,
+            bytes_per_cluster: 4096,
+            bytes_per_file_record_segment: bytes_per_record,
+            mft_valid_data_length: data.len() as u64,
+            mft_start_lcn: 0,
+            ..Default::default()
+        };
+        Self::from_reader(data, volume_data)
+    }
+
     /// Get the volume data
     pub fn volume_data(&self) -> &NtfsVolumeData {
         match self {
             VolumeIO::Volume { volume_data, .. } => volume_data,
             VolumeIO::Physical { volume_data, .. } => volume_data,
+            VolumeIO::Reader { volume_data, .. } => volume_data,
         }
     }
 
@@ -59,6 +156,7 @@ impl VolumeIO {
         match self {
             VolumeIO::Volume { volume_data, .. } => volume_data,
             VolumeIO::Physical { volume_data, .. } => volume_data,
+            VolumeIO::Reader { volume_data, .. } => volume_data,
         }
     }
 
@@ -66,6 +164,48 @@ impl VolumeIO {
     pub fn is_physical(&self) -> bool {
         matches!(self, VolumeIO::Physical { .. })
     }
+
+    /// Check if this reads through a mounted volume handle (\\.\C:)
+    pub fn is_volume_handle(&self) -> bool {
+        matches!(self, VolumeIO::Volume { .. })
+    }
+}
+
+impl<R: VolumeReader + 'static> From<(R, NtfsVolumeData)> for VolumeIO {
+    fn from((reader, volume_data): (R, NtfsVolumeData)) -> Self {
+        VolumeIO::from_reader(reader, volume_data)
+    }
+}
+
+/// Read and validate the NTFS boot sector at `offset`
+fn read_boot_sector(reader: &dyn VolumeReader, offset: u64) -> Result<NtfsBootSector> {
+    let mut boot_buffer = vec![0u8; 512];
+    let bytes_read = reader.read_at(offset, &mut boot_buffer)?;
+    if bytes_read < 512 {
+        return Err(EmFitError::PhysicalDriveError(format!(
+            "Short read for boot sector: {} bytes", bytes_read
+        )));
+    }
+
+    let boot_sector = NtfsBootSector::from_bytes(&boot_buffer)
+        .ok_or(EmFitError::NotNtfsBootSector)?;
+
+    if !boot_sector.is_valid_ntfs() {
+        return Err(EmFitError::NotNtfsBootSector);
+    }
+
+    logging::info("PHYSICAL", &format!(
+        "NTFS boot sector: bytes_per_sector={}, sectors_per_cluster={}, bytes_per_cluster={}, \
+         mft_start_lcn={}, bytes_per_mft_record={}, serial={:016X}",
+        boot_sector.bytes_per_sector,
+        boot_sector.sectors_per_cluster,
+        boot_sector.bytes_per_cluster(),
+        boot_sector.mft_cluster_number,
+        boot_sector.bytes_per_mft_record(),
+        boot_sector.volume_serial_number,
+    ));
+
+    Ok(boot_sector)
 }
 
 // ============================================================================
@@ -99,33 +239,9 @@ pub fn open_physical_drive_for_volume(drive_letter: char) -> Result<VolumeIO> {
     let phys_handle = open_physical_drive(extent.disk_number)?;
 
     // Step 3: Read and parse NTFS boot sector at partition offset
-    let mut boot_buffer = vec![0u8; 512];
-    let bytes_read = read_volume_at(&phys_handle, extent.starting_offset, &mut boot_buffer)?;
-    if bytes_read < 512 {
-        return Err(EmFitError::PhysicalDriveError(format!(
-            "Short read for boot sector: {} bytes", bytes_read
-        )));
-    }
-
-    let boot_sector = NtfsBootSector::from_bytes(&boot_buffer)
-        .ok_or(EmFitError::NotNtfsBootSector)?;
-
-    if !boot_sector.is_valid_ntfs() {
-        return Err(EmFitError::NotNtfsBootSector);
-    }
-
-    logging::info("PHYSICAL", &format!(
-        "NTFS boot sector: bytes_per_sector={}, sectors_per_cluster={}, bytes_per_cluster={}, \
-         mft_start_lcn={}, bytes_per_mft_record={}, serial={:016X}",
-        boot_sector.bytes_per_sector,
-        boot_sector.sectors_per_cluster,
-        boot_sector.bytes_per_cluster(),
-        boot_sector.mft_cluster_number,
-        boot_sector.bytes_per_mft_record(),
-        boot_sector.volume_serial_number,
-    ));
-
+    let boot_sector = read_boot_sector(&phys_handle, extent.starting_offset)?;
     let volume_data = boot_sector.to_volume_data();
+    volume_data.validate()?;
 
     Ok(VolumeIO::Physical {
         handle: phys_handle,
@@ -134,6 +250,20 @@ pub fn open_physical_drive_for_volume(drive_letter: char) -> Result<VolumeIO> {
     })
 }
 
+/// Opens a raw disk image as a volume. `partition_offset` is the byte offset
+/// of the NTFS partition inside the image (0 for a partition image).
+pub fn open_image(path: &Path, partition_offset: u64) -> Result<VolumeIO> {
+    logging::info("PHYSICAL", &format!(
+        "Opening image {} at offset {}", path.display(), partition_offset
+    ));
+
+    let reader = ImageReader::open(path, partition_offset)?;
+    let volume_data = read_boot_sector(&reader, 0)?.to_volume_data();
+    volume_data.validate()?;
+
+    Ok(VolumeIO::from_reader(reader, volume_data))
+}
+
 // ============================================================================
 // MftRecordFetcher — On-demand parent resolution from MFT
 // ============================================================================
@@ -151,7 +281,7 @@ pub struct MftRecordFetcher {
 }
 
 struct FetcherIO {
-    handle: SafeHandle,
+    reader: Box<dyn VolumeReader>,
     /// Only used for Physical mode
     partition_offset: u64,
 }
@@ -159,7 +289,7 @@ struct FetcherIO {
 impl FetcherIO {
     fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize> {
         let physical_offset = self.partition_offset + offset;
-        read_volume_at(&self.handle, physical_offset, buffer)
+        self.reader.read_at(physical_offset, buffer)
     }
 }
 
@@ -186,12 +316,26 @@ impl MftRecordFetcher {
         };
 
         Ok(Self {
-            io: Mutex::new(FetcherIO { handle, partition_offset }),
+            io: Mutex::new(FetcherIO { reader: Box::new(handle), partition_offset }),
             volume_data,
             mft_extents,
         })
     }
 
+    /// Create a fetcher over any `VolumeReader` (e.g. a disk image).
+    /// Reads are volume-relative.
+    pub fn from_reader(
+        reader: impl VolumeReader + 'static,
+        volume_data: NtfsVolumeData,
+        mft_extents: Vec<Extent>,
+    ) -> Self {
+        Self {
+            io: Mutex::new(FetcherIO { reader: Box::new(reader), partition_offset: 0 }),
+            volume_data,
+            mft_extents,
+        }
+    }
+
     /// Fetch parent info (name, parent_record_number) for a given MFT record.
     /// Returns None if the record is invalid or not in use.
     pub fn fetch_parent_info(&self, record_number: u64) -> Option<(String, u64)> {
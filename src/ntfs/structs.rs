@@ -1,6 +1,8 @@
 //! NTFS on-disk structures and constants
 
+use crate::error::{EmFitError, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 
 // ============================================================================
@@ -130,8 +132,17 @@ pub struct NtfsVolumeData {
 }
 
 impl NtfsVolumeData {
-    /// Parse from raw buffer (0x60 bytes from FSCTL_GET_NTFS_VOLUME_DATA)
-    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+    /// Parse from raw buffer (0x60 bytes from FSCTL_GET_NTFS_VOLUME_DATA).
+    /// Fails if the buffer is short or the geometry is unusable (see `validate`).
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let volume_data = Self::read_fields(data).ok_or_else(|| {
+            EmFitError::VolumeDataError("Failed to parse NTFS volume data".to_string())
+        })?;
+        volume_data.validate()?;
+        Ok(volume_data)
+    }
+
+    fn read_fields(data: &[u8]) -> Option<Self> {
         if data.len() < 0x60 {
             return None;
         }
@@ -156,14 +167,103 @@ impl NtfsVolumeData {
         })
     }
 
+    /// Check the sizes the MFT parser divides and multiplies by. Some virtual
+    /// volumes (e.g. mounted VHDs) report zeros here.
+    pub fn validate(&self) -> Result<()> {
+        let check = |name: &str, value: u32| {
+            if value >= 256 && value.is_power_of_two() {
+                Ok(())
+            } else {
+                Err(EmFitError::VolumeDataError(format!(
+                    "Invalid {}: {}", name, value
+                )))
+            }
+        };
+        check("bytes per sector", self.bytes_per_sector)?;
+        check("bytes per cluster", self.bytes_per_cluster)?;
+        check("bytes per file record segment", self.bytes_per_file_record_segment)?;
+        if self.bytes_per_cluster < self.bytes_per_sector {
+            return Err(EmFitError::VolumeDataError(format!(
+                "Cluster size {} is smaller than sector size {}",
+                self.bytes_per_cluster, self.bytes_per_sector
+            )));
+        }
+        Ok(())
+    }
+
     /// Calculate the byte offset of the MFT on disk
     pub fn mft_byte_offset(&self) -> u64 {
-        self.mft_start_lcn * self.bytes_per_cluster as u64
+        self.mft_start_lcn.saturating_mul(self.bytes_per_cluster as u64)
     }
 
-    /// Estimate total MFT records
+    /// Estimate total MFT records (0 if the record size is unknown)
     pub fn estimated_mft_records(&self) -> u64 {
-        self.mft_valid_data_length / self.bytes_per_file_record_segment as u64
+        self.mft_valid_data_length
+            .checked_div(self.bytes_per_file_record_segment as u64)
+            .unwrap_or(0)
+    }
+}
+
+// ============================================================================
+// Volume Information ($Volume metafile)
+// ============================================================================
+
+/// MFT record number of the `$Volume` metafile
+pub const VOLUME_RECORD_NUMBER: u64 = 3;
+
+/// `$VOLUME_INFORMATION` flags
+pub mod volume_flags {
+    pub const DIRTY: u16 = 0x0001;
+    pub const RESIZE_LOG_FILE: u16 = 0x0002;
+    pub const UPGRADE_ON_MOUNT: u16 = 0x0004;
+    pub const MOUNTED_ON_NT4: u16 = 0x0008;
+    pub const DELETE_USN_UNDERWAY: u16 = 0x0010;
+    pub const REPAIR_OBJECT_IDS: u16 = 0x0020;
+    pub const CHKDSK_UNDERWAY: u16 = 0x4000;
+    pub const MODIFIED_BY_CHKDSK: u16 = 0x8000;
+}
+
+/// Label, NTFS version and flags read from the `$Volume` metafile
+#[derive(Debug, Clone, Default)]
+pub struct VolumeInfo {
+    /// Volume label from `$VOLUME_NAME` (empty if unset)
+    pub label: String,
+    pub major_version: u8,
+    pub minor_version: u8,
+    /// `volume_flags` bits
+    pub flags: u16,
+}
+
+impl VolumeInfo {
+    /// Fill version and flags from resident `$VOLUME_INFORMATION` content
+    /// (8 reserved bytes, major, minor, flags)
+    pub fn parse_information(&mut self, data: &[u8]) -> bool {
+        if data.len() < 12 {
+            return false;
+        }
+        self.major_version = data[8];
+        self.minor_version = data[9];
+        self.flags = u16::from_le_bytes([data[10], data[11]]);
+        true
+    }
+
+    /// Set the label from resident `$VOLUME_NAME` content (UTF-16LE)
+    pub fn parse_name(&mut self, data: &[u8]) {
+        let name_u16: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        self.label = String::from_utf16_lossy(&name_u16);
+    }
+
+    /// Version as "major.minor", e.g. "3.1"
+    pub fn version(&self) -> String {
+        format!("{}.{}", self.major_version, self.minor_version)
+    }
+
+    /// Dirty bit set: Windows will run chkdsk on the next mount
+    pub fn is_dirty(&self) -> bool {
+        (self.flags & volume_flags::DIRTY) != 0
     }
 }
 
@@ -546,7 +646,10 @@ pub struct FileNameAttribute {
     pub reparse_value: u32,
     pub name_length: u8,
     pub namespace: FilenameNamespace,
+    /// Decoded name; lone surrogates become U+FFFD (see `raw_name`)
     pub name: String,
+    /// Name exactly as stored (UTF-16LE code units)
+    pub raw_name: Vec<u16>,
 }
 
 impl FileNameAttribute {
@@ -597,6 +700,7 @@ impl FileNameAttribute {
             name_length,
             namespace,
             name,
+            raw_name: name_u16,
         })
     }
 
@@ -604,6 +708,157 @@ impl FileNameAttribute {
     pub fn parent_record_number(&self) -> u64 {
         self.parent_reference & 0x0000_FFFF_FFFF_FFFF
     }
+
+    /// Get the parent's sequence number (upper 16 bits)
+    pub fn parent_sequence_number(&self) -> u16 {
+        (self.parent_reference >> 48) as u16
+    }
+
+    /// `name_anomalies` flags of the stored name
+    pub fn name_anomalies(&self) -> u8 {
+        name_anomalies::detect(&self.raw_name)
+    }
+}
+
+/// Names Windows would not normally create. NTFS stores names as raw
+/// UTF-16 and never validates them, so such names survive on disk but are
+/// hidden by lossy decoding (a lone surrogate turns into U+FFFD).
+pub mod name_anomalies {
+    /// Unpaired UTF-16 surrogate
+    pub const LONE_SURROGATE: u8 = 0x01;
+    /// C0/C1 control character (U+0000..U+001F, U+007F..U+009F)
+    pub const CONTROL_CHAR: u8 = 0x02;
+
+    /// Flags for a name's code units (0 for an ordinary name)
+    pub fn detect(units: &[u16]) -> u8 {
+        let mut flags = 0;
+        for c in char::decode_utf16(units.iter().copied()) {
+            match c {
+                Err(_) => flags |= LONE_SURROGATE,
+                Ok(c) if c.is_control() => flags |= CONTROL_CHAR,
+                Ok(_) => {}
+            }
+        }
+        flags
+    }
+
+    /// Human-readable flag names
+    pub fn describe(flags: u8) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if flags & LONE_SURROGATE != 0 {
+            names.push("lone surrogate");
+        }
+        if flags & CONTROL_CHAR != 0 {
+            names.push("control character");
+        }
+        names
+    }
+
+    /// Name with every anomaly written as `\u{XXXX}` and backslashes
+    /// doubled, so the exact code units can be read back from the text
+    pub fn escape(units: &[u16]) -> String {
+        let mut out = String::with_capacity(units.len());
+        for c in char::decode_utf16(units.iter().copied()) {
+            match c {
+                Err(e) => out.push_str(&format!("\\u{{{:04X}}}", e.unpaired_surrogate())),
+                Ok('\\') => out.push_str("\\\\"),
+                Ok(c) if c.is_control() => out.push_str(&format!("\\u{{{:04X}}}", c as u32)),
+                Ok(c) => out.push(c),
+            }
+        }
+        out
+    }
+}
+
+// ============================================================================
+// Reparse Point Attribute
+// ============================================================================
+
+pub mod reparse_tags {
+    /// Junction or volume mount point
+    pub const MOUNT_POINT: u32 = 0xA000_0003;
+    pub const SYMLINK: u32 = 0xA000_000C;
+}
+
+/// Set in a symbolic link's flags when the target is relative to the link
+const SYMLINK_FLAG_RELATIVE: u32 = 0x0000_0001;
+
+/// Where a junction or symbolic link points, from its $REPARSE_POINT content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReparseTarget {
+    /// `reparse_tags::MOUNT_POINT` or `reparse_tags::SYMLINK`
+    pub tag: u32,
+    /// Substitute name without the `\??\` prefix: `D:\data`, `Volume{guid}\`,
+    /// `\\server\share`, or a relative path such as `..\lib`
+    pub path: String,
+    /// Symbolic link whose `path` is relative to the link's directory
+    pub relative: bool,
+}
+
+impl ReparseTarget {
+    /// Parse a reparse data buffer. Returns `None` for tags other than
+    /// junctions and symbolic links (cloud files, dedup, app links, ...).
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut cursor = Cursor::new(data);
+        let tag = cursor.read_u32::<LittleEndian>().ok()?;
+        let _data_length = cursor.read_u16::<LittleEndian>().ok()?;
+        let _reserved = cursor.read_u16::<LittleEndian>().ok()?;
+        let name_offset = cursor.read_u16::<LittleEndian>().ok()? as usize;
+        let name_length = cursor.read_u16::<LittleEndian>().ok()? as usize;
+        let _print_offset = cursor.read_u16::<LittleEndian>().ok()?;
+        let _print_length = cursor.read_u16::<LittleEndian>().ok()?;
+
+        let (buffer_start, relative) = match tag {
+            reparse_tags::MOUNT_POINT => (16, false),
+            reparse_tags::SYMLINK => {
+                let flags = cursor.read_u32::<LittleEndian>().ok()?;
+                (20, flags & SYMLINK_FLAG_RELATIVE != 0)
+            }
+            _ => return None,
+        };
+
+        let start = buffer_start + name_offset;
+        let name_data = data.get(start..start + name_length)?;
+        let name_u16: Vec<u16> = name_data
+            .chunks_exact(2)
+            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect();
+        let name = String::from_utf16_lossy(&name_u16);
+
+        let path = match name.strip_prefix("\\??\\") {
+            Some(rest) => match rest.strip_prefix("UNC\\") {
+                Some(share) => format!("\\\\{}", share),
+                None => rest.to_string(),
+            },
+            None => name,
+        };
+
+        Some(Self { tag, path, relative })
+    }
+
+    /// Junction or volume mount point rather than a symbolic link
+    pub fn is_mount_point(&self) -> bool {
+        self.tag == reparse_tags::MOUNT_POINT
+    }
+
+    /// Whether the target lies on a volume other than `drive`; `None` if
+    /// the path does not say (e.g. a rooted `\dir` symlink)
+    pub fn is_off_volume(&self, drive: char) -> Option<bool> {
+        if self.relative {
+            return Some(false);
+        }
+        let mut chars = self.path.chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), Some(':')) if letter.is_ascii_alphabetic() => {
+                Some(!letter.eq_ignore_ascii_case(&drive))
+            }
+            // Network share
+            (Some('\\'), Some('\\')) => Some(true),
+            // Volume mount point; a volume is not mounted inside itself
+            _ if self.path.starts_with("Volume{") => Some(true),
+            _ => None,
+        }
+    }
 }
 
 // ============================================================================
@@ -686,6 +941,48 @@ impl DataRun {
 
         (runs, total_clusters)
     }
+
+    /// Number of separate pieces `runs` (as decoded by `decode_runs`)
+    /// occupy on disk. A run starting where the previous one ended is part
+    /// of the same piece; sparse runs hold no clusters and are skipped.
+    pub fn fragment_count(runs: &[DataRun]) -> u32 {
+        let mut fragments = 0u32;
+        let mut next_lcn = None;
+        for run in runs.iter().filter(|run| !run.is_sparse) {
+            if next_lcn != Some(run.lcn_offset) {
+                fragments = fragments.saturating_add(1);
+            }
+            next_lcn = Some(run.lcn_offset + run.cluster_count as i64);
+        }
+        fragments
+    }
+}
+
+/// Where a file's main data stream is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub enum Storage {
+    /// Not known: directories, names from the USN journal, or a $DATA
+    /// attribute in an extension record that could not be read
+    #[default]
+    Unknown,
+    /// Inside the MFT record itself (small files); takes no clusters
+    Resident,
+    /// In clusters, split into `fragments` pieces (0 for an empty or
+    /// entirely sparse file)
+    NonResident { fragments: u32 },
+}
+
+impl Storage {
+    /// Short description for listings, empty when unknown
+    pub fn label(&self) -> String {
+        match self {
+            Storage::Unknown => String::new(),
+            Storage::Resident => "Resident".to_string(),
+            Storage::NonResident { fragments: 0 } => "Non-resident".to_string(),
+            Storage::NonResident { fragments: 1 } => "1 fragment".to_string(),
+            Storage::NonResident { fragments } => format!("{} fragments", fragments),
+        }
+    }
 }
 
 // ============================================================================
@@ -1034,3 +1331,73 @@ pub mod file_attributes {
     pub const NOT_CONTENT_INDEXED: u32 = 0x00002000;
     pub const ENCRYPTED: u32 = 0x00004000;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// FSCTL_GET_NTFS_VOLUME_DATA output with the given geometry and a
+    /// 1 MiB MFT
+    fn volume_data_bytes(bytes_per_sector: u32, bytes_per_cluster: u32, record_size: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 0x60];
+        data[0x28..0x2C].copy_from_slice(&bytes_per_sector.to_le_bytes());
+        data[0x2C..0x30].copy_from_slice(&bytes_per_cluster.to_le_bytes());
+        data[0x30..0x34].copy_from_slice(&record_size.to_le_bytes());
+        data[0x38..0x40].copy_from_slice(&(1u64 << 20).to_le_bytes());
+        data
+    }
+
+    fn rejected(bytes_per_sector: u32, bytes_per_cluster: u32, record_size: u32) -> bool {
+        let data = volume_data_bytes(bytes_per_sector, bytes_per_cluster, record_size);
+        matches!(NtfsVolumeData::from_bytes(&data), Err(EmFitError::VolumeDataError(_)))
+    }
+
+    #[test]
+    fn usual_geometry_is_accepted() {
+        let volume_data = NtfsVolumeData::from_bytes(&volume_data_bytes(512, 4096, 1024)).unwrap();
+        assert_eq!(volume_data.estimated_mft_records(), 1024);
+    }
+
+    #[test]
+    fn zero_sizes_are_rejected() {
+        assert!(rejected(0, 4096, 1024));
+        assert!(rejected(512, 0, 1024));
+        assert!(rejected(512, 4096, 0));
+    }
+
+    #[test]
+    fn sizes_that_are_not_powers_of_two_are_rejected() {
+        assert!(rejected(520, 4096, 1024));
+        assert!(rejected(512, 3000, 1024));
+        assert!(rejected(512, 4096, 1000));
+    }
+
+    #[test]
+    fn sizes_below_256_bytes_are_rejected() {
+        assert!(rejected(128, 4096, 1024));
+        assert!(rejected(512, 4096, 128));
+        // Also smaller than the sector
+        assert!(rejected(512, 128, 1024));
+    }
+
+    #[test]
+    fn cluster_smaller_than_a_sector_is_rejected() {
+        assert!(rejected(4096, 512, 1024));
+    }
+
+    #[test]
+    fn short_buffer_is_rejected() {
+        assert!(NtfsVolumeData::from_bytes(&[0u8; 0x50]).is_err());
+    }
+
+    #[test]
+    fn unknown_record_size_estimates_no_records() {
+        // Built directly, so `validate` never ran
+        let volume_data = NtfsVolumeData {
+            mft_valid_data_length: 1 << 20,
+            ..Default::default()
+        };
+        assert_eq!(volume_data.estimated_mft_records(), 0);
+        assert_eq!(volume_data.mft_byte_offset(), 0);
+    }
+}
@@ -9,6 +9,13 @@ use crate::ntfs::mft::FileEntry;
 use crate::ntfs::structs::*;
 use crate::ntfs::winapi::*;
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// Attempts per `FSCTL_ENUM_USN_DATA` call before enumeration gives up
+const ENUM_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry; doubled for each further attempt
+const ENUM_RETRY_DELAY: Duration = Duration::from_millis(50);
 
 // ============================================================================
 // USN Entry (lightweight version of FileEntry for initial scan)
@@ -51,6 +58,8 @@ pub struct UsnScanner {
     handle: SafeHandle,
     journal_data: Option<UsnJournalData>,
     buffer: Vec<u8>,
+    /// Why the last enumeration stopped before the end, if it did
+    interruption: Option<String>,
 }
 
 impl UsnScanner {
@@ -60,6 +69,7 @@ impl UsnScanner {
             handle,
             journal_data: None,
             buffer: vec![0u8; 64 * 1024], // 64KB buffer
+            interruption: None,
         }
     }
 
@@ -79,85 +89,140 @@ impl UsnScanner {
         self.journal_data.as_ref()
     }
 
+    /// Why the last `enumerate_all` stopped early, if it returned partial data
+    pub fn interruption(&self) -> Option<&str> {
+        self.interruption.as_deref()
+    }
+
+    /// Lazily iterate over the raw USN records of every file on the volume.
+    ///
+    /// Reads one `FSCTL_ENUM_USN_DATA` batch at a time into the scanner's
+    /// buffer, so memory use stays flat however large the volume is. Batches
+    /// are retried like `enumerate_all`; an error that persists is yielded
+    /// once and ends the iteration.
+    ///
+    /// ```no_run
+    /// use emfit::ntfs::{open_volume, UsnScanner};
+    ///
+    /// let mut scanner = UsnScanner::new(open_volume('C')?);
+    /// scanner.initialize()?;
+    /// for record in scanner.iter() {
+    ///     let record = record?;
+    ///     println!("{} {}", record.file_record_number(), record.file_name);
+    /// }
+    /// # Ok::<(), emfit::EmFitError>(())
+    /// ```
+    pub fn iter(&mut self) -> UsnRecords<'_> {
+        let (high_usn, error) = match self.journal_data.as_ref() {
+            Some(journal) => (journal.next_usn as i64, None),
+            None => (
+                0,
+                Some(EmFitError::UsnJournalError("Journal not initialized".to_string())),
+            ),
+        };
+
+        UsnRecords {
+            scanner: self,
+            start_frn: 0,
+            next_frn: 0,
+            high_usn,
+            offset: 0,
+            filled: 0,
+            error,
+            done: false,
+        }
+    }
+
     /// Enumerate all files using FSCTL_ENUM_USN_DATA
     ///
     /// This is the fastest way to enumerate all files on an NTFS volume.
     /// Returns entries via callback to avoid memory pressure.
+    ///
+    /// A failing batch is retried with backoff. If it still fails after some
+    /// entries were delivered, enumeration stops and returns what it has;
+    /// `interruption` then says why.
     pub fn enumerate_all<F>(&mut self, mut callback: F) -> Result<u64>
     where
         F: FnMut(UsnEntry),
     {
-        let journal = self
-            .journal_data
-            .as_ref()
-            .ok_or_else(|| EmFitError::UsnJournalError("Journal not initialized".to_string()))?;
-
-        let high_usn = journal.next_usn as i64;
-        let mut start_frn: u64 = 0;
         let mut count: u64 = 0;
-
-        loop {
-            let (next_frn, bytes_returned) =
-                enum_usn_data(&self.handle, start_frn, high_usn, &mut self.buffer)?;
-
-            if bytes_returned <= 8 {
-                break;
-            }
-
-            // Parse USN records from buffer (skip first 8 bytes which is next FRN)
-            let mut offset = 8;
-            // Minimum USN record is 60 bytes (V2) or 76 bytes (V3), but we check inside from_bytes
-            while offset + 8 < bytes_returned {
-                // First read record_length to know how big this record is
-                if offset + 4 > bytes_returned {
+        let mut failure = None;
+        self.interruption = None;
+
+        for record in self.iter() {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    failure = Some(e);
                     break;
                 }
-                let record_len = u32::from_le_bytes(
-                    self.buffer[offset..offset + 4].try_into().unwrap_or([0; 4])
-                ) as usize;
+            };
+
+            let entry = UsnEntry {
+                record_number: record.file_record_number(),
+                parent_record_number: record.parent_record_number(),
+                file_reference_number: record.file_reference_number, // Full FRN with sequence
+                name: record.file_name,
+                attributes: record.file_attributes,
+                is_directory: (record.file_attributes & file_attributes::DIRECTORY) != 0,
+            };
+
+            // Log USN entries for debugging (filtered by name pattern if set)
+            logging::log_usn_entry(
+                entry.record_number,
+                entry.parent_record_number,
+                entry.file_reference_number,
+                &entry.name,
+                entry.attributes,
+                entry.is_directory,
+            );
+
+            callback(entry);
+            count += 1;
+        }
 
-                // Sanity check record length
-                if record_len < 60 || record_len > 0x10000 || offset + record_len > bytes_returned {
-                    break;
-                }
+        match failure {
+            Some(e) if count > 0 => {
+                logging::warn("USN", &format!(
+                    "Enumeration stopped after {} entries: {}", count, e
+                ));
+                self.interruption = Some(e.to_string());
+            }
+            Some(e) => return Err(e),
+            None => {}
+        }
 
-                if let Some(record) = UsnRecord::from_bytes(&self.buffer[offset..offset + record_len]) {
-                    let entry = UsnEntry {
-                        record_number: record.file_record_number(),
-                        parent_record_number: record.parent_record_number(),
-                        file_reference_number: record.file_reference_number, // Full FRN with sequence
-                        name: record.file_name.clone(),
-                        attributes: record.file_attributes,
-                        is_directory: (record.file_attributes & file_attributes::DIRECTORY) != 0,
-                    };
-
-                    // Log USN entries for debugging (filtered by name pattern if set)
-                    logging::log_usn_entry(
-                        entry.record_number,
-                        entry.parent_record_number,
-                        entry.file_reference_number,
-                        &entry.name,
-                        entry.attributes,
-                        entry.is_directory,
-                    );
-
-                    callback(entry);
-                    count += 1;
+        Ok(count)
+    }
 
-                    offset += record_len;
-                } else {
-                    // Skip this record and try the next
-                    offset += record_len;
-                }
-            }
+    /// One `FSCTL_ENUM_USN_DATA` batch, retried with exponential backoff.
+    ///
+    /// The journal may wrap on a busy volume while we enumerate, so it is
+    /// re-queried before each retry and `high_usn` moved to its new end.
+    fn enum_with_retry(&mut self, start_frn: u64, high_usn: &mut i64) -> Result<(u64, usize)> {
+        let mut delay = ENUM_RETRY_DELAY;
+        let mut attempt = 1;
 
-            if next_frn == 0 || next_frn == start_frn {
-                break;
+        loop {
+            match enum_usn_data(&self.handle, start_frn, *high_usn, &mut self.buffer) {
+                Ok(batch) => return Ok(batch),
+                Err(e) if attempt < ENUM_ATTEMPTS => {
+                    logging::warn("USN", &format!(
+                        "Enumeration at FRN {} failed (attempt {}/{}): {}",
+                        start_frn, attempt, ENUM_ATTEMPTS, e
+                    ));
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+
+                    if let Ok(journal) = query_usn_journal(&self.handle) {
+                        *high_usn = journal.next_usn as i64;
+                        self.journal_data = Some(journal);
+                    }
+                }
+                Err(e) => return Err(e),
             }
-            start_frn = next_frn;
         }
-
-        Ok(count)
     }
 
     /// Enumerate to a HashMap for quick parent lookups
@@ -172,6 +237,86 @@ impl UsnScanner {
     }
 }
 
+// ============================================================================
+// USN Record Iterator
+// ============================================================================
+
+/// Lazy iterator over raw USN records, created by `UsnScanner::iter`
+pub struct UsnRecords<'a> {
+    scanner: &'a mut UsnScanner,
+    /// FRN the current batch was requested from
+    start_frn: u64,
+    /// FRN the next batch starts at, as returned with the current one
+    next_frn: u64,
+    high_usn: i64,
+    /// Next unread record in the scanner's buffer
+    offset: usize,
+    /// Valid bytes of the current batch (0 before the first one)
+    filled: usize,
+    /// Error to yield before finishing
+    error: Option<EmFitError>,
+    done: bool,
+}
+
+impl Iterator for UsnRecords<'_> {
+    type Item = Result<UsnRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            self.done = true;
+            return Some(Err(e));
+        }
+
+        while !self.done {
+            // Records left in the current batch (the first 8 bytes are the next FRN)
+            while self.offset + 8 < self.filled {
+                let buffer = &self.scanner.buffer;
+                let record_len = u32::from_le_bytes(
+                    buffer[self.offset..self.offset + 4].try_into().unwrap_or([0; 4])
+                ) as usize;
+
+                // Sanity check record length; a bad one ends the batch
+                if !(60..=0x10000).contains(&record_len)
+                    || self.offset + record_len > self.filled
+                {
+                    self.offset = self.filled;
+                    break;
+                }
+
+                let record = UsnRecord::from_bytes(&buffer[self.offset..self.offset + record_len]);
+                self.offset += record_len;
+                if let Some(record) = record {
+                    return Some(Ok(record));
+                }
+            }
+
+            // Advance to the next batch
+            if self.filled > 0 {
+                if self.next_frn == 0 || self.next_frn == self.start_frn {
+                    self.done = true;
+                    break;
+                }
+                self.start_frn = self.next_frn;
+            }
+
+            match self.scanner.enum_with_retry(self.start_frn, &mut self.high_usn) {
+                Ok((_, bytes_returned)) if bytes_returned <= 8 => self.done = true,
+                Ok((next_frn, bytes_returned)) => {
+                    self.next_frn = next_frn;
+                    self.filled = bytes_returned;
+                    self.offset = 8;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        None
+    }
+}
+
 // ============================================================================
 // USN Change Monitor
 // ============================================================================
@@ -0,0 +1,167 @@
+//! Volume Shadow Copy Snapshots
+//!
+//! A shadow copy freezes a volume at one instant, so a scan of a busy
+//! system drive sees every record as it was at that moment instead of a mix
+//! of before and after. Files locked by the OS are readable there too.
+//!
+//! Snapshots are created and deleted through the WMI `Win32_ShadowCopy`
+//! class (driven by PowerShell), which needs admin rights and the VSS
+//! service. The snapshot device is then opened read-only like any volume.
+
+use crate::error::{EmFitError, Result};
+use crate::logging;
+use std::io::Write;
+use std::os::windows::process::CommandExt;
+use std::process::{Command, Stdio};
+
+/// Process creation flag that keeps PowerShell from flashing a console
+/// window over the GUI
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// A shadow copy of one volume. Deleted again when dropped.
+#[derive(Debug)]
+pub struct ShadowCopy {
+    /// Drive letter the snapshot was taken of
+    drive_letter: char,
+    /// Snapshot GUID in braces, as WMI reports it
+    id: String,
+    /// Device path, e.g. `\\?\GLOBALROOT\Device\HarddiskVolumeShadowCopy3`
+    device_path: String,
+}
+
+impl ShadowCopy {
+    /// Snapshot `drive_letter`. Takes a few seconds while VSS writers flush
+    /// their data to disk.
+    pub fn create(drive_letter: char) -> Result<Self> {
+        let drive_letter = drive_letter.to_ascii_uppercase();
+        logging::info("VSS", &format!("Creating shadow copy of {}:", drive_letter));
+
+        // Once Create succeeded the snapshot exists, so a later failure
+        // deletes it again instead of leaking it
+        let script = format!(
+            "$r = Invoke-CimMethod -ClassName Win32_ShadowCopy -MethodName Create \
+             -Arguments @{{ Volume = '{}:\\'; Context = 'ClientAccessible' }}\n\
+             if ($r.ReturnValue -ne 0) {{ throw \"Win32_ShadowCopy.Create returned $($r.ReturnValue)\" }}\n\
+             try {{\n\
+             $s = Get-CimInstance Win32_ShadowCopy -Filter \"ID='$($r.ShadowID)'\"\n\
+             if (-not $s) {{ throw \"Shadow copy $($r.ShadowID) not found\" }}\n\
+             $s.ID\n\
+             $s.DeviceObject\n\
+             }} catch {{\n\
+             Get-CimInstance Win32_ShadowCopy -Filter \"ID='$($r.ShadowID)'\" | Remove-CimInstance\n\
+             throw\n\
+             }}\n",
+            drive_letter
+        );
+        let output = run_powershell(&script)?;
+
+        let mut lines = output.lines().map(str::trim).filter(|line| !line.is_empty());
+        let (id, device) = (lines.next(), lines.next());
+        match (id, device) {
+            (Some(id), Some(device)) if device.starts_with("\\\\?\\GLOBALROOT\\") => {
+                let snapshot = Self {
+                    drive_letter,
+                    id: id.to_string(),
+                    device_path: device.to_string(),
+                };
+                logging::info("VSS", &format!(
+                    "Shadow copy {} of {}: at {}",
+                    snapshot.id, drive_letter, snapshot.device_path
+                ));
+                Ok(snapshot)
+            }
+            _ => {
+                // Made but unusable: dropping it releases it
+                if let Some(id) = id {
+                    drop(Self {
+                        drive_letter,
+                        id: id.to_string(),
+                        device_path: String::new(),
+                    });
+                }
+                Err(EmFitError::ShadowCopyError(format!(
+                    "No usable snapshot of {}: was created", drive_letter
+                )))
+            }
+        }
+    }
+
+    /// Drive letter the snapshot was taken of
+    pub fn drive_letter(&self) -> char {
+        self.drive_letter
+    }
+
+    /// Snapshot GUID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Device path to open in place of `\\.\C:`
+    pub fn device_path(&self) -> &str {
+        &self.device_path
+    }
+}
+
+impl Drop for ShadowCopy {
+    fn drop(&mut self) {
+        let script = format!(
+            "Get-CimInstance Win32_ShadowCopy -Filter \"ID='{}'\" | Remove-CimInstance\n",
+            self.id
+        );
+        match run_powershell(&script) {
+            Ok(_) => logging::info("VSS", &format!("Released shadow copy {}", self.id)),
+            Err(e) => logging::warn("VSS", &format!(
+                "Shadow copy {} of {}: not released ({}); delete it with vssadmin",
+                self.id, self.drive_letter, e
+            )),
+        }
+    }
+}
+
+/// Run `script` in a hidden PowerShell, fed through stdin so no quoting
+/// survives the command line. Returns stdout; an error when the script
+/// threw, judged by the exit status.
+///
+/// The script runs as one `try` block (stdin input is otherwise executed
+/// line by line, carrying on past errors) with progress output off, since
+/// PowerShell writes progress records to a redirected stderr.
+fn run_powershell(script: &str) -> Result<String> {
+    let script = format!(
+        "$ErrorActionPreference = 'Stop'\n\
+         $ProgressPreference = 'SilentlyContinue'\n\
+         try {{\n{}}} catch {{\n\
+         [Console]::Error.WriteLine($_.ToString())\n\
+         exit 1\n\
+         }}\n\n\
+         exit 0\n",
+        script
+    );
+
+    let mut child = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| EmFitError::ShadowCopyError(format!("Failed to start PowerShell: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Skip serialized records (`#< CLIXML`) around the message
+        let message = stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('<'))
+            .unwrap_or("");
+        return Err(EmFitError::ShadowCopyError(format!(
+            "PowerShell failed ({}): {}", output.status, message
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
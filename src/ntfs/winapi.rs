@@ -38,6 +38,7 @@ pub const FILE_SHARE_DELETE: u32 = 0x00000004;
 pub const OPEN_EXISTING: u32 = 3;
 pub const FILE_FLAG_NO_BUFFERING: u32 = 0x20000000;
 pub const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x02000000;
+pub const FILE_FLAG_OPEN_REPARSE_POINT: u32 = 0x00200000;
 
 pub const INVALID_HANDLE_VALUE: isize = -1;
 
@@ -83,6 +84,21 @@ impl Drop for SafeHandle {
     }
 }
 
+/// Convert a `windows` error to an `io::Error` carrying the Win32 code.
+///
+/// `CreateFileW` and friends report `HRESULT_FROM_WIN32(code)`; passing
+/// the HRESULT on as a raw OS error would leave `kind()` at `Other`, so a
+/// denied open would not read as `PermissionDenied`.
+fn io_error(e: windows::core::Error) -> std::io::Error {
+    const FACILITY_WIN32: u32 = 7;
+    let hresult = e.code().0 as u32;
+    if hresult >> 16 == 0x8000 | FACILITY_WIN32 {
+        std::io::Error::from_raw_os_error((hresult & 0xFFFF) as i32)
+    } else {
+        std::io::Error::from_raw_os_error(hresult as i32)
+    }
+}
+
 // ============================================================================
 // Volume Operations
 // ============================================================================
@@ -121,7 +137,7 @@ pub fn open_volume_path(path: &str) -> Result<SafeHandle> {
     match handle {
         Ok(h) => SafeHandle::new(h.0 as isize)
             .ok_or_else(|| EmFitError::VolumeOpenError(path.to_string(), std::io::Error::last_os_error())),
-        Err(e) => Err(EmFitError::VolumeOpenError(path.to_string(), std::io::Error::from_raw_os_error(e.code().0 as i32))),
+        Err(e) => Err(EmFitError::VolumeOpenError(path.to_string(), io_error(e))),
     }
 }
 
@@ -153,10 +169,67 @@ pub fn open_file_read(path: &str) -> Result<SafeHandle> {
     match handle {
         Ok(h) => SafeHandle::new(h.0 as isize)
             .ok_or_else(|| EmFitError::IoError(std::io::Error::last_os_error())),
-        Err(e) => Err(EmFitError::IoError(std::io::Error::from_raw_os_error(e.code().0 as i32))),
+        Err(e) => Err(EmFitError::IoError(io_error(e))),
     }
 }
 
+/// MFT record number of a file or directory, from the file index Windows
+/// reports for it (the lower 48 bits of its file reference number), and
+/// the serial number of the volume holding it. A symlink, junction or
+/// mount point at `path` is not followed: the record is the link's own.
+pub fn get_record_number(path: &str) -> Result<(u64, u32)> {
+    use windows::Win32::Storage::FileSystem::{
+        CreateFileW, GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+        FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_MODE,
+    };
+    use windows::Win32::Foundation::HANDLE;
+    use windows::core::PCWSTR;
+
+    let wide_path: Vec<u16> = OsStr::new(path)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    // Attributes only, so files opened exclusively elsewhere still resolve
+    let handle = unsafe {
+        CreateFileW(
+            PCWSTR::from_raw(wide_path.as_ptr()),
+            0x80, // FILE_READ_ATTRIBUTES
+            FILE_SHARE_MODE(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE),
+            None,
+            windows::Win32::Storage::FileSystem::OPEN_EXISTING,
+            // Backup semantics are required for directories
+            FILE_FLAGS_AND_ATTRIBUTES(FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_OPEN_REPARSE_POINT),
+            Some(HANDLE::default()),
+        )
+    };
+
+    let handle = match handle {
+        Ok(h) => SafeHandle::new(h.0 as isize)
+            .ok_or_else(|| EmFitError::IoError(std::io::Error::last_os_error()))?,
+        Err(e) => return Err(EmFitError::IoError(io_error(e))),
+    };
+
+    let mut info = BY_HANDLE_FILE_INFORMATION::default();
+    let result = unsafe {
+        GetFileInformationByHandle(
+            HANDLE(handle.as_raw() as *mut std::ffi::c_void),
+            &mut info,
+        )
+    };
+
+    if result.is_err() {
+        return Err(EmFitError::WindowsError(format!(
+            "GetFileInformationByHandle failed for {}: {}",
+            path,
+            std::io::Error::last_os_error()
+        )));
+    }
+
+    let frn = ((info.nFileIndexHigh as u64) << 32) | (info.nFileIndexLow as u64);
+    Ok((frn & 0x0000_FFFF_FFFF_FFFF, info.dwVolumeSerialNumber))
+}
+
 /// Open a volume root directory for use with OpenFileById
 ///
 /// OpenFileById requires a handle to any file/directory on the volume,
@@ -190,15 +263,47 @@ pub fn open_volume_for_file_id(drive_letter: char) -> Result<SafeHandle> {
     match handle {
         Ok(h) => SafeHandle::new(h.0 as isize)
             .ok_or_else(|| EmFitError::VolumeOpenError(path, std::io::Error::last_os_error())),
-        Err(e) => Err(EmFitError::VolumeOpenError(path, std::io::Error::from_raw_os_error(e.code().0 as i32))),
+        Err(e) => Err(EmFitError::VolumeOpenError(path, io_error(e))),
     }
 }
 
+/// Name of the filesystem on a drive ("NTFS", "ReFS", "exFAT", ...)
+pub fn get_filesystem_name(drive_letter: char) -> Result<String> {
+    use windows::Win32::Storage::FileSystem::GetVolumeInformationW;
+    use windows::core::PCWSTR;
+
+    let root = format!("{}:\\", drive_letter);
+    let wide_root: Vec<u16> = OsStr::new(&root)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut name = [0u16; 261]; // MAX_PATH + 1
+    unsafe {
+        GetVolumeInformationW(
+            PCWSTR::from_raw(wide_root.as_ptr()),
+            None,
+            None,
+            None,
+            None,
+            Some(&mut name),
+        )
+    }
+    .map_err(|e| EmFitError::WindowsError(format!("GetVolumeInformationW({}) failed: {}", root, e)))?;
+
+    let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    Ok(String::from_utf16_lossy(&name[..len]))
+}
+
 // ============================================================================
 // IOCTL Operations
 // ============================================================================
 
 /// Send a DeviceIoControl request
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "trace", skip_all, fields(control_code = %format_args!("0x{:08X}", control_code)), err)
+)]
 pub fn device_io_control(
     handle: &SafeHandle,
     control_code: u32,
@@ -244,9 +349,7 @@ pub fn get_ntfs_volume_data(handle: &SafeHandle) -> Result<NtfsVolumeData> {
     let mut buffer = [0u8; 0x60];
     device_io_control(handle, FSCTL_GET_NTFS_VOLUME_DATA, None, &mut buffer)?;
 
-    NtfsVolumeData::from_bytes(&buffer).ok_or_else(|| {
-        EmFitError::VolumeDataError("Failed to parse NTFS volume data".to_string())
-    })
+    NtfsVolumeData::from_bytes(&buffer)
 }
 
 /// Fetch a single MFT record using FSCTL_GET_NTFS_FILE_RECORD
@@ -454,6 +557,7 @@ pub struct MftEnumDataV1 {
 
 /// Enumerate USN data (all files on volume)
 /// Uses V1 structure for Windows 8+ compatibility with USN record version 2 and 3
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(handle, buffer), err))]
 pub fn enum_usn_data(
     handle: &SafeHandle,
     start_frn: u64,
@@ -810,3 +914,25 @@ pub fn batch_get_file_metadata(
 
     results
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows::core::HRESULT;
+
+    #[test]
+    fn denied_open_reports_access_denied() {
+        let denied = windows::core::Error::from_hresult(HRESULT::from_win32(5)); // ERROR_ACCESS_DENIED
+        let error = EmFitError::VolumeOpenError(r"\\.\C:".to_string(), io_error(denied));
+        assert_eq!(error.kind(), crate::error::ErrorKind::AccessDenied);
+
+        let raw = EmFitError::IoError(std::io::Error::from_raw_os_error(5));
+        assert_eq!(raw.kind(), crate::error::ErrorKind::AccessDenied);
+    }
+
+    #[test]
+    fn non_win32_hresults_pass_through() {
+        let unexpected = windows::core::Error::from_hresult(HRESULT(0x8000_FFFFu32 as i32)); // E_UNEXPECTED
+        assert_eq!(io_error(unexpected).raw_os_error(), Some(0x8000_FFFFu32 as i32));
+    }
+}
@@ -0,0 +1,262 @@
+//! Treemap Palettes
+//!
+//! Colours for the treemap in both UIs, as plain RGB so the TUI and GUI
+//! draw from the same tables. Palettes sit in a small registry (`ALL`) and
+//! are looked up by name from the config or command line; adding one means
+//! adding a const here and listing it in `ALL`.
+
+use crate::file_kind::FileKind;
+
+/// Red, green, blue
+pub type Rgb = (u8, u8, u8);
+
+/// One complete set of treemap colours
+#[derive(Debug)]
+pub struct TreemapPalette {
+    /// Name used by `--palette` and `AppConfig::treemap_palette`
+    pub name: &'static str,
+    /// Menu label
+    pub label: &'static str,
+    /// Directory container borders, cycled by depth
+    pub depth_borders: [Rgb; 7],
+    /// Directory container interiors, cycled by depth
+    pub depth_backgrounds: [Rgb; 7],
+    /// Leaves for directories too small to show their children
+    pub folders: [Rgb; 5],
+    /// Leaf colour for a lowercase extension (no dot)
+    pub file: fn(&str) -> Rgb,
+    /// Leaf and title text
+    pub text: Rgb,
+    /// Leaf text for system files
+    pub system_text: Rgb,
+    /// Background of the selected leaf or container title
+    pub selection_bg: Rgb,
+    pub selection_fg: Rgb,
+    /// Outline around the selected rectangle (GUI)
+    pub selection_outline: Rgb,
+}
+
+/// The original palette: extension colours with reds and greens
+pub const DEFAULT: TreemapPalette = TreemapPalette {
+    name: "default",
+    label: "Default",
+    depth_borders: [
+        (0, 190, 230),   // cyan
+        (90, 200, 70),   // green
+        (230, 190, 40),  // gold
+        (210, 90, 200),  // magenta
+        (70, 140, 240),  // blue
+        (230, 130, 50),  // orange
+        (130, 210, 180), // teal
+    ],
+    depth_backgrounds: [
+        (8, 22, 28),
+        (12, 24, 10),
+        (26, 22, 8),
+        (24, 12, 24),
+        (10, 16, 30),
+        (26, 16, 8),
+        (12, 24, 20),
+    ],
+    folders: [
+        (40, 105, 135),
+        (50, 115, 120),
+        (60, 95, 145),
+        (45, 125, 110),
+        (55, 108, 128),
+    ],
+    file: default_file_color,
+    text: (235, 235, 235),
+    // Amber so system files stand out from user data
+    system_text: (255, 200, 80),
+    // Classic CGA colour 1 – the eye-searing DOS blue
+    selection_bg: (0, 0, 170),
+    selection_fg: (255, 255, 255),
+    selection_outline: (255, 255, 100),
+};
+
+/// Blue/orange palette after Okabe and Ito, with no red/green pairs. Kinds
+/// differ in lightness as well as hue, and selection is a white block.
+pub const COLOR_BLIND: TreemapPalette = TreemapPalette {
+    name: "color-blind",
+    label: "Color-blind Friendly",
+    depth_borders: [
+        (86, 180, 233),  // sky blue
+        (230, 159, 0),   // orange
+        (0, 114, 178),   // blue
+        (240, 228, 66),  // yellow
+        (204, 121, 167), // reddish purple
+        (213, 94, 0),    // vermillion
+        (170, 170, 170), // grey
+    ],
+    depth_backgrounds: [
+        (8, 20, 30),
+        (28, 20, 4),
+        (4, 14, 26),
+        (26, 26, 8),
+        (24, 14, 20),
+        (26, 12, 4),
+        (18, 18, 18),
+    ],
+    folders: [
+        (0, 90, 140),
+        (20, 100, 150),
+        (0, 80, 125),
+        (30, 110, 160),
+        (10, 95, 145),
+    ],
+    file: color_blind_file_color,
+    text: (240, 240, 240),
+    system_text: (255, 230, 140),
+    selection_bg: (255, 255, 255),
+    selection_fg: (0, 0, 0),
+    selection_outline: (255, 255, 255),
+};
+
+/// Every palette, in menu order
+pub const ALL: [&TreemapPalette; 2] = [&DEFAULT, &COLOR_BLIND];
+
+impl TreemapPalette {
+    /// Look up a palette by `name`
+    pub fn by_name(name: &str) -> Option<&'static TreemapPalette> {
+        ALL.iter().copied().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Names of all palettes, for help text and argument parsing
+    pub fn names() -> Vec<&'static str> {
+        ALL.iter().map(|p| p.name).collect()
+    }
+
+    /// The palette after this one in `ALL`, wrapping around
+    pub fn next(&self) -> &'static TreemapPalette {
+        let pos = ALL.iter().position(|p| p.name == self.name).unwrap_or(0);
+        ALL[(pos + 1) % ALL.len()]
+    }
+
+    /// Border colour for a directory container at `depth`
+    pub fn depth_border(&self, depth: usize) -> Rgb {
+        self.depth_borders[depth % self.depth_borders.len()]
+    }
+
+    /// Interior colour for a directory container at `depth`
+    pub fn depth_bg(&self, depth: usize) -> Rgb {
+        self.depth_backgrounds[depth % self.depth_backgrounds.len()]
+    }
+
+    /// Colour for a leaf rectangle (a file, or a directory too small to
+    /// show its children); `index` varies directory leaves
+    pub fn leaf(&self, name: &str, is_directory: bool, index: usize) -> Rgb {
+        if is_directory {
+            return self.folders[index % self.folders.len()];
+        }
+        let ext = name.rsplit('.').next().unwrap_or("").to_lowercase();
+        (self.file)(&ext)
+    }
+}
+
+fn default_file_color(ext: &str) -> Rgb {
+    match ext {
+        // ── executables / system ────────────────────────────────────────
+        "exe" | "com" | "scr"                        => (200, 55, 55),
+        "dll" | "sys" | "drv" | "ocx"                => (175, 65, 65),
+        "msi" | "bat" | "cmd" | "ps1"                => (185, 80, 50),
+
+        // ── archives ────────────────────────────────────────────────────
+        "zip" | "rar" | "7z" | "gz" | "tar"
+        | "xz" | "bz2" | "cab" | "iso"              => (200, 175, 35),
+
+        // ── video ───────────────────────────────────────────────────────
+        "mp4" | "mkv" | "avi" | "mov" | "wmv"
+        | "flv" | "webm" | "m4v" | "ts"             => (160, 45, 195),
+
+        // ── audio ───────────────────────────────────────────────────────
+        "mp3" | "wav" | "flac" | "ogg"
+        | "aac" | "wma" | "m4a" | "opus"            => (35, 175, 135),
+
+        // ── images ──────────────────────────────────────────────────────
+        "jpg" | "jpeg" | "png" | "gif" | "bmp"
+        | "tiff" | "webp" | "ico" | "svg" | "psd"
+        | "raw" | "cr2" | "nef" | "dng"             => (195, 125, 35),
+
+        // ── documents ───────────────────────────────────────────────────
+        "pdf"                                        => (200, 50, 50),
+        "doc" | "docx" | "odt" | "rtf"              => (55, 130, 200),
+        "xls" | "xlsx" | "ods" | "csv"              => (45, 165, 65),
+        "ppt" | "pptx" | "odp"                      => (200, 105, 35),
+
+        // ── text / config ───────────────────────────────────────────────
+        "txt" | "log" | "md" | "cfg" | "ini"
+        | "conf" | "yml" | "yaml" | "toml"          => (120, 120, 120),
+
+        // ── code ────────────────────────────────────────────────────────
+        "rs" | "go" | "c" | "cpp" | "h" | "hpp"
+        | "cs"                                       => (75, 150, 220),
+        "py" | "pyw"                                 => (55, 140, 185),
+        "js" | "jsx" | "tsx"                         => (215, 195, 45),
+        "java" | "kt" | "scala"                     => (170, 100, 55),
+        "html" | "htm" | "css" | "scss"              => (215, 75, 45),
+        "json" | "xml" | "sql"                       => (140, 160, 55),
+
+        // ── game data ───────────────────────────────────────────────────
+        "pak" | "rpf" | "bdt" | "pack" | "assets"
+        | "resource" | "forge" | "wad"               => (185, 75, 165),
+
+        // ── virtual disks / dumps ───────────────────────────────────────
+        "vdi" | "vmdk" | "vhd" | "vhdx" | "qcow2"
+        | "img" | "bin" | "001"                      => (100, 65, 165),
+
+        // ── databases ───────────────────────────────────────────────────
+        "db" | "sqlite" | "mdf" | "ldf" | "bak"     => (135, 115, 45),
+
+        // ── fonts ───────────────────────────────────────────────────────
+        "ttf" | "otf" | "woff" | "woff2"            => (160, 140, 100),
+
+        // ── streaming / media DB ────────────────────────────────────────
+        "stream" | "streamdb"                        => (155, 55, 125),
+
+        // ── MFT dumps ───────────────────────────────────────────────────
+        "mft"                                        => (200, 145, 35),
+
+        // ── fallback: hash extension to a hue ───────────────────────────
+        _ => {
+            let h = ext.bytes().fold(0u32, |a, b| a.wrapping_mul(31).wrapping_add(b as u32));
+            hsl_to_rgb((h % 360) as f64, 0.45, 0.38)
+        }
+    }
+}
+
+/// One colour per `FileKind`; per-extension hues would bring back
+/// pairs that only differ in red/green
+fn color_blind_file_color(ext: &str) -> Rgb {
+    match FileKind::from_extension(ext) {
+        FileKind::Folder => COLOR_BLIND.folders[0],
+        FileKind::Image => (170, 90, 135),     // reddish purple
+        FileKind::Video => (0, 100, 160),      // blue
+        FileKind::Audio => (60, 150, 200),     // sky blue
+        FileKind::Document => (125, 125, 125), // mid grey
+        FileKind::Archive => (205, 135, 0),    // orange
+        FileKind::Code => (165, 150, 30),      // dark yellow
+        FileKind::Executable => (185, 75, 0),  // vermillion
+        FileKind::DiskImage => (80, 80, 130),  // slate
+        FileKind::Other => (70, 70, 70),       // dark grey
+    }
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> Rgb {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match h as u32 {
+        0..=59   => (c, x, 0.0),
+        60..=119 => (x, c, 0.0),
+        120..=179=> (0.0, c, x),
+        180..=239=> (0.0, x, c),
+        240..=299=> (x, 0.0, c),
+        _        => (c, 0.0, x),
+    };
+    (
+        ((r + m) * 255.0) as u8,
+        ((g + m) * 255.0) as u8,
+        ((b + m) * 255.0) as u8,
+    )
+}
@@ -0,0 +1,312 @@
+//! HTML Report Export
+//!
+//! Renders a scanned volume as a single self-contained HTML page: largest
+//! directories, largest files, a per-extension breakdown and an inline SVG
+//! treemap. No external assets, so the file can be mailed or attached as-is.
+
+use crate::file_tree::{FileTree, SearchResult};
+use crate::logging;
+use crate::palette::{Rgb, DEFAULT};
+use crate::treemap::TreemapState;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// Treemap canvas size in pixels
+const TREEMAP_WIDTH: f64 = 1200.0;
+const TREEMAP_HEIGHT: f64 = 640.0;
+
+/// Upper bound on treemap rectangles, to keep the page size reasonable
+const MAX_TREEMAP_RECTS: usize = 5000;
+
+// ============================================================================
+// Report Options
+// ============================================================================
+
+/// What to include in a report
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    /// Number of directories and files in the "largest" tables
+    pub top_count: usize,
+    /// Number of extensions in the breakdown table
+    pub top_extensions: usize,
+    /// Include the SVG treemap
+    pub include_treemap: bool,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            top_count: 25,
+            top_extensions: 20,
+            include_treemap: true,
+        }
+    }
+}
+
+// ============================================================================
+// Extension Breakdown
+// ============================================================================
+
+/// Total size and count of files sharing an extension
+#[derive(Debug, Clone)]
+pub struct ExtensionStats {
+    /// Lowercase extension without the dot ("" for none)
+    pub extension: String,
+    pub file_count: u64,
+    pub total_size: u64,
+}
+
+/// Group all live files in the tree by extension, largest total size first
+pub fn extension_breakdown(tree: &FileTree) -> Vec<ExtensionStats> {
+    let mut by_ext: HashMap<String, (u64, u64)> = HashMap::new();
+
+    for entry in tree.iter_files() {
+        let node = entry.value();
+        if node.is_deleted {
+            continue;
+        }
+        let ext = match node.name.rfind('.') {
+            Some(pos) if pos > 0 => node.name[pos + 1..].to_lowercase(),
+            _ => String::new(),
+        };
+        let stats = by_ext.entry(ext).or_default();
+        stats.0 += 1;
+        stats.1 += node.file_size;
+    }
+
+    let mut breakdown: Vec<ExtensionStats> = by_ext
+        .into_iter()
+        .map(|(extension, (file_count, total_size))| ExtensionStats {
+            extension,
+            file_count,
+            total_size,
+        })
+        .collect();
+    breakdown.sort_by_key(|e| std::cmp::Reverse(e.total_size));
+    breakdown
+}
+
+// ============================================================================
+// HTML Rendering
+// ============================================================================
+
+/// Render a complete HTML report for a tree
+pub fn render_html(tree: &FileTree, options: &ReportOptions) -> String {
+    let drive = format!("{}:", tree.drive_letter);
+    let generated = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+
+    let mut html = String::with_capacity(256 * 1024);
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>EmFit report for {drive}</title>\n<style>{css}</style>\n</head>\n<body>\n\
+         <h1>EmFit report for {drive}</h1>\n<p class=\"meta\">Generated {generated} by EmFit v{version}</p>\n",
+        drive = escape(&drive),
+        css = STYLE,
+        generated = generated,
+        version = crate::VERSION,
+    );
+
+    // Summary
+    let stats = &tree.stats;
+    let _ = write!(
+        html,
+        "<table class=\"summary\">\n\
+         <tr><th>Total size</th><td>{}</td></tr>\n\
+         <tr><th>Allocated</th><td>{}</td></tr>\n\
+         <tr><th>Files</th><td>{}</td></tr>\n\
+         <tr><th>Directories</th><td>{}</td></tr>\n\
+         </table>\n",
+        crate::format_size(stats.total_size),
+        crate::format_size(stats.total_allocated),
+        stats.total_files,
+        stats.total_directories,
+    );
+
+    if options.include_treemap {
+        html.push_str("<h2>Treemap</h2>\n");
+        html.push_str(&render_treemap_svg(tree));
+    }
+
+    let total = stats.total_size.max(1);
+
+    html.push_str("<h2>Largest directories</h2>\n");
+    push_results_table(&mut html, &tree.largest_directories(options.top_count), total);
+
+    html.push_str("<h2>Largest files</h2>\n");
+    push_results_table(&mut html, &tree.largest_files(options.top_count), total);
+
+    html.push_str("<h2>Extensions</h2>\n");
+    html.push_str(
+        "<table>\n<tr><th>#</th><th>Extension</th><th>Files</th><th>Size</th><th>Share</th></tr>\n",
+    );
+    for (i, ext) in extension_breakdown(tree)
+        .iter()
+        .take(options.top_extensions)
+        .enumerate()
+    {
+        let label = if ext.extension.is_empty() {
+            "(none)".to_string()
+        } else {
+            format!(".{}", ext.extension)
+        };
+        let swatch = css_color(DEFAULT.leaf(&format!("x.{}", ext.extension), false, 0));
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td><span class=\"swatch\" style=\"background:{}\"></span>{}</td>\
+             <td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>",
+            i + 1,
+            swatch,
+            escape(&label),
+            ext.file_count,
+            crate::format_size(ext.total_size),
+            share_bar(ext.total_size, total),
+        );
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+
+    html
+}
+
+/// Render the report and write it to `path`
+pub fn write_html(
+    tree: &FileTree,
+    options: &ReportOptions,
+    path: impl AsRef<std::path::Path>,
+) -> crate::Result<()> {
+    let html = render_html(tree, options);
+    std::fs::write(path.as_ref(), &html)?;
+
+    logging::info("REPORT", &format!(
+        "Wrote report for {}: ({} bytes) to {}",
+        tree.drive_letter,
+        html.len(),
+        path.as_ref().display()
+    ));
+    Ok(())
+}
+
+/// Append a table of search results (path, size, share of volume)
+fn push_results_table(html: &mut String, results: &[SearchResult], total: u64) {
+    html.push_str("<table>\n<tr><th>#</th><th>Path</th><th>Size</th><th>Share</th></tr>\n");
+    for (i, result) in results.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"path\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>",
+            i + 1,
+            escape(&result.path),
+            crate::format_size(result.file_size),
+            share_bar(result.file_size, total),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+/// Inline percentage bar
+fn share_bar(size: u64, total: u64) -> String {
+    let pct = size as f64 * 100.0 / total as f64;
+    format!(
+        "<div class=\"bar\"><div style=\"width:{:.1}%\"></div></div> {:.1}%",
+        pct.min(100.0),
+        pct
+    )
+}
+
+/// Lay out the tree as the GUI treemap does and emit it as SVG
+fn render_treemap_svg(tree: &FileTree) -> String {
+    let mut state = TreemapState::new();
+    state.set_canvas_size(TREEMAP_WIDTH as f32, TREEMAP_HEIGHT as f32);
+    match tree.root() {
+        Some(root) => state.build_from_node(tree, &root.key()),
+        None => return String::new(),
+    }
+
+    let mut svg = String::with_capacity(state.rects.len() * 160);
+    let _ = writeln!(
+        svg,
+        "<svg class=\"treemap\" viewBox=\"0 0 {w} {h}\" width=\"{w}\" height=\"{h}\" \
+         xmlns=\"http://www.w3.org/2000/svg\" font-family=\"Segoe UI, sans-serif\" font-size=\"11\">",
+        w = TREEMAP_WIDTH,
+        h = TREEMAP_HEIGHT,
+    );
+
+    for (i, rect) in state.rects.iter().take(MAX_TREEMAP_RECTS).enumerate() {
+        let x = rect.x * TREEMAP_WIDTH;
+        let y = rect.y * TREEMAP_HEIGHT;
+        let w = rect.w * TREEMAP_WIDTH;
+        let h = rect.h * TREEMAP_HEIGHT;
+        if w < 1.0 || h < 1.0 {
+            continue;
+        }
+
+        let (fill, stroke) = if rect.children_rendered {
+            (DEFAULT.depth_bg(rect.depth), DEFAULT.depth_border(rect.depth))
+        } else {
+            (DEFAULT.leaf(&rect.name, rect.is_directory, i), (20, 20, 20))
+        };
+
+        let _ = write!(
+            svg,
+            "<g><title>{} ({})</title><rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" \
+             fill=\"{}\" stroke=\"{}\" stroke-width=\"1\"/>",
+            escape(&rect.name),
+            crate::format_size(rect.size),
+            x, y, w, h,
+            css_color(fill),
+            css_color(stroke),
+        );
+
+        // Label only if it has room (~6.5px per character at 11px)
+        if w >= 40.0 && h >= 14.0 {
+            let max_chars = ((w - 6.0) / 6.5) as usize;
+            let label: String = rect.name.chars().take(max_chars).collect();
+            let _ = write!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"#f0f0f0\">{}</text>",
+                x + 3.0,
+                y + 12.0,
+                escape(&label),
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// `#rrggbb` for a palette colour
+fn css_color((r, g, b): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Escape text for HTML/SVG content and attributes
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+const STYLE: &str = "\
+body{font-family:'Segoe UI',sans-serif;background:#1e1e1e;color:#ddd;margin:24px}\
+h1{margin-bottom:0}h2{margin-top:32px;border-bottom:1px solid #444}\
+.meta{color:#888;margin-top:4px}\
+table{border-collapse:collapse;margin-top:8px}\
+th,td{padding:3px 10px;text-align:left;border-bottom:1px solid #333}\
+th{background:#005f87;color:#fff}\
+.summary th{background:none;color:#aaa}\
+td.num{text-align:right;font-variant-numeric:tabular-nums}\
+td.path{font-family:Consolas,monospace;word-break:break-all}\
+.bar{display:inline-block;width:120px;height:10px;background:#333;vertical-align:middle}\
+.bar div{height:100%;background:#e0b030}\
+.swatch{display:inline-block;width:10px;height:10px;margin-right:6px}\
+svg.treemap{background:#111;max-width:100%;height:auto}";
@@ -2,20 +2,55 @@
 //!
 //! Orchestrates the scanning process, combining USN enumeration
 //! with MFT parsing for complete and accurate results.
-//! Supports direct physical drive reading (bypasses NTFS driver) for maximum reliability.
+//! Supports direct physical drive reading (bypasses NTFS driver) for maximum reliability,
+//! and offline scans of raw disk images through the same MFT parser.
+//!
+//! # USN / MFT reconciliation
+//!
+//! The two sources are read at different moments, so on a busy volume they
+//! can disagree. The MFT is the ground truth for existence, size and
+//! timestamps; USN enumeration only provides a fast first pass of names and
+//! parents.
+//!
+//! - In MFT but not USN: created after enumeration. Added from the MFT.
+//! - In USN but not a valid in-use MFT record: deleted or reused in between.
+//!   Dropped from the tree and listed in `ScanReport::transient`, not counted
+//!   as orphans or anomalies.
+//! - In USN, MFT record unreadable, failing fixup verification or failing
+//!   to parse: kept as enumerated, since nothing contradicts it. The latter
+//!   two are still listed in `ScanReport::fixup_failures` and
+//!   `ScanReport::parse_failures`.
+//! - In USN, past the MFT's valid data length as read at the start: the MFT
+//!   grew mid-scan. Kept as enumerated and listed in
+//!   `ScanReport::past_valid_data`; such records are never read.
+//!
+//! A volume with the dirty flag set (chkdsk pending) still scans, resumable
+//! or not; `ScanReport::volume_dirty` flags it so the result isn't trusted
+//! blindly.
 
 use crate::error::{Result, EmFitError};
-use crate::file_tree::{FileTree, TreeBuilder, TreeNode};
+use crate::file_tree::{FileTree, TreeBuilder, TreeNode, TreeStats, UsnCursor};
 use crate::logging;
+use crate::search_index::SearchIndex;
 use crate::ntfs::{
-    open_volume, MftParser, MftRecordFetcher, NtfsVolumeData,
-    UsnMonitor, UsnScanner, VolumeIO, open_physical_drive_for_volume,
+    open_volume, ChangeEvent, FileEntry, ImageReader, MftParser, MftRecordFetcher,
+    NtfsVolumeData, SafeHandle, ShadowCopy, UsnMonitor, UsnScanner, VolumeIO, VolumeInfo,
+    open_image, open_physical_drive_for_volume,
+};
+use crate::ntfs::winapi::{
+    get_filesystem_name, get_ntfs_volume_data, open_volume_path, query_usn_journal,
 };
-use crate::ntfs::winapi::get_ntfs_volume_data;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
 
 // ============================================================================
 // Scanner Configuration
@@ -34,12 +69,46 @@ pub struct ScanConfig {
     pub include_hidden: bool,
     /// Include system files
     pub include_system: bool,
-    /// Calculate directory sizes
+    /// Include NTFS metafiles ($MFT, $LogFile, $Extend\...).
+    /// Off by default so they don't dominate size analysis.
+    pub include_metafiles: bool,
+    /// Read sizes and timestamps from the MFT after USN enumeration. When
+    /// false and USN enumeration succeeds the scan stops there: names,
+    /// parents and attributes only, with sizes left at 0. The MFT is still
+    /// read when USN enumeration fails or stops early.
     pub calculate_sizes: bool,
     /// Show progress during scan
     pub show_progress: bool,
     /// Number of MFT records to read per batch
     pub batch_size: usize,
+    /// Threads decoding each batch of MFT records (0 = the
+    /// `threads::max_workers()` budget, 1 = parse on the scanning thread)
+    pub parse_threads: usize,
+    /// Batches between checkpoints in `scan_resumable`
+    pub checkpoint_interval: usize,
+    /// Maximum parent levels climbed (fetching missing records on demand)
+    /// when building a path, so parent cycles on corrupt volumes terminate
+    pub max_parent_resolution_depth: u32,
+    /// Keep the stored UTF-16 of names with lone surrogates or control
+    /// characters, for exact forensic output. Displayed names stay lossy;
+    /// such names are flagged either way.
+    pub keep_raw_names: bool,
+    /// Scan a Volume Shadow Copy snapshot of the drive instead of the live
+    /// volume, so files changing during the scan can't leave it
+    /// inconsistent. The snapshot is created when the scan starts and
+    /// deleted when it ends. Reads go through the snapshot's volume handle,
+    /// so `use_physical_drive` is ignored. Needs the VSS service; ignored
+    /// for disk images.
+    pub use_vss: bool,
+    /// Also list deleted files whose MFT records have not been reused yet.
+    /// They are flagged `TreeNode::is_deleted` and left out of all sizes
+    /// and counts. Their contents may already be overwritten.
+    pub include_deleted: bool,
+    /// Largest names-only tree (`TreeBuilder::memory_footprint`) copied for
+    /// `VolumeScanner::with_preview`. The copy lives alongside the scan, so
+    /// bigger volumes skip the preview rather than double peak memory.
+    /// 0 = no limit.
+    pub preview_max_bytes: usize,
 }
 
 impl Default for ScanConfig {
@@ -50,13 +119,147 @@ impl Default for ScanConfig {
             use_physical_drive: true,
             include_hidden: true,
             include_system: true,
+            include_metafiles: false,
             calculate_sizes: true,
             show_progress: true,
             batch_size: 1024,
+            parse_threads: 0,
+            checkpoint_interval: 256,
+            max_parent_resolution_depth: crate::file_tree::DEFAULT_MAX_PARENT_DEPTH,
+            keep_raw_names: false,
+            use_vss: false,
+            include_deleted: false,
+            preview_max_bytes: 512 * 1024 * 1024,
         }
     }
 }
 
+impl ScanConfig {
+    /// Start from the defaults and override only what differs
+    pub fn builder() -> ScanConfigBuilder {
+        ScanConfigBuilder::default()
+    }
+}
+
+/// Fluent builder for `ScanConfig`.
+///
+/// ```no_run
+/// let config = emfit::ScanConfig::builder()
+///     .use_usn(true)
+///     .include_hidden(false)
+///     .show_progress(false)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScanConfigBuilder {
+    config: ScanConfig,
+}
+
+impl ScanConfigBuilder {
+    /// Scan using USN Journal (fast) if available
+    pub fn use_usn(mut self, use_usn: bool) -> Self {
+        self.config.use_usn = use_usn;
+        self
+    }
+
+    /// Scan using direct MFT reading
+    pub fn use_mft(mut self, use_mft: bool) -> Self {
+        self.config.use_mft = use_mft;
+        self
+    }
+
+    /// Use direct physical drive access (bypasses NTFS driver)
+    pub fn use_physical_drive(mut self, use_physical_drive: bool) -> Self {
+        self.config.use_physical_drive = use_physical_drive;
+        self
+    }
+
+    /// Include hidden files
+    pub fn include_hidden(mut self, include_hidden: bool) -> Self {
+        self.config.include_hidden = include_hidden;
+        self
+    }
+
+    /// Include system files
+    pub fn include_system(mut self, include_system: bool) -> Self {
+        self.config.include_system = include_system;
+        self
+    }
+
+    /// Include NTFS metafiles ($MFT, $LogFile, $Extend\...)
+    pub fn include_metafiles(mut self, include_metafiles: bool) -> Self {
+        self.config.include_metafiles = include_metafiles;
+        self
+    }
+
+    /// Read sizes and timestamps from the MFT after USN enumeration. When
+    /// false the MFT is only read if USN enumeration fails or stops early;
+    /// otherwise sizes are left at 0.
+    pub fn calculate_sizes(mut self, calculate_sizes: bool) -> Self {
+        self.config.calculate_sizes = calculate_sizes;
+        self
+    }
+
+    /// Show progress during scan
+    pub fn show_progress(mut self, show_progress: bool) -> Self {
+        self.config.show_progress = show_progress;
+        self
+    }
+
+    /// Number of MFT records to read per batch
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.config.batch_size = batch_size;
+        self
+    }
+
+    /// Threads decoding each batch of MFT records (0 = the worker cap)
+    pub fn parse_threads(mut self, parse_threads: usize) -> Self {
+        self.config.parse_threads = parse_threads;
+        self
+    }
+
+    /// Batches between checkpoints in `scan_resumable`
+    pub fn checkpoint_interval(mut self, checkpoint_interval: usize) -> Self {
+        self.config.checkpoint_interval = checkpoint_interval;
+        self
+    }
+
+    /// Maximum parent levels climbed when building a path
+    pub fn max_parent_resolution_depth(mut self, max_parent_resolution_depth: u32) -> Self {
+        self.config.max_parent_resolution_depth = max_parent_resolution_depth;
+        self
+    }
+
+    /// Keep the stored UTF-16 of anomalous names
+    pub fn keep_raw_names(mut self, keep_raw_names: bool) -> Self {
+        self.config.keep_raw_names = keep_raw_names;
+        self
+    }
+
+    /// Scan a shadow copy snapshot instead of the live volume
+    pub fn use_vss(mut self, use_vss: bool) -> Self {
+        self.config.use_vss = use_vss;
+        self
+    }
+
+    /// Also list deleted files still in the MFT
+    pub fn include_deleted(mut self, include_deleted: bool) -> Self {
+        self.config.include_deleted = include_deleted;
+        self
+    }
+
+    /// Largest tree copied for a scan preview (0 = no limit)
+    pub fn preview_max_bytes(mut self, preview_max_bytes: usize) -> Self {
+        self.config.preview_max_bytes = preview_max_bytes;
+        self
+    }
+
+    /// Finish building
+    pub fn build(self) -> ScanConfig {
+        self.config
+    }
+}
+
 // ============================================================================
 // Scan Progress
 // ============================================================================
@@ -95,20 +298,204 @@ impl ScanPhase {
     }
 }
 
+// ============================================================================
+// Scan Report
+// ============================================================================
+
+/// Anomalies found while scanning a volume, by MFT record number
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanReport {
+    /// Records whose parent chain loops back on itself
+    pub parent_cycles: Vec<u64>,
+    /// Records whose parent directory is not in the tree
+    pub missing_parents: Vec<u64>,
+    /// Records skipped because their update sequence did not verify
+    pub fixup_failures: Vec<u64>,
+    /// Records skipped because their header or attributes could not be
+    /// parsed ("BAAD" or garbage signature, malformed attributes)
+    pub parse_failures: Vec<u64>,
+    /// Records listed by USN enumeration but not in use when the MFT was
+    /// read (deleted or reused in between); dropped from the tree
+    pub transient: Vec<u64>,
+    /// Records past the MFT's valid data length when the scan started, so
+    /// allocated during it: listed by USN or referenced by another record,
+    /// but not read from the MFT. USN entries are kept as enumerated.
+    pub past_valid_data: Vec<u64>,
+    /// MFT record ranges that could not be read. Entries in them are
+    /// missing from the tree unless USN enumeration listed them.
+    pub unread: Vec<Range<u64>>,
+    /// Why USN enumeration stopped early, if it did. The entries read so far
+    /// were kept and the MFT phase filled in the rest.
+    pub usn_interruption: Option<String>,
+    /// The volume's dirty bit was set: it needs chkdsk and its metadata may
+    /// be inconsistent
+    pub volume_dirty: bool,
+}
+
+impl ScanReport {
+    /// Collect anomalies from a built tree, the records the parser skipped
+    /// and the transient USN records dropped before building
+    fn collect(tree: &FileTree, parser: &mut MftParser, transient: Vec<u64>) -> Self {
+        let mut missing_parents: Vec<u64> = tree
+            .find_orphans()
+            .into_iter()
+            .map(|key| key.record_number)
+            .collect();
+        missing_parents.sort_unstable();
+        missing_parents.dedup();
+
+        let mut fixup_failures = parser.take_fixup_failures();
+        fixup_failures.sort_unstable();
+        fixup_failures.dedup();
+        let mut parse_failures = parser.take_parse_failures();
+        parse_failures.sort_unstable();
+        parse_failures.dedup();
+
+        Self {
+            parent_cycles: tree.find_parent_cycles(),
+            missing_parents,
+            fixup_failures,
+            parse_failures,
+            transient,
+            past_valid_data: Vec::new(),
+            unread: Vec::new(),
+            usn_interruption: None,
+            volume_dirty: false,
+        }
+    }
+
+    /// Total number of anomalous records. Transient records and records
+    /// past the valid data length are normal churn on a busy volume and
+    /// not counted.
+    pub fn anomaly_count(&self) -> usize {
+        self.parent_cycles.len()
+            + self.missing_parents.len()
+            + self.fixup_failures.len()
+            + self.parse_failures.len()
+    }
+
+    /// Check if nothing unusual was found
+    pub fn is_clean(&self) -> bool {
+        self.anomaly_count() == 0
+    }
+
+    /// Number of MFT records that could not be read
+    pub fn unread_count(&self) -> u64 {
+        self.unread.iter().map(|range| range.end - range.start).sum()
+    }
+
+    /// Some records could not be read or failed verification, so the tree
+    /// may be missing entries
+    pub fn is_partial(&self) -> bool {
+        !self.unread.is_empty() || !self.fixup_failures.is_empty() || !self.parse_failures.is_empty()
+    }
+
+    /// Note records past the parser's valid data length: those it skipped
+    /// and those in the tree (from USN enumeration)
+    fn note_past_valid_data(&mut self, tree: &FileTree, parser: &mut MftParser) {
+        let mut records = parser.take_past_valid_data();
+        records.extend(
+            tree.iter_entries()
+                .map(|entry| entry.key().record_number)
+                .filter(|&record| parser.is_past_valid_data(record)),
+        );
+        records.sort_unstable();
+        records.dedup();
+        self.past_valid_data = records;
+    }
+
+    fn log(&self) {
+        if self.volume_dirty {
+            logging::warn("SCANNER", "Volume is marked dirty; run chkdsk before trusting this scan");
+        }
+        if let Some(ref reason) = self.usn_interruption {
+            logging::warn("SCANNER", &format!(
+                "USN enumeration incomplete, completed from MFT: {}",
+                reason
+            ));
+        }
+        if !self.transient.is_empty() {
+            logging::info("SCANNER", &format!(
+                "{} records changed between USN and MFT phases (transient)",
+                self.transient.len()
+            ));
+        }
+        if !self.past_valid_data.is_empty() {
+            logging::warn("SCANNER", &format!(
+                "MFT grew during the scan: {} records past its valid data length were not read",
+                self.past_valid_data.len()
+            ));
+        }
+        if !self.unread.is_empty() {
+            logging::warn("SCANNER", &format!(
+                "{} MFT records in {} range(s) could not be read",
+                self.unread_count(),
+                self.unread.len()
+            ));
+        }
+        if self.is_clean() {
+            return;
+        }
+        logging::warn("SCANNER", &format!(
+            "Scan anomalies: {} in parent cycles, {} with missing parents, {} fixup failures, {} unparsable",
+            self.parent_cycles.len(),
+            self.missing_parents.len(),
+            self.fixup_failures.len(),
+            self.parse_failures.len()
+        ));
+    }
+}
+
 // ============================================================================
 // Volume Scanner
 // ============================================================================
 
+/// Drive letter that paths from a disk image are rooted at. Not a valid
+/// volume name, so nothing can accidentally open a live drive for an image.
+pub const IMAGE_DRIVE_LETTER: char = '?';
+
 /// Main scanner for a single volume
 pub struct VolumeScanner {
     /// Drive letter
     drive_letter: char,
+    /// Raw disk image and partition offset, when scanning offline
+    image: Option<(PathBuf, u64)>,
     /// Configuration
     config: ScanConfig,
     /// Volume data
     volume_data: Option<NtfsVolumeData>,
     /// Cancellation flag
     cancelled: Arc<AtomicBool>,
+    /// Anomalies from the last completed scan
+    report: Option<ScanReport>,
+    /// Receives a names-only tree once USN enumeration is done
+    on_preview: Option<Arc<dyn Fn(FileTree) + Send + Sync>>,
+    /// Shadow copy being scanned, held from `open_parser` to the end of the scan
+    snapshot: Option<ShadowCopy>,
+}
+
+/// Reject non-NTFS drives before any raw volume access.
+///
+/// ReFS, exFAT and FAT volumes would otherwise fail deep inside the MFT
+/// reader with an opaque IOCTL error. If the filesystem can't be queried
+/// the scan proceeds and lets the volume open report the problem.
+fn check_filesystem(drive_letter: char) -> Result<()> {
+    let fs_name = match get_filesystem_name(drive_letter) {
+        Ok(name) => name,
+        Err(e) => {
+            logging::warn("SCANNER", &format!("Filesystem query for {}: failed: {}", drive_letter, e));
+            return Ok(());
+        }
+    };
+
+    if fs_name.eq_ignore_ascii_case("NTFS") {
+        return Ok(());
+    }
+
+    if fs_name.eq_ignore_ascii_case("ReFS") {
+        logging::warn("SCANNER", &format!("Drive {}: is ReFS, which has no MFT and is not supported yet", drive_letter));
+    }
+    Err(EmFitError::UnsupportedFilesystem(fs_name))
 }
 
 impl VolumeScanner {
@@ -116,18 +503,54 @@ impl VolumeScanner {
     pub fn new(drive_letter: char) -> Self {
         Self {
             drive_letter: drive_letter.to_ascii_uppercase(),
+            image: None,
             config: ScanConfig::default(),
             volume_data: None,
             cancelled: Arc::new(AtomicBool::new(false)),
+            report: None,
+            on_preview: None,
+            snapshot: None,
         }
     }
 
+    /// Create a scanner over a raw NTFS image (.img/.dd) instead of a live
+    /// volume. `partition_offset` is the byte offset of the NTFS partition
+    /// in the image (0 for a partition image). Needs no admin rights; the USN
+    /// journal is not used. Paths are rooted at `IMAGE_DRIVE_LETTER`.
+    pub fn from_image(path: &Path, partition_offset: u64) -> Result<Self> {
+        // Fail early on a missing file or a non-NTFS offset
+        let io = open_image(path, partition_offset)?;
+
+        let mut scanner = Self::new(IMAGE_DRIVE_LETTER);
+        scanner.volume_data = Some(io.volume_data().clone());
+        scanner.image = Some((path.to_path_buf(), partition_offset));
+        Ok(scanner)
+    }
+
     /// Configure the scanner
     pub fn with_config(mut self, config: ScanConfig) -> Self {
         self.config = config;
         self
     }
 
+    /// Call `on_preview` with a provisional tree as soon as USN enumeration
+    /// has listed the volume's names, before the MFT pass adds sizes and
+    /// timestamps. Runs on its own thread; `scan` still returns the full
+    /// tree. Not called when there is no USN phase or no MFT pass after it,
+    /// or when the names take more than `ScanConfig::preview_max_bytes`.
+    pub fn with_preview(mut self, on_preview: impl Fn(FileTree) + Send + Sync + 'static) -> Self {
+        self.on_preview = Some(Arc::new(on_preview));
+        self
+    }
+
+    /// Stop when `cancelled` is set instead of on `cancel` alone, e.g. to
+    /// share one flag between the scanners of several drives. The scan
+    /// ends with `EmFitError::Cancelled` at the next MFT batch or phase.
+    pub fn with_cancel_token(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = cancelled;
+        self
+    }
+
     /// Get cancellation token
     pub fn cancel_token(&self) -> Arc<AtomicBool> {
         self.cancelled.clone()
@@ -144,117 +567,63 @@ impl VolumeScanner {
     }
 
     /// Perform the scan
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(drive = %self.drive_letter), err))]
     pub fn scan(&mut self) -> Result<FileTree> {
         let start_time = Instant::now();
 
-        logging::separator(&format!("SCAN START: Drive {}", self.drive_letter));
+        logging::separator(&format!("SCAN START: {}", self.source_name()));
         logging::info("SCANNER", &format!("Config: usn={}, mft={}, physical={}, hidden={}, system={}",
             self.config.use_usn, self.config.use_mft, self.config.use_physical_drive,
             self.config.include_hidden, self.config.include_system));
 
         // Initialize progress bar
-        let pb = if self.config.show_progress {
-            let pb = ProgressBar::new(100);
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
-                    .unwrap()
-                    .progress_chars("#>-"),
-            );
-            Some(pb)
-        } else {
-            None
-        };
+        let pb = self.new_progress_bar();
 
         // Phase 1: Open I/O source — try physical drive first, fall back to volume
         if let Some(ref pb) = pb {
-            if self.config.use_physical_drive {
+            if self.image.is_some() {
+                pb.set_message("Opening disk image...");
+            } else if self.config.use_vss {
+                pb.set_message("Creating shadow copy...");
+            } else if self.config.use_physical_drive {
                 pb.set_message("Opening physical drive...");
             } else {
                 pb.set_message("Opening volume...");
             }
         }
 
-        let (io, is_physical) = if self.config.use_physical_drive {
-            match open_physical_drive_for_volume(self.drive_letter) {
-                Ok(io) => {
-                    logging::info("SCANNER", "Physical drive mode active)");
-                    if let Some(ref pb) = pb {
-                        pb.set_message("Physical drive mode active");
-                    }
-                    (io, true)
-                }
-                Err(e) => {
-                    logging::warn("SCANNER", &format!(
-                        "Physical drive access failed: {}. Falling back to volume mode.", e
-                    ));
-                    if let Some(ref pb) = pb {
-                        pb.set_message("Falling back to volume mode...");
-                    }
-                    let handle = open_volume(self.drive_letter)?;
-                    let volume_data = get_ntfs_volume_data(&handle)?;
-                    (VolumeIO::Volume { handle, volume_data }, false)
-                }
-            }
-        } else {
-            let handle = open_volume(self.drive_letter)?;
-            let volume_data = get_ntfs_volume_data(&handle)?;
-            (VolumeIO::Volume { handle, volume_data }, false)
-        };
+        let (mut parser, is_physical) = self.open_parser(pb.as_ref())?;
+        let scanned_at = SystemTime::now();
+        let usn_cursor = self.journal_cursor();
 
-        let volume_data = io.volume_data().clone();
-        self.volume_data = Some(volume_data.clone());
-
-        // Create MFT parser with the I/O source
-        let mut parser = MftParser::new(io)?;
-        parser.load_mft_extents(self.drive_letter)?;
-
-        // Update volume_data after extents are loaded (mft_valid_data_length may have been set)
-        let volume_data = parser.volume_data().clone();
-        self.volume_data = Some(volume_data.clone());
+        let volume_dirty = parser.read_volume_info().is_ok_and(|info| info.is_dirty());
 
         let estimated_records = parser.estimated_records();
         if let Some(ref pb) = pb {
             pb.set_length(estimated_records);
             pb.set_message(format!(
                 "Volume: {} ({} estimated records, {})",
-                self.drive_letter, estimated_records,
-                if is_physical { "physical drive" } else { "volume handle" }
+                self.drive_letter, estimated_records, self.io_mode(is_physical)
             ));
         }
 
-        // Create TreeBuilder with volume info
-        let mut builder = TreeBuilder::with_volume_info(
-            self.drive_letter,
-            volume_data.bytes_per_file_record_segment,
-        );
-
-        // Set up MftRecordFetcher for on-demand parent resolution
-        match MftRecordFetcher::new(
-            self.drive_letter,
-            volume_data.clone(),
-            parser.mft_extents(),
-            is_physical,
-        ) {
-            Ok(fetcher) => {
-                builder.set_record_fetcher(Arc::new(fetcher));
-            }
-            Err(e) => {
-                logging::warn("SCANNER", &format!("Failed to create MftRecordFetcher: {}", e));
-            }
-        }
+        let mut builder = self.new_builder(&parser, is_physical);
 
         // Phase 2: Try USN enumeration first (fast path) — only in volume mode
         let mut usn_success = false;
+        let mut usn_interruption = None;
+        let mut transient = Vec::new();
+        let mut unread = Vec::new();
 
-        if self.config.use_usn && !is_physical {
+        if self.config.use_usn && !is_physical && self.image.is_none() {
             if let Some(ref pb) = pb {
                 pb.set_message("Scanning via USN Journal...");
             }
 
             match self.scan_via_usn(&mut builder, pb.as_ref()) {
-                Ok(count) => {
+                Ok((count, interruption)) => {
                     usn_success = true;
+                    usn_interruption = interruption;
                     logging::info("SCANNER", &format!("USN phase complete: {} entries", count));
                     if let Some(ref pb) = pb {
                         pb.set_message(format!("USN: {} entries found", count));
@@ -273,9 +642,15 @@ impl VolumeScanner {
             return Err(EmFitError::Cancelled);
         }
 
-        // Phase 3: MFT reading for size information (or full scan if USN failed)
-        if self.config.use_mft && (self.config.calculate_sizes || !usn_success) {
+        // Phase 3: MFT reading for size information (or full scan if USN failed
+        // or stopped early)
+        if self.config.use_mft
+            && (self.config.calculate_sizes || !usn_success || usn_interruption.is_some())
+        {
             logging::separator("MFT SCAN PHASE");
+            if usn_success {
+                self.send_preview(&builder);
+            }
             if let Some(ref pb) = pb {
                 if usn_success {
                     pb.set_message("Reading MFT for file sizes...");
@@ -289,6 +664,23 @@ impl VolumeScanner {
 
             self.scan_via_mft_with_parser(&mut parser, &mut builder, pb.as_ref())?;
             logging::info("SCANNER", "MFT phase complete");
+
+            // MFT is authoritative for existence; see the module docs.
+            // Records past the valid data length were never read, and
+            // records failing fixup or parsing could not be checked, so
+            // nothing contradicts their USN entries.
+            unread = parser.take_unread_ranges();
+            if usn_success {
+                let mut skipped = unread.clone();
+                skipped.push(parser.estimated_records()..u64::MAX);
+                skipped.extend(
+                    parser.fixup_failures()
+                        .into_iter()
+                        .chain(parser.parse_failures())
+                        .map(|record| record..record + 1),
+                );
+                transient = builder.drop_unconfirmed_usn_entries(&skipped);
+            }
         }
 
         if self.is_cancelled() {
@@ -301,13 +693,141 @@ impl VolumeScanner {
             pb.set_message("Building file tree...");
         }
 
-        let tree = builder.build();
+        let mut tree = builder.build();
+        tree.stats.mft_fragment_count = parser.extent_count() as u64;
+        tree.scanned_at = Some(scanned_at);
+        tree.usn_cursor = usn_cursor;
+
+        let mut report = ScanReport::collect(&tree, &mut parser, transient);
+        report.note_past_valid_data(&tree, &mut parser);
+        report.unread = unread;
+        report.usn_interruption = usn_interruption;
+        report.volume_dirty = volume_dirty;
+        report.log();
+        self.report = Some(report);
 
         logging::info("SCANNER", &format!(
             "Scan complete: {} files, {} dirs, {:.2}s ({})",
             tree.stats.total_files, tree.stats.total_directories,
             start_time.elapsed().as_secs_f64(),
-            if is_physical { "physical drive" } else { "volume" }
+            self.io_mode(is_physical)
+        ));
+        drop(parser);
+        self.release_snapshot(&mut tree);
+
+        if let Some(ref pb) = pb {
+            pb.finish_with_message(format!(
+                "Complete: {} files, {} directories ({:.2}s)",
+                tree.stats.total_files,
+                tree.stats.total_directories,
+                start_time.elapsed().as_secs_f64()
+            ));
+        }
+
+        logging::flush();
+        Ok(tree)
+    }
+
+    /// Scan via MFT, writing a checkpoint every `checkpoint_interval` batches.
+    ///
+    /// If `checkpoint_path` holds a checkpoint for this volume the scan picks
+    /// up where it stopped instead of starting over. The checkpoint files are
+    /// removed once the scan completes. The USN fast path is not used.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(drive = %self.drive_letter), err))]
+    pub fn scan_resumable(&mut self, checkpoint_path: impl AsRef<Path>) -> Result<FileTree> {
+        let start_time = Instant::now();
+        let checkpoint_path = checkpoint_path.as_ref();
+        let nodes_path = ScanCheckpoint::nodes_path(checkpoint_path);
+
+        logging::separator(&format!("RESUMABLE SCAN START: {}", self.source_name()));
+
+        let pb = self.new_progress_bar();
+        let (mut parser, is_physical) = self.open_parser(pb.as_ref())?;
+        let scanned_at = SystemTime::now();
+        let usn_cursor = self.journal_cursor();
+
+        let volume_dirty = parser.read_volume_info().is_ok_and(|info| info.is_dirty());
+        let total_records = parser.estimated_records();
+        let mut builder = self.new_builder(&parser, is_physical);
+
+        let volume_data = parser.volume_data().clone();
+        let mut checkpoint = match ScanCheckpoint::load(checkpoint_path) {
+            Ok(cp) if cp.matches(self.drive_letter, total_records, &volume_data) => {
+                builder.add_nodes(cp.read_nodes(&nodes_path)?.into_iter());
+                logging::info("SCANNER", &format!(
+                    "Resuming drive {} at record {}/{} ({} files, {} dirs so far)",
+                    self.drive_letter, cp.next_record, total_records,
+                    cp.files_found, cp.directories_found
+                ));
+                cp
+            }
+            Ok(_) => {
+                logging::warn(
+                    "SCANNER",
+                    "Checkpoint belongs to a different volume or an older MFT, starting over",
+                );
+                ScanCheckpoint::new(self.drive_letter, total_records, &volume_data)
+            }
+            Err(_) => ScanCheckpoint::new(self.drive_letter, total_records, &volume_data),
+        };
+        let resumed = checkpoint.next_record > 0;
+        let mut nodes_file = checkpoint.open_nodes(&nodes_path)?;
+
+        if let Some(ref pb) = pb {
+            pb.set_length(total_records);
+            pb.set_position(checkpoint.next_record);
+            pb.set_message(if checkpoint.next_record > 0 {
+                format!("Resuming at record {}...", checkpoint.next_record)
+            } else {
+                "Scanning via MFT...".to_string()
+            });
+        }
+
+        let interval = self.config.checkpoint_interval.max(1);
+        let mut pending = Vec::new();
+        let mut batches = 0usize;
+
+        self.for_each_mft_batch(&mut parser, checkpoint.next_record..u64::MAX, pb.as_ref(), |next_record, entries| {
+            pending.extend(entries);
+            batches += 1;
+
+            if batches.is_multiple_of(interval) {
+                checkpoint.append(&mut nodes_file, &pending, next_record)?;
+                checkpoint.save(checkpoint_path)?;
+                builder.add_file_entries(pending.drain(..));
+            }
+            Ok(true)
+        })?;
+        builder.add_file_entries(pending.into_iter());
+
+        logging::separator("BUILD TREE PHASE");
+        if let Some(ref pb) = pb {
+            pb.set_message("Building file tree...");
+        }
+        let mut tree = builder.build();
+        tree.stats.mft_fragment_count = parser.extent_count() as u64;
+        tree.scanned_at = Some(scanned_at);
+        // Records read before the resume may predate this cursor
+        if !resumed {
+            tree.usn_cursor = usn_cursor;
+        }
+
+        let mut report = ScanReport::collect(&tree, &mut parser, Vec::new());
+        report.note_past_valid_data(&tree, &mut parser);
+        report.unread = parser.take_unread_ranges();
+        report.volume_dirty = volume_dirty;
+        report.log();
+        self.report = Some(report);
+
+        drop(nodes_file);
+        ScanCheckpoint::remove(checkpoint_path);
+        drop(parser);
+        self.release_snapshot(&mut tree);
+
+        logging::info("SCANNER", &format!(
+            "Resumable scan complete: {} files, {} dirs, {:.2}s",
+            tree.stats.total_files, tree.stats.total_directories,
+            start_time.elapsed().as_secs_f64()
         ));
 
         if let Some(ref pb) = pb {
@@ -323,13 +843,15 @@ impl VolumeScanner {
         Ok(tree)
     }
 
-    /// Scan using USN Journal
+    /// Scan using USN Journal. Returns the entry count and, if enumeration
+    /// stopped early, why; entries read before that are still added.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, err))]
     fn scan_via_usn(
         &self,
         builder: &mut TreeBuilder,
         pb: Option<&ProgressBar>,
-    ) -> Result<u64> {
-        let handle = open_volume(self.drive_letter)?;
+    ) -> Result<(u64, Option<String>)> {
+        let handle = self.open_source_volume()?;
         let mut usn_scanner = UsnScanner::new(handle);
         usn_scanner.initialize()?;
 
@@ -362,73 +884,638 @@ impl VolumeScanner {
 
         builder.add_usn_entries(entries.into_iter());
 
-        Ok(count.load(Ordering::Relaxed))
+        let interruption = usn_scanner.interruption().map(str::to_string);
+        Ok((count.load(Ordering::Relaxed), interruption))
+    }
+
+    /// Build the names found so far into a tree on a separate thread and
+    /// hand it to the preview callback, if there is one
+    fn send_preview(&self, builder: &TreeBuilder) {
+        let Some(on_preview) = self.on_preview.clone() else {
+            return;
+        };
+        let limit = self.config.preview_max_bytes;
+        if limit > 0 {
+            let footprint = builder.memory_footprint();
+            if footprint > limit {
+                logging::info("SCANNER", &format!(
+                    "Preview skipped: {} of names exceeds the {} limit",
+                    crate::format_size(footprint as u64),
+                    crate::format_size(limit as u64)
+                ));
+                return;
+            }
+        }
+        let snapshot = builder.snapshot();
+        std::thread::spawn(move || {
+            let tree = snapshot.build();
+            logging::info("SCANNER", &format!(
+                "Preview: {} files, {} dirs",
+                tree.stats.total_files, tree.stats.total_directories
+            ));
+            on_preview(tree);
+        });
+    }
+
+    /// Current USN journal position, taken before reading so changes made
+    /// during the scan still count as newer than the tree
+    fn journal_cursor(&self) -> Option<UsnCursor> {
+        if self.image.is_some() {
+            return None;
+        }
+        let handle = self.open_source_volume().ok()?;
+        let journal = query_usn_journal(&handle).ok()?;
+        Some(UsnCursor {
+            journal_id: journal.usn_journal_id,
+            next_usn: journal.next_usn as i64,
+        })
+    }
+
+    /// Open the volume being scanned: the shadow copy while one is held,
+    /// otherwise the live drive
+    fn open_source_volume(&self) -> Result<SafeHandle> {
+        match self.snapshot {
+            Some(ref snapshot) => open_volume_path(snapshot.device_path()),
+            None => open_volume(self.drive_letter),
+        }
+    }
+
+    /// Delete the shadow copy taken for this scan, if any. `tree` stops
+    /// fetching missing parents on demand, as they would be read from it.
+    fn release_snapshot(&mut self, tree: &mut FileTree) {
+        if let Some(snapshot) = self.snapshot.take() {
+            logging::info("SCANNER", &format!("Releasing shadow copy {}", snapshot.id()));
+            tree.clear_record_fetcher();
+        }
+    }
+
+    /// Create the console progress bar if progress output is enabled
+    fn new_progress_bar(&self) -> Option<ProgressBar> {
+        if !self.config.show_progress {
+            return None;
+        }
+        let pb = ProgressBar::new(100);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        Some(pb)
+    }
+
+    /// "Drive C" or "Image <path>", for log headers
+    fn source_name(&self) -> String {
+        match self.image {
+            Some((ref path, _)) => format!("Image {}", path.display()),
+            None => format!("Drive {}", self.drive_letter),
+        }
+    }
+
+    /// How the volume is being read, for progress and log messages
+    fn io_mode(&self, is_physical: bool) -> &'static str {
+        if self.image.is_some() {
+            "disk image"
+        } else if self.snapshot.is_some() {
+            "shadow copy"
+        } else if is_physical {
+            "physical drive"
+        } else {
+            "volume handle"
+        }
+    }
+
+    /// Open the I/O source (image, or physical drive first with volume as
+    /// fallback) and create an MFT parser with its extents loaded
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, err))]
+    fn open_parser(&mut self, pb: Option<&ProgressBar>) -> Result<(MftParser, bool)> {
+        if self.image.is_none() {
+            check_filesystem(self.drive_letter)?;
+        }
+        if self.config.use_vss && self.image.is_none() {
+            // Replaces (and so releases) any snapshot left by a failed scan
+            self.snapshot = Some(ShadowCopy::create(self.drive_letter)?);
+        }
+
+        let (io, is_physical) = if let Some((ref path, partition_offset)) = self.image {
+            (open_image(path, partition_offset)?, false)
+        } else if let Some(ref snapshot) = self.snapshot {
+            let handle = open_volume_path(snapshot.device_path())?;
+            let volume_data = get_ntfs_volume_data(&handle)?;
+            if let Some(pb) = pb {
+                pb.set_message("Scanning shadow copy");
+            }
+            (VolumeIO::Volume { handle, volume_data }, false)
+        } else if self.config.use_physical_drive {
+            match open_physical_drive_for_volume(self.drive_letter) {
+                Ok(io) => {
+                    logging::info("SCANNER", "Physical drive mode active)");
+                    if let Some(pb) = pb {
+                        pb.set_message("Physical drive mode active");
+                    }
+                    (io, true)
+                }
+                Err(e) => {
+                    logging::warn("SCANNER", &format!(
+                        "Physical drive access failed: {}. Falling back to volume mode.", e
+                    ));
+                    if let Some(pb) = pb {
+                        pb.set_message("Falling back to volume mode...");
+                    }
+                    let handle = open_volume(self.drive_letter)?;
+                    let volume_data = get_ntfs_volume_data(&handle)?;
+                    (VolumeIO::Volume { handle, volume_data }, false)
+                }
+            }
+        } else {
+            let handle = open_volume(self.drive_letter)?;
+            let volume_data = get_ntfs_volume_data(&handle)?;
+            (VolumeIO::Volume { handle, volume_data }, false)
+        };
+
+        self.volume_data = Some(io.volume_data().clone());
+
+        // Create MFT parser with the I/O source
+        let mut parser = MftParser::new(io)?;
+        match self.snapshot {
+            Some(ref snapshot) => parser.load_mft_extents_at(snapshot.device_path())?,
+            None => parser.load_mft_extents(self.drive_letter)?,
+        }
+        logging::info("SCANNER", &format!("$MFT is in {} extent(s)", parser.extent_count()));
+        parser.set_parse_threads(self.config.parse_threads);
+        parser.set_include_deleted(self.config.include_deleted);
+        logging::info("SCANNER", &format!("Parsing MFT records on {} thread(s)", parser.parse_threads()));
+
+        // Update volume_data after extents are loaded (mft_valid_data_length may have been set)
+        self.volume_data = Some(parser.volume_data().clone());
+
+        Ok((parser, is_physical))
+    }
+
+    /// Create a TreeBuilder for the opened volume, with on-demand parent resolution
+    fn new_builder(&self, parser: &MftParser, is_physical: bool) -> TreeBuilder {
+        let volume_data = parser.volume_data().clone();
+
+        // Create TreeBuilder with volume info
+        let mut builder = TreeBuilder::with_volume_info(
+            self.drive_letter,
+            volume_data.bytes_per_file_record_segment,
+        );
+        builder.set_include_metafiles(self.config.include_metafiles);
+        builder.set_max_parent_depth(self.config.max_parent_resolution_depth);
+        builder.set_keep_raw_names(self.config.keep_raw_names);
+        builder.set_bytes_per_cluster(volume_data.bytes_per_cluster);
+
+        // Set up MftRecordFetcher for on-demand parent resolution
+        let fetcher = match self.image {
+            Some((ref path, partition_offset)) => ImageReader::open(path, partition_offset)
+                .map(|reader| {
+                    MftRecordFetcher::from_reader(reader, volume_data, parser.mft_extents())
+                }),
+            None if self.snapshot.is_some() => self.open_source_volume().map(|handle| {
+                MftRecordFetcher::from_reader(handle, volume_data, parser.mft_extents())
+            }),
+            None => MftRecordFetcher::new(
+                self.drive_letter,
+                volume_data,
+                parser.mft_extents(),
+                is_physical,
+            ),
+        };
+        match fetcher {
+            Ok(fetcher) => {
+                builder.set_record_fetcher(Arc::new(fetcher));
+            }
+            Err(e) => {
+                logging::warn("SCANNER", &format!("Failed to create MftRecordFetcher: {}", e));
+            }
+        }
+
+        builder
     }
 
     /// Scan using direct MFT reading with a pre-created parser
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, err))]
     fn scan_via_mft_with_parser(
         &self,
         parser: &mut MftParser,
         builder: &mut TreeBuilder,
         pb: Option<&ProgressBar>,
     ) -> Result<()> {
-        let total_records = parser.estimated_records();
-        let batch_size = self.config.batch_size;
-        let mut processed = 0u64;
         let mut all_entries = Vec::new();
+        self.for_each_mft_entry(parser, pb, |entry| {
+            all_entries.push(entry);
+            true
+        })?;
+
+        builder.add_file_entries(all_entries.into_iter());
+        Ok(())
+    }
 
-        while processed < total_records {
-            if self.is_cancelled() {
-                return Err(EmFitError::Cancelled);
+    /// Read every MFT record in batches and pass each entry that survives
+    /// the hidden/system filters to `on_entry`. Returning false from
+    /// `on_entry` stops the scan early.
+    fn for_each_mft_entry<F>(
+        &self,
+        parser: &mut MftParser,
+        pb: Option<&ProgressBar>,
+        mut on_entry: F,
+    ) -> Result<u64>
+    where
+        F: FnMut(FileEntry) -> bool,
+    {
+        let mut found = 0u64;
+        self.for_each_mft_batch(parser, 0..u64::MAX, pb, |_, entries| {
+            for entry in entries {
+                found += 1;
+                if !on_entry(entry) {
+                    return Ok(false);
+                }
             }
+            Ok(true)
+        })?;
+        Ok(found)
+    }
 
-            let batch_count = std::cmp::min(batch_size, (total_records - processed) as usize);
+    /// Read the MFT records in `records` (cut off at the MFT's end) in
+    /// batches. `on_batch` receives the next unread record number and the
+    /// filtered entries of each batch; returning `Ok(false)` stops the scan
+    /// early.
+    ///
+    /// Reading runs one batch ahead on a second thread, so the disk is busy
+    /// while the previous batch is parsed. The channel holds a single batch,
+    /// which bounds the read-ahead to two batches in memory.
+    fn for_each_mft_batch<F>(
+        &self,
+        parser: &mut MftParser,
+        records: Range<u64>,
+        pb: Option<&ProgressBar>,
+        mut on_batch: F,
+    ) -> Result<()>
+    where
+        F: FnMut(u64, Vec<FileEntry>) -> Result<bool>,
+    {
+        let parser: &MftParser = parser;
+        let total_records = records.end.min(parser.estimated_records());
+        let batch_size = self.config.batch_size;
 
-            match parser.read_records_batch(processed, batch_count) {
-                Ok(batch) => {
-                    let batch_entries = parser.parse_batch_with_extensions(batch);
+        std::thread::scope(|scope| {
+            let (batch_tx, batch_rx) = mpsc::sync_channel(1);
+
+            // Stops after a fatal read error, or when the receiver is dropped
+            // because the scan finished early or was cancelled
+            scope.spawn(move || {
+                let mut next = records.start;
+                while next < total_records {
+                    let batch_count = std::cmp::min(batch_size, (total_records - next) as usize);
+                    let batch = parser.read_records(next, batch_count);
+                    let fatal = matches!(&batch, Err(e) if !e.is_recoverable());
+                    if batch_tx.send((next, batch_count, batch)).is_err() || fatal {
+                        break;
+                    }
+                    next += batch_count as u64;
+                }
+            });
 
-                    for entry in batch_entries {
-                        if !self.config.include_hidden && entry.is_hidden() {
-                            continue;
+            let mut found = 0u64;
+            for (processed, batch_count, batch) in batch_rx {
+                if self.is_cancelled() {
+                    return Err(EmFitError::Cancelled);
+                }
+
+                let mut entries = Vec::new();
+
+                match batch {
+                    Ok(batch) => {
+                        let batch_entries = parser.parse_batch_with_extensions(batch);
+
+                        for entry in batch_entries {
+                            if !self.config.include_hidden && entry.is_hidden() {
+                                continue;
+                            }
+                            if !self.config.include_system && entry.is_system() {
+                                continue;
+                            }
+
+                            entries.push(entry);
                         }
-                        if !self.config.include_system && entry.is_system() {
-                            continue;
+                    }
+                    Err(e) => {
+                        logging::warn("SCANNER", &format!(
+                            "Failed to read records {}..{}: {}",
+                            processed,
+                            processed + batch_count as u64,
+                            e
+                        ));
+                        if !e.is_recoverable() {
+                            // Nothing past this point was read
+                            parser.mark_unread(processed..total_records);
+                            break;
                         }
-
-                        all_entries.push(entry);
                     }
                 }
-                Err(e) => {
-                    if !e.is_recoverable() {
-                        break;
+
+                let processed = processed + batch_count as u64;
+                found += entries.len() as u64;
+
+                if !on_batch(processed, entries)? {
+                    return Ok(());
+                }
+
+                if let Some(pb) = pb {
+                    pb.set_position(processed);
+                    if processed.is_multiple_of(50000) {
+                        pb.set_message(format!(
+                            "MFT: {}/{} records ({} files)",
+                            processed,
+                            total_records,
+                            found
+                        ));
                     }
                 }
             }
 
-            processed += batch_count as u64;
+            Ok(())
+        })
+    }
 
-            if let Some(pb) = pb {
-                pb.set_position(processed);
-                if processed % 50000 == 0 {
-                    pb.set_message(format!(
-                        "MFT: {}/{} records ({} files)",
-                        processed,
-                        total_records,
-                        all_entries.len()
-                    ));
+    /// Stream raw MFT entries to an async consumer.
+    ///
+    /// The synchronous MFT scan runs on a blocking task and entries are
+    /// forwarded through a bounded channel, so the runtime's worker threads
+    /// are never blocked. Dropping the stream stops the scan. No tree is
+    /// built and no progress bar is shown.
+    ///
+    /// Like `scan`, NTFS metafiles and the contents of `$Extend` are left
+    /// out unless `include_metafiles` is set. Without a tree, a folder's
+    /// contents are only recognised once the folder itself has streamed
+    /// by, which holds for `$Extend` since it sits in the reserved records.
+    #[cfg(feature = "tokio")]
+    pub fn scan_stream(mut self) -> impl tokio_stream::Stream<Item = Result<FileEntry>> {
+        let (tx, rx) = tokio::sync::mpsc::channel(self.config.batch_size.max(1));
+
+        tokio::task::spawn_blocking(move || {
+            logging::info("SCANNER", &format!("Streaming scan of drive {}", self.drive_letter));
+
+            let include_metafiles = self.config.include_metafiles;
+            // Metafile folders seen so far, whose entries are metafiles too
+            let mut metafile_dirs = std::collections::HashSet::new();
+            let result = self.open_parser(None).and_then(|(mut parser, _)| {
+                self.for_each_mft_entry(&mut parser, None, |entry| {
+                    if !include_metafiles
+                        && (crate::file_tree::is_metafile_record(entry.record_number)
+                            || metafile_dirs.contains(&entry.parent_record_number))
+                    {
+                        if entry.is_directory {
+                            metafile_dirs.insert(entry.record_number);
+                        }
+                        return true;
+                    }
+                    tx.blocking_send(Ok(entry)).is_ok()
+                })
+            });
+
+            match result {
+                Ok(count) => {
+                    logging::info("SCANNER", &format!("Streaming scan complete: {} entries", count));
+                }
+                Err(e) => {
+                    logging::warn("SCANNER", &format!("Streaming scan failed: {}", e));
+                    let _ = tx.blocking_send(Err(e));
                 }
             }
+        });
+
+        tokio_stream::wrappers::ReceiverStream::new(rx)
+    }
+
+    /// Parse MFT records `start..end` without building a tree, e.g. to
+    /// look at the records around a known deletion. `end` is cut off at
+    /// the MFT's size. Only records in use are returned (and deleted files
+    /// with `include_deleted`), filtered by the hidden/system settings and
+    /// ordered by record number; parents are not
+    /// resolved, so entries carry parent record numbers rather than paths.
+    pub fn scan_record_range(&mut self, start: u64, end: u64) -> Result<Vec<FileEntry>> {
+        logging::info("SCANNER", &format!(
+            "Reading records {}..{} of {}", start, end, self.source_name()
+        ));
+        let (mut parser, _) = self.open_parser(None)?;
+
+        let mut entries = Vec::new();
+        self.for_each_mft_batch(&mut parser, start..end, None, |_, batch| {
+            entries.extend(batch);
+            Ok(true)
+        })?;
+        entries.sort_by_key(|entry| entry.record_number);
+
+        drop(parser);
+        self.snapshot = None;
+        logging::info("SCANNER", &format!("{} records in range", entries.len()));
+        Ok(entries)
+    }
+
+    /// Raw bytes of MFT records `start..end` as they are on disk (fixups
+    /// not yet undone), e.g. to keep as a benchmark fixture. `end` is
+    /// cut off at the MFT's size; records that can't be read end the dump.
+    pub fn read_raw_records(&mut self, start: u64, end: u64) -> Result<Vec<u8>> {
+        const BATCH: u64 = 1024;
+        let (parser, _) = self.open_parser(None)?;
+        let end = end.min(parser.estimated_records());
+
+        let mut raw = Vec::new();
+        let mut next = start;
+        while next < end {
+            let count = (end - next).min(BATCH);
+            let records = parser.read_records(next, count as usize)?;
+            let read = records.len() as u64;
+            raw.extend(records.into_iter().flat_map(|(_, data)| data));
+            if read < count {
+                break;
+            }
+            next += count;
         }
 
-        builder.add_file_entries(all_entries.into_iter());
-        Ok(())
+        drop(parser);
+        self.snapshot = None;
+        Ok(raw)
     }
 
     /// Get volume data after scan
     pub fn volume_data(&self) -> Option<&NtfsVolumeData> {
         self.volume_data.as_ref()
     }
+
+    /// Get the anomaly report of the last completed scan
+    pub fn report(&self) -> Option<&ScanReport> {
+        self.report.as_ref()
+    }
+
+    /// Read the volume label, NTFS version and flags from `$Volume`.
+    /// A dirty volume should be checked with chkdsk before trusting a scan.
+    pub fn volume_info(&mut self) -> Result<VolumeInfo> {
+        let (mut parser, _) = self.open_parser(None)?;
+        let info = parser.read_volume_info();
+        drop(parser);
+        self.snapshot = None;
+        info
+    }
+}
+
+// ============================================================================
+// Scan Checkpoints
+// ============================================================================
+
+/// Current checkpoint format version
+const CHECKPOINT_VERSION: u32 = 2;
+
+/// Resume point of an interrupted `scan_resumable`.
+///
+/// The checkpoint itself is a small JSON file. Nodes found so far are
+/// appended as JSON lines to a `<checkpoint>.nodes` sidecar, which is
+/// truncated back to `nodes_bytes` on resume so a half-written tail from
+/// a killed process is discarded. A checkpoint is only resumed on the same
+/// volume with the MFT still the same size (see `matches`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    pub version: u32,
+    pub drive_letter: char,
+    /// Next MFT record to read
+    pub next_record: u64,
+    /// Estimated record count when the scan started
+    pub total_records: u64,
+    /// Serial number of the scanned volume
+    pub volume_serial_number: u64,
+    /// MFT valid data length when the scan started
+    pub mft_valid_data_length: u64,
+    pub files_found: u64,
+    pub directories_found: u64,
+    /// Sidecar length covered by this checkpoint
+    pub nodes_bytes: u64,
+}
+
+impl ScanCheckpoint {
+    /// Create a checkpoint for a scan of the volume `volume_data` describes,
+    /// starting at record 0
+    pub fn new(drive_letter: char, total_records: u64, volume_data: &NtfsVolumeData) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            drive_letter,
+            next_record: 0,
+            total_records,
+            volume_serial_number: volume_data.volume_serial_number,
+            mft_valid_data_length: volume_data.mft_valid_data_length,
+            files_found: 0,
+            directories_found: 0,
+            nodes_bytes: 0,
+        }
+    }
+
+    /// Check that this checkpoint was written for the same volume in the
+    /// same state: drive letter, serial number and MFT size must all
+    /// match, or `next_record` may point into a different MFT
+    pub fn matches(&self, drive_letter: char, total_records: u64, volume_data: &NtfsVolumeData) -> bool {
+        self.drive_letter == drive_letter
+            && self.total_records == total_records
+            && self.volume_serial_number == volume_data.volume_serial_number
+            && self.mft_valid_data_length == volume_data.mft_valid_data_length
+    }
+
+    /// Path of the node sidecar for a checkpoint file
+    pub fn nodes_path(checkpoint_path: &Path) -> PathBuf {
+        let mut name = checkpoint_path.as_os_str().to_owned();
+        name.push(".nodes");
+        PathBuf::from(name)
+    }
+
+    /// Load a checkpoint file
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        let checkpoint: Self = serde_json::from_slice(&data)
+            .map_err(|e| EmFitError::CheckpointError(e.to_string()))?;
+
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(EmFitError::CheckpointError(format!(
+                "Unsupported checkpoint version {} (expected {})",
+                checkpoint.version, CHECKPOINT_VERSION
+            )));
+        }
+        Ok(checkpoint)
+    }
+
+    /// Write the checkpoint (via a temp file, so a crash never leaves it half-written)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let data = serde_json::to_vec(self)
+            .map_err(|e| EmFitError::CheckpointError(e.to_string()))?;
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Read the nodes covered by this checkpoint from the sidecar
+    pub fn read_nodes(&self, nodes_path: &Path) -> Result<Vec<TreeNode>> {
+        let file = File::open(nodes_path)?;
+        let reader = BufReader::new(file.take(self.nodes_bytes));
+
+        let mut nodes = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let node: TreeNode = serde_json::from_str(&line)
+                .map_err(|e| EmFitError::CheckpointError(e.to_string()))?;
+            nodes.push(node);
+        }
+        Ok(nodes)
+    }
+
+    /// Open the sidecar for appending, dropping anything past `nodes_bytes`
+    fn open_nodes(&self, nodes_path: &Path) -> Result<File> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(nodes_path)?;
+        file.set_len(self.nodes_bytes)?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(file)
+    }
+
+    /// Append the nodes for `entries` to the sidecar and advance to `next_record`
+    fn append(&mut self, file: &mut File, entries: &[FileEntry], next_record: u64) -> Result<()> {
+        let mut writer = BufWriter::new(&mut *file);
+        for entry in entries.iter().filter(|e| e.is_valid || e.is_deleted) {
+            for node in TreeNode::nodes_from_file_entry(entry) {
+                serde_json::to_writer(&mut writer, &node)
+                    .map_err(|e| EmFitError::CheckpointError(e.to_string()))?;
+                writer.write_all(b"\n")?;
+            }
+            if entry.is_deleted {
+                continue;
+            }
+            if entry.is_directory {
+                self.directories_found += 1;
+            } else {
+                self.files_found += 1;
+            }
+        }
+        writer.flush()?;
+        drop(writer);
+        file.sync_data()?;
+
+        self.nodes_bytes = file.stream_position()?;
+        self.next_record = next_record;
+        Ok(())
+    }
+
+    /// Delete a checkpoint and its sidecar
+    pub fn remove(path: &Path) {
+        let _ = std::fs::remove_file(Self::nodes_path(path));
+        let _ = std::fs::remove_file(path);
+    }
 }
 
 // ============================================================================
@@ -465,6 +1552,20 @@ impl MultiVolumeScanner {
             .collect()
     }
 
+    /// Stats of each tree by drive letter, in the order given, with the
+    /// tree's current memory footprint. Combined views sum these; this keeps
+    /// the drives apart for comparison.
+    pub fn drive_stats<'a>(trees: impl IntoIterator<Item = &'a FileTree>) -> Vec<(char, TreeStats)> {
+        trees
+            .into_iter()
+            .map(|tree| {
+                let mut stats = tree.stats.clone();
+                stats.memory_bytes = tree.memory_footprint() as u64;
+                (tree.drive_letter, stats)
+            })
+            .collect()
+    }
+
     /// Detect all NTFS volumes
     pub fn detect_ntfs_volumes() -> Vec<char> {
         let mut volumes = Vec::new();
@@ -518,45 +1619,254 @@ impl ChangeMonitor {
         })
     }
 
+    /// Drive letter being monitored
+    pub fn drive_letter(&self) -> char {
+        self.drive_letter
+    }
+
+    /// Poll for raw change events since the last poll
+    pub fn poll_changes(&mut self) -> Result<Vec<ChangeEvent>> {
+        let monitor = self
+            .monitor
+            .as_mut()
+            .ok_or_else(|| EmFitError::UsnJournalNotActive(self.drive_letter.to_string()))?;
+        monitor.poll_changes()
+    }
+
     /// Poll for changes and apply to tree
     pub fn apply_changes(&mut self, tree: &mut FileTree) -> Result<usize> {
+        let changes = self.poll_changes()?;
+        let count = changes.len();
+
+        for change in &changes {
+            tree.apply_change(change);
+        }
+
+        Ok(count)
+    }
+
+    /// Poll for changes and apply them to a search index incrementally.
+    /// Returns the number of events that modified the index.
+    pub fn apply_changes_to_index(&mut self, index: &mut SearchIndex) -> Result<usize> {
         let monitor = self
             .monitor
             .as_mut()
             .ok_or_else(|| EmFitError::UsnJournalNotActive(self.drive_letter.to_string()))?;
 
         let changes = monitor.poll_changes()?;
-        let count = changes.len();
+        Ok(changes.iter().filter(|c| index.apply_change(c)).count())
+    }
+}
 
-        for change in changes {
-            match change.reason {
-                crate::ntfs::ChangeReason::Created => {
-                    let node = TreeNode {
-                        record_number: change.record_number,
-                        parent_record_number: change.parent_record_number,
-                        name: change.name,
-                        attributes: change.attributes,
-                        is_directory: (change.attributes
-                            & crate::ntfs::structs::file_attributes::DIRECTORY)
-                            != 0,
-                        ..Default::default()
-                    };
-                    tree.insert(node);
-                }
-                crate::ntfs::ChangeReason::Deleted => {
-                    // Mark as deleted or remove
-                    // (Implementation depends on desired behavior)
+// ============================================================================
+// Multi-Drive Monitor
+// ============================================================================
+
+/// A change event tagged with the drive it happened on
+#[derive(Debug, Clone)]
+pub struct DriveChangeEvent {
+    pub drive_letter: char,
+    pub event: ChangeEvent,
+}
+
+/// Background polling thread for one drive
+struct DriveWatcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DriveWatcher {
+    fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Watches several drives at once. Each drive gets its own `ChangeMonitor`
+/// polled on a background thread; all events arrive on one channel.
+pub struct MultiChangeMonitor {
+    sender: mpsc::Sender<DriveChangeEvent>,
+    receiver: mpsc::Receiver<DriveChangeEvent>,
+    watchers: HashMap<char, DriveWatcher>,
+    poll_interval: Duration,
+}
+
+impl MultiChangeMonitor {
+    /// Create a monitor that is not watching any drive yet
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            watchers: HashMap::new(),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Set how often each drive's journal is polled (applies to drives started afterwards)
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Create a monitor watching every drive in `drive_letters`.
+    /// Fails on the first drive whose journal cannot be opened.
+    pub fn for_drives(drive_letters: &[char]) -> Result<Self> {
+        let mut monitor = Self::new();
+        for &letter in drive_letters {
+            monitor.start(letter)?;
+        }
+        Ok(monitor)
+    }
+
+    /// Start watching a drive. Does nothing if it is already watched.
+    pub fn start(&mut self, drive_letter: char) -> Result<()> {
+        let drive_letter = drive_letter.to_ascii_uppercase();
+        if self.watchers.contains_key(&drive_letter) {
+            return Ok(());
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
+        let sender = self.sender.clone();
+        let thread_stop = stop.clone();
+        let poll_interval = self.poll_interval;
+
+        // The monitor is created on its own thread so the volume handle never
+        // crosses threads; the result of opening it is reported back first
+        let thread = std::thread::spawn(move || {
+            let mut monitor = match ChangeMonitor::new(drive_letter) {
+                Ok(monitor) => {
+                    let _ = ready_tx.send(Ok(()));
+                    monitor
                 }
-                crate::ntfs::ChangeReason::RenamedTo => {
-                    // Update name
-                    // (Would need mutable access pattern)
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
                 }
-                _ => {
-                    // Handle other changes
+            };
+
+            while !thread_stop.load(Ordering::SeqCst) {
+                match monitor.poll_changes() {
+                    Ok(events) => {
+                        for event in events {
+                            let tagged = DriveChangeEvent { drive_letter, event };
+                            if sender.send(tagged).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        logging::warn("SCANNER", &format!(
+                            "Stopped watching {}: {}", drive_letter, e
+                        ));
+                        return;
+                    }
                 }
+                std::thread::sleep(poll_interval);
+            }
+        });
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => {
+                logging::info("SCANNER", &format!("Watching {} for changes", drive_letter));
+                self.watchers.insert(
+                    drive_letter,
+                    DriveWatcher {
+                        stop,
+                        thread: Some(thread),
+                    },
+                );
+                Ok(())
+            }
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => Err(EmFitError::UsnJournalNotActive(drive_letter.to_string())),
+        }
+    }
+
+    /// Stop watching a drive. Returns false if it was not being watched.
+    /// Events already queued for the drive are still delivered.
+    pub fn stop(&mut self, drive_letter: char) -> bool {
+        match self.watchers.remove(&drive_letter.to_ascii_uppercase()) {
+            Some(watcher) => {
+                watcher.stop();
+                true
             }
+            None => false,
+        }
+    }
+
+    /// Stop watching all drives
+    pub fn stop_all(&mut self) {
+        for (_, watcher) in self.watchers.drain() {
+            watcher.stop();
         }
+    }
 
-        Ok(count)
+    /// Check if a drive is being watched
+    pub fn is_watching(&self, drive_letter: char) -> bool {
+        self.watchers.contains_key(&drive_letter.to_ascii_uppercase())
+    }
+
+    /// Drives currently being watched, in alphabetical order
+    pub fn drives(&self) -> Vec<char> {
+        let mut drives: Vec<char> = self.watchers.keys().copied().collect();
+        drives.sort_unstable();
+        drives
+    }
+
+    /// Next event if one is queued
+    pub fn try_recv(&self) -> Option<DriveChangeEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Wait up to `timeout` for the next event
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<DriveChangeEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// All queued events, oldest first
+    pub fn drain(&self) -> Vec<DriveChangeEvent> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Default for MultiChangeMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for MultiChangeMonitor {
+    fn drop(&mut self) {
+        self.stop_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_of_another_volume_or_mft_size_does_not_match() {
+        let volume_data = NtfsVolumeData {
+            volume_serial_number: 0x1234,
+            mft_valid_data_length: 1 << 20,
+            ..Default::default()
+        };
+        let checkpoint = ScanCheckpoint::new('C', 1024, &volume_data);
+        assert!(checkpoint.matches('C', 1024, &volume_data));
+        assert!(!checkpoint.matches('D', 1024, &volume_data));
+
+        let other_volume = NtfsVolumeData { volume_serial_number: 0x5678, ..volume_data.clone() };
+        assert!(!checkpoint.matches('C', 1024, &other_volume));
+
+        let grown = NtfsVolumeData { mft_valid_data_length: (1 << 20) + 1024, ..volume_data };
+        assert!(!checkpoint.matches('C', 1024, &grown));
     }
 }
@@ -0,0 +1,1207 @@
+//! Trigram Search Index
+//!
+//! In-memory name index for fast substring/wildcard search across a volume.
+//! Every name is broken into overlapping 3-character trigrams; a query only
+//! verifies entries that contain all trigrams of its literal fragments.
+//!
+//! The index can be kept live by feeding it `ChangeEvent`s from the USN
+//! monitor, so creates/deletes/renames are applied without a rebuild.
+//!
+//! `CompositeIndex` holds one index per drive, each behind its own lock, so
+//! a drive's change events never wait on another drive's shard.
+
+use crate::file_tree::{AttributeFilter, FileTree, NodeKey, TreeNode};
+use crate::logging;
+use crate::ntfs::structs::file_attributes;
+use crate::ntfs::{ChangeEvent, ChangeReason};
+use crate::scanner::DriveChangeEvent;
+use crate::threads;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Trigram key (three lowercase characters)
+type Trigram = [char; 3];
+
+// ============================================================================
+// Index Entry
+// ============================================================================
+
+/// A single indexed file or directory
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    pub record_number: u64,
+    pub parent_record_number: u64,
+    pub name: String,
+    pub name_lower: String,
+    pub file_size: u64,
+    pub modification_time: u64,
+    pub is_directory: bool,
+    /// File attributes (for `attr:`)
+    pub attributes: u32,
+    /// Has alternate data streams (for `attr:ads`)
+    pub has_streams: bool,
+}
+
+impl IndexEntry {
+    /// Create from a TreeNode
+    pub fn from_tree_node(node: &TreeNode) -> Self {
+        Self {
+            record_number: node.record_number,
+            parent_record_number: node.parent_record_number,
+            name: node.name.clone(),
+            name_lower: node.name.to_lowercase(),
+            file_size: node.file_size,
+            modification_time: node.modification_time,
+            is_directory: node.is_directory,
+            attributes: node.attributes,
+            has_streams: node.alternate_stream_count > 0,
+        }
+    }
+
+    /// Create from a USN change event (no size info)
+    pub fn from_change_event(event: &ChangeEvent) -> Self {
+        Self {
+            record_number: event.record_number,
+            parent_record_number: event.parent_record_number,
+            name: event.name.clone(),
+            name_lower: event.name.to_lowercase(),
+            file_size: 0,
+            modification_time: event.timestamp,
+            is_directory: (event.attributes & file_attributes::DIRECTORY) != 0,
+            attributes: event.attributes,
+            has_streams: false,
+        }
+    }
+
+    /// Composite key (record_number, parent_record_number)
+    pub fn key(&self) -> NodeKey {
+        NodeKey::new(self.record_number, self.parent_record_number)
+    }
+}
+
+// ============================================================================
+// Query
+// ============================================================================
+
+/// A parsed search query: one or more `;`-separated wildcard patterns plus
+/// optional inline operators. An entry matches if it matches any pattern and
+/// every operator.
+///
+/// Operators are whitespace-separated terms anywhere in the query:
+/// - `size:>100mb`, `size:<=1kb`, `size:10mb..1gb`, `size:0`
+/// - `modified:>2024-01-01`, `modified:<2024-06-30`, `modified:2024-01-01..2024-03-31`,
+///   `modified:2024-05-17` (that whole day)
+/// - `ext:pdf`, `ext:jpg;png` (repeatable)
+/// - `attr:hidden`, `attr:system;compressed`, `attrib:ads` (repeatable, all
+///   must be set; names as in `AttributeFilter::NAMES`)
+/// - `path:users\me\appdata`: match the patterns against the full path
+///   instead of the name (a bare `path:` applies to the rest of the query).
+///   Plain text matches whole path components anywhere in the path; see
+///   `matches_path`
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// Lowercased patterns, matched by `matches_pattern`
+    pub patterns: Vec<String>,
+    /// `size:` bounds in bytes
+    pub size: Option<QueryRange>,
+    /// `modified:` bounds as FILETIME
+    pub modified: Option<QueryRange>,
+    /// `ext:` extensions, lowercase without the dot
+    pub extensions: Vec<String>,
+    /// `attr:` attributes that must all be set
+    pub attributes: AttributeFilter,
+    /// `path:` given. Only the UIs honour this; `SearchIndex` indexes names.
+    pub match_path: bool,
+}
+
+impl SearchQuery {
+    /// Check if the query has any `size:`, `modified:`, `ext:` or `attr:` operator
+    pub fn has_operators(&self) -> bool {
+        self.size.is_some()
+            || self.modified.is_some()
+            || !self.extensions.is_empty()
+            || !self.attributes.is_empty()
+    }
+
+    /// Check an entry against the operators (patterns are not checked).
+    /// `extension` is the lowercase extension without the dot.
+    pub fn matches_operators(
+        &self,
+        extension: &str,
+        file_size: u64,
+        modification_time: u64,
+        attributes: u32,
+        has_streams: bool,
+    ) -> bool {
+        if !self.attributes.matches_flags(attributes, has_streams) {
+            return false;
+        }
+        if let Some(range) = self.size {
+            if !range.contains(file_size) {
+                return false;
+            }
+        }
+        if let Some(range) = self.modified {
+            if modification_time == 0 || !range.contains(modification_time) {
+                return false;
+            }
+        }
+        self.extensions.is_empty() || self.extensions.iter().any(|e| e == extension)
+    }
+}
+
+/// Inclusive bounds for a `size:` or `modified:` operator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryRange {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+impl QueryRange {
+    /// Check if a value lies within the bounds
+    pub fn contains(&self, value: u64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+/// Parse a query string like `*.cpp; *.h; Makefile` or `report size:>5mb ext:pdf attr:hidden`.
+/// Operator terms that fail to parse are kept as ordinary text.
+pub fn parse_query(query: &str) -> SearchQuery {
+    let mut parsed = SearchQuery::default();
+    let mut text: Vec<&str> = Vec::new();
+
+    for term in query.split_whitespace() {
+        // `path:` is a modifier, not a filter: its value stays a pattern
+        if let Some((name, value)) = term.split_once(':') {
+            if name.eq_ignore_ascii_case("path") {
+                parsed.match_path = true;
+                if !value.is_empty() {
+                    text.push(value);
+                }
+                continue;
+            }
+        }
+        if !parse_operator(term, &mut parsed) {
+            text.push(term);
+        }
+    }
+
+    parsed.patterns = text
+        .join(" ")
+        .split(';')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+    parsed
+}
+
+/// Apply a single `name:value` operator term. Returns false if `term` is not
+/// a recognised operator.
+fn parse_operator(term: &str, query: &mut SearchQuery) -> bool {
+    let Some((name, value)) = term.split_once(':') else {
+        return false;
+    };
+    match name.to_lowercase().as_str() {
+        "size" => match parse_range(value, parse_size, |max| max) {
+            Some(range) => {
+                query.size = Some(range);
+                true
+            }
+            None => false,
+        },
+        "modified" | "dm" => match parse_range(value, parse_date, |start| start + FILETIME_DAY - 1) {
+            Some(range) => {
+                query.modified = Some(range);
+                true
+            }
+            None => false,
+        },
+        "ext" => {
+            let exts: Vec<String> = value
+                .split([';', ','])
+                .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect();
+            if exts.is_empty() {
+                return false;
+            }
+            query.extensions.extend(exts);
+            true
+        }
+        "attr" | "attrib" => {
+            // All names must be known, or the term stays text
+            let mut filter = query.attributes;
+            let mut any = false;
+            for attr in value.split([';', ',']).filter(|a| !a.is_empty()) {
+                if !filter.add(attr) {
+                    return false;
+                }
+                any = true;
+            }
+            if any {
+                query.attributes = filter;
+            }
+            any
+        }
+        _ => false,
+    }
+}
+
+/// 100ns intervals in one day
+const FILETIME_DAY: u64 = 24 * 60 * 60 * 10_000_000;
+
+/// Parse `>x`, `>=x`, `<x`, `<=x`, `a..b` or a bare `x`.
+/// `upper` turns a parsed value into the last value it covers (a date's
+/// start of day into its end of day), used for `<=`, `..b` and bare values.
+fn parse_range(
+    value: &str,
+    parse: fn(&str) -> Option<u64>,
+    upper: fn(u64) -> u64,
+) -> Option<QueryRange> {
+    let range = if let Some(v) = value.strip_prefix(">=") {
+        QueryRange { min: Some(parse(v)?), max: None }
+    } else if let Some(v) = value.strip_prefix("<=") {
+        QueryRange { min: None, max: Some(upper(parse(v)?)) }
+    } else if let Some(v) = value.strip_prefix('>') {
+        QueryRange { min: Some(upper(parse(v)?).saturating_add(1)), max: None }
+    } else if let Some(v) = value.strip_prefix('<') {
+        QueryRange { min: None, max: Some(parse(v)?.checked_sub(1)?) }
+    } else if let Some((a, b)) = value.split_once("..") {
+        QueryRange { min: Some(parse(a)?), max: Some(upper(parse(b)?)) }
+    } else {
+        let v = parse(value)?;
+        QueryRange { min: Some(v), max: Some(upper(v)) }
+    };
+    Some(range)
+}
+
+/// Parse a size like `100mb`, `1.5gb`, `512k` or `4096` into bytes
+fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim().to_lowercase();
+    let split = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let multiplier: u64 = match unit.trim() {
+        "" | "b" => 1,
+        "k" | "kb" => 1 << 10,
+        "m" | "mb" => 1 << 20,
+        "g" | "gb" => 1 << 30,
+        "t" | "tb" => 1 << 40,
+        _ => return None,
+    };
+    let n: f64 = num.parse().ok()?;
+    Some((n * multiplier as f64) as u64)
+}
+
+/// Parse a `YYYY-MM-DD` date into a FILETIME at the start of that day (UTC)
+fn parse_date(s: &str) -> Option<u64> {
+    let date = chrono::NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()?;
+    let unix_secs = date.and_hms_opt(0, 0, 0)?.and_utc().timestamp();
+    // Seconds between 1601-01-01 and 1970-01-01
+    u64::try_from(unix_secs + 11_644_473_600).ok().map(|secs| secs * 10_000_000)
+}
+
+/// Match a lowercase name against a search pattern. The same rules apply
+/// in the index, both UIs and `FileTree::search`:
+///
+/// - Plain text must match the whole name: `report.pdf` finds only files
+///   named exactly that. `*report*` finds it anywhere in the name.
+/// - A single anchor pins plain text to one end and leaves the other open:
+///   `^report` starts with "report", `.pdf$` ends with ".pdf".
+///   `^report.pdf$` is the same as `report.pdf`.
+/// - A pattern with `*` (any run) or `?` (any one character) must match the
+///   whole name too: `report*` starts with "report", `*.pdf` ends with
+///   ".pdf", `r*t.pdf` does both.
+pub fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let (literal, at_start, at_end) = split_anchors(pattern);
+    if literal.contains(['*', '?']) {
+        return wildcard_match(name, literal);
+    }
+    match (at_start, at_end) {
+        (true, false) => name.starts_with(literal),
+        (false, true) => name.ends_with(literal),
+        _ => name == literal,
+    }
+}
+
+/// Match a lowercase full path against a pattern for `path:` searches.
+/// Plain text without anchors or wildcards matches a run of whole path
+/// components anywhere in the path, so `users\me\appdata` finds
+/// `c:\users\me\appdata\local` but not `c:\users\me\appdata2`.
+/// Anything else is matched against the whole path as `matches_pattern` does.
+pub fn matches_path(full_path: &str, pattern: &str) -> bool {
+    let (literal, at_start, at_end) = split_anchors(pattern);
+    if at_start || at_end || literal.contains(['*', '?']) {
+        return matches_pattern(full_path, pattern);
+    }
+    let literal = literal.trim_matches('\\');
+    if literal.is_empty() {
+        return false;
+    }
+    full_path.match_indices(literal).any(|(start, _)| {
+        let end = start + literal.len();
+        (start == 0 || full_path[..start].ends_with('\\'))
+            && (end == full_path.len() || full_path[end..].starts_with('\\'))
+    })
+}
+
+/// Strip a leading `^` and a trailing `$` from a pattern. Returns the rest
+/// and whether each anchor was there.
+pub fn split_anchors(pattern: &str) -> (&str, bool, bool) {
+    let (pattern, at_start) = match pattern.strip_prefix('^') {
+        Some(rest) => (rest, true),
+        None => (pattern, false),
+    };
+    let (pattern, at_end) = match pattern.strip_suffix('$') {
+        Some(rest) => (rest, true),
+        None => (pattern, false),
+    };
+    (pattern, at_start, at_end)
+}
+
+/// Find the byte range in `name` to highlight for `pattern`, if it matches.
+/// That is the text the pattern spells out: plain text where an anchor
+/// pinned it (the whole name without one), and for wildcard patterns
+/// everything from the first to the last character matched by literal
+/// text, as `matches_pattern` matched it (`r*t.pdf` covers all of
+/// `report.pdf`, `*rep*ort*` runs from "rep" to "ort"). Returns None for
+/// patterns without literal text (`*`, `??`) or that don't match at all.
+pub fn match_range(name: &str, pattern: &str) -> Option<(usize, usize)> {
+    let (literal, at_start, at_end) = split_anchors(pattern);
+    if literal.contains(['*', '?']) {
+        return wildcard_span(name, literal).flatten();
+    }
+    if literal.is_empty() || !matches_pattern(name, pattern) {
+        return None;
+    }
+    let start = if at_end && !at_start {
+        name.len() - literal.len()
+    } else {
+        0
+    };
+    Some((start, start + literal.len()))
+}
+
+/// Rank how well `name_lower` matches the best of `patterns`; lower is better.
+///
+/// Sorts by tier (0 whole name or stem, 1 prefix, 2 start of a word,
+/// 3 anywhere else, 4 wildcard-only), then match position, then how many
+/// characters the match leaves over, so `setup.exe` beats `mysetup_old.exe`.
+pub fn relevance_rank(name_lower: &str, patterns: &[&str]) -> (u8, usize, usize) {
+    let stem_len = name_lower
+        .rfind('.')
+        .filter(|&i| i > 0)
+        .unwrap_or(name_lower.len());
+
+    patterns
+        .iter()
+        .map(|pattern| match match_range(name_lower, pattern) {
+            Some((start, end)) => {
+                let tier = if start == 0 && (end == name_lower.len() || end == stem_len) {
+                    0
+                } else if start == 0 {
+                    1
+                } else if !name_lower[..start].ends_with(|c: char| c.is_alphanumeric()) {
+                    2
+                } else {
+                    3
+                };
+                (tier, start, name_lower.len() - (end - start))
+            }
+            None => (4, 0, name_lower.len()),
+        })
+        .min()
+        .unwrap_or((4, 0, name_lower.len()))
+}
+
+/// How results of a text query are ordered before the user clicks a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultOrder {
+    /// Exact and prefix matches first, then by match position
+    #[default]
+    Relevance,
+    Name,
+    SizeDescending,
+}
+
+impl ResultOrder {
+    pub const ALL: [ResultOrder; 3] =
+        [ResultOrder::Relevance, ResultOrder::Name, ResultOrder::SizeDescending];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResultOrder::Relevance => "Relevance",
+            ResultOrder::Name => "Name",
+            ResultOrder::SizeDescending => "Size",
+        }
+    }
+}
+
+/// Row for type-ahead over `rows` results: the first after `selected`
+/// (wrapping) whose lowercase name starts with `c`
+pub fn next_row_by_initial<'a>(
+    rows: usize,
+    selected: Option<usize>,
+    c: char,
+    name_lower: impl Fn(usize) -> &'a str,
+) -> Option<usize> {
+    let initial: String = c.to_lowercase().collect();
+    let start = selected.map_or(0, |row| row + 1);
+    (0..rows)
+        .map(|offset| (start + offset) % rows)
+        .find(|&row| name_lower(row).starts_with(&initial))
+}
+
+/// Date filter of the search filters dialog in both UIs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFilterMode {
+    None,
+    After,
+    Before,
+    Between,
+}
+
+impl DateFilterMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateFilterMode::None => "None",
+            DateFilterMode::After => "After",
+            DateFilterMode::Before => "Before",
+            DateFilterMode::Between => "Between",
+        }
+    }
+
+    pub fn all() -> &'static [DateFilterMode] {
+        &[
+            DateFilterMode::None,
+            DateFilterMode::After,
+            DateFilterMode::Before,
+            DateFilterMode::Between,
+        ]
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            DateFilterMode::None => DateFilterMode::After,
+            DateFilterMode::After => DateFilterMode::Before,
+            DateFilterMode::Before => DateFilterMode::Between,
+            DateFilterMode::Between => DateFilterMode::None,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            DateFilterMode::None => DateFilterMode::Between,
+            DateFilterMode::After => DateFilterMode::None,
+            DateFilterMode::Before => DateFilterMode::After,
+            DateFilterMode::Between => DateFilterMode::Before,
+        }
+    }
+}
+
+/// Size filter of the search filters dialog in both UIs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeFilterMode {
+    None,
+    GreaterThan,
+    LessThan,
+    Between,
+}
+
+impl SizeFilterMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SizeFilterMode::None => "None",
+            SizeFilterMode::GreaterThan => ">",
+            SizeFilterMode::LessThan => "<",
+            SizeFilterMode::Between => "Between",
+        }
+    }
+
+    pub fn all() -> &'static [SizeFilterMode] {
+        &[
+            SizeFilterMode::None,
+            SizeFilterMode::GreaterThan,
+            SizeFilterMode::LessThan,
+            SizeFilterMode::Between,
+        ]
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SizeFilterMode::None => SizeFilterMode::GreaterThan,
+            SizeFilterMode::GreaterThan => SizeFilterMode::LessThan,
+            SizeFilterMode::LessThan => SizeFilterMode::Between,
+            SizeFilterMode::Between => SizeFilterMode::None,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            SizeFilterMode::None => SizeFilterMode::Between,
+            SizeFilterMode::GreaterThan => SizeFilterMode::None,
+            SizeFilterMode::LessThan => SizeFilterMode::GreaterThan,
+            SizeFilterMode::Between => SizeFilterMode::LessThan,
+        }
+    }
+}
+
+/// Compact summary of the search filters for the search bar of either UI,
+/// e.g. "[regex][>10MB][2024-01-01+]". `size` and `date` are the mode with
+/// its first and second value as typed. Empty when no filter is set.
+pub fn filter_tags(
+    regex: &str,
+    size: (SizeFilterMode, &str, &str),
+    date: (DateFilterMode, &str, &str),
+    extension: &str,
+) -> String {
+    let mut tags = String::new();
+    if !regex.is_empty() {
+        tags.push_str("[regex]");
+    }
+    match size {
+        (SizeFilterMode::None, _, _) => {}
+        (SizeFilterMode::GreaterThan, value, _) => tags.push_str(&format!("[>{}]", value)),
+        (SizeFilterMode::LessThan, value, _) => tags.push_str(&format!("[<{}]", value)),
+        (SizeFilterMode::Between, start, end) => tags.push_str(&format!("[{}-{}]", start, end)),
+    }
+    match date {
+        (DateFilterMode::None, _, _) => {}
+        (DateFilterMode::After, start, _) => tags.push_str(&format!("[{}+]", start)),
+        (DateFilterMode::Before, start, _) => tags.push_str(&format!("[..{}]", start)),
+        (DateFilterMode::Between, start, end) => tags.push_str(&format!("[{}..{}]", start, end)),
+    }
+    if !extension.is_empty() {
+        tags.push_str(&format!("[ext:{}]", extension));
+    }
+    tags
+}
+
+/// Results per pattern for the status bar of either UI ("*.tmp 40000,
+/// *.log 9000"), or None unless the last search had several patterns
+pub fn pattern_counts_label(pattern_counts: &[(String, usize)]) -> Option<String> {
+    if pattern_counts.len() < 2 {
+        return None;
+    }
+    let parts: Vec<String> = pattern_counts
+        .iter()
+        .map(|(pattern, count)| format!("{} {}", pattern, count))
+        .collect();
+    Some(parts.join(", "))
+}
+
+/// Lowercase full path from a UI's cached parent path and name, for
+/// `path:` matching. Just the name until the path cache has reached the
+/// entry.
+pub fn full_path_lower(parent_lower: &str, name_lower: &str) -> String {
+    if parent_lower.is_empty() {
+        name_lower.to_string()
+    } else if parent_lower.ends_with('\\') {
+        format!("{}{}", parent_lower, name_lower)
+    } else {
+        format!("{}\\{}", parent_lower, name_lower)
+    }
+}
+
+/// Match a whole lowercase name against a pattern with `*`/`?` wildcards
+fn wildcard_match(name: &str, pattern: &str) -> bool {
+    // `*text`, `text*` and `*text*` are common enough to skip the char walk
+    let literal = |p: &str| !p.contains(['*', '?']);
+    if let Some(rest) = pattern.strip_prefix('*') {
+        match rest.strip_suffix('*') {
+            Some(middle) if literal(middle) => return name.contains(middle),
+            None if literal(rest) => return name.ends_with(rest),
+            _ => {}
+        }
+    } else if let Some(head) = pattern.strip_suffix('*') {
+        if literal(head) {
+            return name.starts_with(head);
+        }
+    }
+
+    wildcard_span(name, pattern).is_some()
+}
+
+/// Walk a whole lowercase name against a `*`/`?` pattern. On a match,
+/// returns the byte range from the first to the last name character matched
+/// by a literal pattern character, or an inner None if the pattern has none.
+fn wildcard_span(name: &str, pattern: &str) -> Option<Option<(usize, usize)>> {
+    let name: Vec<(usize, char)> = name.char_indices().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    let (mut n, mut p) = (0, 0);
+    // First and last name positions matched by literal text so far; reset
+    // with the rest of the state whenever a `*` takes one more character
+    let mut span: Option<(usize, usize)> = None;
+    let mut star = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n].1) {
+            if pattern[p] != '?' {
+                span = Some((span.map_or(n, |(first, _)| first), n));
+            }
+            n += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n, span));
+            p += 1;
+        } else if let Some((sp, sn, saved)) = star {
+            p = sp + 1;
+            n = sn + 1;
+            span = saved;
+            star = Some((sp, sn + 1, saved));
+        } else {
+            return None;
+        }
+    }
+
+    if !pattern[p..].iter().all(|&c| c == '*') {
+        return None;
+    }
+    Some(span.map(|(first, last)| (name[first].0, name[last].0 + name[last].1.len_utf8())))
+}
+
+/// Trigrams of the literal (wildcard-free) fragments of a pattern
+fn pattern_trigrams(pattern: &str) -> HashSet<Trigram> {
+    pattern
+        .split(['*', '?'])
+        .flat_map(trigrams)
+        .collect()
+}
+
+/// All trigrams of a lowercase string
+fn trigrams(s: &str) -> Vec<Trigram> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// Lowercase extension of an entry ("" for directories and dotless names)
+fn entry_extension(entry: &IndexEntry) -> &str {
+    if entry.is_directory {
+        return "";
+    }
+    match entry.name_lower.rfind('.') {
+        Some(pos) if pos > 0 => &entry.name_lower[pos + 1..],
+        _ => "",
+    }
+}
+
+// ============================================================================
+// Search Index
+// ============================================================================
+
+/// A search hit
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    /// Drive of the index the hit came from
+    pub drive_letter: char,
+    pub entry: IndexEntry,
+    /// Full path, if the parent chain could be resolved within the index
+    pub path: Option<String>,
+}
+
+/// Trigram index over file names for a single volume
+pub struct SearchIndex {
+    drive_letter: char,
+    entries: HashMap<NodeKey, IndexEntry>,
+    /// record_number -> keys (multiple for hard links)
+    record_index: HashMap<u64, Vec<NodeKey>>,
+    postings: HashMap<Trigram, HashSet<NodeKey>>,
+}
+
+impl SearchIndex {
+    /// Create an empty index for a drive
+    pub fn new(drive_letter: char) -> Self {
+        Self {
+            drive_letter,
+            entries: HashMap::new(),
+            record_index: HashMap::new(),
+            postings: HashMap::new(),
+        }
+    }
+
+    /// Build an index from every named node in a FileTree
+    pub fn from_tree(tree: &FileTree) -> Self {
+        let mut index = Self::new(tree.drive_letter);
+        for entry in tree.iter_entries() {
+            let node = entry.value();
+            index.add(IndexEntry::from_tree_node(node));
+        }
+        logging::info("SEARCH", &format!(
+            "Built trigram index for {}: {} entries, {} trigrams",
+            tree.drive_letter,
+            index.len(),
+            index.postings.len()
+        ));
+        index
+    }
+
+    /// Drive letter this index covers
+    pub fn drive_letter(&self) -> char {
+        self.drive_letter
+    }
+
+    /// Number of indexed entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if index is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add (or replace) an entry
+    pub fn add(&mut self, entry: IndexEntry) {
+        let key = entry.key();
+        self.remove_key(&key);
+
+        for tri in trigrams(&entry.name_lower) {
+            self.postings.entry(tri).or_default().insert(key);
+        }
+        self.record_index
+            .entry(entry.record_number)
+            .or_default()
+            .push(key);
+        self.entries.insert(key, entry);
+    }
+
+    /// Remove a single entry by key. Returns the removed entry.
+    pub fn remove_key(&mut self, key: &NodeKey) -> Option<IndexEntry> {
+        let entry = self.entries.remove(key)?;
+
+        for tri in trigrams(&entry.name_lower) {
+            if let Some(set) = self.postings.get_mut(&tri) {
+                set.remove(key);
+                if set.is_empty() {
+                    self.postings.remove(&tri);
+                }
+            }
+        }
+        if let Some(keys) = self.record_index.get_mut(&key.record_number) {
+            keys.retain(|k| k != key);
+            if keys.is_empty() {
+                self.record_index.remove(&key.record_number);
+            }
+        }
+
+        Some(entry)
+    }
+
+    /// Remove all entries (hard links) for a record number
+    pub fn remove_record(&mut self, record_number: u64) -> usize {
+        let keys = self.record_index.get(&record_number).cloned().unwrap_or_default();
+        keys.iter().filter(|k| self.remove_key(k).is_some()).count()
+    }
+
+    /// Apply a USN change event without rebuilding the index.
+    /// Returns true if the index was modified.
+    pub fn apply_change(&mut self, event: &ChangeEvent) -> bool {
+        match event.reason {
+            ChangeReason::Created | ChangeReason::RenamedTo => {
+                self.add(IndexEntry::from_change_event(event));
+                true
+            }
+            ChangeReason::Deleted => self.remove_record(event.record_number) > 0,
+            ChangeReason::RenamedFrom => {
+                // The old name is reported with its old parent
+                let key = NodeKey::new(event.record_number, event.parent_record_number);
+                self.remove_key(&key).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Search the index, returning at most `max_results` hits. Hits come
+    /// pattern by pattern, each in record-number order, so the same query
+    /// over the same index always returns the same hits.
+    pub fn search(&self, query: &SearchQuery, max_results: usize) -> Vec<SearchHit> {
+        let mut hits = Vec::new();
+        if max_results == 0 {
+            return hits;
+        }
+
+        self.for_each_match(query, |key, entry| {
+            hits.push(SearchHit {
+                drive_letter: self.drive_letter,
+                entry: entry.clone(),
+                path: self.build_path(key),
+            });
+            hits.len() < max_results
+        });
+
+        hits
+    }
+
+    /// Search the index and return one page of hits plus the total number
+    /// of matches. Hits are ordered by name (then key) so pages stay stable
+    /// between calls; paths are only resolved for the returned page.
+    pub fn search_paged(
+        &self,
+        query: &SearchQuery,
+        offset: usize,
+        limit: usize,
+    ) -> (Vec<SearchHit>, usize) {
+        let mut matches: Vec<(&str, NodeKey)> = Vec::new();
+        self.for_each_match(query, |key, entry| {
+            matches.push((&entry.name_lower, *key));
+            true
+        });
+
+        let total = matches.len();
+        let end = offset.saturating_add(limit).min(total);
+        if offset >= end {
+            return (Vec::new(), total);
+        }
+
+        // Only the first `end` matches need to be sorted
+        let by_name = |a: &(&str, NodeKey), b: &(&str, NodeKey)| {
+            a.0.cmp(b.0)
+                .then(a.1.record_number.cmp(&b.1.record_number))
+                .then(a.1.parent_record_number.cmp(&b.1.parent_record_number))
+        };
+        if end < total {
+            matches.select_nth_unstable_by(end, by_name);
+        }
+        let page = &mut matches[..end];
+        page.sort_unstable_by(by_name);
+
+        let hits = page[offset..]
+            .iter()
+            .filter_map(|(_, key)| {
+                self.entries.get(key).map(|entry| SearchHit {
+                    drive_letter: self.drive_letter,
+                    entry: entry.clone(),
+                    path: self.build_path(key),
+                })
+            })
+            .collect();
+        (hits, total)
+    }
+
+    /// Call `on_match` once for every entry matching the query.
+    /// Returning false from `on_match` stops the search.
+    fn for_each_match<'a, F>(&'a self, query: &SearchQuery, mut on_match: F)
+    where
+        F: FnMut(&NodeKey, &'a IndexEntry) -> bool,
+    {
+        let mut seen: HashSet<NodeKey> = HashSet::new();
+
+        // Operator-only queries ("size:>1gb") scan every entry
+        let match_all = [String::from("*")];
+        let patterns: &[String] = if query.patterns.is_empty() && query.has_operators() {
+            &match_all
+        } else {
+            &query.patterns
+        };
+
+        for pattern in patterns {
+            for key in self.candidates(pattern) {
+                if seen.contains(&key) {
+                    continue;
+                }
+                if let Some(entry) = self.entries.get(&key) {
+                    if matches_pattern(&entry.name_lower, pattern)
+                        && query.matches_operators(
+                            entry_extension(entry),
+                            entry.file_size,
+                            entry.modification_time,
+                            entry.attributes,
+                            entry.has_streams,
+                        )
+                    {
+                        seen.insert(key);
+                        if !on_match(&key, entry) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Candidate keys for a pattern: intersection of its trigram postings,
+    /// or every entry if the pattern has no 3-character literal fragment.
+    /// Sorted by record (then parent), since the sets iterate in no
+    /// particular order.
+    fn candidates(&self, pattern: &str) -> Vec<NodeKey> {
+        let tris = pattern_trigrams(split_anchors(pattern).0);
+        let mut keys: Vec<NodeKey> = if tris.is_empty() {
+            self.entries.keys().copied().collect()
+        } else {
+            let mut sets: Vec<&HashSet<NodeKey>> = Vec::with_capacity(tris.len());
+            for tri in &tris {
+                match self.postings.get(tri) {
+                    Some(set) => sets.push(set),
+                    None => return Vec::new(),
+                }
+            }
+            sets.sort_by_key(|s| s.len());
+
+            sets[0]
+                .iter()
+                .filter(|k| sets[1..].iter().all(|s| s.contains(k)))
+                .copied()
+                .collect()
+        };
+        keys.sort_unstable_by_key(|k| (k.record_number, k.parent_record_number));
+        keys
+    }
+
+    /// Build a full path by walking parents within the index.
+    /// Returns None if a parent is missing.
+    pub fn build_path(&self, key: &NodeKey) -> Option<String> {
+        let entry = self.entries.get(key)?;
+        let mut parts = vec![entry.name.clone()];
+        let mut current = entry.parent_record_number;
+        let mut depth = 0;
+
+        while current != 5 && current != 0 {
+            let parent_key = self.record_index.get(&current)?.first()?;
+            let parent = self.entries.get(parent_key)?;
+            parts.push(parent.name.clone());
+            current = parent.parent_record_number;
+
+            // Guard against parent cycles
+            depth += 1;
+            if depth > 1024 {
+                return None;
+            }
+        }
+
+        parts.reverse();
+        Some(format!("{}:\\{}", self.drive_letter, parts.join("\\")))
+    }
+}
+
+// ============================================================================
+// Composite Index
+// ============================================================================
+
+/// Search indexes for several drives, one shard per drive.
+///
+/// Each shard sits behind its own lock, so applying a drive's change events
+/// only blocks that drive; searches fan out over all shards in parallel and
+/// merge the hits drive by drive.
+#[derive(Default)]
+pub struct CompositeIndex {
+    /// (uppercase drive letter, shard), sorted by letter. The letter is kept
+    /// outside the lock so finding a shard never waits on another drive.
+    shards: Vec<(char, RwLock<SearchIndex>)>,
+}
+
+impl CompositeIndex {
+    /// Create an index with no drives
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build one shard per tree
+    pub fn from_trees<'a>(trees: impl IntoIterator<Item = &'a FileTree>) -> Self {
+        let mut index = Self::new();
+        for tree in trees {
+            index.insert(SearchIndex::from_tree(tree));
+        }
+        index
+    }
+
+    /// Add a drive's index, replacing the shard it had before
+    pub fn insert(&mut self, shard: SearchIndex) -> Option<SearchIndex> {
+        let letter = shard.drive_letter().to_ascii_uppercase();
+        match self.position(letter) {
+            Ok(pos) => {
+                let slot = self.shards[pos].1.get_mut().unwrap_or_else(PoisonError::into_inner);
+                Some(std::mem::replace(slot, shard))
+            }
+            Err(pos) => {
+                self.shards.insert(pos, (letter, RwLock::new(shard)));
+                None
+            }
+        }
+    }
+
+    /// Drop a drive's shard
+    pub fn remove(&mut self, drive_letter: char) -> Option<SearchIndex> {
+        let pos = self.position(drive_letter.to_ascii_uppercase()).ok()?;
+        Some(
+            self.shards
+                .remove(pos)
+                .1
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Drive letters with a shard, in order
+    pub fn drives(&self) -> Vec<char> {
+        self.shards.iter().map(|(letter, _)| *letter).collect()
+    }
+
+    /// Number of indexed entries across all drives
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|(_, s)| read(s).len()).sum()
+    }
+
+    /// Check if no drive has any entry
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|(_, s)| read(s).is_empty())
+    }
+
+    /// Apply a USN change event to its drive's shard, locking only that
+    /// shard. Returns true if the shard was modified; events for drives
+    /// without a shard are ignored.
+    pub fn apply_change(&self, drive_letter: char, event: &ChangeEvent) -> bool {
+        self.with_shard_mut(drive_letter, |shard| shard.apply_change(event))
+            .unwrap_or(false)
+    }
+
+    /// Apply an event from `MultiChangeMonitor` to its drive's shard
+    pub fn apply_drive_change(&self, change: &DriveChangeEvent) -> bool {
+        self.apply_change(change.drive_letter, &change.event)
+    }
+
+    /// Run `f` on one drive's shard under its read lock
+    pub fn with_shard<R>(&self, drive_letter: char, f: impl FnOnce(&SearchIndex) -> R) -> Option<R> {
+        let pos = self.position(drive_letter.to_ascii_uppercase()).ok()?;
+        Some(f(&read(&self.shards[pos].1)))
+    }
+
+    /// Run `f` on one drive's shard under its write lock; the other shards
+    /// stay available to searches and updates meanwhile
+    pub fn with_shard_mut<R>(
+        &self,
+        drive_letter: char,
+        f: impl FnOnce(&mut SearchIndex) -> R,
+    ) -> Option<R> {
+        let pos = self.position(drive_letter.to_ascii_uppercase()).ok()?;
+        Some(f(&mut write(&self.shards[pos].1)))
+    }
+
+    /// Search every drive, returning at most `max_results` hits. Hits take
+    /// turns by drive (in drive-letter order), so when the cap is reached
+    /// every drive with matches still has its share.
+    pub fn search(&self, query: &SearchQuery, max_results: usize) -> Vec<SearchHit> {
+        let per_drive: Vec<Vec<SearchHit>> = threads::with_workers(self.shards.len(), |workers| {
+            self.shards
+                .par_iter()
+                .with_min_len(self.shards.len().div_ceil(workers).max(1))
+                .map(|(_, s)| read(s).search(query, max_results))
+                .collect()
+        });
+
+        let total = per_drive.iter().map(Vec::len).sum::<usize>().min(max_results);
+        let mut hits = Vec::with_capacity(total);
+        let mut drives: Vec<_> = per_drive.into_iter().map(Vec::into_iter).collect();
+        while hits.len() < total {
+            for drive in &mut drives {
+                if hits.len() == total {
+                    break;
+                }
+                hits.extend(drive.next());
+            }
+        }
+        hits
+    }
+
+    /// One page of hits across all drives plus the total number of matches,
+    /// ordered by name (then drive and key) like `SearchIndex::search_paged`.
+    /// Each shard resolves up to `offset + limit` paths, so deep pages cost
+    /// more than on a single index.
+    pub fn search_paged(
+        &self,
+        query: &SearchQuery,
+        offset: usize,
+        limit: usize,
+    ) -> (Vec<SearchHit>, usize) {
+        let end = offset.saturating_add(limit);
+        let per_drive: Vec<(Vec<SearchHit>, usize)> = threads::with_workers(self.shards.len(), |workers| {
+            self.shards
+                .par_iter()
+                .with_min_len(self.shards.len().div_ceil(workers).max(1))
+                .map(|(_, s)| read(s).search_paged(query, 0, end))
+                .collect()
+        });
+
+        let total = per_drive.iter().map(|(_, count)| count).sum();
+        let mut hits: Vec<SearchHit> = per_drive.into_iter().flat_map(|(h, _)| h).collect();
+        hits.sort_by(|a, b| {
+            a.entry
+                .name_lower
+                .cmp(&b.entry.name_lower)
+                .then(a.drive_letter.cmp(&b.drive_letter))
+                .then(a.entry.record_number.cmp(&b.entry.record_number))
+                .then(a.entry.parent_record_number.cmp(&b.entry.parent_record_number))
+        });
+        let hits = hits.into_iter().skip(offset).take(limit).collect();
+        (hits, total)
+    }
+
+    /// Shard position for an uppercase drive letter, or where it would go
+    fn position(&self, drive_letter: char) -> std::result::Result<usize, usize> {
+        self.shards.binary_search_by_key(&drive_letter, |(letter, _)| *letter)
+    }
+}
+
+/// Read-lock a shard. A panic mid-update can't leave the maps unsound, so a
+/// poisoned lock is still used rather than taking every drive down.
+fn read(shard: &RwLock<SearchIndex>) -> RwLockReadGuard<'_, SearchIndex> {
+    shard.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Write-lock a shard; see `read` about poisoning
+fn write(shard: &RwLock<SearchIndex>) -> RwLockWriteGuard<'_, SearchIndex> {
+    shard.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_row_by_initial_wraps_past_the_selection() {
+        let names = ["alpha", "beta", "apple", "cherry"];
+        let name = |row: usize| names[row];
+        assert_eq!(next_row_by_initial(4, None, 'A', name), Some(0));
+        assert_eq!(next_row_by_initial(4, Some(0), 'a', name), Some(2));
+        assert_eq!(next_row_by_initial(4, Some(2), 'a', name), Some(0));
+        assert_eq!(next_row_by_initial(4, Some(1), 'z', name), None);
+        assert_eq!(next_row_by_initial(0, None, 'a', name), None);
+    }
+
+    #[test]
+    fn match_range_covers_interior_wildcards() {
+        assert_eq!(match_range("report.pdf", "r*t.pdf"), Some((0, 10)));
+        assert_eq!(match_range("my report.pdf", "*rep*ort*"), Some((3, 9)));
+        assert_eq!(match_range("setup.exe", "s?tup*"), Some((0, 5)));
+        assert_eq!(match_range("notes.txt", "*"), None);
+        assert_eq!(match_range("notes.txt", "r*t.pdf"), None);
+    }
+
+    #[test]
+    fn match_range_follows_anchors_for_plain_text() {
+        assert_eq!(match_range("report.pdf", "^rep"), Some((0, 3)));
+        assert_eq!(match_range("report.pdf", ".pdf$"), Some((6, 10)));
+        assert_eq!(match_range("report.pdf", "report.pdf"), Some((0, 10)));
+        assert_eq!(match_range("report.pdf", "rep"), None);
+    }
+
+    #[test]
+    fn path_query_matches_whole_components_of_a_full_path() {
+        let query = parse_query(r"path:users\me\appdata");
+        assert!(query.match_path);
+        let full_path = full_path_lower(r"c:\users\me\appdata\local", "temp");
+        assert!(query.patterns.iter().any(|p| matches_path(&full_path, p)));
+
+        let sibling = full_path_lower(r"c:\users\me\appdata2", "temp");
+        assert!(!query.patterns.iter().any(|p| matches_path(&sibling, p)));
+        assert!(!matches_path(&full_path, r"sers\me"));
+        assert!(matches_path(&full_path, "temp"));
+        assert!(matches_path(&full_path, r"*\appdata\*"));
+        assert!(matches_path(&full_path, r"^c:\users"));
+        assert!(!matches_path(&full_path, r"\"));
+    }
+
+    #[test]
+    fn match_range_reports_byte_offsets() {
+        assert_eq!(match_range("über.txt", "*er*"), Some((3, 5)));
+    }
+}
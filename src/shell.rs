@@ -0,0 +1,103 @@
+//! Shell Helpers
+//!
+//! File operations and consoles the UIs' action menus run on the selected
+//! items, written once here so the TUI and GUI behave the same.
+
+use crate::logging;
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::os::windows::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+
+/// How long `wt.exe`, which hands off to the running terminal and exits,
+/// gets to report a failure before it counts as started
+const TERMINAL_START_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Set or clear one attribute flag on a file or folder, returning the
+/// attributes it has afterwards
+pub fn set_attribute_flag(path: &str, flag: u32, on: bool) -> std::io::Result<u32> {
+    use windows::Win32::Storage::FileSystem::{
+        GetFileAttributesW, SetFileAttributesW, FILE_ATTRIBUTE_NORMAL, FILE_FLAGS_AND_ATTRIBUTES,
+        INVALID_FILE_ATTRIBUTES,
+    };
+    use windows::core::PCWSTR;
+
+    let file: Vec<u16> = OsStr::new(path)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    unsafe {
+        let current = GetFileAttributesW(PCWSTR(file.as_ptr()));
+        if current == INVALID_FILE_ATTRIBUTES {
+            return Err(std::io::Error::last_os_error());
+        }
+        let updated = if on { current | flag } else { current & !flag };
+        // NORMAL is only valid on its own and stands for "no attributes"
+        let value = if updated == 0 {
+            FILE_ATTRIBUTE_NORMAL
+        } else {
+            FILE_FLAGS_AND_ATTRIBUTES(updated)
+        };
+        if SetFileAttributesW(PCWSTR(file.as_ptr()), value).is_err() {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(updated)
+    }
+}
+
+/// Open a console in the item's directory (its parent, for files).
+/// Prefers Windows Terminal and falls back to cmd, also when Windows
+/// Terminal starts but exits with an error. Fails if the directory is gone
+/// or neither console could be started.
+pub fn open_terminal_here(path: &str) -> std::io::Result<()> {
+    let path = Path::new(path);
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    if !dir.is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} is not a folder", dir.display()),
+        ));
+    }
+
+    match start_windows_terminal(dir) {
+        Ok(()) => return Ok(()),
+        Err(e) => logging::warn(
+            "SHELL",
+            &format!("Windows Terminal failed for {}, using cmd: {}", dir.display(), e),
+        ),
+    }
+    Command::new("cmd.exe")
+        .current_dir(dir)
+        .creation_flags(CREATE_NEW_CONSOLE)
+        .spawn()
+        .map(drop)
+}
+
+/// Run `wt.exe -d dir` and wait briefly for it to hand off
+fn start_windows_terminal(dir: &Path) -> std::io::Result<()> {
+    // wt splits its command line into commands at every `;`
+    let dir = dir.to_string_lossy().replace(';', "\\;");
+    let mut child = Command::new("wt.exe").arg("-d").arg(dir).spawn()?;
+
+    let started = Instant::now();
+    while started.elapsed() < TERMINAL_START_TIMEOUT {
+        match child.try_wait()? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => {
+                return Err(std::io::Error::other(format!("wt.exe exited with {}", status)));
+            }
+            None => std::thread::sleep(Duration::from_millis(20)),
+        }
+    }
+    // Still running: it is the terminal itself
+    Ok(())
+}
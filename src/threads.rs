@@ -0,0 +1,206 @@
+//! Background Worker Threads
+//!
+//! One process-wide budget of threads for background work. Set it once at
+//! startup with `configure`; without that it is one thread per core.
+//!
+//! Every background thread holds a slot of the budget while it runs: the
+//! UIs' short jobs (sorting, metadata refresh, filter previews) through
+//! `spawn_worker`, path caching through `spawn_long_lived`, and the
+//! parallel parts of MFT parsing and multi-drive search through
+//! `with_workers`. With two or more slots one is kept for long-lived jobs,
+//! so path caching never waits behind short jobs and short jobs never
+//! wait minutes behind it.
+
+use crate::logging;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+
+/// Worker thread budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThreadConfig {
+    /// Most background threads busy at once, across all kinds of work
+    pub max_workers: usize,
+}
+
+impl ThreadConfig {
+    /// At most `max_workers` background threads (0 = one per core)
+    pub fn new(max_workers: usize) -> Self {
+        let max_workers = match max_workers {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        Self { max_workers }
+    }
+}
+
+impl Default for ThreadConfig {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Configured limit (0 until `configure` is called)
+static MAX_WORKERS: AtomicUsize = AtomicUsize::new(0);
+/// Slots currently taken
+static BUDGET: Mutex<Busy> = Mutex::new(Busy { short: 0, long: 0 });
+static SLOT_FREED: Condvar = Condvar::new();
+
+thread_local! {
+    /// Whether this thread already holds a slot, so `with_workers` counts
+    /// it rather than waiting on a budget it is itself part of
+    static HOLDS_SLOT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Apply `config` to the whole process. Call it before any work starts:
+/// rayon sizes its global pool on first use and cannot resize it later.
+pub fn configure(config: ThreadConfig) {
+    let max_workers = config.max_workers.max(1);
+    MAX_WORKERS.store(max_workers, Ordering::Relaxed);
+    if let Err(e) = rayon::ThreadPoolBuilder::new()
+        .num_threads(max_workers)
+        .build_global()
+    {
+        logging::warn("THREADS", &format!("Rayon pool already running, not resized: {}", e));
+    }
+    logging::info("THREADS", &format!("At most {} background thread(s)", max_workers));
+}
+
+/// The configured budget: the most background threads busy at once
+pub fn max_workers() -> usize {
+    match MAX_WORKERS.load(Ordering::Relaxed) {
+        0 => ThreadConfig::default().max_workers,
+        n => n,
+    }
+}
+
+/// Run `work` on a new thread once a slot is free. The thread is started
+/// right away and waits there, so the caller (usually a UI event loop)
+/// never blocks.
+pub fn spawn_worker<F>(work: F) -> JoinHandle<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let _slot = Slots::acquire(Kind::Short);
+        work();
+    })
+}
+
+/// Run long-lived, cancellable `work` on a new thread in the slot kept for
+/// such jobs (the only slot, with a budget of one). A second long job
+/// waits for the first to finish.
+pub fn spawn_long_lived<F>(work: F) -> JoinHandle<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let _slot = Slots::acquire(Kind::Long);
+        work();
+    })
+}
+
+/// Run parallel work on up to `want` slots, passing `f` how many it got
+/// (at least 1). Takes what is free once one slot is; a thread that
+/// already holds a slot counts it and never waits. `f` must keep to that
+/// many threads at once, e.g. by splitting its input into as many pieces.
+pub fn with_workers<R>(want: usize, f: impl FnOnce(usize) -> R) -> R {
+    let held = HOLDS_SLOT.get();
+    let extra = want.max(1) - usize::from(held);
+
+    let mut busy = lock();
+    if !held {
+        while busy.short_free() == 0 {
+            busy = SLOT_FREED.wait(busy).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+    let count = busy.short_free().min(extra);
+    busy.short += count;
+    drop(busy);
+
+    let _slots = Slots { kind: Kind::Short, count };
+    f(count + usize::from(held))
+}
+
+fn lock() -> MutexGuard<'static, Busy> {
+    BUDGET.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Slots in use by each kind of work
+struct Busy {
+    short: usize,
+    long: usize,
+}
+
+impl Busy {
+    /// Slots kept for long-lived jobs: one, unless that would leave none
+    fn reserved() -> usize {
+        usize::from(max_workers() >= 2)
+    }
+
+    /// Slots a short job or parallel section could take right now
+    fn short_free(&self) -> usize {
+        let max = max_workers();
+        let shared = max - Self::reserved();
+        shared
+            .saturating_sub(self.short)
+            .min(max.saturating_sub(self.short + self.long))
+    }
+
+    fn long_free(&self) -> bool {
+        let max = max_workers();
+        self.long < Self::reserved().max(1) && self.short + self.long < max
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Short,
+    Long,
+}
+
+/// Busy slots; freed when dropped, also if the work panics
+struct Slots {
+    kind: Kind,
+    count: usize,
+}
+
+impl Slots {
+    /// Wait for one slot of `kind` for the current thread
+    fn acquire(kind: Kind) -> Self {
+        let mut busy = lock();
+        loop {
+            let free = match kind {
+                Kind::Short => busy.short_free() > 0,
+                Kind::Long => busy.long_free(),
+            };
+            if free {
+                break;
+            }
+            busy = SLOT_FREED.wait(busy).unwrap_or_else(PoisonError::into_inner);
+        }
+        match kind {
+            Kind::Short => busy.short += 1,
+            Kind::Long => busy.long += 1,
+        }
+        HOLDS_SLOT.set(true);
+        Slots { kind, count: 1 }
+    }
+}
+
+impl Drop for Slots {
+    fn drop(&mut self) {
+        if self.count == 0 {
+            return;
+        }
+        let mut busy = lock();
+        match self.kind {
+            Kind::Short => busy.short -= self.count,
+            Kind::Long => busy.long -= self.count,
+        }
+        drop(busy);
+        // Short and long waiters wait on the same condvar
+        SLOT_FREED.notify_all();
+    }
+}
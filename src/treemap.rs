@@ -1,4 +1,11 @@
-use crate::file_tree::{FileTree, NodeKey};
+//! Treemap Layout
+//!
+//! Squarified treemap layout in canvas coordinates, with no UI toolkit
+//! behind it: the GUI draws the rectangles with egui and the HTML report
+//! writes them out as SVG. The TUI keeps its own cell-based layout.
+
+use crate::file_tree::{ChildSort, FileTree, NodeKey};
+use crate::SizeBasis;
 use std::sync::Arc;
 
 // ============================================================================
@@ -20,6 +27,8 @@ pub struct TreemapRect {
     /// When true the rect is a directory container whose children have been
     /// laid out inside it.
     pub children_rendered: bool,
+    /// Has the system attribute; drawn with a marker
+    pub is_system: bool,
 }
 
 /// Persistent state for the treemap view.
@@ -30,6 +39,8 @@ pub struct TreemapState {
     pub current_key: NodeKey,
     pub canvas_w: f64,
     pub canvas_h: f64,
+    /// Logical or allocated size – set before building layout.
+    pub size_basis: SizeBasis,
 }
 
 impl TreemapState {
@@ -41,6 +52,7 @@ impl TreemapState {
             current_key: NodeKey::root(),
             canvas_w: 800.0,
             canvas_h: 600.0,
+            size_basis: SizeBasis::default(),
         }
     }
 
@@ -61,7 +73,8 @@ impl TreemapState {
 
         for tree in trees {
             if let Some(root) = tree.root() {
-                let drive = format!("{}:", tree.drive_letter);
+                // Same `C:\` the paths in the results start with
+                let drive = tree.build_path_for_key(&root.key());
                 self.breadcrumb.push((root.key(), drive));
                 self.layout_children(tree, &root.key(), 0.0, 0.0, 1.0, 1.0, 0);
             }
@@ -100,32 +113,32 @@ impl TreemapState {
             return;
         }
 
-        let children = tree.get_children(parent_key);
-        let mut items: Vec<(String, u64, bool, NodeKey)> = Vec::new();
+        // Squarify needs items sorted largest-first
+        let children = tree.children(parent_key, ChildSort::Size(self.size_basis));
+        let mut items: Vec<(String, u64, bool, NodeKey, bool)> = Vec::new();
+        // Deleted files only take room inside a deleted folder, matching
+        // what FileTree::calculate_sizes counts
+        let parent_deleted = tree.get_by_key(parent_key).is_some_and(|n| n.is_deleted);
 
         for child in &children {
-            if child.name == "." || child.name == ".." {
+            if child.is_self_reference() || (child.is_deleted && !parent_deleted) {
                 continue;
             }
-            let size = if child.is_directory {
-                if child.total_size > 0 {
-                    child.total_size
-                } else {
-                    child.file_size
-                }
-            } else {
-                child.file_size
-            };
+            let size = self.size_basis.size_of(child);
             if size > 0 {
-                items.push((child.name.clone(), size, child.is_directory, child.key()));
+                items.push((
+                    child.name.clone(),
+                    size,
+                    child.is_directory,
+                    child.key(),
+                    child.is_system(),
+                ));
             }
         }
         if items.is_empty() {
             return;
         }
 
-        items.sort_by(|a, b| b.1.cmp(&a.1));
-
         let limit = match depth {
             0 => 2000,
             1 => 1000,
@@ -147,7 +160,7 @@ impl TreemapState {
     fn squarify_strip(
         &mut self,
         tree: &FileTree,
-        items: &[(String, u64, bool, NodeKey)],
+        items: &[(String, u64, bool, NodeKey, bool)],
         x: f64,
         y: f64,
         w: f64,
@@ -233,7 +246,7 @@ impl TreemapState {
     fn place_item(
         &mut self,
         tree: &FileTree,
-        item: &(String, u64, bool, NodeKey),
+        item: &(String, u64, bool, NodeKey, bool),
         x: f64,
         y: f64,
         w: f64,
@@ -256,6 +269,7 @@ impl TreemapState {
             h,
             key: item.3,
             children_rendered: can_nest,
+            is_system: item.4,
         });
 
         if can_nest {
@@ -1,17 +1,28 @@
-use crate::file_tree::NodeKey;
+use crate::arena::{ExtId, ExtensionTable, StrId, StringArena};
+use crate::file_tree::{DateField, NodeKey};
+use crate::ntfs::structs::{file_attributes, Storage};
+use crate::shell;
 use crate::tui::colors;
+use crate::palette::{self, TreemapPalette};
+use crate::search_index::{full_path_lower, matches_path, next_row_by_initial};
+use crate::threads;
+use crate::tui::glyphs::{self, Glyphs};
 use crate::tui::menu::{
-    ActionKind, ActiveMenu, ActionsMenu, ConfirmDialog, RenameDialog, SearchFiltersMenu,
-    SearchFilterField,
+    ActionKind, ActiveMenu, ActionsMenu, BatchRenameDialog, ConfirmDialog, RenameDialog,
+    SearchFiltersMenu, SearchFilterField,
 };
-use crate::tui::search::{matches_pattern, SearchState};
-use crate::tui::table::{SortColumn, SortOrder, TableState};
+use crate::tui::search::{matches_pattern, relevance_rank, ResultOrder, SearchState};
+use crate::tui::table::{SortColumn, SortOrder, TableState, HIDDEN_COLUMN};
 use crate::tui::treemap::TreemapState;
 use crate::tui::ui;
-use crate::{FileTree, MultiVolumeScanner, ScanConfig, VolumeScanner};
+use crate::{
+    FileTree, JournalStatus, KindSummary, MultiVolumeScanner, ScanConfig, SizeBasis, SizeSum,
+    VolumeScanner,
+};
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::prelude::*;
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -19,27 +30,100 @@ use std::time::{Duration, Instant};
 /// Messages from background threads
 pub enum BgMessage {
     ScanProgress(String),
+    /// Names-only tree from USN enumeration, replaced by `ScanComplete`
+    ScanPreview(Arc<FileTree>),
     ScanComplete(Arc<FileTree>),
     ScanError(String),
-    SortComplete(SortColumn, Vec<usize>),
+    /// Sorted result indices, tagged with the sort's generation
+    SortComplete(u64, SortColumn, Vec<usize>),
     MetadataRefreshComplete(Vec<(usize, u64, u64)>),
-    PathCacheComplete(Vec<(usize, String)>),
+    /// One batch of resolved parent paths, tagged with the path cache's
+    /// generation
+    PathCacheProgress(u64, Vec<(usize, String)>),
+    PathCacheComplete(u64),
+    /// How the loaded drives' USN journals compare with their scans
+    JournalChecked(JournalStatus),
+    /// Match count for the filters dialog, tagged with its request generation
+    FilterPreview(u64, usize),
+}
+
+impl BgMessage {
+    /// Whether handling this message changes the entries or adds to the
+    /// string arena
+    fn writes_entries(&self) -> bool {
+        matches!(
+            self,
+            BgMessage::ScanPreview(_)
+                | BgMessage::ScanComplete(_)
+                | BgMessage::MetadataRefreshComplete(_)
+                | BgMessage::PathCacheProgress(..)
+        )
+    }
 }
 
-/// Lightweight cached entry for fast search/sort without touching the tree
+/// Pause after the last edit in the filters dialog before recounting
+const FILTER_PREVIEW_DELAY: Duration = Duration::from_millis(150);
+
+/// Entries counted between checks for a newer filters dialog count
+const FILTER_PREVIEW_BATCH: usize = 50_000;
+
+/// Entries resolved per path-cache message
+const PATH_CACHE_BATCH: usize = 50_000;
+
+/// How often the loaded drives' USN journals are compared with their scans
+const JOURNAL_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Levels and folders per level in a copied size report
+const SIZE_REPORT_DEPTH: usize = 2;
+const SIZE_REPORT_TOP: usize = 10;
+
+/// Lightweight cached entry for fast search/sort without touching the tree.
+/// Strings live in `App::strings`; the `StrId` fields index into it.
 #[derive(Clone)]
 pub struct EntryData {
     pub tree_index: usize,
     pub key: NodeKey,
     pub file_reference_number: u64,
-    pub name: String,
-    pub name_lower: String,
-    pub extension: String,
+    pub name: StrId,
+    pub name_lower: StrId,
+    /// Interned lowercase extension (see `extensions`)
+    pub extension: ExtId,
     pub file_size: u64,
     pub modification_time: u64,
     pub is_directory: bool,
-    pub cached_path: String,
-    pub path_lower: String,
+    /// File attributes (hidden, system, ...)
+    pub attributes: u32,
+    /// Type label for NTFS metafiles, shown instead of the extension type
+    pub metafile_label: Option<&'static str>,
+    /// Junction or symlink pointing to another volume; shown with a link icon
+    pub off_volume_link: bool,
+    /// Has alternate data streams (for `attr:ads`)
+    pub has_streams: bool,
+    /// Deleted file still in the MFT; drawn struck through
+    pub is_deleted: bool,
+    /// Resident or non-resident data, for the Storage column
+    pub storage: Storage,
+    /// Parent directory path (interned, shared by siblings)
+    pub cached_path: StrId,
+    pub path_lower: StrId,
+}
+
+impl EntryData {
+    /// Check if the entry has the hidden attribute
+    pub fn is_hidden(&self) -> bool {
+        (self.attributes & file_attributes::HIDDEN) != 0
+    }
+
+    /// Check if the entry has the system attribute
+    pub fn is_system(&self) -> bool {
+        (self.attributes & file_attributes::SYSTEM) != 0
+    }
+
+    /// File whose size and modification time have not been read yet
+    /// (USN scans leave both at zero until the metadata refresh)
+    pub fn has_unknown_metadata(&self) -> bool {
+        !self.is_directory && self.file_size == 0 && self.modification_time == 0
+    }
 }
 
 /// Row data extracted for rendering (only built for visible rows)
@@ -48,14 +132,20 @@ pub struct RowData {
     pub path: String,
     pub file_size: u64,
     pub is_directory: bool,
-    pub modification_time: u64,
+    /// Timestamp picked by `App::date_field`
+    pub date: u64,
     pub extension: String,
+    pub metafile_label: Option<&'static str>,
 }
 
 pub struct App {
     // Data
     pub trees: Vec<Arc<FileTree>>,
-    pub all_entries: Vec<EntryData>,
+    /// Shared with sort and filter preview workers, which only read it
+    pub all_entries: Arc<Vec<EntryData>>,
+    /// Compact storage for entry names and cached paths
+    pub strings: Arc<StringArena>,
+    pub extensions: ExtensionTable,
     pub filtered_indices: Vec<usize>,
 
     // Sub-states
@@ -65,13 +155,30 @@ pub struct App {
     // Scanning state
     pub is_scanning: bool,
     pub is_sorting: bool,
+    // Tags each background sort; bumped whenever a running sort's results
+    // go stale, so its SortComplete is dropped
+    sort_generation: u64,
     pub is_refreshing_metadata: bool,
+    /// Parent paths resolved so far and total, while caching runs
+    pub path_cache_progress: Option<(usize, usize)>,
+    path_cache_cancel: Arc<AtomicBool>,
+    /// Tags each path cache run; bumped when a new run starts or a drive
+    /// rescan shifts entry indices, so stale batches are dropped
+    path_cache_generation: u64,
     pub scan_progress: String,
     pub status_message: String,
     pub total_count: u64,
 
     // Drives
     pub selected_drives: Vec<char>,
+    // Drive being rescanned on its own (Shift+F9)
+    rescan_drive: Option<char>,
+    // Drives whose loaded tree is a scan preview, still without sizes
+    preview_drives: Vec<char>,
+    // Worst USN journal status of the loaded drives, and when it was last
+    // asked for; checked every `JOURNAL_CHECK_INTERVAL`
+    journal_status: JournalStatus,
+    journal_checked_at: Option<Instant>,
 
     // Sort optimization
     last_sort_column: Option<SortColumn>,
@@ -80,6 +187,10 @@ pub struct App {
     // Channel
     bg_receiver: Option<Receiver<BgMessage>>,
     bg_sender: Option<Sender<BgMessage>>,
+    // Messages waiting for a sort or filter preview to drop its clones of
+    // `all_entries` and `strings`, so handling them doesn't copy either;
+    // looked at again when the next message arrives
+    held_messages: Vec<BgMessage>,
 
     // Metadata refresh tracking
     pending_metadata_refresh: std::collections::HashSet<usize>,
@@ -90,8 +201,17 @@ pub struct App {
     // Persistent search filters (applied even when menu is closed)
     pub search_filters: SearchFiltersMenu,
 
+    // Live match count in the filters dialog: when to recount, and the
+    // generation of the latest request (older results are dropped)
+    filter_preview_due: Option<Instant>,
+    filter_preview_generation: u64,
+    // Stops the running count once a newer one starts
+    filter_preview_cancel: Arc<AtomicBool>,
+
     // Treemap view
     pub treemap: Option<TreemapState>,
+    // Treemap sized by logical or allocated size (kept while it is closed)
+    pub treemap_size: SizeBasis,
 
     // Menu bar
     pub menu_bar: Option<MenuBarState>,
@@ -101,6 +221,47 @@ pub struct App {
 
     // Quit flag
     pub should_quit: bool,
+
+    // Redraw throttling: only redraw when something changed
+    tick_rate: Duration,
+    needs_redraw: bool,
+
+    // List hidden and system files in results (filtered at search time,
+    // so toggling needs no rescan)
+    pub show_hidden: bool,
+    pub show_system: bool,
+
+    // Match text patterns against the full path rather than the name
+    pub match_path: bool,
+
+    // Folder Enter browsed into (tree index, key) and the query that set;
+    // while the query is unchanged the results are its direct children
+    browsed_folder: Option<(String, usize, NodeKey)>,
+
+    // Hide files whose size and date are not known yet
+    pub hide_unknown_metadata: bool,
+    // Results held back by `hide_unknown_metadata`, refreshed in the background
+    unknown_metadata_held: std::collections::HashSet<usize>,
+
+    // Whether the selected size in the status bar includes directories
+    pub size_sum: SizeSum,
+
+    // Timestamp shown and sorted in the date column
+    pub date_field: DateField,
+
+    // Icons and borders (Unicode or ASCII fallbacks)
+    pub glyphs: &'static Glyphs,
+
+    // Treemap colours
+    pub palette: &'static TreemapPalette,
+
+    // Path to select once its drive is loaded (`--select`)
+    pub pending_select: Option<String>,
+
+    // Read sizes from the MFT while scanning (`--no-sizes` turns it off)
+    pub calculate_sizes: bool,
+    // List deleted files still in the MFT (`--deleted`)
+    pub include_deleted: bool,
 }
 
 /// A preset filter loaded from Filters.csv
@@ -137,20 +298,35 @@ pub enum MenuBarAction {
     // File
     Open,
     OpenInExplorer,
+    OpenTerminal,
     Properties,
     Rescan,
+    RescanDrive,
     Quit,
     // Edit
     CopyPath,
     SelectAll,
     Rename,
+    BatchRename,
     Delete,
     // View
     Treemap,
     SearchFilters,
+    ToggleHidden,
+    ToggleSystem,
+    ToggleMatchPath,
+    ToggleUnknownMetadata,
+    ToggleSizeSum,
+    CycleDateField,
+    CyclePalette,
+    DriveStats,
+    KindSummary,
+    ToggleColumn(SortColumn),
+    SetResultOrder(ResultOrder),
     // Tools
     ApplyPresetFilter(usize),
     ClearFilters,
+    StopPathCache,
     // Help
     About,
     KeyboardShortcuts,
@@ -165,60 +341,157 @@ impl App {
 
         let mut app = Self {
             trees: Vec::new(),
-            all_entries: Vec::new(),
+            all_entries: Arc::new(Vec::new()),
+            strings: Arc::new(StringArena::new()),
+            extensions: ExtensionTable::new(),
             filtered_indices: Vec::new(),
             search: SearchState::default(),
             table: TableState::default(),
             is_scanning: false,
             is_sorting: false,
+            sort_generation: 0,
             is_refreshing_metadata: false,
+            path_cache_progress: None,
+            path_cache_cancel: Arc::new(AtomicBool::new(false)),
+            path_cache_generation: 0,
             scan_progress: String::new(),
             status_message: "Ready".to_string(),
             total_count: 0,
             selected_drives,
+            rescan_drive: None,
+            preview_drives: Vec::new(),
+            journal_status: JournalStatus::Unknown,
+            journal_checked_at: None,
             last_sort_column: None,
             last_sort_order: SortOrder::Ascending,
             bg_receiver: None,
             bg_sender: None,
+            held_messages: Vec::new(),
             pending_metadata_refresh: std::collections::HashSet::new(),
             active_menu: ActiveMenu::None,
             search_filters: SearchFiltersMenu::new(),
+            filter_preview_due: None,
+            filter_preview_generation: 0,
+            filter_preview_cancel: Arc::new(AtomicBool::new(false)),
             treemap: None,
+            treemap_size: SizeBasis::default(),
             menu_bar: None,
             preset_filters,
             should_quit: false,
+            tick_rate: Duration::from_millis(crate::AppConfig::default().refresh_interval_ms),
+            needs_redraw: true,
+            show_hidden: crate::AppConfig::default().show_hidden_results,
+            show_system: crate::AppConfig::default().show_system_results,
+            match_path: false,
+            browsed_folder: None,
+            hide_unknown_metadata: false,
+            unknown_metadata_held: std::collections::HashSet::new(),
+            size_sum: SizeSum::default(),
+            date_field: DateField::default(),
+            glyphs: &glyphs::UNICODE,
+            palette: &palette::DEFAULT,
+            pending_select: None,
+            calculate_sizes: true,
+            include_deleted: false,
         };
 
-        if !app.selected_drives.is_empty() {
-            app.start_scan();
-        }
-
+        app.table.load_column_visibility();
         app
     }
 
+    /// Set how often background messages are polled and the screen may be redrawn
+    pub fn with_tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /// Show or hide hidden files in results
+    pub fn with_show_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self
+    }
+
+    /// Show or hide system files in results
+    pub fn with_show_system(mut self, show_system: bool) -> Self {
+        self.show_system = show_system;
+        self
+    }
+
+    /// Select `path` in the results once the scan has loaded it
+    pub fn with_select_path(mut self, path: Option<String>) -> Self {
+        self.pending_select = path;
+        self
+    }
+
+    /// Read sizes during the scan, or list names first and let the metadata
+    /// refresh fill in the sizes of shown results
+    pub fn with_calculate_sizes(mut self, calculate_sizes: bool) -> Self {
+        self.calculate_sizes = calculate_sizes;
+        self
+    }
+
+    /// Also list deleted files whose MFT records were not reused yet
+    pub fn with_include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = include_deleted;
+        self
+    }
+
+    /// Draw with ASCII fallbacks instead of emoji and box drawing
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.glyphs = Glyphs::select(ascii);
+        self
+    }
+
+    /// Colour the treemap with the named palette (unknown names keep the default)
+    pub fn with_palette(mut self, name: &str) -> Self {
+        self.palette = TreemapPalette::by_name(name).unwrap_or(&palette::DEFAULT);
+        self
+    }
+
     pub fn run(&mut self, terminal: &mut Terminal<impl Backend>) -> crate::Result<()> {
-        let tick_rate = Duration::from_millis(50);
+        let tick_rate = self.tick_rate;
         let mut last_tick = Instant::now();
 
+        // First scan, now that the builder options are set
+        if self.trees.is_empty() {
+            self.start_scan();
+        }
+
         loop {
-            terminal.draw(|frame| ui::draw(frame, self))
-                .map_err(|e| crate::EmFitError::WindowsError(format!("Terminal draw error: {}", e)))?;
+            if self.needs_redraw {
+                terminal.draw(|frame| ui::draw(frame, self))
+                    .map_err(|e| crate::EmFitError::WindowsError(format!("Terminal draw error: {}", e)))?;
+                self.needs_redraw = false;
+            }
 
             let timeout = tick_rate.saturating_sub(last_tick.elapsed());
             if event::poll(timeout).unwrap_or(false) {
-                if let Ok(Event::Key(key)) = event::read() {
+                match event::read() {
                     // Only handle key press events, ignore key release and repeat
-                    if key.kind == KeyEventKind::Press {
+                    Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
                         self.handle_key(key);
+                        self.needs_redraw = true;
+                    }
+                    Ok(Event::Resize(_, _)) => {
+                        self.needs_redraw = true;
                     }
+                    _ => {}
                 }
             }
 
             if last_tick.elapsed() >= tick_rate {
-                self.process_messages();
-                if self.search.needs_search && !self.is_scanning {
+                if self.process_messages() {
+                    self.needs_redraw = true;
+                }
+                self.check_journals();
+                if self.search.needs_search && !(self.is_scanning && self.all_entries.is_empty()) {
                     self.perform_search();
                     self.search.needs_search = false;
+                    self.needs_redraw = true;
+                }
+                if self.filter_preview_due.is_some_and(|due| Instant::now() >= due) {
+                    self.filter_preview_due = None;
+                    self.start_filter_preview();
                 }
                 last_tick = Instant::now();
             }
@@ -234,10 +507,14 @@ impl App {
             return;
         }
 
+        self.cancel_path_cache();
         self.is_scanning = true;
         self.scan_progress = "Starting scan...".to_string();
         self.trees.clear();
-        self.all_entries.clear();
+        self.preview_drives.clear();
+        self.all_entries = Arc::new(Vec::new());
+        self.strings = Arc::new(StringArena::new());
+        self.extensions = ExtensionTable::new();
         self.filtered_indices.clear();
         self.table.selected = None;
         self.total_count = 0;
@@ -245,25 +522,22 @@ impl App {
 
         let (tx, rx) = channel();
         self.bg_receiver = Some(rx);
+        self.held_messages.clear();
         self.bg_sender = Some(tx.clone());
 
         let drives = self.selected_drives.clone();
+        let config = scan_config(self.calculate_sizes, self.include_deleted);
 
         thread::spawn(move || {
             for drive in drives {
                 let _ = tx.send(BgMessage::ScanProgress(format!("Scanning {}:...", drive)));
 
-                let config = ScanConfig {
-                    use_usn: true,
-                    use_mft: true,
-                    include_hidden: true,
-                    include_system: true,
-                    calculate_sizes: true,
-                    show_progress: false, // Don't write progress bars to stdout
-                    ..Default::default()
-                };
-
-                let mut scanner = VolumeScanner::new(drive).with_config(config);
+                let preview_tx = tx.clone();
+                let mut scanner = VolumeScanner::new(drive)
+                    .with_config(config.clone())
+                    .with_preview(move |tree| {
+                        let _ = preview_tx.send(BgMessage::ScanPreview(Arc::new(tree)));
+                    });
 
                 match scanner.scan() {
                     Ok(tree) => {
@@ -280,59 +554,130 @@ impl App {
         });
     }
 
-    fn process_messages(&mut self) {
-        let rx = match &self.bg_receiver {
-            Some(rx) => rx,
-            None => return,
+    /// Rescan only the drive of the selected result. The other drives keep
+    /// their trees and entries; the new tree replaces the old one when done.
+    fn start_drive_rescan(&mut self) {
+        if self.is_scanning {
+            return;
+        }
+        let Some(drive) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+            .and_then(|entry| self.trees.get(entry.tree_index))
+            .map(|tree| tree.drive_letter)
+        else {
+            self.status_message = "Select a result to rescan its drive".to_string();
+            return;
         };
+        let Some(tx) = self.bg_sender.clone() else {
+            return;
+        };
+
+        // Path cache batches are addressed by entry index, which the
+        // replacement shifts
+        self.cancel_path_cache();
+        self.is_scanning = true;
+        self.rescan_drive = Some(drive);
+        self.scan_progress = format!("Rescanning {}:...", drive);
+        let config = scan_config(self.calculate_sizes, self.include_deleted);
+
+        thread::spawn(move || {
+            let mut scanner = VolumeScanner::new(drive).with_config(config);
+            match scanner.scan() {
+                Ok(tree) => {
+                    let _ = tx.send(BgMessage::ScanComplete(Arc::new(tree)));
+                }
+                Err(e) => {
+                    let _ = tx.send(BgMessage::ScanError(format!(
+                        "Error scanning {}: {}",
+                        drive, e
+                    )));
+                }
+            }
+        });
+    }
 
-        while let Ok(msg) = rx.try_recv() {
+    /// Drain background messages. Returns true if any were received.
+    fn process_messages(&mut self) -> bool {
+        let Some(rx) = &self.bg_receiver else {
+            return false;
+        };
+        // Workers drop their clones before sending their result, so held
+        // messages can only go once something new arrives
+        let received: Vec<BgMessage> = rx.try_iter().collect();
+        if received.is_empty() {
+            return false;
+        }
+        let mut messages = std::mem::take(&mut self.held_messages);
+        messages.extend(received);
+
+        let mut scan_finished = false;
+        let mut tree_loaded = false;
+        let mut messages = messages.into_iter();
+        while let Some(msg) = messages.next() {
+            // Workers only read their clones of the entries and the arena,
+            // but writing to either while they hold one copies it whole. Hold
+            // this message and the ones after it (to keep their order) until
+            // they are done.
+            if msg.writes_entries() && self.shared_with_workers() {
+                self.held_messages.push(msg);
+                self.held_messages.extend(messages);
+                break;
+            }
             match msg {
                 BgMessage::ScanProgress(msg) => {
                     self.scan_progress = msg;
                 }
+                BgMessage::ScanPreview(tree) => {
+                    // Stale once the drive's full tree is in
+                    let drive = tree.drive_letter;
+                    if self.trees.iter().any(|t| t.drive_letter == drive) {
+                        continue;
+                    }
+                    self.scan_progress = format!(
+                        "{}: {} names found, reading sizes...",
+                        drive,
+                        tree.stats.total_files + tree.stats.total_directories
+                    );
+                    self.load_tree(tree);
+                    self.preview_drives.push(drive);
+                    tree_loaded = true;
+                }
                 BgMessage::ScanComplete(tree) => {
                     let drive = tree.drive_letter;
                     let files = tree.stats.total_files;
                     let dirs = tree.stats.total_directories;
 
-                    // Prevent duplicates
-                    if self.trees.iter().any(|t| t.drive_letter == drive) {
+                    // Prevent duplicates, unless this drive is being rescanned
+                    // or only its preview is loaded
+                    let existing = self.trees.iter().any(|t| t.drive_letter == drive);
+                    if existing
+                        && !self.preview_drives.contains(&drive)
+                        && self.rescan_drive != Some(drive)
+                    {
                         continue;
                     }
-
-                    // Build EntryData with cached sort keys
-                    let tree_index = self.trees.len();
-                    for entry in tree.iter() {
-                        let key = *entry.key();
-                        let node = entry.value();
-                        if !node.name.is_empty() {
-                            let extension = extract_extension(&node.name);
-                            self.all_entries.push(EntryData {
-                                tree_index,
-                                key,
-                                file_reference_number: node.file_reference_number,
-                                name: node.name.clone(),
-                                name_lower: node.name.to_lowercase(),
-                                extension,
-                                file_size: node.file_size,
-                                modification_time: node.modification_time,
-                                is_directory: node.is_directory,
-                                cached_path: String::new(),
-                                path_lower: String::new(),
-                            });
-                        }
-                    }
-
-                    self.trees.push(tree);
-                    self.total_count += files + dirs;
+                    self.load_tree(tree);
+                    self.preview_drives.retain(|&d| d != drive);
+                    tree_loaded = true;
                     self.status_message = format!(
                         "Loaded {}: - {} files, {} directories",
                         drive, files, dirs
                     );
 
                     // Check if all drives are done
-                    if self.trees.len() >= self.selected_drives.len() {
+                    if self.rescan_drive == Some(drive) {
+                        self.rescan_drive = None;
+                        self.is_scanning = false;
+                        self.scan_progress.clear();
+                        self.status_message = format!(
+                            "Rescanned {}: - {} files, {} directories",
+                            drive, files, dirs
+                        );
+                        scan_finished = true;
+                    } else if self.scanned_tree_count() >= self.selected_drives.len() {
                         self.is_scanning = false;
                         self.scan_progress.clear();
                         let total_files: u64 =
@@ -341,22 +686,29 @@ impl App {
                             self.trees.iter().map(|t| t.stats.total_directories).sum();
                         self.status_message =
                             format!("{} files, {} folders", total_files, total_dirs);
-                        self.search.needs_search = true;
-                        self.start_path_cache();
+                        scan_finished = true;
                     }
                 }
                 BgMessage::ScanError(msg) => {
                     self.status_message = msg;
                     // If this was the last drive (including errors), mark scanning done
-                    if self.trees.len() >= self.selected_drives.len().saturating_sub(1) {
+                    if self.rescan_drive.take().is_some()
+                        || self.scanned_tree_count() >= self.selected_drives.len().saturating_sub(1)
+                    {
                         self.is_scanning = false;
                         self.scan_progress.clear();
+                        // A failed drive's preview is all there will be
+                        self.preview_drives.clear();
                         if !self.all_entries.is_empty() {
                             self.search.needs_search = true;
                         }
                     }
                 }
-                BgMessage::SortComplete(column, sorted_indices) => {
+                BgMessage::SortComplete(generation, column, sorted_indices) => {
+                    // Superseded by a new search or sort, or a drive rescan
+                    if generation != self.sort_generation {
+                        continue;
+                    }
                     self.filtered_indices = sorted_indices;
                     self.last_sort_column = Some(column);
                     self.last_sort_order = self.table.sort_order;
@@ -364,142 +716,239 @@ impl App {
                     self.status_message = format!("{} objects", self.filtered_indices.len());
                 }
                 BgMessage::MetadataRefreshComplete(updates) => {
+                    let mut held_resolved = false;
+                    let entries = Arc::make_mut(&mut self.all_entries);
                     for (entry_idx, file_size, modification_time) in updates {
-                        if let Some(entry) = self.all_entries.get_mut(entry_idx) {
+                        // Not pending if a drive rescan dropped it meanwhile
+                        if !self.pending_metadata_refresh.remove(&entry_idx) {
+                            continue;
+                        }
+                        if let Some(entry) = entries.get_mut(entry_idx) {
                             entry.file_size = file_size;
                             entry.modification_time = modification_time;
                         }
-                        self.pending_metadata_refresh.remove(&entry_idx);
+                        if self.unknown_metadata_held.contains(&entry_idx) {
+                            held_resolved = true;
+                        }
                     }
                     self.is_refreshing_metadata = false;
+                    // Held-back results now have metadata; search again to show them
+                    if held_resolved {
+                        self.search.needs_search = true;
+                    }
                 }
-                BgMessage::PathCacheComplete(paths) => {
+                BgMessage::PathCacheProgress(generation, paths) => {
+                    // Indices from before a drive rescan, or a replaced run
+                    if generation != self.path_cache_generation {
+                        continue;
+                    }
+                    if let Some((done, _)) = self.path_cache_progress.as_mut() {
+                        *done += paths.len();
+                    }
+                    let strings = Arc::make_mut(&mut self.strings);
+                    let entries = Arc::make_mut(&mut self.all_entries);
                     for (entry_idx, path) in paths {
-                        if let Some(entry) = self.all_entries.get_mut(entry_idx) {
-                            entry.path_lower = path.to_lowercase();
-                            entry.cached_path = path;
+                        if let Some(entry) = entries.get_mut(entry_idx) {
+                            entry.cached_path = strings.intern(&path);
+                            entry.path_lower = strings.intern(&path.to_lowercase());
                         }
                     }
+                }
+                BgMessage::JournalChecked(status) => {
+                    self.journal_status = status;
+                }
+                BgMessage::PathCacheComplete(generation) => {
+                    if generation != self.path_cache_generation {
+                        continue;
+                    }
+                    self.path_cache_progress = None;
                     self.status_message = format!(
-                        "{} objects (paths cached)",
-                        self.filtered_indices.len()
+                        "{} objects (paths cached, {})",
+                        self.filtered_indices.len(),
+                        self.memory_report()
                     );
                 }
+                BgMessage::FilterPreview(generation, count) => {
+                    if generation == self.filter_preview_generation {
+                        if let ActiveMenu::SearchFilters(ref mut menu) = self.active_menu {
+                            menu.preview_count = Some(count);
+                        }
+                    }
+                }
             }
         }
-    }
 
-    fn perform_search(&mut self) {
-        self.filtered_indices.clear();
-        self.last_sort_column = None;
-
-        if self.trees.is_empty() || self.all_entries.is_empty() {
-            return;
+        if scan_finished {
+            // Search now so the path cache can start with the results
+            self.perform_search();
+            self.search.needs_search = false;
+            if let Some(path) = self.pending_select.take() {
+                if !self.reveal_path(&path) {
+                    self.status_message = format!("Not in the results: {}", path);
+                }
+            }
+            self.start_path_cache();
+        } else if tree_loaded {
+            // Show what the scan has found so far
+            self.perform_search();
+            self.search.needs_search = false;
         }
+        true
+    }
 
-        let raw_query = self.search.query.trim().to_lowercase();
-
-        // Parse backtick-scoped path: `C:\path\to\folder` pattern
-        let (scope_path, search_query) = parse_scope_path(&raw_query);
+    /// Add a scanned tree and its entries, replacing the drive's current
+    /// tree (its scan preview, or the old tree on a rescan) if there is one
+    fn load_tree(&mut self, tree: Arc<FileTree>) {
+        let drive = tree.drive_letter;
+        // A fresh tree is current; look again after the usual interval
+        self.journal_status = JournalStatus::Unknown;
+        self.journal_checked_at = Some(Instant::now());
+        let existing = self.trees.iter().position(|t| t.drive_letter == drive);
+
+        // Names of the replaced tree, reused where unchanged so the arena
+        // doesn't hold them twice
+        let mut old_names: std::collections::HashMap<NodeKey, (StrId, StrId)> =
+            std::collections::HashMap::new();
+        if let Some(old_index) = existing {
+            // Forget the old tree's entries. Indices shift, so results and
+            // background work that refer to them go too; names that changed
+            // stay in `strings` until a full scan.
+            let stats = &self.trees[old_index].stats;
+            self.total_count = self
+                .total_count
+                .saturating_sub(stats.total_files + stats.total_directories);
+            old_names = self
+                .all_entries
+                .iter()
+                .filter(|e| e.tree_index == old_index)
+                .map(|e| (e.key, (e.name, e.name_lower)))
+                .collect();
+            Arc::make_mut(&mut self.all_entries).retain(|e| e.tree_index != old_index);
+            self.filtered_indices.clear();
+            self.unknown_metadata_held.clear();
+            self.pending_metadata_refresh.clear();
+            // A sort still running would hand back stale indices
+            self.is_sorting = false;
+            self.sort_generation += 1;
+            // Likewise a path cache still running
+            self.path_cache_cancel.store(true, Ordering::Relaxed);
+            self.path_cache_generation += 1;
+            self.path_cache_progress = None;
+            self.last_sort_column = None;
+            self.table.selected = None;
+            self.table.selections.clear();
+            self.treemap = None;
+        }
 
-        // Build regex filter from search_filters if present
-        let regex_filter = if !self.search_filters.regex_pattern.is_empty() {
-            regex::Regex::new(&self.search_filters.regex_pattern).ok()
-        } else {
-            None
-        };
+        // Build EntryData with cached sort keys
+        let tree_index = existing.unwrap_or(self.trees.len());
+        let strings = Arc::make_mut(&mut self.strings);
+        let all_entries = Arc::make_mut(&mut self.all_entries);
+        for entry in tree.iter_entries() {
+            let key = *entry.key();
+            let node = entry.value();
+            let extension = self
+                .extensions
+                .intern(&extract_extension(&node.name));
+            let (name, name_lower) = match old_names.get(&key) {
+                Some(&(name, name_lower)) if strings.get(name) == node.name => (name, name_lower),
+                _ => {
+                    let name = strings.push(&node.name);
+                    let lower = node.name.to_lowercase();
+                    let name_lower = if lower == node.name {
+                        name
+                    } else {
+                        strings.push(&lower)
+                    };
+                    (name, name_lower)
+                }
+            };
+            all_entries.push(EntryData {
+                tree_index,
+                key,
+                file_reference_number: node.file_reference_number,
+                name,
+                name_lower,
+                extension,
+                file_size: node.file_size,
+                modification_time: node.modification_time,
+                is_directory: node.is_directory,
+                attributes: node.attributes,
+                metafile_label: node.metafile_label(),
+                off_volume_link: node.is_off_volume_link(drive),
+                has_streams: node.alternate_stream_count > 0,
+                is_deleted: node.is_deleted,
+                storage: node.storage,
+                cached_path: StringArena::EMPTY,
+                path_lower: StringArena::EMPTY,
+            });
+        }
 
-        // Parse date filter
-        let date_filter = self.build_date_filter();
+        self.total_count += tree.stats.total_files + tree.stats.total_directories;
+        match existing {
+            Some(old_index) => self.trees[old_index] = tree,
+            None => self.trees.push(tree),
+        }
+    }
 
-        // Parse size filter
-        let size_filter = self.build_size_filter();
+    /// Whether a sort or filter preview still holds a clone of the entries
+    /// or the string arena
+    fn shared_with_workers(&mut self) -> bool {
+        Arc::get_mut(&mut self.all_entries).is_none() || Arc::get_mut(&mut self.strings).is_none()
+    }
 
-        // Parse extension filter
-        let ext_filter: Vec<String> = if !self.search_filters.extension_filter.is_empty() {
-            self.search_filters
-                .extension_filter
-                .split(';')
-                .map(|s| s.trim().to_lowercase())
-                .filter(|s| !s.is_empty())
-                .collect()
-        } else {
-            Vec::new()
-        };
+    /// Trees loaded from finished scans, not counting previews
+    fn scanned_tree_count(&self) -> usize {
+        self.trees.len() - self.preview_drives.len()
+    }
 
-        let has_filters = regex_filter.is_some()
-            || date_filter.is_some()
-            || size_filter.is_some()
-            || !ext_filter.is_empty();
+    fn perform_search(&mut self) {
+        self.filtered_indices.clear();
+        self.unknown_metadata_held.clear();
+        // A sort still running works on the old results
+        self.is_sorting = false;
+        self.sort_generation += 1;
+        self.search.pattern_counts.clear();
+        self.last_sort_column = None;
 
-        let no_text_query = search_query.is_empty();
+        if self.trees.is_empty() || self.all_entries.is_empty() {
+            return;
+        }
 
-        // Build pattern list from the text query
-        let patterns: Vec<&str> = if no_text_query {
-            Vec::new()
+        let filter = EntryFilter::new(
+            &self.search.query,
+            &self.search_filters,
+            &self.extensions,
+            (self.show_hidden, self.show_system),
+            self.match_path,
+            self.browsed_folder(),
+        );
+        self.search.active_patterns = filter.patterns.clone();
+        // Results per pattern, only worth telling apart with several
+        let mut pattern_counts = vec![0usize; if filter.patterns.len() > 1 { filter.patterns.len() } else { 0 }];
+
+        if filter.matches_everything() && !self.hide_unknown_metadata {
+            self.filtered_indices = (0..self.all_entries.len()).collect();
         } else {
-            search_query
-                .split(';')
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .collect()
-        };
-
-        for (idx, entry) in self.all_entries.iter().enumerate() {
-            // Scope path filter
-            if let Some(ref scope) = scope_path {
-                if entry.path_lower.is_empty()
-                    || !entry.path_lower.starts_with(scope.as_str())
-                {
-                    continue;
-                }
-            }
-
-            // Text pattern filter
-            let text_match = if no_text_query {
-                true
-            } else {
-                patterns.iter().any(|p| matches_pattern(&entry.name_lower, p))
-            };
-
-            if !text_match {
-                continue;
-            }
-
-            // Regex filter
-            if let Some(ref re) = regex_filter {
-                if !re.is_match(&entry.name) {
-                    continue;
-                }
-            }
-
-            // Date filter
-            if let Some(ref df) = date_filter {
-                if !df.matches(entry.modification_time) {
-                    continue;
-                }
-            }
-
-            // Size filter
-            if let Some(ref sf) = size_filter {
-                if !sf.matches(entry.file_size) {
+            for (idx, entry) in self.all_entries.iter().enumerate() {
+                if !filter.matches(entry, &self.strings, &self.extensions) {
                     continue;
                 }
-            }
-
-            // Extension filter
-            if !ext_filter.is_empty() {
-                if !ext_filter.contains(&entry.extension) {
-                    continue;
+                if self.hide_unknown_metadata && entry.has_unknown_metadata() {
+                    self.unknown_metadata_held.insert(idx);
+                } else {
+                    self.filtered_indices.push(idx);
+                    if !pattern_counts.is_empty() {
+                        filter.count_pattern_matches(entry, &self.strings, &mut pattern_counts);
+                    }
                 }
             }
-
-            self.filtered_indices.push(idx);
         }
+        self.search.pattern_counts = filter.patterns.iter().cloned().zip(pattern_counts).collect();
 
-        // If no query and no filters, show everything
-        if no_text_query && !has_filters && scope_path.is_none() {
-            self.filtered_indices = (0..self.all_entries.len()).collect();
+        if !filter.patterns.is_empty() {
+            let patterns: Vec<&str> = filter.patterns.iter().map(|p| p.as_str()).collect();
+            self.apply_result_order(&patterns);
         }
 
         // Reset selection
@@ -518,57 +967,118 @@ impl App {
         self.trigger_metadata_refresh();
     }
 
-    fn build_date_filter(&self) -> Option<DateFilter> {
-        use crate::tui::menu::{parse_date_to_filetime, DateFilterMode};
+    /// Order fresh text-query results by `search.default_order`
+    fn apply_result_order(&mut self, patterns: &[&str]) {
+        let entries = &self.all_entries;
+        let strings = &self.strings;
 
-        match self.search_filters.date_mode {
-            DateFilterMode::None => None,
-            DateFilterMode::After => {
-                let start = parse_date_to_filetime(&self.search_filters.date_start)?;
-                Some(DateFilter::After(start))
-            }
-            DateFilterMode::Before => {
-                let end = parse_date_to_filetime(&self.search_filters.date_start)?;
-                Some(DateFilter::Before(end))
+        match self.search.default_order {
+            ResultOrder::Relevance => {
+                self.filtered_indices.sort_by_cached_key(|&idx| {
+                    let name_lower = strings.get(entries[idx].name_lower);
+                    (relevance_rank(name_lower, patterns), name_lower)
+                });
             }
-            DateFilterMode::Between => {
-                let start = parse_date_to_filetime(&self.search_filters.date_start)?;
-                let end = parse_date_to_filetime(&self.search_filters.date_end)?;
-                Some(DateFilter::Between(start, end))
+            ResultOrder::Name => {
+                self.filtered_indices
+                    .sort_by_key(|&idx| strings.get(entries[idx].name_lower));
+                self.table.sort_column = SortColumn::Name;
+                self.table.sort_order = SortOrder::Ascending;
+                self.last_sort_column = Some(SortColumn::Name);
+                self.last_sort_order = SortOrder::Ascending;
+            }
+            ResultOrder::SizeDescending => {
+                self.filtered_indices
+                    .sort_by_key(|&idx| std::cmp::Reverse(entries[idx].file_size));
+                self.table.sort_column = SortColumn::Size;
+                self.table.sort_order = SortOrder::Descending;
+                self.last_sort_column = Some(SortColumn::Size);
+                self.last_sort_order = SortOrder::Descending;
             }
         }
     }
 
-    fn build_size_filter(&self) -> Option<SizeFilter> {
-        use crate::tui::menu::{parse_size_str, SizeFilterMode};
-
-        match self.search_filters.size_mode {
-            SizeFilterMode::None => None,
-            SizeFilterMode::GreaterThan => {
-                let val = parse_size_str(&self.search_filters.size_value)?;
-                Some(SizeFilter::GreaterThan(val))
-            }
-            SizeFilterMode::LessThan => {
-                let val = parse_size_str(&self.search_filters.size_value)?;
-                Some(SizeFilter::LessThan(val))
-            }
-            SizeFilterMode::Between => {
-                let start = parse_size_str(&self.search_filters.size_value)?;
-                let end = parse_size_str(&self.search_filters.size_end)?;
-                Some(SizeFilter::Between(start, end))
-            }
+    /// Recount the filters dialog's matches shortly, once editing pauses
+    fn schedule_filter_preview(&mut self) {
+        if let ActiveMenu::SearchFilters(ref mut menu) = self.active_menu {
+            menu.preview_count = None;
+            self.filter_preview_due = Some(Instant::now() + FILTER_PREVIEW_DELAY);
         }
     }
 
+    /// Count on a background thread how many cached entries the dialog's
+    /// filters would match together with the current query
+    fn start_filter_preview(&mut self) {
+        let ActiveMenu::SearchFilters(ref menu) = self.active_menu else {
+            return;
+        };
+        let Some(tx) = self.bg_sender.clone() else {
+            return;
+        };
+
+        let filter = EntryFilter::new(
+            &self.search.query,
+            menu,
+            &self.extensions,
+            (self.show_hidden, self.show_system),
+            self.match_path,
+            self.browsed_folder(),
+        );
+        self.filter_preview_generation += 1;
+        let generation = self.filter_preview_generation;
+        self.filter_preview_cancel.store(true, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.filter_preview_cancel = cancel.clone();
+        let hide_unknown = self.hide_unknown_metadata;
+        let entries = self.all_entries.clone();
+        let strings = self.strings.clone();
+        let extensions = self.extensions.clone();
+
+        threads::spawn_worker(move || {
+            let count = if filter.matches_everything() && !hide_unknown {
+                entries.len()
+            } else {
+                let mut count = 0;
+                for chunk in entries.chunks(FILTER_PREVIEW_BATCH) {
+                    // Superseded: the partial count goes out under the old
+                    // generation and is dropped
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    count += chunk
+                        .iter()
+                        .filter(|entry| !(hide_unknown && entry.has_unknown_metadata()))
+                        .filter(|entry| filter.matches(entry, &strings, &extensions))
+                        .count();
+                }
+                count
+            };
+            // Let held messages through as soon as this arrives
+            drop((entries, strings));
+            let _ = tx.send(BgMessage::FilterPreview(generation, count));
+        });
+    }
+
     fn trigger_metadata_refresh(&mut self) {
-        if self.is_refreshing_metadata || self.filtered_indices.is_empty() {
+        // A preview's sizes come with its full tree
+        if self.is_refreshing_metadata
+            || !self.preview_drives.is_empty()
+            || (self.filtered_indices.is_empty() && self.unknown_metadata_held.is_empty())
+        {
             return;
         }
 
         let max_refresh = 10000;
         let mut needs_refresh: Vec<(usize, usize, NodeKey, u64)> = Vec::new();
 
-        for &entry_idx in self.filtered_indices.iter().take(max_refresh) {
+        // Held-back results too, with a budget of their own: behind a long
+        // result list they would never be refreshed and stay hidden
+        let candidates = self
+            .filtered_indices
+            .iter()
+            .take(max_refresh)
+            .chain(self.unknown_metadata_held.iter().take(max_refresh));
+        for &entry_idx in candidates {
             if self.pending_metadata_refresh.contains(&entry_idx) {
                 continue;
             }
@@ -597,7 +1107,7 @@ impl App {
             None => return,
         };
 
-        thread::spawn(move || {
+        threads::spawn_worker(move || {
             use std::collections::HashMap;
 
             let mut by_tree: HashMap<usize, Vec<(usize, NodeKey, u64)>> = HashMap::new();
@@ -631,7 +1141,37 @@ impl App {
         });
     }
 
-    fn start_path_cache(&self) {
+    /// Worst USN journal status of the loaded drives as last checked
+    pub fn journal_status(&self) -> JournalStatus {
+        self.journal_status
+    }
+
+    /// Compare the loaded drives' USN journals with their scans in the
+    /// background, at most every `JOURNAL_CHECK_INTERVAL`, so the status bar
+    /// can tell when the results have gone stale
+    fn check_journals(&mut self) {
+        if self.is_scanning
+            || self.trees.is_empty()
+            || self.journal_checked_at.is_some_and(|at| at.elapsed() < JOURNAL_CHECK_INTERVAL)
+        {
+            return;
+        }
+        let Some(tx) = self.bg_sender.clone() else {
+            return;
+        };
+        self.journal_checked_at = Some(Instant::now());
+
+        let trees = self.trees.clone();
+        threads::spawn_worker(move || {
+            let status = JournalStatus::worst(trees.iter().map(|tree| tree.journal_status()));
+            let _ = tx.send(BgMessage::JournalChecked(status));
+        });
+    }
+
+    /// Resolve every entry's parent path in the background, current results
+    /// first. Paths arrive in batches so path search and sorting improve as
+    /// caching goes; `cancel_path_cache` stops it between batches.
+    fn start_path_cache(&mut self) {
         if self.all_entries.is_empty() {
             return;
         }
@@ -641,41 +1181,73 @@ impl App {
             None => return,
         };
 
-        // Build a list of (index, tree_index, key) for all entries
-        let work: Vec<(usize, usize, NodeKey)> = self
-            .all_entries
-            .iter()
-            .enumerate()
-            .map(|(i, e)| (i, e.tree_index, e.key))
-            .collect();
+        // (index, tree_index, key) for every entry, filtered rows first
+        let mut queued = vec![false; self.all_entries.len()];
+        let mut work: Vec<(usize, usize, NodeKey)> = Vec::with_capacity(self.all_entries.len());
+        for idx in self.filtered_indices.iter().copied().chain(0..self.all_entries.len()) {
+            if std::mem::replace(&mut queued[idx], true) {
+                continue;
+            }
+            let entry = &self.all_entries[idx];
+            // Entries kept across a drive rescan already have their path
+            if entry.cached_path != StringArena::EMPTY {
+                continue;
+            }
+            work.push((idx, entry.tree_index, entry.key));
+        }
 
         let trees = self.trees.clone();
+        self.path_cache_cancel.store(true, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.path_cache_cancel = cancel.clone();
+        self.path_cache_generation += 1;
+        let generation = self.path_cache_generation;
+        self.path_cache_progress = Some((0, work.len()));
+
+        threads::spawn_long_lived(move || {
+            for chunk in work.chunks(PATH_CACHE_BATCH) {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
 
-        thread::spawn(move || {
-            let mut results: Vec<(usize, String)> = Vec::with_capacity(work.len());
-
-            for (idx, tree_index, key) in work {
-                if let Some(tree) = trees.get(tree_index) {
-                    let full_path = tree.build_path_for_key(&key);
-                    let parent_dir = std::path::Path::new(&full_path)
-                        .parent()
-                        .map(|p| p.to_string_lossy().to_string())
-                        .unwrap_or_else(|| full_path);
-                    results.push((idx, parent_dir));
+                let batch: Vec<(usize, String)> = chunk
+                    .iter()
+                    .filter_map(|&(idx, tree_index, key)| {
+                        let tree = trees.get(tree_index)?;
+                        let full_path = tree.build_path_for_key(&key);
+                        let parent_dir = std::path::Path::new(&full_path)
+                            .parent()
+                            .map(|p| p.to_string_lossy().to_string())
+                            .unwrap_or_else(|| full_path);
+                        Some((idx, parent_dir))
+                    })
+                    .collect();
+
+                if tx.send(BgMessage::PathCacheProgress(generation, batch)).is_err() {
+                    return;
                 }
             }
 
-            let _ = tx.send(BgMessage::PathCacheComplete(results));
+            let _ = tx.send(BgMessage::PathCacheComplete(generation));
         });
     }
 
+    /// Stop a running path cache; paths resolved so far are kept
+    fn cancel_path_cache(&mut self) {
+        self.path_cache_cancel.store(true, Ordering::Relaxed);
+        if let Some((done, total)) = self.path_cache_progress.take() {
+            self.status_message = format!("Path caching stopped ({} of {} entries)", done, total);
+        }
+    }
+
     pub fn get_row_data(&self, entry_index: usize) -> Option<RowData> {
         let entry = self.all_entries.get(entry_index)?;
         let tree = self.trees.get(entry.tree_index)?;
         let _node = tree.get_by_key(&entry.key)?;
 
-        let parent_dir = if !entry.cached_path.is_empty() {
-            entry.cached_path.clone()
+        let cached_path = self.strings.get(entry.cached_path);
+        let parent_dir = if !cached_path.is_empty() {
+            cached_path.to_string()
         } else {
             let path = tree.build_path_for_key(&entry.key);
             std::path::Path::new(&path)
@@ -685,12 +1257,13 @@ impl App {
         };
 
         Some(RowData {
-            name: entry.name.clone(),
+            name: self.strings.get(entry.name).to_string(),
             path: parent_dir,
             file_size: entry.file_size,
             is_directory: entry.is_directory,
-            modification_time: entry.modification_time,
-            extension: entry.extension.clone(),
+            date: self.entry_time(entry),
+            extension: self.extensions.get(entry.extension).to_string(),
+            metafile_label: entry.metafile_label,
         })
     }
 
@@ -718,32 +1291,47 @@ impl App {
             return;
         }
 
+        self.sort_results(column, new_order);
+    }
+
+    /// Sort the results in the background by `column` in `order`
+    fn sort_results(&mut self, column: SortColumn, order: SortOrder) {
+        if self.is_sorting {
+            return;
+        }
         self.table.sort_column = column;
-        self.table.sort_order = new_order;
+        self.table.sort_order = order;
         self.is_sorting = true;
+        self.sort_generation += 1;
+        let generation = self.sort_generation;
 
         let mut indices = self.filtered_indices.clone();
         let entries = self.all_entries.clone();
+        let strings = self.strings.clone();
+        let extensions = self.extensions.clone();
+        let ext_ranks = self.extensions.sort_ranks();
         let sort_column = column;
-        let sort_order = new_order;
+        let sort_order = order;
+        let date_field = self.date_field;
         let trees = self.trees.clone();
 
         if let Some(tx) = &self.bg_sender {
             let tx = tx.clone();
-            thread::spawn(move || {
+            threads::spawn_worker(move || {
                 // For path sorting, build a full-path cache if needed
                 let path_cache: Option<std::collections::HashMap<usize, String>> =
                     if sort_column == SortColumn::Path {
                         let mut cache = std::collections::HashMap::new();
                         for &idx in &indices {
                             let entry = &entries[idx];
-                            let full_path = if !entry.path_lower.is_empty() {
+                            let path_lower = strings.get(entry.path_lower);
+                            let full_path = if !path_lower.is_empty() {
                                 // Combine cached parent path + filename for full path sort
-                                format!("{}\\{}", entry.path_lower, entry.name_lower)
+                                format!("{}\\{}", path_lower, strings.get(entry.name_lower))
                             } else if let Some(tree) = trees.get(entry.tree_index) {
                                 tree.build_path_for_key(&entry.key).to_lowercase()
                             } else {
-                                entry.name_lower.clone()
+                                strings.get(entry.name_lower).to_string()
                             };
                             cache.insert(idx, full_path);
                         }
@@ -752,29 +1340,71 @@ impl App {
                         None
                     };
 
+                // Created/accessed times live only in the trees; look each up once
+                let time_cache: Option<std::collections::HashMap<usize, u64>> =
+                    if sort_column == SortColumn::DateModified && date_field != DateField::Modified {
+                        let cache = indices
+                            .iter()
+                            .map(|&idx| {
+                                let entry = &entries[idx];
+                                let time = trees
+                                    .get(entry.tree_index)
+                                    .map(|t| t.time_for_key(&entry.key, date_field))
+                                    .unwrap_or(0);
+                                (idx, time)
+                            })
+                            .collect();
+                        Some(cache)
+                    } else {
+                        None
+                    };
+
                 indices.sort_by(|&a, &b| {
                     let ea = &entries[a];
                     let eb = &entries[b];
 
                     let cmp = match sort_column {
-                        SortColumn::Name => ea.name_lower.cmp(&eb.name_lower),
+                        SortColumn::Name => {
+                            strings.get(ea.name_lower).cmp(strings.get(eb.name_lower))
+                        }
                         SortColumn::Path => {
                             let pa = path_cache.as_ref().unwrap().get(&a).map(|s| s.as_str()).unwrap_or("");
                             let pb = path_cache.as_ref().unwrap().get(&b).map(|s| s.as_str()).unwrap_or("");
                             pa.cmp(pb)
                         }
                         SortColumn::Size => ea.file_size.cmp(&eb.file_size),
-                        SortColumn::Extension => ea.extension.cmp(&eb.extension),
-                        SortColumn::DateModified => {
-                            ea.modification_time.cmp(&eb.modification_time)
+                        SortColumn::Extension => {
+                            ext_ranks[ea.extension as usize].cmp(&ext_ranks[eb.extension as usize])
                         }
+                        SortColumn::DateModified => match time_cache {
+                            Some(ref times) => times.get(&a).cmp(&times.get(&b)),
+                            None => ea.modification_time.cmp(&eb.modification_time),
+                        },
                         SortColumn::Type => {
-                            let ta = colors::type_label(ea.is_directory, &ea.extension);
-                            let tb = colors::type_label(eb.is_directory, &eb.extension);
+                            let ta = ea.metafile_label.unwrap_or_else(|| {
+                                colors::type_label(ea.is_directory, extensions.get(ea.extension))
+                            });
+                            let tb = eb.metafile_label.unwrap_or_else(|| {
+                                colors::type_label(eb.is_directory, extensions.get(eb.extension))
+                            });
                             ta.cmp(tb)
                         }
+                        SortColumn::Drive => {
+                            let da = trees.get(ea.tree_index).map(|t| t.drive_letter);
+                            let db = trees.get(eb.tree_index).map(|t| t.drive_letter);
+                            da.cmp(&db)
+                        }
+                        SortColumn::Record => ea.key.record_number.cmp(&eb.key.record_number),
+                        SortColumn::Storage => ea.storage.cmp(&eb.storage),
                     };
 
+                    // Equal keys fall back to drive and record number so the
+                    // order doesn't depend on scan or previous-sort order
+                    let cmp = cmp.then_with(|| {
+                        (ea.tree_index, ea.key.record_number)
+                            .cmp(&(eb.tree_index, eb.key.record_number))
+                    });
+
                     if sort_order == SortOrder::Descending {
                         cmp.reverse()
                     } else {
@@ -782,7 +1412,9 @@ impl App {
                     }
                 });
 
-                let _ = tx.send(BgMessage::SortComplete(sort_column, indices));
+                // Let held messages through as soon as this arrives
+                drop((entries, strings));
+                let _ = tx.send(BgMessage::SortComplete(generation, sort_column, indices));
             });
         }
     }
@@ -834,6 +1466,10 @@ impl App {
                 }
                 return;
             }
+            KeyCode::F(9) if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.start_drive_rescan();
+                return;
+            }
             KeyCode::F(9) => {
                 self.start_scan();
                 return;
@@ -842,6 +1478,10 @@ impl App {
                 self.open_menu_bar();
                 return;
             }
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.clear_search_filters();
+                return;
+            }
             _ => {}
         }
 
@@ -936,6 +1576,15 @@ impl App {
             // Space: toggle selection of current item
             KeyCode::Char(' ') => self.table.toggle_selection(),
 
+            // Enter: browse into a folder, open a file; Backspace: back out
+            KeyCode::Enter => {
+                let browsed = self.browse_selected_folder();
+                if !browsed {
+                    self.execute_menu_bar_action(MenuBarAction::Open);
+                }
+            }
+            KeyCode::Backspace => self.browse_up(),
+
             // Ctrl+A: select all
             KeyCode::Char('a') if has_ctrl => self.table.select_all(total),
 
@@ -950,14 +1599,14 @@ impl App {
             // Column resize (Ctrl+Left/Right resizes the current sort column)
             KeyCode::Left if has_ctrl => {
                 let idx = self.table.sort_column.index();
-                if self.table.column_widths[idx] != 0 {
+                if !matches!(self.table.column_widths[idx], 0 | HIDDEN_COLUMN) {
                     self.table.column_widths[idx] =
                         self.table.column_widths[idx].saturating_sub(1).max(5);
                 }
             }
             KeyCode::Right if has_ctrl => {
                 let idx = self.table.sort_column.index();
-                if self.table.column_widths[idx] != 0 {
+                if !matches!(self.table.column_widths[idx], 0 | HIDDEN_COLUMN) {
                     self.table.column_widths[idx] =
                         (self.table.column_widths[idx] + 1).min(100);
                 }
@@ -967,6 +1616,15 @@ impl App {
                 self.search.focused = true;
             }
 
+            // Show/hide columns (Shift + the column's sort key)
+            KeyCode::F(2) if has_shift => self.toggle_column(SortColumn::Path),
+            KeyCode::F(4) if has_shift => self.toggle_column(SortColumn::Extension),
+            KeyCode::F(5) if has_shift => self.toggle_column(SortColumn::DateModified),
+            KeyCode::F(6) if has_shift => self.toggle_column(SortColumn::Type),
+            KeyCode::F(7) if has_shift => self.toggle_column(SortColumn::Drive),
+            KeyCode::F(8) if has_shift => self.toggle_column(SortColumn::Record),
+            KeyCode::F(11) if has_shift => self.toggle_column(SortColumn::Storage),
+
             // Sort columns
             KeyCode::F(1) => self.handle_sort_click(SortColumn::Name),
             KeyCode::F(2) => self.handle_sort_click(SortColumn::Path),
@@ -974,6 +1632,9 @@ impl App {
             KeyCode::F(4) => self.handle_sort_click(SortColumn::Extension),
             KeyCode::F(5) => self.handle_sort_click(SortColumn::DateModified),
             KeyCode::F(6) => self.handle_sort_click(SortColumn::Type),
+            KeyCode::F(7) => self.handle_sort_click(SortColumn::Drive),
+            KeyCode::F(8) => self.handle_sort_click(SortColumn::Record),
+            KeyCode::F(11) => self.handle_sort_click(SortColumn::Storage),
 
             // Actions menu
             KeyCode::Char('m') if !has_ctrl && !has_shift => {
@@ -985,12 +1646,23 @@ impl App {
                 self.open_search_filters();
             }
 
+            // Filter to the selected row's extension
+            KeyCode::Char('e') if has_ctrl => {
+                self.filter_to_selected_extension();
+            }
+
             // Treemap view
             KeyCode::Char('t') if !has_ctrl && !has_shift => {
                 self.toggle_treemap();
                 return;
             }
 
+            // Sorted by name, a letter or digit jumps to the next name
+            // starting with it (Shift+letter for J, K, M and T)
+            KeyCode::Char(c) if !has_ctrl && c.is_alphanumeric() && self.sorted_by_name() => {
+                self.jump_to_initial(c);
+            }
+
             // Any other printable char focuses search and types it
             KeyCode::Char(c) if !has_ctrl && !has_shift => {
                 self.search.focused = true;
@@ -1003,6 +1675,19 @@ impl App {
         }
     }
 
+    /// Show or hide a table column and remember the choice in Columns.cfg
+    fn toggle_column(&mut self, column: SortColumn) {
+        let visible = self.table.toggle_column(column);
+        self.status_message = format!(
+            "{} column {}",
+            column.label(),
+            if visible { "shown" } else { "hidden" }
+        );
+        if let Err(e) = self.table.save_column_visibility() {
+            self.status_message = format!("Could not save column settings: {}", e);
+        }
+    }
+
     // --- Menu methods ---
 
     fn open_actions_menu(&mut self) {
@@ -1029,39 +1714,368 @@ impl App {
         menu.extension_filter = self.search_filters.extension_filter.clone();
         menu.extension_cursor = self.search_filters.extension_filter.len();
         self.active_menu = ActiveMenu::SearchFilters(menu);
+        self.filter_preview_due = Some(Instant::now());
+    }
+
+    /// Clear the filters dialog state but keep the text query
+    fn clear_search_filters(&mut self) {
+        if !self.search_filters.has_any_filter() {
+            return;
+        }
+        self.search_filters.clear_all();
+        self.search.needs_search = true;
+        self.status_message = "Filters cleared".to_string();
+    }
+
+    /// Narrow results to the selected row's extension: fills the extension
+    /// filter and clears the text query, keeping any other filters
+    fn filter_to_selected_extension(&mut self) {
+        let Some(entry) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+        else {
+            return;
+        };
+        let ext = self.extensions.get(entry.extension);
+        if entry.is_directory || ext.is_empty() {
+            self.status_message = "Selected item has no extension".to_string();
+            return;
+        }
+        self.search_filters.extension_filter = ext.to_string();
+        self.search_filters.extension_cursor = ext.len();
+        self.search.query.clear();
+        self.search.cursor_pos = 0;
+        self.search.needs_search = true;
+        self.status_message = format!("Filter: .{} (Ctrl+L clears)", ext);
+    }
+
+    /// The timestamp `date_field` picks for an entry. Only modification
+    /// times are kept per entry; the others are read from the tree.
+    pub fn entry_time(&self, entry: &EntryData) -> u64 {
+        match self.date_field {
+            DateField::Modified => entry.modification_time,
+            field => self
+                .trees
+                .get(entry.tree_index)
+                .map(|t| t.time_for_key(&entry.key, field))
+                .unwrap_or(0),
+        }
+    }
+
+    /// Scope the search to the selected folder, replacing the query, so the
+    /// results list that folder's contents. Returns false if the cursor row
+    /// is not a folder.
+    fn browse_selected_folder(&mut self) -> bool {
+        let Some(entry) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+        else {
+            return false;
+        };
+        if !entry.is_directory {
+            return false;
+        }
+        let (tree_index, key) = (entry.tree_index, entry.key);
+        self.browse_folder(tree_index, key)
+    }
+
+    /// Replace the query with a folder's path and list its direct children
+    fn browse_folder(&mut self, tree_index: usize, key: NodeKey) -> bool {
+        let Some(tree) = self.trees.get(tree_index) else {
+            return false;
+        };
+        let path = tree.build_path_for_key(&key);
+        self.set_scope(Some(&path));
+        self.browsed_folder = Some((self.search.query.clone(), tree_index, key));
+        self.status_message = format!("Browsing {} (Backspace goes up)", path);
+        true
+    }
+
+    /// Tree index and record number of the browsed folder, if the query is
+    /// still the one `browse_folder` set
+    fn browsed_folder(&self) -> Option<(usize, u64)> {
+        self.browsed_folder
+            .as_ref()
+            .filter(|(query, ..)| *query == self.search.query)
+            .map(|&(_, tree_index, key)| (tree_index, key.record_number))
+    }
+
+    /// Whether the results are in name order, so type-ahead makes sense
+    fn sorted_by_name(&self) -> bool {
+        // Until a column is clicked, text results keep `ResultOrder::Name`
+        let by_name = match self.last_sort_column {
+            Some(column) => column == SortColumn::Name,
+            None => self.search.default_order == ResultOrder::Name,
+        };
+        !self.is_sorting && by_name
+    }
+
+    /// Move the selection to the next row (wrapping) whose name starts
+    /// with `c`, like type-ahead in a file manager
+    fn jump_to_initial(&mut self, c: char) {
+        let entries = &self.all_entries;
+        let strings = &self.strings;
+        let filtered = &self.filtered_indices;
+        let found = next_row_by_initial(filtered.len(), self.table.selected, c, |row| {
+            strings.get(entries[filtered[row]].name_lower)
+        });
+        match found {
+            Some(row) => {
+                self.table.select_row(row);
+            }
+            None => self.status_message = format!("No name starting with '{}'", c),
+        }
+    }
+
+    /// Move the search scope up one folder, or drop it at the drive root
+    fn browse_up(&mut self) {
+        // A browsed folder goes up through the tree, so the parent lists
+        // its children too; the root's parent is itself
+        let browsed = self
+            .browsed_folder
+            .as_ref()
+            .filter(|(query, _, key)| *query == self.search.query && key.parent_record_number != key.record_number)
+            .map(|&(_, tree_index, key)| (tree_index, key));
+        if let Some((tree_index, key)) = browsed {
+            let parent = self.trees.get(tree_index).and_then(|tree| tree.get(key.parent_record_number));
+            if let Some(parent) = parent {
+                self.browse_folder(tree_index, parent.key());
+                return;
+            }
+        }
+        let (Some(scope), _) = parse_scope_path(&self.search.query) else {
+            return;
+        };
+        match scope.rsplit_once('\\') {
+            Some((parent, _)) => {
+                self.set_scope(Some(parent));
+                self.status_message = format!("Browsing {}", parent);
+            }
+            None => {
+                self.set_scope(None);
+                self.status_message = "Scope cleared".to_string();
+            }
+        }
+    }
+
+    /// Replace the query with a bare `scope` (or clear it)
+    fn set_scope(&mut self, scope: Option<&str>) {
+        self.search.query = match scope {
+            // A drive root normalises to "c:"; keep the backslash so it reads as a path
+            Some(s) if s.ends_with(':') => format!("`{}\\` ", s),
+            Some(s) => format!("`{}` ", s),
+            None => String::new(),
+        };
+        self.search.cursor_pos = self.search.query.len();
+        self.search.needs_search = true;
+    }
+
+    /// Copy a text report of the selected folder's largest subfolders
+    fn copy_size_report(&mut self) {
+        let Some(entry) = self
+            .table
+            .selected
+            .and_then(|i| self.filtered_indices.get(i))
+            .and_then(|&idx| self.all_entries.get(idx))
+        else {
+            return;
+        };
+        if !entry.is_directory {
+            self.status_message = "Size report needs a folder".to_string();
+            return;
+        }
+        let Some(tree) = self.trees.get(entry.tree_index) else {
+            return;
+        };
+        let report = tree.size_report(&entry.key, SIZE_REPORT_DEPTH, SIZE_REPORT_TOP);
+        crate::tui::menu::copy_to_clipboard(&report);
+        self.status_message = format!("Copied size report ({} lines)", report.lines().count());
+    }
+
+    /// Summary of name/path storage, e.g. "names 1.2 GB (saved 2.3 GB)"
+    pub fn memory_report(&self) -> String {
+        // Each entry holds 4 ids instead of 4 Strings
+        let id_bytes = self.all_entries.len() as u64 * 4 * std::mem::size_of::<StrId>() as u64;
+        let used = self.strings.heap_bytes() as u64 + id_bytes;
+        let saved = self.strings.string_equivalent_bytes().saturating_sub(used);
+        format!(
+            "names {} (saved {})",
+            crate::format_size(used),
+            crate::format_size(saved)
+        )
+    }
+
+    /// Summed size of the selected items (or the cursor row), following `size_sum`
+    pub fn selected_size(&self) -> u64 {
+        let size_of = |logical_idx: &usize| -> u64 {
+            let Some(entry) = self
+                .filtered_indices
+                .get(*logical_idx)
+                .and_then(|&i| self.all_entries.get(i))
+            else {
+                return 0;
+            };
+            if !entry.is_directory {
+                entry.file_size
+            } else if self.size_sum == SizeSum::AsIs {
+                self.trees
+                    .get(entry.tree_index)
+                    .map(|t| t.total_size_for_key(&entry.key))
+                    .unwrap_or(0)
+            } else {
+                0
+            }
+        };
+        if self.table.selections.is_empty() {
+            self.table.selected.as_ref().map(size_of).unwrap_or(0)
+        } else {
+            self.table.selections.iter().map(size_of).sum()
+        }
+    }
+
+    /// Indices into `all_entries` of the selected items, or of the cursor
+    /// row when nothing is multi-selected
+    fn selected_entry_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .table
+            .selections
+            .iter()
+            .filter_map(|&logical_idx| self.filtered_indices.get(logical_idx).copied())
+            .filter(|&entry_idx| entry_idx < self.all_entries.len())
+            .collect();
+        // If no multi-selection but cursor is set, use cursor
+        if indices.is_empty() {
+            if let Some(&entry_idx) = self
+                .table
+                .selected
+                .and_then(|logical_idx| self.filtered_indices.get(logical_idx))
+            {
+                if entry_idx < self.all_entries.len() {
+                    indices.push(entry_idx);
+                }
+            }
+        }
+        indices
+    }
+
+    /// Full path of an entry, from the path cache when it has reached it
+    fn entry_path(&self, entry: &EntryData) -> Option<String> {
+        let cached_path = self.strings.get(entry.cached_path);
+        if !cached_path.is_empty() {
+            Some(format!("{}\\{}", cached_path, self.strings.get(entry.name)))
+        } else {
+            self.trees
+                .get(entry.tree_index)
+                .map(|tree| tree.build_path_for_key(&entry.key))
+        }
     }
 
     /// Get the full paths for all selected items
     pub fn get_selected_paths(&self) -> Vec<String> {
-        let mut paths = Vec::new();
-        for &logical_idx in &self.table.selections {
-            if let Some(&entry_idx) = self.filtered_indices.get(logical_idx) {
-                if let Some(entry) = self.all_entries.get(entry_idx) {
-                    if !entry.cached_path.is_empty() {
-                        let full = format!("{}\\{}", entry.cached_path, entry.name);
-                        paths.push(full);
-                    } else if let Some(tree) = self.trees.get(entry.tree_index) {
-                        paths.push(tree.build_path_for_key(&entry.key));
-                    }
+        self.selected_entry_indices()
+            .into_iter()
+            .filter_map(|entry_idx| self.entry_path(&self.all_entries[entry_idx]))
+            .collect()
+    }
+
+    /// Index into `all_entries` of the entry at a full path, if its drive
+    /// is loaded
+    pub fn index_of_path(&self, path: &str) -> Option<usize> {
+        let (tree_index, key) = self
+            .trees
+            .iter()
+            .enumerate()
+            .find_map(|(i, tree)| tree.key_for_path(path).map(|key| (i, key)))?;
+        self.all_entries
+            .iter()
+            .position(|entry| entry.tree_index == tree_index && entry.key == key)
+    }
+
+    /// Select the entry at `path` and scroll to it, clearing the search
+    /// and filters if they hide it. Returns false if the path is not loaded
+    /// or is hidden by the hidden/system file toggles.
+    pub fn reveal_path(&mut self, path: &str) -> bool {
+        let Some(entry_idx) = self.index_of_path(path) else {
+            return false;
+        };
+        if !self.filtered_indices.contains(&entry_idx) {
+            self.search_filters.clear_all();
+            self.search.query.clear();
+            self.search.cursor_pos = 0;
+            self.perform_search();
+            self.search.needs_search = false;
+        }
+        let Some(row) = self.filtered_indices.iter().position(|&idx| idx == entry_idx) else {
+            return false;
+        };
+        self.table.select_row(row);
+        true
+    }
+
+    /// Whether an attribute toggle on the selection sets `flag`: it does
+    /// unless every selected item already has it, so mixed selections end
+    /// up uniform
+    fn attribute_toggle_sets(&self, flag: u32) -> bool {
+        self.selected_entry_indices()
+            .iter()
+            .any(|&entry_idx| self.all_entries[entry_idx].attributes & flag == 0)
+    }
+
+    /// Set or clear `flag` on the selected items on disk, then patch the
+    /// cached attributes of the entries and trees so no rescan is needed
+    fn execute_attribute_toggle(&mut self, flag: u32) {
+        let on = self.attribute_toggle_sets(flag);
+        let mut changed: std::collections::HashMap<(usize, u64), u32> =
+            std::collections::HashMap::new();
+        let mut failed = 0;
+        for entry_idx in self.selected_entry_indices() {
+            let entry = &self.all_entries[entry_idx];
+            let Some(path) = self.entry_path(entry) else {
+                continue;
+            };
+            match shell::set_attribute_flag(&path, flag, on) {
+                Ok(attributes) => {
+                    changed.insert((entry.tree_index, entry.key.record_number), attributes);
+                }
+                Err(e) => {
+                    failed += 1;
+                    self.status_message = format!("Error changing {}: {}", path, e);
                 }
             }
         }
-        // If no multi-selection but cursor is set, use cursor
-        if paths.is_empty() {
-            if let Some(logical_idx) = self.table.selected {
-                if let Some(&entry_idx) = self.filtered_indices.get(logical_idx) {
-                    if let Some(entry) = self.all_entries.get(entry_idx) {
-                        if !entry.cached_path.is_empty() {
-                            let full = format!("{}\\{}", entry.cached_path, entry.name);
-                            paths.push(full);
-                        } else if let Some(tree) = self.trees.get(entry.tree_index) {
-                            paths.push(tree.build_path_for_key(&entry.key));
-                        }
-                    }
-                }
+
+        for (&(tree_index, record_number), &attributes) in &changed {
+            if let Some(tree) = self.trees.get(tree_index) {
+                tree.set_attributes(record_number, attributes);
+            }
+        }
+        // Hard links are separate entries sharing one record
+        for entry in Arc::make_mut(&mut self.all_entries).iter_mut() {
+            if let Some(&attributes) = changed.get(&(entry.tree_index, entry.key.record_number)) {
+                entry.attributes = attributes;
             }
         }
-        paths
+
+        if !changed.is_empty() {
+            let verb = if on { "Set" } else { "Cleared" };
+            self.status_message = if failed > 0 {
+                format!(
+                    "{} {} on {} item(s), {} failed",
+                    verb,
+                    attribute_name(flag),
+                    changed.len(),
+                    failed
+                )
+            } else {
+                format!("{} {} on {} item(s)", verb, attribute_name(flag), changed.len())
+            };
+            // Filters such as hiding hidden files may now apply differently
+            self.search.needs_search = true;
+        }
     }
 
     fn execute_action(&mut self, action: ActionKind) {
@@ -1084,12 +2098,21 @@ impl App {
                 }
                 self.status_message = format!("Opened in Explorer: {} item(s)", paths.len());
             }
+            ActionKind::OpenTerminal => {
+                // One console is enough; several selected items usually share a folder
+                self.status_message = match shell::open_terminal_here(&paths[0]) {
+                    Ok(()) => format!("Opened terminal for {}", paths[0]),
+                    Err(e) => format!("Error opening terminal: {}", e),
+                };
+            }
             ActionKind::Properties => {
                 for path in &paths {
                     crate::tui::menu::show_properties(path);
                 }
                 self.status_message = format!("Properties: {} item(s)", paths.len());
             }
+            ActionKind::FilterExtension => self.filter_to_selected_extension(),
+            ActionKind::CopySizeReport => self.copy_size_report(),
             ActionKind::CopyPath => {
                 let text = paths.join("\n");
                 crate::tui::menu::copy_to_clipboard(&text);
@@ -1101,8 +2124,19 @@ impl App {
                 } else {
                     format!("Delete {} items?", paths.len())
                 };
-                self.active_menu =
-                    ActiveMenu::Confirm(ConfirmDialog::new(msg, ActionKind::Delete));
+                self.active_menu =
+                    ActiveMenu::Confirm(ConfirmDialog::new(msg, ActionKind::Delete));
+                return;
+            }
+            ActionKind::ToggleReadonly | ActionKind::ToggleHidden => {
+                let flag = toggle_flag(action);
+                let verb = if self.attribute_toggle_sets(flag) { "Set" } else { "Clear" };
+                let msg = if paths.len() == 1 {
+                    format!("{} {} on {}?", verb, attribute_name(flag), paths[0])
+                } else {
+                    format!("{} {} on {} items?", verb, attribute_name(flag), paths.len())
+                };
+                self.active_menu = ActiveMenu::Confirm(ConfirmDialog::new(msg, action));
                 return;
             }
             ActionKind::Rename => {
@@ -1119,10 +2153,24 @@ impl App {
                     ActiveMenu::Rename(RenameDialog::new(name, full_path.clone()));
                 return;
             }
+            ActionKind::BatchRename => {
+                self.active_menu = ActiveMenu::BatchRename(BatchRenameDialog::new(paths));
+                return;
+            }
         }
         self.active_menu = ActiveMenu::None;
     }
 
+    fn execute_confirmed(&mut self, action: ActionKind) {
+        match action {
+            ActionKind::Delete => self.execute_delete(),
+            ActionKind::ToggleReadonly | ActionKind::ToggleHidden => {
+                self.execute_attribute_toggle(toggle_flag(action))
+            }
+            _ => {}
+        }
+    }
+
     fn execute_delete(&mut self) {
         let paths = self.get_selected_paths();
         let mut deleted = 0;
@@ -1164,6 +2212,25 @@ impl App {
         }
     }
 
+    fn execute_batch_rename(&mut self, items: &[crate::batch_rename::RenameItem]) {
+        let skipped = items.iter().filter(|item| item.to.is_err()).count();
+        let (renamed, errors) = crate::batch_rename::execute(items);
+
+        self.status_message = match errors.first() {
+            Some(first) => format!(
+                "Renamed {} item(s), {} failed (first: {})",
+                renamed,
+                errors.len(),
+                first
+            ),
+            None if skipped > 0 => format!("Renamed {} item(s), skipped {}", renamed, skipped),
+            None => format!("Renamed {} item(s)", renamed),
+        };
+        if renamed > 0 {
+            self.search.needs_search = true;
+        }
+    }
+
     fn apply_search_filters(&mut self) {
         // Copy the menu's filter state to the persistent filters
         if let ActiveMenu::SearchFilters(ref menu) = self.active_menu {
@@ -1222,17 +2289,11 @@ impl App {
                     }
                     KeyCode::Enter => {
                         if confirm.confirm_selected {
-                            match confirm.action {
-                                ActionKind::Delete => self.execute_delete(),
-                                _ => {}
-                            }
+                            self.execute_confirmed(confirm.action);
                         }
                     }
                     KeyCode::Char('y') | KeyCode::Char('Y') => {
-                        match confirm.action {
-                            ActionKind::Delete => self.execute_delete(),
-                            _ => {}
-                        }
+                        self.execute_confirmed(confirm.action);
                     }
                     KeyCode::Char('n') | KeyCode::Char('N') => {
                         // Cancel - already set to None
@@ -1308,6 +2369,98 @@ impl App {
                     }
                 }
             }
+            ActiveMenu::BatchRename(mut dialog) => {
+                match key.code {
+                    KeyCode::Esc => {}
+                    KeyCode::Enter if dialog.rename_count() > 0 => {
+                        if let Ok(ref items) = dialog.preview {
+                            self.execute_batch_rename(items);
+                        }
+                    }
+                    KeyCode::Tab => {
+                        if key.modifiers.contains(KeyModifiers::SHIFT) {
+                            dialog.focused_field = dialog.focused_field.prev();
+                        } else {
+                            dialog.focused_field = dialog.focused_field.next();
+                        }
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Up => {
+                        dialog.scroll = dialog.scroll.saturating_sub(1);
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Down => {
+                        if dialog.scroll + 1 < dialog.paths.len() {
+                            dialog.scroll += 1;
+                        }
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Char(c) => {
+                        let (text, cursor) = dialog.current_text_mut();
+                        text.insert(*cursor, c);
+                        *cursor += c.len_utf8();
+                        dialog.refresh_preview();
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Backspace => {
+                        let (text, cursor) = dialog.current_text_mut();
+                        if *cursor > 0 {
+                            let prev = text[..*cursor]
+                                .char_indices()
+                                .last()
+                                .map(|(i, _)| i)
+                                .unwrap_or(0);
+                            text.remove(prev);
+                            *cursor = prev;
+                        }
+                        dialog.refresh_preview();
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Delete => {
+                        let (text, cursor) = dialog.current_text_mut();
+                        if *cursor < text.len() {
+                            text.remove(*cursor);
+                        }
+                        dialog.refresh_preview();
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Left => {
+                        let (text, cursor) = dialog.current_text_mut();
+                        if *cursor > 0 {
+                            *cursor = text[..*cursor]
+                                .char_indices()
+                                .last()
+                                .map(|(i, _)| i)
+                                .unwrap_or(0);
+                        }
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Right => {
+                        let (text, cursor) = dialog.current_text_mut();
+                        if *cursor < text.len() {
+                            *cursor = text[*cursor..]
+                                .char_indices()
+                                .nth(1)
+                                .map(|(i, _)| *cursor + i)
+                                .unwrap_or(text.len());
+                        }
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::Home => {
+                        let (_, cursor) = dialog.current_text_mut();
+                        *cursor = 0;
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    KeyCode::End => {
+                        let (text, cursor) = dialog.current_text_mut();
+                        *cursor = text.len();
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                    _ => {
+                        self.active_menu = ActiveMenu::BatchRename(dialog);
+                    }
+                }
+            }
             ActiveMenu::SearchFilters(mut filters) => {
                 match key.code {
                     KeyCode::Esc => {
@@ -1331,6 +2484,7 @@ impl App {
                             SearchFilterField::Clear => {
                                 filters.clear_all();
                                 self.active_menu = ActiveMenu::SearchFilters(filters);
+                                self.schedule_filter_preview();
                             }
                             SearchFilterField::Cancel => {
                                 // Cancel - already set to None
@@ -1354,6 +2508,7 @@ impl App {
                             _ => {}
                         }
                         self.active_menu = ActiveMenu::SearchFilters(filters);
+                        self.schedule_filter_preview();
                     }
                     KeyCode::Right if filters.focused_field.is_mode_selector() => {
                         match filters.focused_field {
@@ -1366,6 +2521,7 @@ impl App {
                             _ => {}
                         }
                         self.active_menu = ActiveMenu::SearchFilters(filters);
+                        self.schedule_filter_preview();
                     }
                     KeyCode::Char(c) if filters.focused_field.is_text_input() => {
                         if let Some((text, cursor)) = filters.current_text_mut() {
@@ -1373,6 +2529,7 @@ impl App {
                             *cursor += c.len_utf8();
                         }
                         self.active_menu = ActiveMenu::SearchFilters(filters);
+                        self.schedule_filter_preview();
                     }
                     KeyCode::Backspace if filters.focused_field.is_text_input() => {
                         if let Some((text, cursor)) = filters.current_text_mut() {
@@ -1387,6 +2544,7 @@ impl App {
                             }
                         }
                         self.active_menu = ActiveMenu::SearchFilters(filters);
+                        self.schedule_filter_preview();
                     }
                     KeyCode::Delete if filters.focused_field.is_text_input() => {
                         if let Some((text, cursor)) = filters.current_text_mut() {
@@ -1395,6 +2553,7 @@ impl App {
                             }
                         }
                         self.active_menu = ActiveMenu::SearchFilters(filters);
+                        self.schedule_filter_preview();
                     }
                     KeyCode::Left if filters.focused_field.is_text_input() => {
                         if let Some((text, cursor)) = filters.current_text_mut() {
@@ -1445,11 +2604,27 @@ impl App {
             if let Ok((w, h)) = crossterm::terminal::size() {
                 state.set_screen_size(w, h);
             }
+            state.size_basis = self.treemap_size;
             state.build_from_trees(&self.trees);
             self.treemap = Some(state);
         }
     }
 
+    /// Lay the treemap out again at its current level (after the size
+    /// basis changed)
+    fn rebuild_treemap(&mut self) {
+        let Some(tm) = self.treemap.as_mut() else {
+            return;
+        };
+        tm.size_basis = self.treemap_size;
+        let key = tm.current_key;
+        if key == NodeKey::root() {
+            tm.build_from_trees(&self.trees);
+        } else if let Some(tree) = self.trees.iter().find(|t| t.get_by_key(&key).is_some()) {
+            tm.build_from_node(tree, &key);
+        }
+    }
+
     fn handle_treemap_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Esc | KeyCode::Char('t') => {
@@ -1465,6 +2640,11 @@ impl App {
                     tm.move_prev();
                 }
             }
+            KeyCode::Char('a') => {
+                // Logical <-> allocated size
+                self.treemap_size = self.treemap_size.toggled();
+                self.rebuild_treemap();
+            }
             KeyCode::Enter => {
                 // Drill down into selected directory
                 let drill_info = self.treemap.as_ref().and_then(|tm| {
@@ -1601,12 +2781,18 @@ impl App {
                     crate::tui::menu::open_in_explorer(path);
                 }
             }
+            MenuBarAction::OpenTerminal => {
+                self.execute_action(ActionKind::OpenTerminal);
+            }
             MenuBarAction::Properties => {
                 let paths = self.get_selected_paths();
                 for path in &paths {
                     crate::tui::menu::show_properties(path);
                 }
             }
+            MenuBarAction::RescanDrive => {
+                self.start_drive_rescan();
+            }
             MenuBarAction::Rescan => {
                 self.start_scan();
             }
@@ -1626,6 +2812,9 @@ impl App {
             MenuBarAction::Rename => {
                 self.execute_action(ActionKind::Rename);
             }
+            MenuBarAction::BatchRename => {
+                self.execute_action(ActionKind::BatchRename);
+            }
             MenuBarAction::Delete => {
                 self.execute_action(ActionKind::Delete);
             }
@@ -1635,6 +2824,98 @@ impl App {
             MenuBarAction::SearchFilters => {
                 self.open_search_filters();
             }
+            MenuBarAction::ToggleHidden => {
+                self.show_hidden = !self.show_hidden;
+                self.search.needs_search = true;
+                self.status_message = if self.show_hidden {
+                    "Showing hidden files".to_string()
+                } else {
+                    "Hiding hidden files".to_string()
+                };
+            }
+            MenuBarAction::ToggleSystem => {
+                self.show_system = !self.show_system;
+                self.search.needs_search = true;
+                self.status_message = if self.show_system {
+                    "Showing system files".to_string()
+                } else {
+                    "Hiding system files".to_string()
+                };
+            }
+            MenuBarAction::ToggleMatchPath => {
+                self.match_path = !self.match_path;
+                self.search.needs_search = true;
+                self.status_message = if self.match_path {
+                    "Matching full paths (plain text matches whole folder names)".to_string()
+                } else {
+                    "Matching names".to_string()
+                };
+            }
+            MenuBarAction::ToggleUnknownMetadata => {
+                self.hide_unknown_metadata = !self.hide_unknown_metadata;
+                self.search.needs_search = true;
+                self.status_message = if self.hide_unknown_metadata {
+                    "Hiding files with unknown size/date".to_string()
+                } else {
+                    "Showing files with unknown size/date".to_string()
+                };
+            }
+            MenuBarAction::ToggleSizeSum => {
+                self.size_sum = self.size_sum.toggled();
+                self.status_message = format!("Selected size sums {}", self.size_sum.label());
+            }
+            MenuBarAction::CycleDateField => {
+                self.date_field = self.date_field.next();
+                self.status_message = format!("Date column shows {}", self.date_field.label());
+                // A date sort was by the old field
+                if self.table.sort_column == SortColumn::DateModified {
+                    self.last_sort_column = None;
+                    self.sort_results(SortColumn::DateModified, self.table.sort_order);
+                }
+            }
+            MenuBarAction::CyclePalette => {
+                self.palette = self.palette.next();
+                self.status_message = format!("Treemap palette: {}", self.palette.label);
+            }
+            MenuBarAction::DriveStats => {
+                use crate::tui::menu::InfoDialog;
+                let stats = MultiVolumeScanner::drive_stats(self.trees.iter().map(|t| t.as_ref()));
+                let mut lines = if stats.is_empty() {
+                    vec!["No drives scanned yet.".to_string()]
+                } else {
+                    drive_stats_lines(&stats)
+                };
+                lines.push(String::new());
+                lines.push("Press any key to close.".to_string());
+                self.active_menu =
+                    ActiveMenu::Info(InfoDialog::new("Drive Statistics".to_string(), lines));
+            }
+            MenuBarAction::KindSummary => {
+                use crate::tui::menu::InfoDialog;
+                let mut lines = if self.all_entries.is_empty() {
+                    vec!["No drives scanned yet.".to_string()]
+                } else {
+                    let summary = KindSummary::from_entries(
+                        self.all_entries
+                            .iter()
+                            .map(|e| (e.is_directory, e.extension, e.file_size)),
+                        &self.extensions,
+                    );
+                    kind_summary_lines(&summary, self.glyphs.bar)
+                };
+                lines.push(String::new());
+                lines.push("Press any key to close.".to_string());
+                self.active_menu =
+                    ActiveMenu::Info(InfoDialog::new("File Categories".to_string(), lines));
+            }
+            MenuBarAction::ToggleColumn(column) => {
+                self.toggle_column(column);
+            }
+            MenuBarAction::SetResultOrder(order) => {
+                self.search.default_order = order;
+                self.search.needs_search = true;
+                self.status_message = format!("Results ordered by {}", order.label());
+            }
             MenuBarAction::ApplyPresetFilter(idx) => {
                 if let Some(filter) = self.preset_filters.get(idx) {
                     self.apply_preset_filter(filter.clone());
@@ -1647,6 +2928,9 @@ impl App {
                 self.search.needs_search = true;
                 self.status_message = "Filters cleared".to_string();
             }
+            MenuBarAction::StopPathCache => {
+                self.cancel_path_cache();
+            }
             MenuBarAction::About => {
                 use crate::tui::menu::InfoDialog;
                 self.active_menu = ActiveMenu::Info(InfoDialog::new(
@@ -1670,18 +2954,24 @@ impl App {
                     "Keyboard Shortcuts".to_string(),
                     vec![
                         "Tab / /        Focus search bar".to_string(),
-                        "F1-F6          Sort by column".to_string(),
+                        "F1-F8, F11     Sort by column".to_string(),
                         "F9             Rescan drives".to_string(),
+                        "Shift+F9       Rescan selected result's drive".to_string(),
                         "F10            Open menu bar".to_string(),
                         "M              Open actions menu".to_string(),
                         "T              Toggle treemap view".to_string(),
                         "Ctrl+F         Search filters".to_string(),
+                        "Ctrl+L         Clear search filters".to_string(),
+                        "Ctrl+E         Filter to selected extension".to_string(),
                         "Ctrl+A         Select all".to_string(),
                         "Shift+Up/Down  Extend selection".to_string(),
                         "Space          Toggle selection".to_string(),
                         "Left/Right     Horizontal scroll".to_string(),
                         "Ctrl+Left/Right  Resize column".to_string(),
-                        "Enter          Open file".to_string(),
+                        "Shift+F2/F4-F8/F11 Show/hide column".to_string(),
+                        "Enter          Open file / browse folder".to_string(),
+                        "Backspace      Browse parent folder".to_string(),
+                        "A-Z / 0-9      Jump to name (when sorted by name)".to_string(),
                         "Esc            Clear / Back / Quit".to_string(),
                         "Ctrl+Q         Quit".to_string(),
                         String::new(),
@@ -1718,6 +3008,7 @@ impl App {
             self.status_message = format!("Filter: {}", filter.name);
 
             // Do a custom filtered search for directories only
+            self.search.active_patterns.clear();
             self.filtered_indices.clear();
             self.last_sort_column = None;
             for (idx, entry) in self.all_entries.iter().enumerate() {
@@ -1767,6 +3058,11 @@ fn build_menu_bar_menus(preset_filters: &[PresetFilter]) -> Vec<MenuBarMenu> {
                     shortcut: "".to_string(),
                     action: MenuBarAction::OpenInExplorer,
                 },
+                MenuBarItem {
+                    label: "Open Terminal Here".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::OpenTerminal,
+                },
                 MenuBarItem {
                     label: "Properties".to_string(),
                     shortcut: "".to_string(),
@@ -1777,6 +3073,11 @@ fn build_menu_bar_menus(preset_filters: &[PresetFilter]) -> Vec<MenuBarMenu> {
                     shortcut: "F9".to_string(),
                     action: MenuBarAction::Rescan,
                 },
+                MenuBarItem {
+                    label: "Rescan Selected Drive".to_string(),
+                    shortcut: "Shift+F9".to_string(),
+                    action: MenuBarAction::RescanDrive,
+                },
                 MenuBarItem {
                     label: "Quit".to_string(),
                     shortcut: "Ctrl+Q".to_string(),
@@ -1802,6 +3103,11 @@ fn build_menu_bar_menus(preset_filters: &[PresetFilter]) -> Vec<MenuBarMenu> {
                     shortcut: "".to_string(),
                     action: MenuBarAction::Rename,
                 },
+                MenuBarItem {
+                    label: "Batch Rename".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::BatchRename,
+                },
                 MenuBarItem {
                     label: "Delete".to_string(),
                     shortcut: "".to_string(),
@@ -1822,6 +3128,101 @@ fn build_menu_bar_menus(preset_filters: &[PresetFilter]) -> Vec<MenuBarMenu> {
                     shortcut: "Ctrl+F".to_string(),
                     action: MenuBarAction::SearchFilters,
                 },
+                MenuBarItem {
+                    label: "Hidden Files".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::ToggleHidden,
+                },
+                MenuBarItem {
+                    label: "System Files".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::ToggleSystem,
+                },
+                MenuBarItem {
+                    label: "Match Full Path".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::ToggleMatchPath,
+                },
+                MenuBarItem {
+                    label: "Unknown Size/Date Files".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::ToggleUnknownMetadata,
+                },
+                MenuBarItem {
+                    label: "Date Column Field".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::CycleDateField,
+                },
+                MenuBarItem {
+                    label: "Sum Directories in Selection".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::ToggleSizeSum,
+                },
+                MenuBarItem {
+                    label: "Treemap Palette".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::CyclePalette,
+                },
+                MenuBarItem {
+                    label: "Drive Statistics".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::DriveStats,
+                },
+                MenuBarItem {
+                    label: "File Categories".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::KindSummary,
+                },
+                MenuBarItem {
+                    label: "Path Column".to_string(),
+                    shortcut: "Shift+F2".to_string(),
+                    action: MenuBarAction::ToggleColumn(SortColumn::Path),
+                },
+                MenuBarItem {
+                    label: "Ext Column".to_string(),
+                    shortcut: "Shift+F4".to_string(),
+                    action: MenuBarAction::ToggleColumn(SortColumn::Extension),
+                },
+                MenuBarItem {
+                    label: "Date Column".to_string(),
+                    shortcut: "Shift+F5".to_string(),
+                    action: MenuBarAction::ToggleColumn(SortColumn::DateModified),
+                },
+                MenuBarItem {
+                    label: "Type Column".to_string(),
+                    shortcut: "Shift+F6".to_string(),
+                    action: MenuBarAction::ToggleColumn(SortColumn::Type),
+                },
+                MenuBarItem {
+                    label: "Drive Column".to_string(),
+                    shortcut: "Shift+F7".to_string(),
+                    action: MenuBarAction::ToggleColumn(SortColumn::Drive),
+                },
+                MenuBarItem {
+                    label: "Record Column".to_string(),
+                    shortcut: "Shift+F8".to_string(),
+                    action: MenuBarAction::ToggleColumn(SortColumn::Record),
+                },
+                MenuBarItem {
+                    label: "Storage Column".to_string(),
+                    shortcut: "Shift+F11".to_string(),
+                    action: MenuBarAction::ToggleColumn(SortColumn::Storage),
+                },
+                MenuBarItem {
+                    label: "Order: Relevance".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::SetResultOrder(ResultOrder::Relevance),
+                },
+                MenuBarItem {
+                    label: "Order: Name".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::SetResultOrder(ResultOrder::Name),
+                },
+                MenuBarItem {
+                    label: "Order: Size".to_string(),
+                    shortcut: "".to_string(),
+                    action: MenuBarAction::SetResultOrder(ResultOrder::SizeDescending),
+                },
             ],
         },
     ];
@@ -1844,6 +3245,11 @@ fn build_menu_bar_menus(preset_filters: &[PresetFilter]) -> Vec<MenuBarMenu> {
         shortcut: "".to_string(),
         action: MenuBarAction::ClearFilters,
     });
+    tools_items.push(MenuBarItem {
+        label: "Stop Path Caching".to_string(),
+        shortcut: "".to_string(),
+        action: MenuBarAction::StopPathCache,
+    });
     menus.push(MenuBarMenu {
         label: "Tools".to_string(),
         items: tools_items,
@@ -1970,6 +3376,36 @@ fn parse_csv_fields(line: &str) -> Vec<String> {
     fields
 }
 
+/// Scan settings for full scans and single-drive rescans
+fn scan_config(calculate_sizes: bool, include_deleted: bool) -> ScanConfig {
+    ScanConfig::builder()
+        .use_usn(true)
+        .use_mft(true)
+        .include_hidden(true)
+        .include_system(true)
+        .calculate_sizes(calculate_sizes)
+        .include_deleted(include_deleted)
+        .show_progress(false) // Don't write progress bars to stdout
+        .build()
+}
+
+/// Attribute flag flipped by a toggle action
+fn toggle_flag(action: ActionKind) -> u32 {
+    match action {
+        ActionKind::ToggleReadonly => file_attributes::READONLY,
+        _ => file_attributes::HIDDEN,
+    }
+}
+
+/// Lowercase name of a togglable attribute flag, for prompts
+fn attribute_name(flag: u32) -> &'static str {
+    if flag == file_attributes::READONLY {
+        "read-only"
+    } else {
+        "hidden"
+    }
+}
+
 fn extract_extension(name: &str) -> String {
     if let Some(dot_pos) = name.rfind('.') {
         if dot_pos > 0 && dot_pos < name.len() - 1 {
@@ -1979,6 +3415,221 @@ fn extract_extension(name: &str) -> String {
     String::new()
 }
 
+/// Every condition a result must meet, built once per search (or filter
+/// preview) from the query bar and the filters dialog
+struct EntryFilter {
+    show_hidden: bool,
+    show_system: bool,
+    scope_path: Option<String>,
+    /// Browsed folder (tree index, record number): only its direct
+    /// children match, in place of `scope_path`
+    folder: Option<(usize, u64)>,
+    /// Lowercased text patterns; any one must match the name
+    patterns: Vec<String>,
+    /// Test `patterns` against the full path instead (`path:` or View menu)
+    match_path: bool,
+    regex: Option<regex::Regex>,
+    date: Option<DateFilter>,
+    size: Option<SizeFilter>,
+    /// Extension filter ids; `None` if no extension filter is set. Extensions
+    /// that don't exist in the table can never match, so may be empty.
+    extensions: Option<Vec<ExtId>>,
+    /// Inline `size:`, `modified:` and `ext:` operators from the query bar
+    query: crate::search_index::SearchQuery,
+}
+
+impl EntryFilter {
+    fn new(
+        query_text: &str,
+        filters: &SearchFiltersMenu,
+        extensions: &ExtensionTable,
+        (show_hidden, show_system): (bool, bool),
+        match_path: bool,
+        folder: Option<(usize, u64)>,
+    ) -> Self {
+        let raw_query = query_text.trim().to_lowercase();
+
+        // Parse backtick-scoped path: `C:\path\to\folder` pattern
+        let (scope_path, search_query) = parse_scope_path(&raw_query);
+        let query = crate::search_index::parse_query(&search_query);
+
+        let regex = if !filters.regex_pattern.is_empty() {
+            regex::Regex::new(&filters.regex_pattern).ok()
+        } else {
+            None
+        };
+
+        let ext_filter: Vec<String> = filters
+            .extension_filter
+            .split(';')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let extensions = if ext_filter.is_empty() {
+            None
+        } else {
+            Some(ext_filter.iter().filter_map(|e| extensions.id_of(e)).collect())
+        };
+
+        Self {
+            show_hidden,
+            show_system,
+            scope_path,
+            folder,
+            patterns: query.patterns.clone(),
+            match_path: match_path || query.match_path,
+            regex,
+            date: build_date_filter(filters),
+            size: build_size_filter(filters),
+            extensions,
+            query,
+        }
+    }
+
+    /// No query, filters or scope: every entry is a result
+    fn matches_everything(&self) -> bool {
+        self.show_hidden
+            && self.show_system
+            && self.scope_path.is_none()
+            && self.folder.is_none()
+            && self.patterns.is_empty()
+            && self.regex.is_none()
+            && self.date.is_none()
+            && self.size.is_none()
+            && self.extensions.is_none()
+            && !self.query.has_operators()
+    }
+
+    /// Add one to `counts[i]` for each of `patterns[i]` that `entry` matches
+    fn count_pattern_matches(&self, entry: &EntryData, strings: &StringArena, counts: &mut [usize]) {
+        let name_lower = strings.get(entry.name_lower);
+        let full_path = self
+            .match_path
+            .then(|| full_path_lower(strings.get(entry.path_lower), name_lower));
+        for (count, pattern) in counts.iter_mut().zip(&self.patterns) {
+            let matched = match full_path {
+                Some(ref full_path) => matches_path(full_path, pattern),
+                None => matches_pattern(name_lower, pattern),
+            };
+            if matched {
+                *count += 1;
+            }
+        }
+    }
+
+    fn matches(&self, entry: &EntryData, strings: &StringArena, extensions: &ExtensionTable) -> bool {
+        if (!self.show_hidden && entry.is_hidden()) || (!self.show_system && entry.is_system()) {
+            return false;
+        }
+
+        // Browsed folder or scope path filter
+        if let Some((tree_index, folder)) = self.folder {
+            if entry.tree_index != tree_index
+                || entry.key.parent_record_number != folder
+                || entry.key.record_number == folder
+            {
+                return false;
+            }
+        } else if let Some(ref scope) = self.scope_path {
+            let path_lower = strings.get(entry.path_lower);
+            if path_lower.is_empty()
+                || !path_in_scope(path_lower, strings.get(entry.name_lower), scope)
+            {
+                return false;
+            }
+        }
+
+        // Text pattern filter
+        if !self.patterns.is_empty() {
+            let name_lower = strings.get(entry.name_lower);
+            let matched = if self.match_path {
+                let full_path = full_path_lower(strings.get(entry.path_lower), name_lower);
+                self.patterns.iter().any(|p| matches_path(&full_path, p))
+            } else {
+                self.patterns.iter().any(|p| matches_pattern(name_lower, p))
+            };
+            if !matched {
+                return false;
+            }
+        }
+
+        if let Some(ref re) = self.regex {
+            if !re.is_match(strings.get(entry.name)) {
+                return false;
+            }
+        }
+
+        if let Some(ref df) = self.date {
+            if !df.matches(entry.modification_time) {
+                return false;
+            }
+        }
+
+        if let Some(ref sf) = self.size {
+            if !sf.matches(entry.file_size) {
+                return false;
+            }
+        }
+
+        if let Some(ref ids) = self.extensions {
+            if !ids.contains(&entry.extension) {
+                return false;
+            }
+        }
+
+        !self.query.has_operators()
+            || self.query.matches_operators(
+                extensions.get(entry.extension),
+                entry.file_size,
+                entry.modification_time,
+                entry.attributes,
+                entry.has_streams,
+            )
+    }
+}
+
+fn build_date_filter(filters: &SearchFiltersMenu) -> Option<DateFilter> {
+    use crate::tui::menu::{parse_date_to_filetime, DateFilterMode};
+
+    match filters.date_mode {
+        DateFilterMode::None => None,
+        DateFilterMode::After => {
+            let start = parse_date_to_filetime(&filters.date_start)?;
+            Some(DateFilter::After(start))
+        }
+        DateFilterMode::Before => {
+            let end = parse_date_to_filetime(&filters.date_start)?;
+            Some(DateFilter::Before(end))
+        }
+        DateFilterMode::Between => {
+            let start = parse_date_to_filetime(&filters.date_start)?;
+            let end = parse_date_to_filetime(&filters.date_end)?;
+            Some(DateFilter::Between(start, end))
+        }
+    }
+}
+
+fn build_size_filter(filters: &SearchFiltersMenu) -> Option<SizeFilter> {
+    use crate::tui::menu::{parse_size_str, SizeFilterMode};
+
+    match filters.size_mode {
+        SizeFilterMode::None => None,
+        SizeFilterMode::GreaterThan => {
+            let val = parse_size_str(&filters.size_value)?;
+            Some(SizeFilter::GreaterThan(val))
+        }
+        SizeFilterMode::LessThan => {
+            let val = parse_size_str(&filters.size_value)?;
+            Some(SizeFilter::LessThan(val))
+        }
+        SizeFilterMode::Between => {
+            let start = parse_size_str(&filters.size_value)?;
+            let end = parse_size_str(&filters.size_end)?;
+            Some(SizeFilter::Between(start, end))
+        }
+    }
+}
+
 /// Date filter for search
 enum DateFilter {
     After(u64),
@@ -2023,7 +3674,7 @@ impl SizeFilter {
 fn parse_scope_path(query: &str) -> (Option<String>, String) {
     if let Some(start) = query.find('`') {
         if let Some(end) = query[start + 1..].find('`') {
-            let scope = query[start + 1..start + 1 + end].trim().to_string();
+            let scope = normalize_scope(&query[start + 1..start + 1 + end]);
             let rest_before = query[..start].trim();
             let rest_after = query[start + 1 + end + 1..].trim();
             let remaining = format!("{} {}", rest_before, rest_after)
@@ -2036,3 +3687,83 @@ fn parse_scope_path(query: &str) -> (Option<String>, String) {
     }
     (None, query.to_string())
 }
+
+/// Normalize a scope path for matching: forward slashes become backslashes
+/// and trailing separators are dropped, so `C:\` and `c:/` both become `c:`.
+fn normalize_scope(scope: &str) -> String {
+    scope
+        .trim()
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_string()
+}
+
+/// Per-drive stats as aligned text columns, plus a total column when
+/// more than one drive is loaded
+fn drive_stats_lines(stats: &[(char, crate::TreeStats)]) -> Vec<String> {
+    let mut columns: Vec<(String, [(&str, String); 6])> = stats
+        .iter()
+        .map(|(drive, s)| (format!("{}:", drive), s.summary_rows()))
+        .collect();
+    if stats.len() > 1 {
+        let total = stats
+            .iter()
+            .fold(crate::TreeStats::default(), |acc, (_, s)| acc.combined(s));
+        columns.push(("Total".to_string(), total.summary_rows()));
+    }
+    let Some((_, first)) = columns.first() else {
+        return vec!["No drives scanned yet.".to_string()];
+    };
+
+    let mut lines = vec![columns.iter().fold(format!("{:<10}", ""), |line, (label, _)| {
+        line + &format!("{:>12}", label)
+    })];
+    for (row, (label, _)) in first.iter().enumerate() {
+        lines.push(columns.iter().fold(format!("{:<10}", label), |line, (_, rows)| {
+            line + &format!("{:>12}", rows[row].1)
+        }));
+    }
+    lines
+}
+
+/// Cells in a full-width bar of the file categories view
+const KIND_BAR_WIDTH: usize = 20;
+
+/// One line per file kind (items, size, share of the total size as a bar),
+/// largest first
+fn kind_summary_lines(summary: &KindSummary, bar: &str) -> Vec<String> {
+    let total = summary.total_size().max(1);
+    let mut lines = vec![format!("{:<12}{:>12}{:>12}", "", "Items", "Size")];
+    for (kind, count, size) in summary.rows() {
+        let cells = (size as u128 * KIND_BAR_WIDTH as u128 / total as u128) as usize;
+        lines.push(format!(
+            "{:<12}{:>12}{:>12}  {:<width$} {:>3}%",
+            kind.label(),
+            count,
+            crate::format_size(size),
+            bar.repeat(cells),
+            size as u128 * 100 / total as u128,
+            width = KIND_BAR_WIDTH
+        ));
+    }
+    lines.push(format!(
+        "{:<12}{:>12}{:>12}",
+        "Total",
+        summary.total_count(),
+        crate::format_size(summary.total_size())
+    ));
+    lines
+}
+
+/// Check whether the full path (`parent_lower` + name) is the scope folder
+/// or lies beneath it. Matches on whole path components.
+fn path_in_scope(parent_lower: &str, name_lower: &str, scope: &str) -> bool {
+    let parent = parent_lower.trim_end_matches('\\');
+    if scope.len() <= parent.len() {
+        parent.starts_with(scope)
+            && (scope.len() == parent.len() || parent.as_bytes()[scope.len()] == b'\\')
+    } else {
+        // The scope names this entry itself
+        scope.starts_with(parent) && scope[parent.len()..].strip_prefix('\\') == Some(name_lower)
+    }
+}
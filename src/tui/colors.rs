@@ -17,6 +17,19 @@ pub fn color_for_extension(ext: &str) -> Color {
     }
 }
 
+/// Accent for the Drive column; cycles so each loaded drive reads differently
+pub fn color_for_drive(tree_index: usize) -> Color {
+    const PALETTE: [Color; 6] = [
+        Color::LightCyan,
+        Color::LightMagenta,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightRed,
+    ];
+    PALETTE[tree_index % PALETTE.len()]
+}
+
 pub fn icon_for_entry(is_directory: bool, ext: &str) -> &'static str {
     if is_directory {
         return "\u{1F4C1}"; // folder
@@ -0,0 +1,101 @@
+//! Glyph sets for the TUI
+//!
+//! Emoji icons and box drawing render as tofu on legacy conhost without a
+//! suitable font. Everything the TUI draws beyond plain ASCII comes from a
+//! `Glyphs` set so it can be swapped for ASCII fallbacks as a whole.
+
+use ratatui::symbols::border;
+
+/// Symbols used by the table, dialogs and treemap
+#[derive(Debug)]
+pub struct Glyphs {
+    /// Per-type emoji from `colors::icon_for_entry` rather than `folder`/`file`
+    pub type_icons: bool,
+    pub folder: &'static str,
+    pub file: &'static str,
+    /// Junction or symlink pointing to another volume
+    pub link: &'static str,
+    pub search: &'static str,
+    pub busy: &'static str,
+    pub sort_ascending: &'static str,
+    pub sort_descending: &'static str,
+    /// Separator between breadcrumb segments
+    pub crumb: &'static str,
+    pub dash: &'static str,
+    pub ellipsis: &'static str,
+    /// Key hint for horizontal scrolling
+    pub arrows_horizontal: &'static str,
+    /// Key hint for moving in all directions
+    pub arrows: &'static str,
+    /// Filled cell of a text bar chart
+    pub bar: &'static str,
+    pub border: border::Set<'static>,
+}
+
+/// Emoji and box drawing (Windows Terminal, VS Code, ...)
+pub const UNICODE: Glyphs = Glyphs {
+    type_icons: true,
+    folder: "\u{1F4C1}",
+    file: "\u{1F4C4}",
+    link: "\u{1F517}",
+    search: "\u{1F50D}",
+    busy: "\u{23F3}",
+    sort_ascending: " \u{25B2}",
+    sort_descending: " \u{25BC}",
+    crumb: " \u{25B8} ",
+    dash: "\u{2500}",
+    ellipsis: "\u{2026}",
+    arrows_horizontal: "\u{2190}\u{2192}",
+    arrows: "\u{2190}\u{2191}\u{2193}\u{2192}",
+    bar: "\u{2588}",
+    border: border::PLAIN,
+};
+
+/// Plain ASCII for consoles without a Unicode font
+pub const ASCII: Glyphs = Glyphs {
+    type_icons: false,
+    folder: "[D]",
+    file: "[F]",
+    link: "[L]",
+    search: ">",
+    busy: "*",
+    sort_ascending: " ^",
+    sort_descending: " v",
+    crumb: " > ",
+    dash: "-",
+    ellipsis: "~",
+    arrows_horizontal: "Left/Right",
+    arrows: "Arrows",
+    bar: "#",
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+};
+
+impl Glyphs {
+    /// ASCII set if `ascii`, otherwise Unicode
+    pub fn select(ascii: bool) -> &'static Glyphs {
+        if ascii {
+            &ASCII
+        } else {
+            &UNICODE
+        }
+    }
+}
+
+/// Guess whether the console renders emoji and box drawing.
+///
+/// Windows Terminal, VS Code, ConEmu and mintty-style terminals announce
+/// themselves through the environment; a bare legacy conhost does not.
+pub fn terminal_supports_unicode() -> bool {
+    ["WT_SESSION", "TERM_PROGRAM", "ConEmuANSI", "TERM"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some())
+}
@@ -1,16 +1,26 @@
+use crate::batch_rename::{self, RenameItem, RenameTemplate};
+use crate::search_index::filter_tags;
 use std::io::Write;
 use std::os::windows::process::CommandExt;
 use std::process::{Command, Stdio};
 
+pub use crate::search_index::{DateFilterMode, SizeFilterMode};
+
 /// The type of action available in the actions menu
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActionKind {
     Open,
     OpenInExplorer,
+    OpenTerminal,
     Properties,
     Delete,
+    ToggleReadonly,
+    ToggleHidden,
     Rename,
+    BatchRename,
     CopyPath,
+    CopySizeReport,
+    FilterExtension,
 }
 
 /// Actions popup menu state
@@ -25,10 +35,16 @@ impl ActionsMenu {
             items: vec![
                 ("Open", ActionKind::Open),
                 ("Open in Explorer", ActionKind::OpenInExplorer),
+                ("Open Terminal Here", ActionKind::OpenTerminal),
                 ("Properties", ActionKind::Properties),
                 ("Delete", ActionKind::Delete),
+                ("Toggle Read-only", ActionKind::ToggleReadonly),
+                ("Toggle Hidden", ActionKind::ToggleHidden),
                 ("Rename", ActionKind::Rename),
+                ("Batch Rename", ActionKind::BatchRename),
                 ("Copy Path", ActionKind::CopyPath),
+                ("Copy Size Report", ActionKind::CopySizeReport),
+                ("Filter to This Extension", ActionKind::FilterExtension),
             ],
             selected: 0,
         }
@@ -88,78 +104,89 @@ impl RenameDialog {
     }
 }
 
-/// Date filter mode for search filters
+/// Focusable input in the batch rename dialog
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DateFilterMode {
-    None,
-    After,
-    Before,
-    Between,
+pub enum BatchRenameField {
+    Pattern,
+    Find,
+    Replace,
 }
 
-impl DateFilterMode {
-    pub fn label(&self) -> &'static str {
-        match self {
-            DateFilterMode::None => "None",
-            DateFilterMode::After => "After",
-            DateFilterMode::Before => "Before",
-            DateFilterMode::Between => "Between",
-        }
-    }
-
+impl BatchRenameField {
     pub fn next(&self) -> Self {
         match self {
-            DateFilterMode::None => DateFilterMode::After,
-            DateFilterMode::After => DateFilterMode::Before,
-            DateFilterMode::Before => DateFilterMode::Between,
-            DateFilterMode::Between => DateFilterMode::None,
+            BatchRenameField::Pattern => BatchRenameField::Find,
+            BatchRenameField::Find => BatchRenameField::Replace,
+            BatchRenameField::Replace => BatchRenameField::Pattern,
         }
     }
 
     pub fn prev(&self) -> Self {
         match self {
-            DateFilterMode::None => DateFilterMode::Between,
-            DateFilterMode::After => DateFilterMode::None,
-            DateFilterMode::Before => DateFilterMode::After,
-            DateFilterMode::Between => DateFilterMode::Before,
+            BatchRenameField::Pattern => BatchRenameField::Replace,
+            BatchRenameField::Find => BatchRenameField::Pattern,
+            BatchRenameField::Replace => BatchRenameField::Find,
         }
     }
 }
 
-/// Size filter mode for search filters
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SizeFilterMode {
-    None,
-    GreaterThan,
-    LessThan,
-    Between,
+/// Batch rename dialog: a name template and optional regex find/replace,
+/// previewed for every selected item
+pub struct BatchRenameDialog {
+    pub paths: Vec<String>,
+    pub focused_field: BatchRenameField,
+    pub pattern: String,
+    pub pattern_cursor: usize,
+    pub find: String,
+    pub find_cursor: usize,
+    pub replace: String,
+    pub replace_cursor: usize,
+    /// Planned renames for the current fields, or the regex error
+    pub preview: Result<Vec<RenameItem>, String>,
+    /// First preview line shown
+    pub scroll: usize,
 }
 
-impl SizeFilterMode {
-    pub fn label(&self) -> &'static str {
-        match self {
-            SizeFilterMode::None => "None",
-            SizeFilterMode::GreaterThan => ">",
-            SizeFilterMode::LessThan => "<",
-            SizeFilterMode::Between => "Between",
-        }
+impl BatchRenameDialog {
+    pub fn new(paths: Vec<String>) -> Self {
+        let pattern = "{name}.{ext}".to_string();
+        let mut dialog = Self {
+            paths,
+            focused_field: BatchRenameField::Pattern,
+            pattern_cursor: pattern.len(),
+            pattern,
+            find: String::new(),
+            find_cursor: 0,
+            replace: String::new(),
+            replace_cursor: 0,
+            preview: Ok(Vec::new()),
+            scroll: 0,
+        };
+        dialog.refresh_preview();
+        dialog
     }
 
-    pub fn next(&self) -> Self {
-        match self {
-            SizeFilterMode::None => SizeFilterMode::GreaterThan,
-            SizeFilterMode::GreaterThan => SizeFilterMode::LessThan,
-            SizeFilterMode::LessThan => SizeFilterMode::Between,
-            SizeFilterMode::Between => SizeFilterMode::None,
+    /// Get the text input and cursor for the focused field
+    pub fn current_text_mut(&mut self) -> (&mut String, &mut usize) {
+        match self.focused_field {
+            BatchRenameField::Pattern => (&mut self.pattern, &mut self.pattern_cursor),
+            BatchRenameField::Find => (&mut self.find, &mut self.find_cursor),
+            BatchRenameField::Replace => (&mut self.replace, &mut self.replace_cursor),
         }
     }
 
-    pub fn prev(&self) -> Self {
-        match self {
-            SizeFilterMode::None => SizeFilterMode::Between,
-            SizeFilterMode::GreaterThan => SizeFilterMode::None,
-            SizeFilterMode::LessThan => SizeFilterMode::GreaterThan,
-            SizeFilterMode::Between => SizeFilterMode::LessThan,
+    /// Re-plan the renames after an edit
+    pub fn refresh_preview(&mut self) {
+        self.preview = RenameTemplate::new(&self.pattern, &self.find, &self.replace)
+            .map(|template| batch_rename::plan(&self.paths, &template))
+            .map_err(|e| e.to_string());
+    }
+
+    /// Number of items that would be renamed
+    pub fn rename_count(&self) -> usize {
+        match self.preview {
+            Ok(ref items) => items.iter().filter(|item| item.will_rename()).count(),
+            Err(_) => 0,
         }
     }
 }
@@ -250,6 +277,8 @@ pub struct SearchFiltersMenu {
     pub size_end_cursor: usize,
     pub extension_filter: String,
     pub extension_cursor: usize,
+    /// Entries the dialog's current filters would match; `None` while counting
+    pub preview_count: Option<usize>,
 }
 
 impl SearchFiltersMenu {
@@ -270,6 +299,7 @@ impl SearchFiltersMenu {
             size_end_cursor: 0,
             extension_filter: String::new(),
             extension_cursor: 0,
+            preview_count: None,
         }
     }
 
@@ -309,6 +339,16 @@ impl SearchFiltersMenu {
             || self.size_mode != SizeFilterMode::None
             || !self.extension_filter.is_empty()
     }
+
+    /// Summary of the active filters for the search bar (see `filter_tags`)
+    pub fn indicator(&self) -> String {
+        filter_tags(
+            &self.regex_pattern,
+            (self.size_mode, &self.size_value, &self.size_end),
+            (self.date_mode, &self.date_start, &self.date_end),
+            &self.extension_filter,
+        )
+    }
 }
 
 /// Info dialog for displaying multi-line information
@@ -329,6 +369,7 @@ pub enum ActiveMenu {
     Actions(ActionsMenu),
     Confirm(ConfirmDialog),
     Rename(RenameDialog),
+    BatchRename(BatchRenameDialog),
     SearchFilters(SearchFiltersMenu),
     Info(InfoDialog),
 }
@@ -354,13 +395,19 @@ pub fn open_file(path: &str) {
         .spawn();
 }
 
-/// Open Windows Explorer with the file selected
+/// Open Windows Explorer on the containing folder with the file selected.
+/// Directories are opened directly rather than selected in their parent.
 pub fn open_in_explorer(path: &str) {
     // Use raw_arg to avoid Rust's automatic argument quoting, which breaks
     // paths containing spaces or special characters like parentheses.
     // explorer.exe expects: /select,"C:\path with spaces\file"
+    let arg = if std::path::Path::new(path).is_dir() {
+        format!("\"{}\"", path)
+    } else {
+        format!("/select,\"{}\"", path.trim_end_matches('\\'))
+    };
     let _ = Command::new("explorer.exe")
-        .raw_arg(format!("/select,\"{}\"", path))
+        .raw_arg(arg)
         .creation_flags(0x08000000) // CREATE_NO_WINDOW
         .spawn();
 }
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod colors;
+pub mod glyphs;
 pub mod menu;
 pub mod search;
 pub mod table;
@@ -16,6 +17,11 @@ use std::io;
 
 /// Entry point: set up terminal, run event loop, restore terminal on exit
 pub fn run() -> crate::Result<()> {
+    run_with_config(&crate::AppConfig::default())
+}
+
+/// Same as `run`, using the refresh interval from `config`
+pub fn run_with_config(config: &crate::AppConfig) -> crate::Result<()> {
     // Set console to UTF-8 for proper unicode character display
     unsafe {
         let _ = windows::Win32::System::Console::SetConsoleOutputCP(65001);
@@ -37,8 +43,19 @@ pub fn run() -> crate::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    // Fall back to ASCII on consoles that cannot show emoji or box drawing
+    let ascii = config.ascii_ui || !glyphs::terminal_supports_unicode();
+
     // Create app and run
-    let mut app = app::App::new();
+    let mut app = app::App::new()
+        .with_tick_rate(std::time::Duration::from_millis(config.refresh_interval_ms.max(1)))
+        .with_show_hidden(config.show_hidden_results)
+        .with_show_system(config.show_system_results)
+        .with_ascii(ascii)
+        .with_palette(&config.treemap_palette)
+        .with_select_path(config.select_path.clone())
+        .with_calculate_sizes(config.calculate_sizes)
+        .with_include_deleted(config.include_deleted);
     let result = app.run(&mut terminal);
 
     // Restore terminal
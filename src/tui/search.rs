@@ -4,6 +4,16 @@ pub struct SearchState {
     pub cursor_pos: usize,
     pub focused: bool,
     pub needs_search: bool,
+    /// Lowercased `;`-separated patterns from the last search, used for highlighting
+    pub active_patterns: Vec<String>,
+    /// Ordering applied to fresh results of a text query
+    pub default_order: ResultOrder,
+    /// Results each `;`-separated pattern matched in the last search, when
+    /// there was more than one. A result matching several patterns counts
+    /// for each, so the counts can add up to more than the results. Results
+    /// held back for unknown metadata are not counted; their metadata
+    /// arriving runs the search again, which recounts.
+    pub pattern_counts: Vec<(String, usize)>,
 }
 
 impl Default for SearchState {
@@ -13,40 +23,48 @@ impl Default for SearchState {
             cursor_pos: 0,
             focused: true,
             needs_search: false,
+            active_patterns: Vec::new(),
+            default_order: ResultOrder::default(),
+            pattern_counts: Vec::new(),
         }
     }
 }
 
-/// Check if a filename matches a pattern.
-/// Supports `*` wildcards: `*.ext`, `prefix*`, `*text*`, or plain substring.
-pub fn matches_pattern(name: &str, pattern: &str) -> bool {
-    if pattern.is_empty() {
-        return true;
-    }
+/// Check if a filename matches a pattern (the index's rules: whole-name
+/// text and wildcards, `^`/`$` to leave one end open)
+pub use crate::search_index::matches_pattern;
 
-    let has_leading_star = pattern.starts_with('*');
-    let has_trailing_star = pattern.ends_with('*');
+pub use crate::search_index::{match_range, pattern_counts_label, relevance_rank, ResultOrder};
 
-    match (has_leading_star, has_trailing_star) {
-        (true, true) if pattern.len() > 2 => {
-            // *text* -> contains
-            name.contains(&pattern[1..pattern.len() - 1])
-        }
-        (true, true) => {
-            // Just "*" or "**" -> matches everything
-            true
-        }
-        (true, false) => {
-            // *.ext -> ends with
-            name.ends_with(&pattern[1..])
-        }
-        (false, true) => {
-            // prefix* -> starts with
-            name.starts_with(&pattern[..pattern.len() - 1])
+/// Compute a per-character highlight mask for `name` against all patterns.
+/// Matching is case-insensitive; patterns are expected to be lowercase.
+pub fn highlight_mask(name: &str, patterns: &[String]) -> Vec<bool> {
+    let char_count = name.chars().count();
+    let mut mask = vec![false; char_count];
+    if patterns.is_empty() {
+        return mask;
+    }
+
+    // Lowercase char by char so byte offsets in the lowered string can be
+    // mapped back to character positions in the original name
+    let mut lowered = String::with_capacity(name.len());
+    let mut byte_to_char: Vec<usize> = Vec::with_capacity(name.len());
+    for (ci, ch) in name.chars().enumerate() {
+        for lc in ch.to_lowercase() {
+            lowered.push(lc);
+            for _ in 0..lc.len_utf8() {
+                byte_to_char.push(ci);
+            }
         }
-        (false, false) => {
-            // plain substring match
-            name.contains(pattern)
+    }
+
+    for pattern in patterns {
+        if let Some((start, end)) = match_range(&lowered, pattern) {
+            for &ci in &byte_to_char[start..end] {
+                mask[ci] = true;
+            }
         }
     }
+
+    mask
 }
@@ -1,3 +1,4 @@
+use crate::tui::glyphs::Glyphs;
 use std::collections::BTreeSet;
 
 /// Which column is sorted
@@ -10,6 +11,12 @@ pub enum SortColumn {
     Extension,
     DateModified,
     Type,
+    /// Drive letter of the volume the entry was scanned from
+    Drive,
+    /// MFT record number; roughly the order records were allocated in
+    Record,
+    /// Resident in the MFT record, or the number of fragments on disk
+    Storage,
 }
 
 /// Sort direction
@@ -21,14 +28,47 @@ pub enum SortOrder {
 }
 
 impl SortOrder {
-    pub fn indicator(&self) -> &'static str {
+    pub fn indicator(&self, glyphs: &Glyphs) -> &'static str {
         match self {
-            SortOrder::Ascending => " \u{25B2}",
-            SortOrder::Descending => " \u{25BC}",
+            SortOrder::Ascending => glyphs.sort_ascending,
+            SortOrder::Descending => glyphs.sort_descending,
         }
     }
 }
 
+/// `column_widths` value of a hidden column
+pub const HIDDEN_COLUMN: u16 = u16::MAX;
+
+/// Default column widths: [Name, Path, Size, Ext, DateModified, Type, Drive, Record,
+/// Storage]. 0 = Fill.
+pub const DEFAULT_COLUMN_WIDTHS: [u16; 9] = [25, 0, 12, 8, 20, 18, 6, 10, 13];
+
+/// Columns that can be hidden (Name and Size always stay)
+pub const TOGGLEABLE_COLUMNS: [SortColumn; 7] = [
+    SortColumn::Path,
+    SortColumn::Extension,
+    SortColumn::DateModified,
+    SortColumn::Type,
+    SortColumn::Drive,
+    SortColumn::Record,
+    SortColumn::Storage,
+];
+
+/// Columns hidden until the user shows them
+const HIDDEN_BY_DEFAULT: [SortColumn; 2] = [SortColumn::Record, SortColumn::Storage];
+
+/// Settings file next to the executable holding the hidden columns
+const COLUMNS_FILE: &str = "Columns.cfg";
+
+/// Startup widths: the defaults with `HIDDEN_BY_DEFAULT` columns hidden
+fn default_column_widths() -> [u16; 9] {
+    let mut widths = DEFAULT_COLUMN_WIDTHS;
+    for col in HIDDEN_BY_DEFAULT {
+        widths[col.index()] = HIDDEN_COLUMN;
+    }
+    widths
+}
+
 /// Table display state
 pub struct TableState {
     pub selected: Option<usize>,
@@ -36,8 +76,9 @@ pub struct TableState {
     pub visible_rows: usize,
     pub sort_column: SortColumn,
     pub sort_order: SortOrder,
-    /// Column widths: [Name, Path, Size, Ext, DateModified, Type]. 0 = Fill.
-    pub column_widths: [u16; 6],
+    /// Column widths: [Name, Path, Size, Ext, DateModified, Type, Drive, Record,
+    /// Storage]. 0 = Fill, `HIDDEN_COLUMN` = not shown.
+    pub column_widths: [u16; 9],
     /// Horizontal scroll offset (characters)
     pub horizontal_offset: u16,
     /// Multi-selection: set of selected logical indices
@@ -54,7 +95,7 @@ impl Default for TableState {
             visible_rows: 20,
             sort_column: SortColumn::Name,
             sort_order: SortOrder::Ascending,
-            column_widths: [25, 0, 12, 8, 20, 18],
+            column_widths: default_column_widths(),
             horizontal_offset: 0,
             selections: BTreeSet::new(),
             anchor: None,
@@ -72,11 +113,103 @@ impl SortColumn {
             SortColumn::Extension => 3,
             SortColumn::DateModified => 4,
             SortColumn::Type => 5,
+            SortColumn::Drive => 6,
+            SortColumn::Record => 7,
+            SortColumn::Storage => 8,
+        }
+    }
+
+    /// Header label
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortColumn::Name => "Name",
+            SortColumn::Path => "Path",
+            SortColumn::Size => "Size",
+            SortColumn::Extension => "Ext",
+            SortColumn::DateModified => "Date Modified",
+            SortColumn::Type => "Type",
+            SortColumn::Drive => "Drive",
+            SortColumn::Record => "Record",
+            SortColumn::Storage => "Storage",
         }
     }
+
+    /// Parse a label written by `label`
+    fn from_label(label: &str) -> Option<Self> {
+        [
+            SortColumn::Name,
+            SortColumn::Path,
+            SortColumn::Size,
+            SortColumn::Extension,
+            SortColumn::DateModified,
+            SortColumn::Type,
+            SortColumn::Drive,
+            SortColumn::Record,
+            SortColumn::Storage,
+        ]
+        .into_iter()
+        .find(|col| col.label().eq_ignore_ascii_case(label))
+    }
 }
 
 impl TableState {
+    /// Check if a column is shown
+    pub fn is_column_visible(&self, column: SortColumn) -> bool {
+        self.column_widths[column.index()] != HIDDEN_COLUMN
+    }
+
+    /// Show or hide a column. Name and Size cannot be hidden.
+    /// Returns whether the column is now visible.
+    pub fn toggle_column(&mut self, column: SortColumn) -> bool {
+        if !TOGGLEABLE_COLUMNS.contains(&column) {
+            return true;
+        }
+        let idx = column.index();
+        if self.column_widths[idx] == HIDDEN_COLUMN {
+            self.column_widths[idx] = DEFAULT_COLUMN_WIDTHS[idx];
+            true
+        } else {
+            self.column_widths[idx] = HIDDEN_COLUMN;
+            false
+        }
+    }
+
+    /// Columns currently hidden
+    pub fn hidden_columns(&self) -> Vec<SortColumn> {
+        TOGGLEABLE_COLUMNS
+            .into_iter()
+            .filter(|&col| !self.is_column_visible(col))
+            .collect()
+    }
+
+    /// Hide the columns listed in `Columns.cfg`, if present
+    pub fn load_column_visibility(&mut self) {
+        let Ok(content) = std::fs::read_to_string(columns_file_path()) else {
+            return;
+        };
+        // The file lists every hidden column, including ones hidden by default
+        for col in TOGGLEABLE_COLUMNS {
+            self.column_widths[col.index()] = DEFAULT_COLUMN_WIDTHS[col.index()];
+        }
+        for line in content.lines() {
+            if let Some(list) = line.trim().strip_prefix("hidden=") {
+                for label in list.split(',') {
+                    if let Some(col) = SortColumn::from_label(label.trim()) {
+                        if TOGGLEABLE_COLUMNS.contains(&col) {
+                            self.column_widths[col.index()] = HIDDEN_COLUMN;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write the hidden columns to `Columns.cfg`
+    pub fn save_column_visibility(&self) -> std::io::Result<()> {
+        let labels: Vec<&str> = self.hidden_columns().iter().map(|c| c.label()).collect();
+        std::fs::write(columns_file_path(), format!("hidden={}\n", labels.join(",")))
+    }
+
     pub fn select_next(&mut self, total: usize) {
         if total == 0 {
             return;
@@ -232,6 +365,15 @@ impl TableState {
         self.ensure_visible(total - 1);
     }
 
+    /// Select a single row and scroll it into view
+    pub fn select_row(&mut self, index: usize) {
+        self.selected = Some(index);
+        self.selections.clear();
+        self.selections.insert(index);
+        self.anchor = Some(index);
+        self.ensure_visible(index);
+    }
+
     fn ensure_visible(&mut self, index: usize) {
         if index < self.scroll_offset {
             self.scroll_offset = index;
@@ -240,3 +382,11 @@ impl TableState {
         }
     }
 }
+
+/// `Columns.cfg` next to the executable (current directory as fallback)
+fn columns_file_path() -> std::path::PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.join(COLUMNS_FILE)))
+        .unwrap_or_else(|| std::path::PathBuf::from(COLUMNS_FILE))
+}
@@ -1,4 +1,7 @@
-use crate::file_tree::{FileTree, NodeKey};
+use crate::file_tree::{ChildSort, FileTree, NodeKey};
+use crate::palette::{Rgb, TreemapPalette};
+use crate::tui::glyphs::Glyphs;
+use crate::SizeBasis;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 use std::sync::Arc;
@@ -28,6 +31,8 @@ pub struct TreemapRect {
     /// laid out inside it.  The renderer draws a border+title and the children
     /// paint on top of the interior background.
     pub children_rendered: bool,
+    /// Has the system attribute; drawn with a marker
+    pub is_system: bool,
 }
 
 /// Persistent state for the treemap view.
@@ -40,6 +45,8 @@ pub struct TreemapState {
     pub screen_w: f64,
     /// Terminal height of the treemap canvas (after subtracting chrome).
     pub screen_h: f64,
+    /// Logical or allocated size – set before building layout.
+    pub size_basis: SizeBasis,
 }
 
 impl TreemapState {
@@ -51,6 +58,7 @@ impl TreemapState {
             current_key: NodeKey::root(),
             screen_w: 160.0,
             screen_h: 45.0,
+            size_basis: SizeBasis::default(),
         }
     }
 
@@ -75,7 +83,8 @@ impl TreemapState {
 
         for tree in trees {
             if let Some(root) = tree.root() {
-                let drive = format!("{}:", tree.drive_letter);
+                // Same `C:\` the paths in the results start with
+                let drive = tree.build_path_for_key(&root.key());
                 self.breadcrumb.push((root.key(), drive));
                 self.layout_children(tree, &root.key(), 0.0, 0.0, 1.0, 1.0, 0);
             }
@@ -119,29 +128,32 @@ impl TreemapState {
             return;
         }
 
-        let children = tree.get_children(parent_key);
-        let mut items: Vec<(String, u64, bool, NodeKey)> = Vec::new();
+        // Squarify needs items sorted largest-first
+        let children = tree.children(parent_key, ChildSort::Size(self.size_basis));
+        let mut items: Vec<(String, u64, bool, NodeKey, bool)> = Vec::new();
+        // Deleted files only take room inside a deleted folder, matching
+        // what FileTree::calculate_sizes counts
+        let parent_deleted = tree.get_by_key(parent_key).is_some_and(|n| n.is_deleted);
 
         for child in &children {
-            if child.name == "." || child.name == ".." {
+            if child.is_self_reference() || (child.is_deleted && !parent_deleted) {
                 continue;
             }
-            let size = if child.is_directory {
-                if child.total_size > 0 { child.total_size } else { child.file_size }
-            } else {
-                child.file_size
-            };
+            let size = self.size_basis.size_of(child);
             if size > 0 {
-                items.push((child.name.clone(), size, child.is_directory, child.key()));
+                items.push((
+                    child.name.clone(),
+                    size,
+                    child.is_directory,
+                    child.key(),
+                    child.is_system(),
+                ));
             }
         }
         if items.is_empty() {
             return;
         }
 
-        // Squarify needs items sorted largest-first
-        items.sort_by(|a, b| b.1.cmp(&a.1));
-
         let limit = match depth {
             0 => 2000,
             1 => 1000,
@@ -165,7 +177,7 @@ impl TreemapState {
     fn squarify_strip(
         &mut self,
         tree: &FileTree,
-        items: &[(String, u64, bool, NodeKey)],
+        items: &[(String, u64, bool, NodeKey, bool)],
         x: f64,
         y: f64,
         w: f64,
@@ -257,7 +269,7 @@ impl TreemapState {
     fn place_item(
         &mut self,
         tree: &FileTree,
-        item: &(String, u64, bool, NodeKey),
+        item: &(String, u64, bool, NodeKey, bool),
         x: f64,
         y: f64,
         w: f64,
@@ -283,6 +295,7 @@ impl TreemapState {
             h,
             key: item.3,
             children_rendered: can_nest,
+            is_system: item.4,
         });
 
         if can_nest {
@@ -359,133 +372,9 @@ impl TreemapState {
 // Colour palette
 // ============================================================================
 
-/// Border colour for directory containers, cycling through depth.
-fn depth_border_color(depth: usize) -> Color {
-    match depth % 7 {
-        0 => Color::Rgb(0, 190, 230),   // cyan
-        1 => Color::Rgb(90, 200, 70),   // green
-        2 => Color::Rgb(230, 190, 40),  // gold
-        3 => Color::Rgb(210, 90, 200),  // magenta
-        4 => Color::Rgb(70, 140, 240),  // blue
-        5 => Color::Rgb(230, 130, 50),  // orange
-        _ => Color::Rgb(130, 210, 180), // teal
-    }
-}
-
-/// Dark tinted background inside directory containers.
-fn depth_bg_color(depth: usize) -> Color {
-    match depth % 7 {
-        0 => Color::Rgb(8, 22, 28),
-        1 => Color::Rgb(12, 24, 10),
-        2 => Color::Rgb(26, 22, 8),
-        3 => Color::Rgb(24, 12, 24),
-        4 => Color::Rgb(10, 16, 30),
-        5 => Color::Rgb(26, 16, 8),
-        _ => Color::Rgb(12, 24, 20),
-    }
-}
-
-/// Colour for leaf rectangles (files or tiny directories).
-fn leaf_color(name: &str, is_directory: bool, index: usize) -> Color {
-    if is_directory {
-        let palette = [
-            Color::Rgb(40, 105, 135),
-            Color::Rgb(50, 115, 120),
-            Color::Rgb(60, 95, 145),
-            Color::Rgb(45, 125, 110),
-            Color::Rgb(55, 108, 128),
-        ];
-        return palette[index % palette.len()];
-    }
-
-    let ext = name.rsplit('.').next().unwrap_or("").to_lowercase();
-    match ext.as_str() {
-        // ── executables / system ────────────────────────────────────────
-        "exe" | "com" | "scr"                        => Color::Rgb(200, 55, 55),
-        "dll" | "sys" | "drv" | "ocx"                => Color::Rgb(175, 65, 65),
-        "msi" | "bat" | "cmd" | "ps1"                => Color::Rgb(185, 80, 50),
-
-        // ── archives ────────────────────────────────────────────────────
-        "zip" | "rar" | "7z" | "gz" | "tar"
-        | "xz" | "bz2" | "cab" | "iso"              => Color::Rgb(200, 175, 35),
-
-        // ── video ───────────────────────────────────────────────────────
-        "mp4" | "mkv" | "avi" | "mov" | "wmv"
-        | "flv" | "webm" | "m4v" | "ts"             => Color::Rgb(160, 45, 195),
-
-        // ── audio ───────────────────────────────────────────────────────
-        "mp3" | "wav" | "flac" | "ogg"
-        | "aac" | "wma" | "m4a" | "opus"            => Color::Rgb(35, 175, 135),
-
-        // ── images ──────────────────────────────────────────────────────
-        "jpg" | "jpeg" | "png" | "gif" | "bmp"
-        | "tiff" | "webp" | "ico" | "svg" | "psd"
-        | "raw" | "cr2" | "nef" | "dng"             => Color::Rgb(195, 125, 35),
-
-        // ── documents ───────────────────────────────────────────────────
-        "pdf"                                        => Color::Rgb(200, 50, 50),
-        "doc" | "docx" | "odt" | "rtf"              => Color::Rgb(55, 130, 200),
-        "xls" | "xlsx" | "ods" | "csv"              => Color::Rgb(45, 165, 65),
-        "ppt" | "pptx" | "odp"                      => Color::Rgb(200, 105, 35),
-
-        // ── text / config ───────────────────────────────────────────────
-        "txt" | "log" | "md" | "cfg" | "ini"
-        | "conf" | "yml" | "yaml" | "toml"          => Color::Rgb(120, 120, 120),
-
-        // ── code ────────────────────────────────────────────────────────
-        "rs" | "go" | "c" | "cpp" | "h" | "hpp"
-        | "cs"                                       => Color::Rgb(75, 150, 220),
-        "py" | "pyw"                                 => Color::Rgb(55, 140, 185),
-        "js" | "jsx" | "tsx"                         => Color::Rgb(215, 195, 45),
-        "java" | "kt" | "scala"                     => Color::Rgb(170, 100, 55),
-        "html" | "htm" | "css" | "scss"              => Color::Rgb(215, 75, 45),
-        "json" | "xml" | "sql"                       => Color::Rgb(140, 160, 55),
-
-        // ── game data ───────────────────────────────────────────────────
-        "pak" | "rpf" | "bdt" | "pack" | "assets"
-        | "resource" | "forge" | "wad"               => Color::Rgb(185, 75, 165),
-
-        // ── virtual disks / dumps ───────────────────────────────────────
-        "vdi" | "vmdk" | "vhd" | "vhdx" | "qcow2"
-        | "img" | "bin" | "001"                      => Color::Rgb(100, 65, 165),
-
-        // ── databases ───────────────────────────────────────────────────
-        "db" | "sqlite" | "mdf" | "ldf" | "bak"     => Color::Rgb(135, 115, 45),
-
-        // ── fonts ───────────────────────────────────────────────────────
-        "ttf" | "otf" | "woff" | "woff2"            => Color::Rgb(160, 140, 100),
-
-        // ── streaming / media DB ────────────────────────────────────────
-        "stream" | "streamdb"                        => Color::Rgb(155, 55, 125),
-
-        // ── MFT dumps ───────────────────────────────────────────────────
-        "mft"                                        => Color::Rgb(200, 145, 35),
-
-        // ── fallback: hash extension to a hue ───────────────────────────
-        _ => {
-            let h = ext.bytes().fold(0u32, |a, b| a.wrapping_mul(31).wrapping_add(b as u32));
-            hsl_to_rgb((h % 360) as f64, 0.45, 0.38)
-        }
-    }
-}
-
-fn hsl_to_rgb(h: f64, s: f64, l: f64) -> Color {
-    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
-    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
-    let m = l - c / 2.0;
-    let (r, g, b) = match h as u32 {
-        0..=59   => (c, x, 0.0),
-        60..=119 => (x, c, 0.0),
-        120..=179=> (0.0, c, x),
-        180..=239=> (0.0, x, c),
-        240..=299=> (x, 0.0, c),
-        _        => (c, 0.0, x),
-    };
-    Color::Rgb(
-        ((r + m) * 255.0) as u8,
-        ((g + m) * 255.0) as u8,
-        ((b + m) * 255.0) as u8,
-    )
+/// Palette colour as a terminal colour
+fn rgb((r, g, b): Rgb) -> Color {
+    Color::Rgb(r, g, b)
 }
 
 // ============================================================================
@@ -493,7 +382,13 @@ fn hsl_to_rgb(h: f64, s: f64, l: f64) -> Color {
 // ============================================================================
 
 /// Draw the complete treemap view: breadcrumb bar, treemap canvas, info bar.
-pub fn draw_treemap(frame: &mut Frame, state: &TreemapState, area: Rect) {
+pub fn draw_treemap(
+    frame: &mut Frame,
+    state: &TreemapState,
+    area: Rect,
+    glyphs: &Glyphs,
+    palette: &TreemapPalette,
+) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -509,9 +404,9 @@ pub fn draw_treemap(frame: &mut Frame, state: &TreemapState, area: Rect) {
         .iter()
         .map(|(_, n)| n.as_str())
         .collect::<Vec<_>>()
-        .join(" \u{25B8} ");
+        .join(glyphs.crumb);
     frame.render_widget(
-        Paragraph::new(format!(" \u{1F4C1} {} ", crumb))
+        Paragraph::new(format!(" {} {} ", glyphs.folder, crumb))
             .style(Style::default().fg(Color::White).bg(Color::Rgb(30, 30, 50))),
         chunks[0],
     );
@@ -541,23 +436,26 @@ pub fn draw_treemap(frame: &mut Frame, state: &TreemapState, area: Rect) {
         let is_sel = i == state.selected;
 
         if rect.children_rendered {
-            draw_container(frame, rect, cell, is_sel);
+            draw_container(frame, rect, cell, is_sel, glyphs, palette);
         } else {
-            draw_leaf(frame, rect, cell, is_sel, i);
+            draw_leaf(frame, rect, cell, is_sel, i, glyphs, palette);
         }
     }
 
     // ── Info bar ────────────────────────────────────────────────────────
     let info = if let Some(r) = state.selected_rect() {
-        let icon = if r.is_directory { "\u{1F4C1}" } else { "\u{1F4C4}" };
+        let icon = if r.is_directory { glyphs.folder } else { glyphs.file };
         format!(
-            " {} {} \u{2500} {} | \u{2190}\u{2191}\u{2193}\u{2192}:Nav  Enter:Drill  Bksp:Up  Esc/T:Close",
+            " {} {} {} {} {} | {}:Nav  Enter:Drill  Bksp:Up  A:Size  Esc/T:Close",
             icon,
             r.name,
+            glyphs.dash,
             crate::format_size(r.size),
+            state.size_basis.label(),
+            glyphs.arrows,
         )
     } else {
-        " Treemap | Arrows:Nav  Enter:Drill  Backspace:Up  Esc/T:Close".into()
+        " Treemap | Arrows:Nav  Enter:Drill  Backspace:Up  A:Size  Esc/T:Close".into()
     };
     frame.render_widget(
         Paragraph::new(info)
@@ -570,9 +468,16 @@ pub fn draw_treemap(frame: &mut Frame, state: &TreemapState, area: Rect) {
 // Directory container: bordered box with a title line.  The dark interior
 // will be overwritten by children that paint after this rect.
 // ────────────────────────────────────────────────────────────────────────────
-fn draw_container(frame: &mut Frame, rect: &TreemapRect, area: Rect, selected: bool) {
-    let bg = depth_bg_color(rect.depth);
-    let border_fg = depth_border_color(rect.depth);
+fn draw_container(
+    frame: &mut Frame,
+    rect: &TreemapRect,
+    area: Rect,
+    selected: bool,
+    glyphs: &Glyphs,
+    palette: &TreemapPalette,
+) {
+    let bg = rgb(palette.depth_bg(rect.depth));
+    let border_fg = rgb(palette.depth_border(rect.depth));
 
     // Fill background
     frame.render_widget(
@@ -581,42 +486,46 @@ fn draw_container(frame: &mut Frame, rect: &TreemapRect, area: Rect, selected: b
     );
 
     // Title
-    let title = fit_title(&rect.name, rect.size, area.width);
+    let title = fit_title(&rect.name, rect.size, area.width, glyphs.ellipsis);
     let has_title = !title.is_empty();
 
-    // Selection: paint the top title row in CGA blue so the user can see
-    // which container is highlighted without adding any extra borders.
-    let title_bg = if selected { CGA_BLUE } else { bg };
+    // Selection: paint the top title row in the selection colour, and
+    // underline it so it does not rely on colour alone.
+    let title_style = if selected {
+        Style::default()
+            .fg(rgb(palette.selection_fg))
+            .bg(rgb(palette.selection_bg))
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    } else {
+        Style::default()
+            .fg(rgb(palette.text))
+            .bg(bg)
+            .add_modifier(Modifier::BOLD)
+    };
 
     let mut block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(Style::default().fg(border_fg));
 
     if has_title {
-        block = block
-            .title(title)
-            .title_style(
-                Style::default()
-                    .fg(Color::White)
-                    .bg(title_bg)
-                    .add_modifier(Modifier::BOLD),
-            );
+        block = block.title(title).title_style(title_style);
     }
 
     frame.render_widget(block, area);
 
     // If selected and the title was empty (box too small), paint the whole
-    // interior CGA blue so selection is still visible.
+    // interior in the selection colour so selection is still visible.
     if selected && !has_title {
         frame.render_widget(
-            Paragraph::new("").style(Style::default().bg(CGA_BLUE)),
+            Paragraph::new("").style(Style::default().bg(rgb(palette.selection_bg))),
             area,
         );
     }
 }
 
 /// Fit a "name (size)" title into the available width.
-fn fit_title(name: &str, size: u64, width: u16) -> String {
+fn fit_title(name: &str, size: u64, width: u16, ellipsis: &str) -> String {
     if width < 6 {
         return String::new();
     }
@@ -639,28 +548,37 @@ fn fit_title(name: &str, size: u64, width: u16) -> String {
         return String::new();
     }
     let trunc: String = name.chars().take(max).collect();
-    format!(" {}\u{2026} ", trunc)
+    format!(" {}{} ", trunc, ellipsis)
 }
 
 // ────────────────────────────────────────────────────────────────────────────
 // Leaf rectangle: solid coloured block for a file or tiny directory.
-// Selection = MS-DOS CGA colour 1 (eye-searing blue) background.
+// Selection = the palette's selection background (CGA blue by default),
+// in bold underlined text so it does not rely on colour alone.
 // Each row is rendered as a single padded span for clean full-width fill.
 // ────────────────────────────────────────────────────────────────────────────
 
-/// Classic CGA colour 1 – the eye-searing DOS blue.
-const CGA_BLUE: Color = Color::Rgb(0, 0, 170);
-
-fn draw_leaf(frame: &mut Frame, rect: &TreemapRect, area: Rect, selected: bool, idx: usize) {
+fn draw_leaf(
+    frame: &mut Frame,
+    rect: &TreemapRect,
+    area: Rect,
+    selected: bool,
+    idx: usize,
+    glyphs: &Glyphs,
+    palette: &TreemapPalette,
+) {
     let bg = if selected {
-        CGA_BLUE
+        palette.selection_bg
     } else {
-        leaf_color(&rect.name, rect.is_directory, idx)
+        palette.leaf(&rect.name, rect.is_directory, idx)
     };
     let fg = if selected {
-        Color::White
+        palette.selection_fg
+    } else if rect.is_system {
+        // System files get their own text colour so they stand out from user data
+        palette.system_text
     } else {
-        Color::Rgb(235, 235, 235)
+        palette.text
     };
 
     let w = area.width as usize;
@@ -668,9 +586,12 @@ fn draw_leaf(frame: &mut Frame, rect: &TreemapRect, area: Rect, selected: bool,
 
     // Build rows of text, each exactly `w` chars wide so the background
     // fills every cell cleanly with no wrapping artefacts.
-    let rows = leaf_rows(&rect.name, rect.size, w, h);
+    let rows = leaf_rows(&rect.name, rect.size, w, h, glyphs.ellipsis);
 
-    let style = Style::default().fg(fg).bg(bg);
+    let mut style = Style::default().fg(rgb(fg)).bg(rgb(bg));
+    if selected {
+        style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    }
 
     for (row_idx, row_text) in rows.iter().enumerate() {
         if row_idx >= h {
@@ -695,7 +616,7 @@ fn draw_leaf(frame: &mut Frame, rect: &TreemapRect, area: Rect, selected: bool,
 
 /// Build one string per row for a leaf rectangle.  Each string is exactly
 /// `w` chars wide (space-padded) so the background colour fills cleanly.
-fn leaf_rows(name: &str, size: u64, w: usize, h: usize) -> Vec<String> {
+fn leaf_rows(name: &str, size: u64, w: usize, h: usize, ellipsis: &str) -> Vec<String> {
     if w == 0 || h == 0 {
         return Vec::new();
     }
@@ -704,13 +625,13 @@ fn leaf_rows(name: &str, size: u64, w: usize, h: usize) -> Vec<String> {
 
     if h >= 2 && w >= 6 {
         // Row 0: name (truncated)
-        rows.push(pad_row(&trunc(name, w), w));
+        rows.push(pad_row(&trunc(name, w, ellipsis), w));
         // Row 1: size
         let s = crate::format_size(size);
-        rows.push(pad_row(&trunc(&s, w), w));
+        rows.push(pad_row(&trunc(&s, w, ellipsis), w));
     } else if w >= 3 {
         // Single row: name
-        rows.push(pad_row(&trunc(name, w), w));
+        rows.push(pad_row(&trunc(name, w, ellipsis), w));
     } else if w >= 1 {
         // Tiny: first char(s)
         let t: String = name.chars().take(w).collect();
@@ -734,8 +655,8 @@ fn pad_row(s: &str, w: usize) -> String {
     }
 }
 
-/// Truncate string to `max` display chars, adding … if needed.
-fn trunc(s: &str, max: usize) -> String {
+/// Truncate string to `max` display chars, ending in `ellipsis` if needed.
+fn trunc(s: &str, max: usize, ellipsis: &str) -> String {
     let char_count = s.chars().count();
     if char_count <= max {
         s.to_string()
@@ -743,6 +664,6 @@ fn trunc(s: &str, max: usize) -> String {
         s.chars().take(max).collect()
     } else {
         let t: String = s.chars().take(max - 1).collect();
-        format!("{}\u{2026}", t)
+        format!("{}{}", t, ellipsis)
     }
 }
@@ -1,7 +1,10 @@
 use crate::file_tree::NodeKey;
+use crate::ntfs::structs::Storage;
 use crate::tui::app::{App, MenuBarState};
 use crate::tui::colors;
-use crate::tui::menu::{ActiveMenu, SearchFilterField};
+use crate::tui::glyphs::Glyphs;
+use crate::tui::menu::{ActiveMenu, BatchRenameField, SearchFilterField};
+use crate::tui::search::pattern_counts_label;
 use crate::tui::table::SortColumn;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table};
@@ -32,7 +35,7 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
             app.treemap = Some(tm);
         }
         let tm = app.treemap.as_ref().unwrap();
-        crate::tui::treemap::draw_treemap(frame, tm, area);
+        crate::tui::treemap::draw_treemap(frame, tm, area, app.glyphs, app.palette);
         return;
     }
 
@@ -55,31 +58,35 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     match &app.active_menu {
         ActiveMenu::None => {}
         ActiveMenu::Actions(actions) => {
-            draw_actions_menu(frame, actions, area);
+            draw_actions_menu(frame, actions, area, app.glyphs);
         }
         ActiveMenu::Confirm(confirm) => {
-            draw_confirm_dialog(frame, confirm, area);
+            draw_confirm_dialog(frame, confirm, area, app.glyphs);
         }
         ActiveMenu::Rename(rename) => {
-            draw_rename_dialog(frame, rename, area);
+            draw_rename_dialog(frame, rename, area, app.glyphs);
+        }
+        ActiveMenu::BatchRename(dialog) => {
+            draw_batch_rename_dialog(frame, dialog, area, app.glyphs);
         }
         ActiveMenu::SearchFilters(filters) => {
-            draw_search_filters(frame, filters, area);
+            draw_search_filters(frame, filters, area, app.glyphs);
         }
         ActiveMenu::Info(info) => {
-            draw_info_dialog(frame, info, area);
+            draw_info_dialog(frame, info, area, app.glyphs);
         }
     }
 
     // Draw menu bar dropdown if open
     if let Some(ref menu_bar) = app.menu_bar {
-        draw_menu_bar_dropdown(frame, menu_bar, area);
+        draw_menu_bar_dropdown(frame, menu_bar, area, app.glyphs);
     }
 
     // Show cursor in search bar when focused (and no menu is active)
     if matches!(app.active_menu, ActiveMenu::None) && app.menu_bar.is_none() && app.search.focused {
-        // Account for border (1) + space (1) + search icon " \u{1F50D} " (approx 4 display cols)
-        let cursor_x = chunks[1].x + 1 + 4 + app.search.cursor_pos as u16;
+        // Account for border (1) + the " <icon> " prefix
+        let prefix = Span::raw(format!(" {} ", app.glyphs.search)).width() as u16;
+        let cursor_x = chunks[1].x + 1 + prefix + app.search.cursor_pos as u16;
         let cursor_y = chunks[1].y + 1;
         frame.set_cursor_position(Position::new(cursor_x, cursor_y));
     }
@@ -119,7 +126,7 @@ fn draw_menu_bar_strip(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(Paragraph::new(line), area);
 }
 
-fn draw_menu_bar_dropdown(frame: &mut Frame, menu_bar: &MenuBarState, area: Rect) {
+fn draw_menu_bar_dropdown(frame: &mut Frame, menu_bar: &MenuBarState, area: Rect, glyphs: &Glyphs) {
     let menu_labels = [" File ", " Edit ", " View ", " Tools ", " Help "];
     let idx = menu_bar.active_menu_index;
 
@@ -154,6 +161,7 @@ fn draw_menu_bar_dropdown(frame: &mut Frame, menu_bar: &MenuBarState, area: Rect
 
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(Style::default().fg(Color::Cyan));
 
     let inner = block.inner(popup_area);
@@ -188,18 +196,35 @@ fn draw_menu_bar_dropdown(frame: &mut Frame, menu_bar: &MenuBarState, area: Rect
 }
 
 fn draw_search_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let glyphs = app.glyphs;
     let border_style = if app.search.focused {
         Style::default().fg(Color::Cyan)
     } else {
         Style::default().fg(Color::DarkGray)
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(border_style)
         .title(" Search ");
 
-    let search_text = format!(" \u{1F50D} {}", app.search.query);
+    // Active filters are easy to forget, so show them on the search bar itself
+    let filter_tags = app.search_filters.indicator();
+    if !filter_tags.is_empty() {
+        block = block.title(
+            Line::from(vec![
+                Span::styled(
+                    format!(" {} ", filter_tags),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled("Ctrl+L:Clear ", Style::default().fg(Color::DarkGray)),
+            ])
+            .right_aligned(),
+        );
+    }
+
+    let search_text = format!(" {} {}", glyphs.search, app.search.query);
     let paragraph = Paragraph::new(search_text)
         .block(block)
         .style(Style::default().fg(Color::White));
@@ -213,19 +238,30 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
     app.table.visible_rows = table_inner_height;
 
     // Build header
-    let header_columns: [(&str, SortColumn); 6] = [
+    let header_columns: [(&str, SortColumn); 9] = [
         ("Name", SortColumn::Name),
         ("Path", SortColumn::Path),
         ("Size", SortColumn::Size),
         ("Ext", SortColumn::Extension),
-        ("Date Modified", SortColumn::DateModified),
+        (app.date_field.label(), SortColumn::DateModified),
         ("Type", SortColumn::Type),
+        ("Drive", SortColumn::Drive),
+        ("Record", SortColumn::Record),
+        ("Storage", SortColumn::Storage),
     ];
 
+    // Indices of shown columns; hidden ones are dropped from header, rows and widths
+    let visible: Vec<usize> = header_columns
+        .iter()
+        .filter(|(_, col)| app.table.is_column_visible(*col))
+        .map(|(_, col)| col.index())
+        .collect();
+
     let h_off_header = app.table.horizontal_offset as usize;
-    let header = Row::new(header_columns.iter().map(|(name, col)| {
+    let header = Row::new(visible.iter().map(|&idx| {
+        let (name, col) = &header_columns[idx];
         let text = if app.table.sort_column == *col {
-            format!("{}{}", name, app.table.sort_order.indicator())
+            format!("{}{}", name, app.table.sort_order.indicator(app.glyphs))
         } else {
             name.to_string()
         };
@@ -266,13 +302,17 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
                     } else {
                         crate::format_size(row_data.file_size)
                     };
-                    let date = if row_data.modification_time > 0 {
-                        crate::format_filetime(row_data.modification_time)
+                    let date = if row_data.date > 0 {
+                        crate::format_filetime(row_data.date)
                     } else {
                         String::new()
                     };
-                    let type_label =
-                        colors::type_label(row_data.is_directory, &row_data.extension).to_string();
+                    let type_label = row_data
+                        .metafile_label
+                        .unwrap_or_else(|| {
+                            colors::type_label(row_data.is_directory, &row_data.extension)
+                        })
+                        .to_string();
                     (
                         row_data.name,
                         row_data.path,
@@ -289,26 +329,42 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
                     } else {
                         crate::format_size(entry.file_size)
                     };
-                    let date = if entry.modification_time > 0 {
-                        crate::format_filetime(entry.modification_time)
+                    let date = app.entry_time(entry);
+                    let date = if date > 0 {
+                        crate::format_filetime(date)
                     } else {
                         String::new()
                     };
-                    let type_label =
-                        colors::type_label(entry.is_directory, &entry.extension).to_string();
+                    let extension = app.extensions.get(entry.extension);
+                    let type_label = entry
+                        .metafile_label
+                        .unwrap_or_else(|| colors::type_label(entry.is_directory, extension))
+                        .to_string();
                     (
-                        entry.name.clone(),
+                        app.strings.get(entry.name).to_string(),
                         String::new(),
                         size,
-                        entry.extension.clone(),
+                        extension.to_string(),
                         date,
                         type_label,
                         entry.is_directory,
                     )
                 };
 
-            let icon = colors::icon_for_entry(is_dir, &ext);
-            let name_color = if is_dir {
+            let icon = if entry.off_volume_link {
+                app.glyphs.link
+            } else if app.glyphs.type_icons {
+                colors::icon_for_entry(is_dir, &ext)
+            } else if is_dir {
+                app.glyphs.folder
+            } else {
+                app.glyphs.file
+            };
+            let name_color = if entry.is_deleted {
+                Color::Red
+            } else if entry.is_hidden() {
+                Color::DarkGray
+            } else if is_dir {
                 Color::LightBlue
             } else {
                 colors::color_for_extension(&ext)
@@ -323,11 +379,18 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
                 Color::Reset
             };
 
-            let fg_modifier = if is_selected {
+            let mut fg_modifier = if is_selected {
                 Modifier::BOLD
             } else {
                 Modifier::empty()
             };
+            // System files are set in italics on top of their colour
+            if entry.is_system() {
+                fg_modifier |= Modifier::ITALIC;
+            }
+            if entry.is_deleted {
+                fg_modifier |= Modifier::CROSSED_OUT;
+            }
 
             // Apply horizontal offset to all text content
             let h_off = app.table.horizontal_offset as usize;
@@ -344,15 +407,28 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
                 }
             };
 
-            let name_text = apply_offset(format!("{} {}", icon, name));
+            let name_line = highlighted_name(
+                icon,
+                &name,
+                &app.search.active_patterns,
+                h_off,
+                Style::default().fg(name_color).bg(bg).add_modifier(fg_modifier),
+            );
             let path_text = apply_offset(path);
             let size_text = apply_offset(size_str);
             let ext_text = apply_offset(ext);
             let date_text = apply_offset(date_str);
             let type_text = apply_offset(type_str);
-
-            let name_cell = Cell::from(name_text)
-                .style(Style::default().fg(name_color).bg(bg).add_modifier(fg_modifier));
+            let drive_text = apply_offset(
+                app.trees
+                    .get(entry.tree_index)
+                    .map(|t| format!("{}:", t.drive_letter))
+                    .unwrap_or_default(),
+            );
+            let record_text = apply_offset(entry.key.record_number.to_string());
+            let storage_text = apply_offset(entry.storage.label());
+
+            let name_cell = Cell::from(name_line).style(Style::default().bg(bg));
             let path_cell = Cell::from(path_text).style(Style::default().fg(Color::Gray).bg(bg));
             let size_cell = Cell::from(size_text)
                 .style(Style::default().fg(Color::Green).bg(bg));
@@ -360,15 +436,30 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
             let date_cell = Cell::from(date_text).style(Style::default().fg(Color::White).bg(bg));
             let type_cell = Cell::from(type_text)
                 .style(Style::default().fg(Color::DarkGray).bg(bg).add_modifier(Modifier::ITALIC));
-
-            Row::new(vec![
-                name_cell, path_cell, size_cell, ext_cell, date_cell, type_cell,
-            ])
+            let drive_cell = Cell::from(drive_text)
+                .style(Style::default().fg(colors::color_for_drive(entry.tree_index)).bg(bg));
+            let record_cell = Cell::from(record_text)
+                .style(Style::default().fg(Color::DarkGray).bg(bg));
+            let storage_cell = Cell::from(storage_text).style(Style::default().fg(match entry.storage {
+                Storage::Resident => Color::Cyan,
+                Storage::NonResident { fragments } if fragments > 1 => Color::Yellow,
+                _ => Color::DarkGray,
+            }).bg(bg));
+
+            let mut cells = [
+                Some(name_cell), Some(path_cell), Some(size_cell),
+                Some(ext_cell), Some(date_cell), Some(type_cell), Some(drive_cell),
+                Some(record_cell), Some(storage_cell),
+            ];
+            Row::new(visible.iter().filter_map(|&idx| cells[idx].take()))
         })
         .collect();
 
-    let widths: Vec<Constraint> = app.table.column_widths.iter().map(|&w| {
-        if w == 0 {
+    // With the Path column hidden, Name takes the spare room
+    let name_fills = !app.table.is_column_visible(SortColumn::Path);
+    let widths: Vec<Constraint> = visible.iter().map(|&idx| {
+        let w = app.table.column_widths[idx];
+        if w == 0 || (name_fills && idx == SortColumn::Name.index()) {
             Constraint::Fill(1)
         } else {
             Constraint::Length(w)
@@ -385,9 +476,50 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(table, area);
 }
 
+/// Build the Name column as styled spans, bolding and recoloring the parts
+/// of the name matched by the active search patterns.
+fn highlighted_name<'a>(
+    icon: &str,
+    name: &str,
+    patterns: &[String],
+    h_off: usize,
+    base_style: Style,
+) -> Line<'a> {
+    let highlight_style = base_style
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+    let prefix = format!("{} ", icon);
+    let prefix_len = prefix.chars().count();
+    let name_mask = crate::tui::search::highlight_mask(name, patterns);
+
+    let mut spans: Vec<Span> = Vec::new();
+    let mut current = String::new();
+    let mut current_hl = false;
+
+    for (i, ch) in prefix.chars().chain(name.chars()).enumerate() {
+        if i < h_off {
+            continue;
+        }
+        let hl = i >= prefix_len && name_mask[i - prefix_len];
+        if hl != current_hl && !current.is_empty() {
+            let style = if current_hl { highlight_style } else { base_style };
+            spans.push(Span::styled(std::mem::take(&mut current), style));
+        }
+        current_hl = hl;
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        let style = if current_hl { highlight_style } else { base_style };
+        spans.push(Span::styled(current, style));
+    }
+
+    Line::from(spans)
+}
+
 fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let left_text = if app.is_scanning {
-        format!(" \u{23F3} Scanning... {}", app.scan_progress)
+        format!(" {} Scanning... {}", app.glyphs.busy, app.scan_progress)
     } else if app.is_sorting {
         " Sorting...".to_string()
     } else {
@@ -397,13 +529,37 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         );
         let total_size: u64 = app.trees.iter().map(|t| t.stats.total_size).sum();
         let total_size_str = crate::format_size(total_size);
-        format!(
-            " {} objects | {} selected | {} total",
-            obj_count, selected_count, total_size_str
-        )
+        let mut text = format!(
+            " {} objects | {} selected ({}, {}) | {} total",
+            obj_count,
+            selected_count,
+            crate::format_size(app.selected_size()),
+            app.size_sum.label(),
+            total_size_str
+        );
+        if let Some(counts) = pattern_counts_label(&app.search.pattern_counts) {
+            text.push_str(&format!(" | {}", counts));
+        }
+        // Oldest drive decides how stale the results are
+        if let Some(age) = app.trees.iter().filter_map(|t| t.scan_age()).max() {
+            text.push_str(&format!(" | scanned {}", crate::format_age(age)));
+        }
+        if let Some(note) = app.journal_status().stale_note() {
+            text.push_str(&format!(" ({}, F9 to rescan)", note));
+        }
+        if let Some((done, total)) = app.path_cache_progress {
+            text.push_str(&format!(
+                " | Caching paths {}%",
+                done * 100 / total.max(1)
+            ));
+        }
+        text
     };
 
-    let right_text = " Tab:Search  F1-F6:Sort  \u{2190}\u{2192}:Scroll  M:Menu  Ctrl+F:Filters  T:Treemap  F10:MenuBar  Ctrl+Q:Quit ";
+    let right_text = format!(
+        " Tab:Search  F1-F8:Sort  {}:Scroll  M:Menu  Ctrl+F:Filters  Ctrl+L:Clear Filters  T:Treemap  F10:MenuBar  Ctrl+Q:Quit ",
+        app.glyphs.arrows_horizontal
+    );
 
     // Build the status line: left-aligned text + padding + right-aligned text
     let available_width = area.width as usize;
@@ -435,6 +591,7 @@ fn draw_actions_menu(
     frame: &mut Frame,
     actions: &crate::tui::menu::ActionsMenu,
     area: Rect,
+    glyphs: &Glyphs,
 ) {
     let width = 28;
     let height = (actions.items.len() as u16) + 2; // +2 for borders
@@ -444,6 +601,7 @@ fn draw_actions_menu(
 
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(Style::default().fg(Color::Cyan))
         .title(" Actions ")
         .title_style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD));
@@ -472,6 +630,7 @@ fn draw_confirm_dialog(
     frame: &mut Frame,
     confirm: &crate::tui::menu::ConfirmDialog,
     area: Rect,
+    glyphs: &Glyphs,
 ) {
     let width = (confirm.message.len() as u16 + 4).max(30).min(area.width - 4);
     let height = 5;
@@ -481,6 +640,7 @@ fn draw_confirm_dialog(
 
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(Style::default().fg(Color::Red))
         .title(" Confirm ")
         .title_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
@@ -523,6 +683,7 @@ fn draw_rename_dialog(
     frame: &mut Frame,
     rename: &crate::tui::menu::RenameDialog,
     area: Rect,
+    glyphs: &Glyphs,
 ) {
     let width = 50.min(area.width - 4);
     let height = 5;
@@ -532,6 +693,7 @@ fn draw_rename_dialog(
 
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(Style::default().fg(Color::Yellow))
         .title(" Rename ")
         .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
@@ -559,10 +721,120 @@ fn draw_rename_dialog(
     frame.set_cursor_position(Position::new(cursor_x, input_area.y));
 }
 
+fn draw_batch_rename_dialog(
+    frame: &mut Frame,
+    dialog: &crate::tui::menu::BatchRenameDialog,
+    area: Rect,
+    glyphs: &Glyphs,
+) {
+    let width = 76u16.min(area.width.saturating_sub(4));
+    let height = (dialog.paths.len() as u16 + 6).clamp(8, area.height.saturating_sub(4).max(8));
+    let popup_area = centered_rect(width, height, area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let footer = match dialog.preview {
+        Ok(_) => Line::from(format!(
+            " {} of {} will be renamed, Enter to apply ",
+            dialog.rename_count(),
+            dialog.paths.len()
+        )),
+        Err(ref e) => Line::from(Span::styled(
+            format!(" Regex: {} ", e.lines().last().unwrap_or_default()),
+            Style::default().fg(Color::Red),
+        )),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(glyphs.border)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title(" Batch Rename ({name} {ext} {n} {n:3}) ")
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .title_bottom(footer.right_aligned());
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let label_w = 10u16;
+    let field_w = inner.width.saturating_sub(label_w + 2);
+
+    let draw_field =
+        |frame: &mut Frame, y: u16, label: &str, value: &str, placeholder: &str, focused: bool| {
+            let label_style = if focused {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            frame.render_widget(
+                Paragraph::new(label).style(label_style),
+                Rect::new(inner.x + 1, y, label_w, 1),
+            );
+
+            let bg = if focused {
+                Color::Rgb(40, 40, 50)
+            } else {
+                Color::Rgb(30, 30, 40)
+            };
+            let (display, style) = if value.is_empty() {
+                (
+                    placeholder,
+                    Style::default().fg(Color::DarkGray).bg(bg).add_modifier(Modifier::ITALIC),
+                )
+            } else {
+                (value, Style::default().fg(Color::White).bg(bg))
+            };
+            frame.render_widget(
+                Paragraph::new(format!("{:w$}", display, w = field_w as usize)).style(style),
+                Rect::new(inner.x + label_w + 1, y, field_w, 1),
+            );
+        };
+
+    draw_field(frame, inner.y, " Pattern:", &dialog.pattern, "{name}.{ext}", dialog.focused_field == BatchRenameField::Pattern);
+    draw_field(frame, inner.y + 1, " Find:", &dialog.find, "regex (optional)", dialog.focused_field == BatchRenameField::Find);
+    draw_field(frame, inner.y + 2, " Replace:", &dialog.replace, "$1 refers to a group", dialog.focused_field == BatchRenameField::Replace);
+
+    frame.render_widget(
+        Paragraph::new(format!("-- Preview {}", glyphs.dash.repeat(inner.width.saturating_sub(13) as usize)))
+            .style(Style::default().fg(Color::DarkGray)),
+        Rect::new(inner.x + 1, inner.y + 3, inner.width.saturating_sub(2), 1),
+    );
+
+    // Preview rows: "old -> new", skipped items in red with the reason
+    if let Ok(ref items) = dialog.preview {
+        let rows = inner.height.saturating_sub(4) as usize;
+        let lines: Vec<Line> = items
+            .iter()
+            .skip(dialog.scroll)
+            .take(rows)
+            .map(|item| {
+                let old = Span::styled(item.name(), Style::default().fg(Color::Gray));
+                let (new, style) = match item.to {
+                    Ok(ref to) if item.will_rename() => (to.clone(), Style::default().fg(Color::Green)),
+                    Ok(_) => ("(unchanged)".to_string(), Style::default().fg(Color::DarkGray)),
+                    Err(ref why) => (format!("skipped: {}", why), Style::default().fg(Color::Red)),
+                };
+                Line::from(vec![old, Span::raw(" -> "), Span::styled(new, style)])
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines),
+            Rect::new(inner.x + 1, inner.y + 4, inner.width.saturating_sub(2), rows as u16),
+        );
+    }
+
+    let (cursor_y, cursor) = match dialog.focused_field {
+        BatchRenameField::Pattern => (inner.y, dialog.pattern_cursor),
+        BatchRenameField::Find => (inner.y + 1, dialog.find_cursor),
+        BatchRenameField::Replace => (inner.y + 2, dialog.replace_cursor),
+    };
+    frame.set_cursor_position(Position::new(inner.x + label_w + 1 + cursor as u16, cursor_y));
+}
+
 fn draw_search_filters(
     frame: &mut Frame,
     filters: &crate::tui::menu::SearchFiltersMenu,
     area: Rect,
+    glyphs: &Glyphs,
 ) {
     let width = 60u16.min(area.width.saturating_sub(4));
     let height = 16u16;
@@ -572,9 +844,17 @@ fn draw_search_filters(
 
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(Style::default().fg(Color::Cyan))
         .title(" Search Filters (Tab to navigate, Enter to apply) ")
-        .title_style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD));
+        .title_style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+        .title_bottom(
+            Line::from(match filters.preview_count {
+                Some(count) => format!(" Would match: {} ", count),
+                None => format!(" Counting{} ", glyphs.ellipsis),
+            })
+            .right_aligned(),
+        );
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -758,8 +1038,9 @@ fn draw_info_dialog(
     frame: &mut Frame,
     info: &crate::tui::menu::InfoDialog,
     area: Rect,
+    glyphs: &Glyphs,
 ) {
-    let max_line_len = info.lines.iter().map(|l| l.len()).max().unwrap_or(20);
+    let max_line_len = info.lines.iter().map(|l| l.chars().count()).max().unwrap_or(20);
     let width = ((max_line_len + 4) as u16).max(30).min(area.width.saturating_sub(4));
     let height = ((info.lines.len() + 3) as u16).min(area.height.saturating_sub(4));
     let popup_area = centered_rect(width, height, area);
@@ -769,6 +1050,7 @@ fn draw_info_dialog(
     let title = format!(" {} ", info.title);
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(glyphs.border)
         .border_style(Style::default().fg(Color::Cyan))
         .title(title)
         .title_style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD));